@@ -1,9 +1,14 @@
 //! See how fast we can send view updates.
 //!
-//! (Run this with `--release` to get a fair estimate.)
+//! (Run this with `--release` in a terminal to get a fair estimate: when
+//! no bar is drawn, updates skip the paint checks entirely.)
 
+use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+use std::thread;
 use std::time::Instant;
 
+use nutmeg::{Clock, CoarseClock, Options, SyncView, SystemClock};
+
 struct IntModel(usize);
 
 impl nutmeg::Model for IntModel {
@@ -12,17 +17,58 @@ impl nutmeg::Model for IntModel {
     }
 }
 
-fn main() {
+fn bench(name: &str, clock: &'static dyn Clock) {
     let start = Instant::now();
-    let view = nutmeg::View::new(IntModel(0), nutmeg::Options::default());
+    let view = nutmeg::View::new(IntModel(0), Options::default().clock(clock));
     let n = 10_000_000;
     for i in 0..n {
         view.update(|IntModel(count)| *count = i);
     }
     view.message(format!(
-        "{}ms to send {} updates; average {}ns/update\n",
+        "{name}: {}ms to send {} updates; average {}ns/update\n",
         start.elapsed().as_millis(),
         n,
         start.elapsed().as_nanos() / n as u128,
     ));
 }
+
+struct AtomicModel(AtomicUsize);
+
+impl nutmeg::Model for AtomicModel {
+    fn render(&mut self, _width: usize) -> String {
+        format!("count: {}", self.0.load(Relaxed))
+    }
+}
+
+/// Send updates from several threads at once to a [SyncView].
+fn bench_sync(name: &str, clock: &'static dyn Clock) {
+    let start = Instant::now();
+    let view = SyncView::new(
+        AtomicModel(AtomicUsize::new(0)),
+        Options::default().clock(clock),
+    );
+    let threads = 4;
+    let n = 10_000_000;
+    thread::scope(|scope| {
+        for _ in 0..threads {
+            scope.spawn(|| {
+                for _ in 0..n / threads {
+                    view.update(|AtomicModel(count)| count.fetch_add(1, Relaxed));
+                }
+            });
+        }
+    });
+    view.message(format!(
+        "{name}: {}ms to send {} updates from {threads} threads; average {}ns/update\n",
+        start.elapsed().as_millis(),
+        n,
+        start.elapsed().as_nanos() / n as u128,
+    ));
+}
+
+fn main() {
+    bench("system clock", &SystemClock);
+    bench("coarse clock", &CoarseClock);
+    bench_sync("sync view, system clock", &SystemClock);
+    bench_sync("sync view, coarse clock", &CoarseClock);
+}
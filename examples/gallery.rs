@@ -0,0 +1,10 @@
+//! Show how each of the built-in models looks, in several states and widths.
+
+fn main() {
+    for frame in nutmeg::models::gallery() {
+        println!(
+            "{:>14} {:>15} {:>3}: {}",
+            frame.model, frame.state, frame.width, frame.rendered
+        );
+    }
+}
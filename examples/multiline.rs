@@ -18,7 +18,7 @@ impl nutmeg::Model for Model {
             self.i,
             "*".repeat(self.i),
             self.start.elapsed().as_secs_f32(),
-            if self.i % 2 == 0 {
+            if self.i.is_multiple_of(2) {
                 Paint::red("XXX")
             } else {
                 Paint::yellow("XXX")
@@ -2,78 +2,58 @@
 
 //! Demonstrate multiple threads writing to a single view.
 //!
-//! A single View is shared in an Arc across all threads. (A scoped thread
-//! would also work.)
+//! A single View is shared across scoped threads. (An Arc would also work.)
 //!
-//! Each thread periodically updates the model, which will make it repaint
+//! Each thread adds a task to the view, which is drawn as a line below the
+//! model until the thread finishes it. Updating the task repaints the view,
 //! subject to the update rate limit.
 
-use std::fmt::Write;
-use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::thread::{self, sleep};
 use std::time::Duration;
 
 use rand::Rng;
 
-const THREAD_WORK_MAX: usize = 20;
+const THREAD_WORK_MAX: u64 = 20;
+const THREADS: usize = 20;
 
-/// Per-thread progress.
-struct JobState {
-    x: usize,
-    complete: bool,
-}
-
-/// Overall task progress.
+/// Overall progress; the per-thread progress is shown by tasks.
 struct Model {
-    job_state: Vec<JobState>,
+    complete: AtomicUsize,
 }
 
 impl nutmeg::Model for Model {
     fn render(&mut self, _width: usize) -> String {
-        let mut s = String::new();
-        let n_jobs = self.job_state.len();
-        let n_complete = self.job_state.iter().filter(|j| j.complete).count();
-        writeln!(s, "{n_complete}/{n_jobs} complete").unwrap();
-        for (i, js) in self.job_state.iter().enumerate() {
-            let remains = THREAD_WORK_MAX - js.x;
-            writeln!(s, "{:3}: {}{}", i, "#".repeat(js.x), "_".repeat(remains)).unwrap();
-        }
-        s
+        let n_complete = self.complete.load(Ordering::Relaxed);
+        format!("{n_complete}/{THREADS} complete")
     }
 }
 
-fn work(i_thread: usize, arc_view: Arc<nutmeg::View<Model>>) {
+fn work(i_thread: usize, view: &nutmeg::View<Model>) {
     let mut rng = rand::thread_rng();
-    for j in 0..=THREAD_WORK_MAX {
-        arc_view.update(|model| model.job_state[i_thread].x = j);
+    let task = view.add_task(format!("{i_thread:3}"));
+    for j in 1..=THREAD_WORK_MAX {
         sleep(Duration::from_millis(rng.gen_range(100..600)));
+        task.increment(1);
+        task.set_message("#".repeat(j as usize));
     }
-    arc_view.update(|model| model.job_state[i_thread].complete = true);
+    task.finish();
+    view.update(|model| model.complete.fetch_add(1, Ordering::Relaxed));
 }
 
 fn main() {
     let model = Model {
-        job_state: Vec::new(),
+        complete: AtomicUsize::new(0),
     };
     let view = nutmeg::View::new(model, nutmeg::Options::default());
     view.update(|_m| ());
-    let arc_view = Arc::new(view);
-    let mut join_handles = Vec::new();
-    for i_thread in 0..20 {
-        arc_view.update(|model| {
-            model.job_state.push(JobState {
-                x: 0,
-                complete: false,
-            })
-        });
-        sleep(Duration::from_millis(100));
-        let give_arc_view = arc_view.clone();
-        join_handles.push(thread::spawn(move || work(i_thread, give_arc_view)));
-    }
-    for join_handle in join_handles {
-        arc_view.update(|_m| ());
-        join_handle.join().unwrap();
-    }
-    arc_view.update(|_| ());
+    thread::scope(|scope| {
+        for i_thread in 0..THREADS {
+            let view = &view;
+            scope.spawn(move || work(i_thread, view));
+            sleep(Duration::from_millis(100));
+        }
+    });
+    view.update(|_| ());
     sleep(Duration::from_millis(500));
 }
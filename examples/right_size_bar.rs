@@ -13,7 +13,8 @@ impl nutmeg::Model for Model {
     fn render(&mut self, width: usize) -> String {
         let start = format!("i={} | ", self.i);
         let end = format!(" | {:.3}s", self.start_time.elapsed().as_secs_f64());
-        let fill_len = width - start.len() - end.len();
+        // Leave at least one column for the fill, even on a very narrow terminal.
+        let fill_len = width.saturating_sub(start.len() + end.len()).max(1);
         let mut fill: Vec<u8> = vec![b'.'; fill_len];
         fill[self.i % fill_len] = b'~';
         let fill: String = String::from_utf8(fill).unwrap();
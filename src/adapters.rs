@@ -0,0 +1,180 @@
+// Copyright 2023 Martin Pool.
+
+//! Feed progress from other libraries' archives and transfers into a view.
+//!
+//! These adapters don't depend on the libraries themselves; they accept the
+//! plain counts and iterators those libraries expose, so the same glue works
+//! across versions.
+//!
+//! For a `git2` fetch or clone, pass the object counts from the
+//! `transfer_progress` callback to [transfer_progress]:
+//!
+//! ```ignore
+//! let feed = nutmeg::adapters::transfer_progress(&view);
+//! callbacks.transfer_progress(|p| {
+//!     feed(p.received_objects(), p.total_objects());
+//!     true
+//! });
+//! ```
+//!
+//! For a `zip` archive, track the iteration over its entries by index with
+//! [track_iter]:
+//!
+//! ```ignore
+//! for i in nutmeg::adapters::track_iter(0..archive.len(), &view) {
+//!     let file = archive.by_index(i)?;
+//!     // ...
+//! }
+//! ```
+//!
+//! Any iterator can also be tracked with [ProgressIterExt::progress_with]:
+//!
+//! ```ignore
+//! use nutmeg::adapters::ProgressIterExt;
+//!
+//! for path in paths.iter().progress_with(&view) {
+//!     // ...
+//! }
+//! ```
+//!
+//! `tar` archives don't record how many entries they contain, so the entries
+//! can be counted into an [UnboundedModel] with [count_iter]:
+//!
+//! ```ignore
+//! for entry in nutmeg::adapters::count_iter(archive.entries()?, &view) {
+//!     entry?.unpack_in(dest)?;
+//! }
+//! ```
+
+use crate::callback::ProgressCallback;
+use crate::models::UnboundedModel;
+use crate::ProgressSink;
+
+/// Make a callback that records how many objects out of a total have been
+/// received, such as from the transfer progress of a `git2` fetch.
+pub fn transfer_progress<M, S>(view: &S) -> impl Fn(usize, usize) + '_
+where
+    M: ProgressCallback,
+    S: ProgressSink<M>,
+{
+    move |received, total| view.update(|model| model.set_progress(received as u64, total as u64))
+}
+
+/// Wrap an iterator of known length so that the model in `view` is updated
+/// with the number of items taken so far, out of the length of the iterator.
+///
+/// If the iterator's length isn't known from its [Iterator::size_hint], the
+/// total is left as zero.
+///
+/// ```
+/// use nutmeg::models::LinearModel;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(LinearModel::new("Extracting", 0), Options::default());
+/// let names = ["a.txt", "b.txt", "c.txt"];
+/// for name in nutmeg::adapters::track_iter(names.iter(), &view) {
+///     view.message(format!("extract {name}\n"));
+/// }
+/// let model = view.finish();
+/// assert_eq!((model.done(), model.total()), (3, 3));
+/// ```
+pub fn track_iter<I, M, S>(iter: I, view: &S) -> TrackIter<'_, I::IntoIter, M, S>
+where
+    I: IntoIterator,
+    M: ProgressCallback,
+    S: ProgressSink<M>,
+{
+    let iter = iter.into_iter();
+    let total = match iter.size_hint() {
+        (lower, Some(upper)) if lower == upper => lower as u64,
+        _ => 0,
+    };
+    view.update(|model| model.set_progress(0, total));
+    TrackIter {
+        iter,
+        view,
+        done: 0,
+        total,
+        _model: std::marker::PhantomData,
+    }
+}
+
+/// An iterator that updates a view as items are taken, returned by [track_iter].
+pub struct TrackIter<'a, I, M, S> {
+    iter: I,
+    view: &'a S,
+    done: u64,
+    total: u64,
+    _model: std::marker::PhantomData<fn(&mut M)>,
+}
+
+impl<'a, I, M, S> Iterator for TrackIter<'a, I, M, S>
+where
+    I: Iterator,
+    M: ProgressCallback,
+    S: ProgressSink<M>,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<I::Item> {
+        let item = self.iter.next()?;
+        self.done += 1;
+        let (done, total) = (self.done, self.total);
+        self.view.update(|model| model.set_progress(done, total));
+        Some(item)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// Adds [ProgressIterExt::progress_with] to every iterator.
+pub trait ProgressIterExt: Iterator + Sized {
+    /// Update the model in `view` with the number of items taken so far, out
+    /// of the length of the iterator, as for [track_iter].
+    ///
+    /// ```
+    /// use nutmeg::adapters::ProgressIterExt;
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let view = View::new(LinearModel::new("Squaring", 0), Options::default());
+    /// let squares: Vec<u32> = (1..=10).progress_with(&view).map(|i| i * i).collect();
+    /// assert_eq!(squares.len(), 10);
+    /// let model = view.finish();
+    /// assert_eq!((model.done(), model.total()), (10, 10));
+    /// ```
+    fn progress_with<M, S>(self, view: &S) -> TrackIter<'_, Self, M, S>
+    where
+        M: ProgressCallback,
+        S: ProgressSink<M>,
+    {
+        track_iter(self, view)
+    }
+}
+
+impl<I: Iterator> ProgressIterExt for I {}
+
+/// Wrap an iterator of unknown length, such as the entries of a `tar`
+/// archive, so that each item taken increments the count in an
+/// [UnboundedModel].
+///
+/// ```
+/// use nutmeg::models::UnboundedModel;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(UnboundedModel::new("Unpacking"), Options::default());
+/// let entries = (0..100).filter(|i| i % 3 == 0);
+/// let unpacked = nutmeg::adapters::count_iter(entries, &view).count();
+/// assert_eq!(view.finish().done(), unpacked);
+/// ```
+pub fn count_iter<'a, I, S>(iter: I, view: &'a S) -> impl Iterator<Item = I::Item> + 'a
+where
+    I: IntoIterator,
+    I::IntoIter: 'a,
+    S: ProgressSink<UnboundedModel>,
+{
+    iter.into_iter()
+        .inspect(move |_| view.update(|model| model.increment(1)))
+}
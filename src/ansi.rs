@@ -1,37 +1,256 @@
 // Copyright 2022 Martin Pool.
 
-//! Draw ANSI escape sequences.
+//! ANSI escape sequences used to draw the progress bar.
+//!
+//! These are the same sequences the [crate::View] uses itself. Applications that
+//! print their own control sequences through the view can use them to stay
+//! consistent with it.
+//!
+//! Note that [crate::Model::render] should not move the cursor up or down, or
+//! clear the screen, because the view keeps track of where the cursor is in order
+//! to erase the progress bar.
+//!
+//! ```
+//! use nutmeg::ansi;
+//!
+//! assert_eq!(ansi::cursor_up(3), "\x1b[3A");
+//! assert_eq!(ansi::CLEAR_TO_END_OF_LINE, "\x1b[0K");
+//! ```
 
 // References:
 // * <https://gist.github.com/fnky/458719343aabd01cfb17a3a4f7296797>
 
-#![allow(unused)]
-
 use std::borrow::Cow;
 
-pub(crate) const MOVE_TO_START_OF_LINE: &str = "\x1b[1G";
+/// Move the cursor to the first column of the current line.
+pub const MOVE_TO_START_OF_LINE: &str = "\x1b[1G";
 
 // https://vt100.net/docs/vt510-rm/DECAWM
-pub(crate) const DISABLE_LINE_WRAP: &str = "\x1b[?7l";
-pub(crate) const ENABLE_LINE_WRAP: &str = "\x1b[?7h";
+/// Turn off automatic wrapping of long lines (DECAWM).
+pub const DISABLE_LINE_WRAP: &str = "\x1b[?7l";
+/// Turn on automatic wrapping of long lines (DECAWM).
+pub const ENABLE_LINE_WRAP: &str = "\x1b[?7h";
+
+/// Hide the cursor.
+pub const HIDE_CURSOR: &str = "\x1b[?25l";
+/// Show the cursor.
+pub const SHOW_CURSOR: &str = "\x1b[?25h";
+
+/// Save the cursor position (DECSC).
+pub const SAVE_CURSOR: &str = "\x1b7";
+/// Restore the cursor position saved by [SAVE_CURSOR] (DECRC).
+pub const RESTORE_CURSOR: &str = "\x1b8";
+
+pub(crate) const RESET_SCROLL_REGION: &str = "\x1b[r";
+
+pub(crate) const ENTER_ALTERNATE_SCREEN: &str = "\x1b[?1049h";
+pub(crate) const EXIT_ALTERNATE_SCREEN: &str = "\x1b[?1049l";
+
+// https://gist.github.com/christianparpart/d8a62cc1ab659194337d73e399004036
+/// Begin a synchronized update (mode 2026): the terminal holds what's drawn
+/// until [END_SYNCHRONIZED_UPDATE], and then shows it all at once.
+pub const BEGIN_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026h";
+/// End a synchronized update begun by [BEGIN_SYNCHRONIZED_UPDATE].
+pub const END_SYNCHRONIZED_UPDATE: &str = "\x1b[?2026l";
 
-pub(crate) const CLEAR_TO_END_OF_LINE: &str = "\x1b[0K";
-pub(crate) const CLEAR_CURRENT_LINE: &str = "\x1b[2K";
-pub(crate) const CLEAR_TO_END_OF_SCREEN: &str = "\x1b[0J";
+/// Clear from the cursor to the end of the line.
+pub const CLEAR_TO_END_OF_LINE: &str = "\x1b[0K";
+/// Clear the whole current line, without moving the cursor.
+pub const CLEAR_CURRENT_LINE: &str = "\x1b[2K";
+/// Clear from the cursor to the end of the screen.
+pub const CLEAR_TO_END_OF_SCREEN: &str = "\x1b[0J";
 
-pub(crate) fn up_n_lines_and_home(n: usize) -> Cow<'static, str> {
-    if n > 0 {
-        format!("\x1b[{n}F").into()
+/// Reset all text styles, such as colors and bold, to the default (SGR 0).
+pub const RESET_STYLE: &str = "\x1b[0m";
+
+/// Ring the terminal bell.
+pub const BELL: &str = "\x07";
+
+/// Move the cursor up `n` lines, staying in the same column.
+///
+/// Returns an empty string if `n` is zero.
+pub fn cursor_up(n: usize) -> Cow<'static, str> {
+    if n == 0 {
+        "".into()
     } else {
-        MOVE_TO_START_OF_LINE.into()
+        format!("\x1b[{n}A").into()
     }
 }
 
-#[cfg(windows)]
+/// Move the cursor down `n` lines, staying in the same column.
+///
+/// Returns an empty string if `n` is zero.
+pub fn cursor_down(n: usize) -> Cow<'static, str> {
+    if n == 0 {
+        "".into()
+    } else {
+        format!("\x1b[{n}B").into()
+    }
+}
+
+/// A piece of a string that's either plain text or a single control sequence.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Segment<'s> {
+    Text(&'s str),
+    Control(&'s str),
+}
+
+/// Split `s` into runs of plain text and individual escape sequences.
+pub(crate) fn segments(s: &str) -> impl Iterator<Item = Segment<'_>> {
+    let bytes = s.as_bytes();
+    let mut i = 0;
+    std::iter::from_fn(move || {
+        if i >= bytes.len() {
+            return None;
+        }
+        let start = i;
+        if bytes[i] != 0x1b {
+            while i < bytes.len() && bytes[i] != 0x1b {
+                i += 1;
+            }
+            return Some(Segment::Text(&s[start..i]));
+        }
+        i += 1;
+        match bytes.get(i) {
+            Some(b'[') => {
+                // CSI: parameter and intermediate bytes, then a final byte.
+                i += 1;
+                while i < bytes.len() && (0x20..0x40).contains(&bytes[i]) {
+                    i += 1;
+                }
+                if i < bytes.len() {
+                    i += 1;
+                }
+            }
+            Some(c) if c.is_ascii() => i += 1,
+            _ => (),
+        }
+        Some(Segment::Control(&s[start..i]))
+    })
+}
+
+/// Remove all escape sequences from `s`, leaving only the plain text.
+pub(crate) fn strip_sequences(s: &str) -> String {
+    segments(s)
+        .filter_map(|seg| match seg {
+            Segment::Text(t) => Some(t),
+            Segment::Control(_) => None,
+        })
+        .collect()
+}
+
+/// Find the first control sequence in `s` that moves the cursor vertically,
+/// scrolls, or clears the screen, any of which would confuse the view's
+/// count of where the cursor is.
+///
+/// Styling sequences, and those that move within or clear part of the
+/// current line, are allowed.
+pub(crate) fn find_forbidden_sequence(s: &str) -> Option<&str> {
+    segments(s).find_map(|seg| match seg {
+        Segment::Control(c) if is_forbidden(c) => Some(c),
+        _ => None,
+    })
+}
+
+fn is_forbidden(control: &str) -> bool {
+    match control.as_bytes() {
+        [0x1b, b'[', .., final_byte] => matches!(
+            final_byte,
+            b'A' | b'B' | b'E' | b'F' | b'H' | b'J' | b'S' | b'T' | b'f' | b'r' | b's' | b'u'
+        ),
+        // An unterminated CSI.
+        [0x1b, b'['] => true,
+        // Save or restore cursor, index, reverse index, next line, full reset.
+        [0x1b, c] => matches!(c, b'7' | b'8' | b'D' | b'M' | b'E' | b'c'),
+        _ => false,
+    }
+}
+
+/// A set of control sequences understood by some family of terminals.
+///
+/// Most modern terminal emulators understand the default [ControlDialect::XTERM]
+/// sequences, but users on unusual terminals may choose a more conservative dialect
+/// using [crate::Options::control_dialect].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ControlDialect {
+    /// Move to the start of the line.
+    start_of_line: &'static str,
+    /// True if the terminal understands "cursor previous line" (CPL); otherwise
+    /// move up with "cursor up" (CUU) and then to the start of the line.
+    previous_line: bool,
+    /// Clear from the cursor to the end of the screen.
+    clear_to_end_of_screen: &'static str,
+    /// True if the terminal reliably supports turning off line wrapping.
+    line_wrap: bool,
+}
+
+impl ControlDialect {
+    /// The full set of sequences understood by xterm and most modern terminal emulators.
+    ///
+    /// This is the default.
+    pub const XTERM: ControlDialect = ControlDialect {
+        start_of_line: MOVE_TO_START_OF_LINE,
+        previous_line: true,
+        clear_to_end_of_screen: CLEAR_TO_END_OF_SCREEN,
+        line_wrap: true,
+    };
+
+    /// A minimal set of sequences understood by the VT100 and its descendants.
+    pub const VT100: ControlDialect = ControlDialect {
+        start_of_line: "\r",
+        previous_line: false,
+        clear_to_end_of_screen: CLEAR_TO_END_OF_SCREEN,
+        line_wrap: true,
+    };
+
+    /// A subset that is safe on Windows consoles, which does not change the line
+    /// wrap mode: lines are instead truncated to the terminal width.
+    pub const WINDOWS: ControlDialect = ControlDialect {
+        start_of_line: "\r",
+        previous_line: false,
+        clear_to_end_of_screen: CLEAR_TO_END_OF_SCREEN,
+        line_wrap: false,
+    };
+
+    /// Move the cursor up `n` lines, and to the start of that line.
+    pub(crate) fn up_n_lines_and_home(&self, n: usize) -> Cow<'static, str> {
+        if n == 0 {
+            self.start_of_line.into()
+        } else if self.previous_line {
+            format!("\x1b[{n}F").into()
+        } else {
+            format!("{}{}", cursor_up(n), self.start_of_line).into()
+        }
+    }
+
+    pub(crate) fn clear_to_end_of_screen(&self) -> &'static str {
+        self.clear_to_end_of_screen
+    }
+
+    /// True if this dialect allows the line wrap mode to be changed.
+    pub(crate) fn supports_line_wrap(&self) -> bool {
+        self.line_wrap
+    }
+}
+
+impl Default for ControlDialect {
+    fn default() -> Self {
+        ControlDialect::XTERM
+    }
+}
+
+#[cfg(all(windows, feature = "windows-console"))]
 pub(crate) fn enable_windows_ansi() -> bool {
     crate::windows::enable_windows_ansi()
 }
 
+/// Without the `windows-console` feature, escape sequences aren't enabled, so
+/// progress bars aren't drawn on the Windows console.
+#[cfg(all(windows, not(feature = "windows-console")))]
+pub(crate) fn enable_windows_ansi() -> bool {
+    false
+}
+
 #[cfg(not(windows))]
 pub(crate) fn enable_windows_ansi() -> bool {
     true
@@ -0,0 +1,89 @@
+// Copyright 2023 Martin Pool.
+
+//! Adapt progress callbacks from other libraries to update a model.
+//!
+//! Many libraries report progress by calling a closure with the amount of
+//! work done and the total expected, such as bytes transferred by a download,
+//! entries extracted from an archive, or objects fetched by a clone. The
+//! functions here make such a closure that updates a [View].
+//!
+//! ```
+//! use nutmeg::models::LinearModel;
+//! use nutmeg::{Options, View};
+//!
+//! /// Some library function that reports progress through a callback.
+//! fn download(progress: impl Fn(u64, u64)) {
+//!     for done in 0..=100 {
+//!         progress(done * 1024, 100 * 1024);
+//!     }
+//! }
+//!
+//! let view = View::new(LinearModel::new("Downloading", 0), Options::default());
+//! download(nutmeg::callback::from_fn(&view));
+//! let model = view.finish();
+//! assert_eq!(model.done(), 102400);
+//! assert_eq!(model.total(), 102400);
+//! ```
+
+use crate::models::{BytesModel, LinearModel};
+use crate::{Model, ProgressSink};
+#[allow(unused)] // for docstrings
+use crate::{NullView, View};
+
+/// A model that can be updated from a callback reporting the amount of work
+/// done and the total amount expected.
+pub trait ProgressCallback: Model {
+    /// Record that `done` out of `total` units of work are complete.
+    fn set_progress(&mut self, done: u64, total: u64);
+}
+
+impl ProgressCallback for LinearModel {
+    fn set_progress(&mut self, done: u64, total: u64) {
+        self.set_done(usize::try_from(done).unwrap_or(usize::MAX));
+        self.set_total(usize::try_from(total).unwrap_or(usize::MAX));
+    }
+}
+
+impl ProgressCallback for BytesModel {
+    fn set_progress(&mut self, done: u64, total: u64) {
+        self.set_done(done);
+        self.set_total(total);
+    }
+}
+
+/// Make a callback that updates the model in `view` each time it's called
+/// with the amount of work done and the total.
+///
+/// `view` can be a [View] or a [NullView].
+pub fn from_fn<M, S>(view: &S) -> impl Fn(u64, u64) + '_
+where
+    M: ProgressCallback,
+    S: ProgressSink<M>,
+{
+    move |done, total| view.update(|model| model.set_progress(done, total))
+}
+
+/// Make a callback that calls `update_fn` on the model in `view` with the
+/// amount of work done and the total, for models that don't implement
+/// [ProgressCallback].
+///
+/// ```
+/// use nutmeg::models::UnboundedModel;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(UnboundedModel::new("Extracting"), Options::default());
+/// let callback = nutmeg::callback::from_fn_with(&view, |model, done, _total| {
+///     model.set_done(done as usize)
+/// });
+/// callback(3, 0);
+/// drop(callback);
+/// assert_eq!(view.finish().done(), 3);
+/// ```
+pub fn from_fn_with<'a, M, S, F>(view: &'a S, update_fn: F) -> impl Fn(u64, u64) + 'a
+where
+    M: Model,
+    S: ProgressSink<M>,
+    F: Fn(&mut M, u64, u64) + 'a,
+{
+    move |done, total| view.update(|model| update_fn(model, done, total))
+}
@@ -0,0 +1,49 @@
+// Copyright 2023 Martin Pool.
+
+//! Requests to cancel the work shown by a view, for example from a Ctrl-C handler.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+#[allow(unused)] // for docstrings
+use crate::View;
+
+/// Set by [request_cancellation], for every view in the process.
+static REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Ask the work shown by every view in this process to stop.
+///
+/// This only sets a flag: it's up to the application to check
+/// [View::cancellation_requested] in its work loop, and to stop. Views
+/// finished afterwards print the [Model::cancelled_message](crate::Model::cancelled_message).
+///
+/// Nutmeg doesn't install a Ctrl-C handler to call this: it's intended to be
+/// called from one installed by the application. (The handler installed by
+/// [View::install_signal_cleanup] only cleans up views when the process is
+/// about to be killed.) It doesn't lock or allocate,
+/// so it's safe to call from a signal handler.
+///
+/// ```
+/// use nutmeg::{Options, View};
+/// use nutmeg::models::LinearModel;
+///
+/// let view = View::new(LinearModel::new("Files", 1000), Options::default());
+/// for i in 0..1000 {
+///     if view.cancellation_requested() {
+///         break;
+///     }
+///     if i == 10 {
+///         // Typically from the Ctrl-C handler.
+///         nutmeg::request_cancellation();
+///     }
+///     view.update(|model| model.increment(1));
+/// }
+/// assert_eq!(view.finish().done(), 11);
+/// ```
+pub fn request_cancellation() {
+    REQUESTED.store(true, Ordering::SeqCst);
+}
+
+/// True if [request_cancellation] has been called in this process.
+pub(crate) fn is_requested() -> bool {
+    REQUESTED.load(Ordering::SeqCst)
+}
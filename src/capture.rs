@@ -0,0 +1,141 @@
+// Copyright 2023 Martin Pool.
+
+//! Structured records of output captured for tests.
+
+use std::time::{Duration, Instant};
+
+use crate::OptionsWarning;
+#[allow(unused)] // for docstrings
+use crate::{Destination, Options, View};
+
+/// One thing a view did to its destination, and when.
+///
+/// Retrieved from [View::captured_events], for views drawing to
+/// [Destination::Capture], [Destination::CaptureNamed] or
+/// [Destination::CaptureBounded], or received from [View::subscribe] for any
+/// destination.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CapturedEvent {
+    /// The time of the event, according to the fake clock if [Options::fake_clock]
+    /// is enabled, and otherwise the real clock.
+    pub time: Instant,
+    /// What happened.
+    pub kind: CapturedEventKind,
+}
+
+/// The kinds of [CapturedEvent].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum CapturedEventKind {
+    /// A progress bar frame was drawn, with this rendered text.
+    Frame(String),
+    /// The progress bar was erased.
+    Erase,
+    /// A message was printed.
+    Message(String),
+    /// The final message was printed when the view finished.
+    FinalMessage(String),
+    /// A call to [crate::Model::render] took longer than
+    /// [Options::slow_render_threshold], taking this long.
+    SlowRender(Duration),
+    /// The view's options, or the way it's used, probably don't do what was
+    /// intended. See [Options::strict].
+    OptionsWarning(OptionsWarning),
+}
+
+impl CapturedEvent {
+    /// True if this event drew a progress bar frame.
+    pub fn is_frame(&self) -> bool {
+        matches!(self.kind, CapturedEventKind::Frame(_))
+    }
+
+    /// The number of lines in the frame drawn by this event, or None if it's
+    /// not a frame.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let view = View::new(DisplayModel("one\ntwo"), Options::default().destination(Destination::Capture));
+    /// let events = view.captured_events();
+    /// view.update(|_| ());
+    /// assert_eq!(events.lock().unwrap()[0].frame_line_count(), Some(2));
+    /// ```
+    pub fn frame_line_count(&self) -> Option<usize> {
+        match &self.kind {
+            CapturedEventKind::Frame(frame) => Some(frame.split('\n').count()),
+            _ => None,
+        }
+    }
+}
+
+/// Return the shortest interval between consecutive frames in `events`, or
+/// None if there are fewer than two frames.
+///
+/// This can be used to check that drawing is rate-limited.
+///
+/// ```
+/// use std::time::{Duration, Instant};
+/// use nutmeg::{min_frame_interval, Destination, Options, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// let options = Options::default()
+///     .destination(Destination::Capture)
+///     .fake_clock(true)
+///     .update_interval(Duration::from_millis(100));
+/// let view = View::new(DisplayModel(0), options);
+/// let events = view.captured_events();
+/// let mut clock = Instant::now();
+/// for i in 0..100 {
+///     view.set_fake_clock(clock);
+///     view.update(|model| model.0 = i);
+///     clock += Duration::from_millis(10);
+/// }
+/// let events = events.lock().unwrap();
+/// assert_eq!(events.iter().filter(|e| e.is_frame()).count(), 10);
+/// assert!(min_frame_interval(&events).unwrap() >= Duration::from_millis(100));
+/// ```
+pub fn min_frame_interval(events: &[CapturedEvent]) -> Option<Duration> {
+    let frame_times: Vec<Instant> = events
+        .iter()
+        .filter(|e| e.is_frame())
+        .map(|e| e.time)
+        .collect();
+    frame_times.windows(2).map(|w| w[1] - w[0]).min()
+}
+
+/// Remove output from the start of `buf` so that it's at most `limit` bytes,
+/// without splitting a character.
+pub(crate) fn trim_output(buf: &mut String, limit: usize) {
+    if buf.len() > limit {
+        let mut start = buf.len() - limit;
+        while !buf.is_char_boundary(start) {
+            start += 1;
+        }
+        buf.drain(..start);
+    }
+}
+
+/// Remove the oldest events so that they use roughly at most `limit` bytes.
+pub(crate) fn trim_events(events: &mut Vec<CapturedEvent>, limit: usize) {
+    let mut size: usize = events.iter().map(event_size).sum();
+    let mut remove = 0;
+    while size > limit && remove < events.len() {
+        size -= event_size(&events[remove]);
+        remove += 1;
+    }
+    events.drain(..remove);
+}
+
+/// The approximate memory used by an event.
+fn event_size(event: &CapturedEvent) -> usize {
+    std::mem::size_of::<CapturedEvent>()
+        + match &event.kind {
+            CapturedEventKind::Frame(text)
+            | CapturedEventKind::Message(text)
+            | CapturedEventKind::FinalMessage(text) => text.len(),
+            CapturedEventKind::Erase
+            | CapturedEventKind::SlowRender(_)
+            | CapturedEventKind::OptionsWarning(_) => 0,
+        }
+}
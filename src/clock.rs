@@ -0,0 +1,263 @@
+// Copyright 2023 Martin Pool.
+
+//! Sources of the current time, used to decide when to repaint.
+
+use std::cell::Cell;
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::thread::{self, Thread};
+use std::time::{Duration, Instant};
+
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// A source of the current time, used by the [View] to rate-limit repainting.
+///
+/// Set by [Options::clock]. The view reads the clock on every update, so the
+/// cost of reading it can dominate the cost of [View::update] when the
+/// application sends many updates.
+pub trait Clock: fmt::Debug + Send + Sync {
+    /// Return the current time.
+    ///
+    /// Successive calls should never go backwards.
+    fn now(&self) -> Instant;
+
+    /// Return a cheap reading of the time, which may lag behind [Clock::now]
+    /// by a few milliseconds but is never ahead of it.
+    ///
+    /// Between frames, [View::update] compares this to when the next frame
+    /// is due, and if it's too soon, changes the model without reading
+    /// [Clock::now]. The next frame may be drawn as late as the lag.
+    ///
+    /// By default this is [Clock::now].
+    fn now_coarse(&self) -> Instant {
+        self.now()
+    }
+}
+
+/// The precise system clock, [Instant::now].
+///
+/// This is the default. Its [Clock::now_coarse] reads the [CoarseClock], so
+/// that updates between frames don't read the system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn now_coarse(&self) -> Instant {
+        CoarseClock.now()
+    }
+}
+
+/// A cheap clock that is only updated every [CoarseClock::RESOLUTION].
+///
+/// Reading the clock is a single atomic load. The time is kept up to date by
+/// a background thread, which is started the first time the clock is read.
+/// While the clock is being read the thread wakes every
+/// [CoarseClock::RESOLUTION]; once it hasn't been read for
+/// [CoarseClock::IDLE_TIMEOUT] the thread sleeps until the next read, which
+/// then takes the precise time, so an idle clock costs nothing.
+///
+/// This is precise enough for the default update interval, and can make
+/// [View::update] cheaper on platforms where [Instant::now] is slow.
+///
+/// ```
+/// use nutmeg::{CoarseClock, Options};
+///
+/// let options = Options::new().clock(&CoarseClock);
+/// ```
+#[derive(Debug, Clone, Copy, Default)]
+pub struct CoarseClock;
+
+impl CoarseClock {
+    /// How often the clock is updated.
+    pub const RESOLUTION: Duration = Duration::from_millis(2);
+
+    /// How long the clock can go unread before its thread stops updating it.
+    pub const IDLE_TIMEOUT: Duration = Duration::from_millis(100);
+}
+
+/// The time when the coarse clock was started, and nanoseconds since then.
+struct CoarseState {
+    base: Instant,
+    elapsed_nanos: AtomicU64,
+    /// Set by readers, and cleared by the thread each time it updates the clock.
+    read: AtomicBool,
+    /// Set by the thread when it stops updating the clock.
+    idle: AtomicBool,
+    thread: OnceLock<Thread>,
+}
+
+impl CoarseState {
+    fn update(&self) {
+        let nanos = self.base.elapsed().as_nanos() as u64;
+        // fetch_max, so that a reader waking the thread can't race it backwards.
+        self.elapsed_nanos.fetch_max(nanos, Ordering::Relaxed);
+    }
+
+    fn run(&self) {
+        let idle_ticks = CoarseClock::IDLE_TIMEOUT.as_nanos() / CoarseClock::RESOLUTION.as_nanos();
+        let mut unread = 0;
+        loop {
+            thread::sleep(CoarseClock::RESOLUTION);
+            self.update();
+            if self.read.swap(false, Ordering::Relaxed) {
+                unread = 0;
+            } else {
+                unread += 1;
+                if unread >= idle_ticks {
+                    self.idle.store(true, Ordering::SeqCst);
+                    // A reader that sees `idle` unparks the thread, which
+                    // may happen before it parks.
+                    while self.idle.load(Ordering::SeqCst) {
+                        thread::park();
+                    }
+                    unread = 0;
+                }
+            }
+        }
+    }
+}
+
+impl Clock for CoarseClock {
+    fn now(&self) -> Instant {
+        static STATE: OnceLock<&'static CoarseState> = OnceLock::new();
+        let state = STATE.get_or_init(|| {
+            let state: &'static CoarseState = Box::leak(Box::new(CoarseState {
+                base: Instant::now(),
+                elapsed_nanos: AtomicU64::new(0),
+                read: AtomicBool::new(true),
+                idle: AtomicBool::new(false),
+                thread: OnceLock::new(),
+            }));
+            let handle = thread::Builder::new()
+                .name("nutmeg-clock".to_owned())
+                .spawn(move || state.run())
+                .expect("spawn coarse clock thread");
+            let _ = state.thread.set(handle.thread().clone());
+            state
+        });
+        if !state.read.load(Ordering::Relaxed) {
+            state.read.store(true, Ordering::Relaxed);
+        }
+        if state.idle.load(Ordering::Relaxed) && state.idle.swap(false, Ordering::SeqCst) {
+            // The stored time is stale.
+            state.update();
+            if let Some(thread) = state.thread.get() {
+                thread.unpark();
+            }
+        }
+        state.base + Duration::from_nanos(state.elapsed_nanos.load(Ordering::Relaxed))
+    }
+}
+
+/// When a view next needs to consider painting, as decided while it was
+/// last locked.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PaintTime {
+    /// Any update might paint, so the view must be checked every time.
+    Unknown,
+    /// Updates won't paint until something else changes the view.
+    Never,
+    /// Updates won't paint before this time on the view's clock.
+    After(Instant),
+}
+
+/// A lock-free copy of the [PaintTime] of a view, so that updates that are
+/// too soon to paint can skip the checks made under the view's lock.
+///
+/// A view can also paint early if the terminal was resized since the
+/// schedule was stored.
+pub(crate) struct NextPaint {
+    /// 0 for [PaintTime::Unknown], `u64::MAX` for [PaintTime::Never], or
+    /// otherwise nanoseconds since [epoch].
+    nanos: AtomicU64,
+    /// The value of [crate::resize::generation] when the schedule was stored.
+    resize_generation: AtomicUsize,
+}
+
+impl NextPaint {
+    pub(crate) const fn new() -> NextPaint {
+        NextPaint {
+            nanos: AtomicU64::new(0),
+            resize_generation: AtomicUsize::new(0),
+        }
+    }
+
+    pub(crate) fn store(&self, time: PaintTime, resize_generation: usize) {
+        let nanos = match time {
+            PaintTime::Unknown => 0,
+            PaintTime::Never => u64::MAX,
+            PaintTime::After(time) => {
+                let nanos = time.saturating_duration_since(epoch()).as_nanos();
+                nanos.min(u64::MAX as u128 - 1) as u64
+            }
+        };
+        self.resize_generation
+            .store(resize_generation, Ordering::Relaxed);
+        self.nanos.store(nanos, Ordering::Relaxed);
+    }
+
+    /// True if an update now might paint, reading [Clock::now_coarse] only if
+    /// the answer depends on the time.
+    ///
+    /// `drawn` is false if the bar was erased by [crate::View::emergency_restore]
+    /// since the schedule was stored, in which case it's due to be redrawn.
+    pub(crate) fn is_due(&self, clock: &dyn Clock, drawn: bool) -> bool {
+        match self.nanos.load(Ordering::Relaxed) {
+            0 => true,
+            _ if self.resize_generation.load(Ordering::Relaxed) != crate::resize::generation() => {
+                true
+            }
+            u64::MAX => false,
+            _ if !drawn => true,
+            nanos => clock.now_coarse() >= epoch() + Duration::from_nanos(nanos),
+        }
+    }
+}
+
+/// An arbitrary early time, from which paint times are counted.
+fn epoch() -> Instant {
+    static EPOCH: OnceLock<Instant> = OnceLock::new();
+    *EPOCH.get_or_init(Instant::now)
+}
+
+thread_local! {
+    /// The time at which the view that's rendering the model on this thread
+    /// is drawing the frame.
+    static FRAME_TIME: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// The time at which the view that's currently rendering the model on this
+/// thread is drawing the frame, from its [Options::clock] or fake clock, or
+/// [Instant::now] if no view is rendering.
+///
+/// Models that animate with the passage of time, such as
+/// [SpinnerModel](crate::models::SpinnerModel), can use this rather than
+/// reading the clock themselves, so that they follow the view's clock.
+pub fn frame_time() -> Instant {
+    FRAME_TIME.with(Cell::get).unwrap_or_else(Instant::now)
+}
+
+/// Makes a frame time current on this thread, until the scope is dropped.
+pub(crate) struct FrameTimeScope {
+    previous: Option<Instant>,
+}
+
+impl FrameTimeScope {
+    pub(crate) fn enter(time: Instant) -> FrameTimeScope {
+        FrameTimeScope {
+            previous: FRAME_TIME.with(|c| c.replace(Some(time))),
+        }
+    }
+}
+
+impl Drop for FrameTimeScope {
+    fn drop(&mut self) {
+        FRAME_TIME.with(|c| c.set(self.previous));
+    }
+}
@@ -0,0 +1,205 @@
+// Copyright 2023 Martin Pool.
+
+//! Counters that many threads can increment without locking the view.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::Arc;
+
+#[allow(unused)] // for docstrings
+use crate::View;
+
+/// How a [Counter] stores its count between paints.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum CounterMode {
+    /// One atomic cell shared by every thread.
+    ///
+    /// This is the smallest, and is cheap when only a few threads increment
+    /// the counter.
+    #[default]
+    Single,
+
+    /// One cell for each thread, up to the number of CPUs, each on its own
+    /// cache line.
+    ///
+    /// Threads incrementing the counter don't contend with each other, so
+    /// this suits many threads incrementing it very often. The cells are
+    /// summed when the view paints.
+    Sharded,
+}
+
+/// The index of the shard used by each thread, assigned in turn to threads
+/// as they first increment a sharded counter.
+static NEXT_SHARD: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static SHARD: usize = NEXT_SHARD.fetch_add(1, Ordering::Relaxed);
+}
+
+/// One cell of a counter, aligned so that no two cells share a cache line.
+#[derive(Debug, Default)]
+#[repr(align(128))]
+struct Cell(AtomicU64);
+
+/// The cells of a counter, shared between its handles and the view.
+#[derive(Debug)]
+pub(crate) struct Cells {
+    mode: CounterMode,
+    cells: Box<[Cell]>,
+}
+
+impl Cells {
+    pub(crate) fn new(mode: CounterMode) -> Cells {
+        let len = match mode {
+            CounterMode::Single => 1,
+            CounterMode::Sharded => std::thread::available_parallelism()
+                .map_or(1, usize::from)
+                .next_power_of_two(),
+        };
+        Cells {
+            mode,
+            cells: (0..len).map(|_| Cell::default()).collect(),
+        }
+    }
+
+    fn add(&self, n: u64) {
+        let i = if self.cells.len() == 1 {
+            0
+        } else {
+            SHARD.with(|shard| *shard) & (self.cells.len() - 1)
+        };
+        self.cells[i].0.fetch_add(n, Ordering::Relaxed);
+    }
+
+    fn peek(&self) -> u64 {
+        self.cells
+            .iter()
+            .map(|cell| cell.0.load(Ordering::Relaxed))
+            .fold(0, u64::wrapping_add)
+    }
+
+    /// Return the count so far, and reset it to zero.
+    pub(crate) fn take(&self) -> u64 {
+        self.cells
+            .iter()
+            .map(|cell| cell.0.swap(0, Ordering::Relaxed))
+            .fold(0, u64::wrapping_add)
+    }
+}
+
+/// A handle that increments a count which is added into the model when the
+/// view next paints, returned by [View::counter].
+///
+/// Incrementing a counter doesn't lock the view, so threads doing many small
+/// pieces of work can count each one without waiting for each other. The
+/// count is folded into the model by the function passed to [View::counter]
+/// before the model is rendered, inspected, or finished.
+///
+/// Incrementing the counter doesn't cause the view to paint, so something
+/// else must: for example [View::with_ticker], or other calls to
+/// [View::update].
+///
+/// Counters can be cloned, and the clones share the same count. A thread
+/// counting very many small pieces of work can batch its counts in a
+/// [LocalCounter] from [Counter::local].
+#[derive(Debug, Clone)]
+pub struct Counter {
+    cells: Arc<Cells>,
+}
+
+impl Counter {
+    pub(crate) fn new(cells: Arc<Cells>) -> Counter {
+        Counter { cells }
+    }
+
+    /// Add `n` to the count.
+    pub fn increment(&self, n: u64) {
+        self.cells.add(n)
+    }
+
+    /// The count that hasn't yet been added into the model.
+    pub fn pending(&self) -> u64 {
+        self.cells.peek()
+    }
+
+    /// How this counter stores its count.
+    pub fn mode(&self) -> CounterMode {
+        self.cells.mode
+    }
+
+    /// Make a [LocalCounter] that counts on one thread without touching the
+    /// shared count on every increment.
+    pub fn local(&self) -> LocalCounter {
+        LocalCounter {
+            counter: self.clone(),
+            count: 0,
+            increments: 0,
+        }
+    }
+}
+
+/// A batch of counts kept by one thread, and added to its [Counter] every
+/// [LocalCounter::FLUSH_INTERVAL] increments and when it's dropped.
+///
+/// Incrementing a local counter is plain arithmetic, with no atomic
+/// operations, so it suits the tightest loops. Counts that haven't been
+/// flushed aren't seen by [Counter::pending] or drawn by the view.
+///
+/// Local counters fit pools of worker threads that start each piece of work
+/// with some per-task state, such as rayon's `for_each_init`:
+///
+/// ```
+/// use nutmeg::models::LinearModel;
+/// use nutmeg::{CounterMode, Options, View};
+///
+/// let view = View::new(LinearModel::new("Hashing", 4000), Options::default());
+/// let counter = view.counter(CounterMode::Sharded, |model, n| model.increment(n as usize));
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             let mut local = counter.local();
+///             for _ in 0..1000 {
+///                 local.increment(1);
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(view.inspect_model(|model| model.done()), 4000);
+/// ```
+#[derive(Debug)]
+pub struct LocalCounter {
+    counter: Counter,
+    /// The count not yet added to `counter`.
+    count: u64,
+    /// The number of increments since the last flush.
+    increments: u32,
+}
+
+impl LocalCounter {
+    /// How many calls to [LocalCounter::increment] are batched before the
+    /// count is added to the shared [Counter].
+    pub const FLUSH_INTERVAL: u32 = 256;
+
+    /// Add `n` to the count.
+    pub fn increment(&mut self, n: u64) {
+        self.count = self.count.wrapping_add(n);
+        self.increments += 1;
+        if self.increments >= LocalCounter::FLUSH_INTERVAL {
+            self.flush();
+        }
+    }
+
+    /// Add the count so far to the shared [Counter].
+    pub fn flush(&mut self) {
+        if self.count != 0 {
+            self.counter.increment(self.count);
+            self.count = 0;
+        }
+        self.increments = 0;
+    }
+}
+
+impl Drop for LocalCounter {
+    fn drop(&mut self) {
+        self.flush()
+    }
+}
@@ -1,14 +1,25 @@
 // Copyright 2022-2023 Martin Pool.
 
+use std::cell::Cell;
+#[cfg(feature = "capture")]
+use std::collections::BTreeMap;
 use std::env;
+use std::ffi::OsStr;
+use std::fmt;
+use std::io::{self, Write};
 use std::result::Result;
+#[cfg(feature = "capture")]
+use std::sync::Arc;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
 
+use crate::to_print::{WriteToPrint, WriteToStderr};
 #[allow(unused)] // for docstrings
 use crate::View;
 use crate::{ansi, width};
 
 /// Destinations for progress bar output.
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, Copy)]
 pub enum Destination {
     /// Draw to stdout.
     Stdout,
@@ -16,23 +27,391 @@ pub enum Destination {
     Stderr,
     /// Draw to an internal capture buffer, which can be retrieved with [View::captured_output].
     ///
-    /// This is intended for testing.
+    /// This is intended for testing, and is only available with the `capture`
+    /// feature.
     ///
-    /// A width of 80 columns is used.
+    /// A width of 80 columns and a height of 24 lines is used.
+    #[cfg(feature = "capture")]
     Capture,
+    /// Draw to a capture buffer shared by all views with the same name.
+    ///
+    /// The buffer can be retrieved by name, from anywhere in the process, with
+    /// [Destination::captured_output_named]. This lets integration tests that run
+    /// several components, each drawing its own progress, check the output of each
+    /// component separately.
+    ///
+    /// Names can be structured hierarchically with `/`, for example
+    /// `"server/worker-1"`, and [Destination::capture_names] can be used to
+    /// find all those under a prefix.
+    ///
+    /// Typically constructed with [Destination::capture_named].
+    ///
+    /// A width of 80 columns is used.
+    #[cfg(feature = "capture")]
+    CaptureNamed(&'static str),
+    /// Draw to an internal capture buffer, like [Destination::Capture], but
+    /// keep only the most recent output, up to this many bytes.
+    ///
+    /// Older output is discarded from the start of the buffer as new output is
+    /// written, so the buffer may begin partway through a line or an escape
+    /// sequence. [View::captured_events] are similarly limited to roughly this
+    /// many bytes of the most recent events.
+    ///
+    /// This suits long-running tests and recorders that only need to look at
+    /// what was drawn most recently.
+    ///
+    /// Typically constructed with [Destination::capture_bounded].
+    #[cfg(feature = "capture")]
+    CaptureBounded(usize),
+    /// Draw to an application-defined destination.
+    ///
+    /// This allows drawing to, for example, a pty, a terminal widget in a GUI,
+    /// or a network connection.
+    ///
+    /// The destination is a static reference so that [crate::Options] can remain
+    /// `Copy` and `const`. Destinations created at runtime can be leaked with
+    /// `Box::leak`.
+    Custom(&'static dyn DestinationTrait),
+}
+
+impl PartialEq for Destination {
+    fn eq(&self, other: &Destination) -> bool {
+        match (self, other) {
+            (Destination::Stdout, Destination::Stdout)
+            | (Destination::Stderr, Destination::Stderr) => true,
+            #[cfg(feature = "capture")]
+            (Destination::Capture, Destination::Capture) => true,
+            #[cfg(feature = "capture")]
+            (Destination::CaptureNamed(a), Destination::CaptureNamed(b)) => a == b,
+            #[cfg(feature = "capture")]
+            (Destination::CaptureBounded(a), Destination::CaptureBounded(b)) => a == b,
+            (Destination::Custom(a), Destination::Custom(b)) => std::ptr::addr_eq(*a, *b),
+            _ => false,
+        }
+    }
+}
+
+impl Eq for Destination {}
+
+/// An application-defined destination for progress bars and messages, used
+/// through [Destination::Custom].
+///
+/// Methods take `&self`, since the destination may be shared by several views,
+/// so implementations will typically use interior mutability.
+///
+/// # Example
+///
+/// ```
+/// use std::io;
+/// use std::sync::Mutex;
+/// use nutmeg::{Destination, DestinationTrait, Options, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// #[derive(Debug)]
+/// struct Log(Mutex<Vec<String>>);
+///
+/// impl DestinationTrait for Log {
+///     fn init(&self) -> bool {
+///         true
+///     }
+///     fn width(&self) -> Option<usize> {
+///         Some(40)
+///     }
+///     fn write(&self, buf: &str) -> io::Result<()> {
+///         self.0.lock().unwrap().push(buf.to_owned());
+///         Ok(())
+///     }
+///     fn flush(&self) -> io::Result<()> {
+///         Ok(())
+///     }
+/// }
+///
+/// static LOG: Log = Log(Mutex::new(Vec::new()));
+///
+/// let view = View::new(DisplayModel("hi"), Options::new().destination(Destination::Custom(&LOG)));
+/// view.message("hello\n");
+/// drop(view);
+/// assert_eq!(LOG.0.lock().unwrap().as_slice(), ["hello\n"]);
+/// ```
+pub trait DestinationTrait: fmt::Debug + Send + Sync + 'static {
+    /// Prepare the destination, and return true if progress bars can be drawn on it.
+    ///
+    /// This is called once, before the first output from a view. If it returns false,
+    /// messages are still written but progress bars are not drawn.
+    fn init(&self) -> bool;
+
+    /// Return the width of the destination in columns, or None if it's unknown,
+    /// in which case progress bars are not drawn.
+    fn width(&self) -> Option<usize>;
+
+    /// Return the height of the destination in lines, or None if it's unknown.
+    ///
+    /// Models can find the height through [current_height] to decide how much
+    /// detail to show. By default, this returns None.
+    fn height(&self) -> Option<usize> {
+        None
+    }
+
+    /// Return true if the destination understands synchronized updates
+    /// (mode 2026), so that each frame can be shown all at once without
+    /// flicker.
+    ///
+    /// This is called once, after [DestinationTrait::init]. By default, this
+    /// returns false.
+    fn synchronized_output(&self) -> bool {
+        false
+    }
+
+    /// Write text, which may contain ANSI escape sequences.
+    fn write(&self, buf: &str) -> io::Result<()>;
+
+    /// Flush any buffered output.
+    fn flush(&self) -> io::Result<()>;
+}
+
+/// A destination that draws to any [Write] implementation, such as a file, a
+/// pty, or a `Box<dyn Write + Send>`, with a width given by the application.
+///
+/// Since the writer is owned by the destination, this is used with
+/// [View::with_destination], or [View::write_to], rather than through
+/// [Destination].
+///
+/// Progress bars are always drawn, as if the writer were a terminal, so this
+/// suits golden-file tests of the exact output, as well as terminals that
+/// nutmeg can't otherwise find.
+///
+/// ```
+/// use std::io::Write;
+/// use nutmeg::{Options, View, WriterDestination};
+/// use nutmeg::models::DisplayModel;
+///
+/// let writer: Box<dyn Write + Send> = Box::new(std::io::sink());
+/// let destination = WriterDestination::new(writer, 60).with_height(20);
+/// let view = View::with_destination(DisplayModel("working"), Options::new(), destination);
+/// view.update(|_| ());
+/// ```
+pub struct WriterDestination<W: Write + Send> {
+    writer: Mutex<W>,
+    width: usize,
+    height: Option<usize>,
+}
+
+impl<W: Write + Send> WriterDestination<W> {
+    /// Construct a destination writing to `writer`, which is `width` columns
+    /// wide, and of unknown height.
+    pub fn new(writer: W, width: usize) -> WriterDestination<W> {
+        WriterDestination {
+            writer: Mutex::new(writer),
+            width,
+            height: None,
+        }
+    }
+
+    /// Set the height of the destination, in lines.
+    pub fn with_height(self, height: usize) -> WriterDestination<W> {
+        WriterDestination {
+            height: Some(height),
+            ..self
+        }
+    }
+
+    /// Return the writer.
+    pub fn into_inner(self) -> W {
+        self.writer
+            .into_inner()
+            .expect("WriterDestination mutex is not poisoned")
+    }
+}
+
+impl<W: Write + Send> fmt::Debug for WriterDestination<W> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WriterDestination")
+            .field("width", &self.width)
+            .field("height", &self.height)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<W: Write + Send + 'static> DestinationTrait for WriterDestination<W> {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(self.width)
+    }
+
+    fn height(&self) -> Option<usize> {
+        self.height
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.writer
+            .lock()
+            .expect("lock WriterDestination")
+            .write_all(buf.as_bytes())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        self.writer.lock().expect("lock WriterDestination").flush()
+    }
+}
+
+/// Buffers for [Destination::CaptureNamed], by name.
+#[cfg(feature = "capture")]
+static NAMED_CAPTURES: Mutex<BTreeMap<&'static str, Arc<Mutex<String>>>> =
+    Mutex::new(BTreeMap::new());
+
+impl DestinationTrait for Destination {
+    fn init(&self) -> bool {
+        self.initalize().is_ok()
+    }
+
+    fn width(&self) -> Option<usize> {
+        self.builtin_width()
+    }
+
+    fn height(&self) -> Option<usize> {
+        match self {
+            Destination::Stdout => width::stdout_height(),
+            Destination::Stderr => width::stderr_height(),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => Some(24),
+            Destination::Custom(d) => d.height(),
+        }
+    }
+
+    fn synchronized_output(&self) -> bool {
+        match self {
+            Destination::Stdout | Destination::Stderr => terminal_supports_synchronized_output(),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => false,
+            Destination::Custom(d) => d.synchronized_output(),
+        }
+    }
+
+    /// Write to the destination.
+    ///
+    /// [Destination::Capture] is written by the [View] that owns the buffer, so
+    /// writing to it here returns an error.
+    fn write(&self, buf: &str) -> io::Result<()> {
+        match self {
+            Destination::Stdout => WriteToPrint.write_all(buf.as_bytes()),
+            Destination::Stderr => WriteToStderr.write_all(buf.as_bytes()),
+            Destination::Custom(d) => d.write(buf),
+            #[cfg(feature = "capture")]
+            Destination::CaptureNamed(name) => {
+                Destination::captured_output_named(name)
+                    .lock()
+                    .expect("lock named capture buffer")
+                    .push_str(buf);
+                Ok(())
+            }
+            #[cfg(feature = "capture")]
+            Destination::Capture | Destination::CaptureBounded(_) => Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "capture destinations are written through their View",
+            )),
+        }
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        match self {
+            Destination::Stdout => WriteToPrint.flush(),
+            Destination::Stderr => WriteToStderr.flush(),
+            Destination::Custom(d) => d.flush(),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => Ok(()),
+        }
+    }
 }
 
 impl Destination {
+    /// Construct a destination that captures output into a buffer shared
+    /// by all views with this name.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let options = Options::new().destination(Destination::capture_named("doc/worker-1"));
+    /// let view = View::new(DisplayModel("working"), options);
+    /// view.message("hello\n");
+    /// drop(view);
+    /// let output = Destination::captured_output_named("doc/worker-1");
+    /// assert_eq!(output.lock().unwrap().as_str(), "hello\n");
+    /// ```
+    #[cfg(feature = "capture")]
+    pub const fn capture_named(name: &'static str) -> Destination {
+        Destination::CaptureNamed(name)
+    }
+
+    /// Construct a destination that captures output into a buffer keeping
+    /// only the most recent `bytes` of output.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let options = Options::new().destination(Destination::capture_bounded(8));
+    /// let view = View::new(DisplayModel("working"), options);
+    /// let output = view.captured_output();
+    /// view.message("first\n");
+    /// view.message("second\n");
+    /// drop(view);
+    /// assert_eq!(output.lock().unwrap().as_str(), "\nsecond\n");
+    /// ```
+    #[cfg(feature = "capture")]
+    pub const fn capture_bounded(bytes: usize) -> Destination {
+        Destination::CaptureBounded(bytes)
+    }
+
+    /// Return the buffer of output captured by views using
+    /// [Destination::CaptureNamed] with this name.
+    ///
+    /// If no view has yet used this name, a new empty buffer is created, which
+    /// will receive output from views created later.
+    #[cfg(feature = "capture")]
+    pub fn captured_output_named(name: &'static str) -> Arc<Mutex<String>> {
+        NAMED_CAPTURES
+            .lock()
+            .expect("lock named captures")
+            .entry(name)
+            .or_default()
+            .clone()
+    }
+
+    /// Return the names of all named capture buffers starting with `prefix`, in
+    /// sorted order.
+    ///
+    /// An empty prefix returns all names.
+    #[cfg(feature = "capture")]
+    pub fn capture_names(prefix: &str) -> Vec<&'static str> {
+        NAMED_CAPTURES
+            .lock()
+            .expect("lock named captures")
+            .keys()
+            .filter(|name| name.starts_with(prefix))
+            .copied()
+            .collect()
+    }
+
     /// Determine if this destination is possible, and, if necessary, enable Windows ANSI support.
     pub(crate) fn initalize(&self) -> Result<(), ()> {
         if match self {
-            Destination::Stdout => {
-                atty::is(atty::Stream::Stdout) && !is_dumb_term() && ansi::enable_windows_ansi()
-            }
-            Destination::Stderr => {
-                atty::is(atty::Stream::Stderr) && !is_dumb_term() && ansi::enable_windows_ansi()
-            }
-            Destination::Capture => true,
+            Destination::Stdout => is_tty(1) && !is_dumb_term() && ansi::enable_windows_ansi(),
+            Destination::Stderr => is_tty(2) && !is_dumb_term() && ansi::enable_windows_ansi(),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => true,
+            Destination::Custom(d) => d.init(),
         } {
             Ok(())
         } else {
@@ -40,15 +419,204 @@ impl Destination {
         }
     }
 
-    pub(crate) fn width(&self) -> Option<usize> {
+    /// The file descriptor for this destination, if it has one.
+    pub(crate) const fn fd(&self) -> Option<i32> {
+        match self {
+            Destination::Stdout => Some(1),
+            Destination::Stderr => Some(2),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => None,
+            Destination::Custom(_) => None,
+        }
+    }
+
+    fn builtin_width(&self) -> Option<usize> {
         match self {
             Destination::Stdout => width::stdout_width(),
             Destination::Stderr => width::stderr_width(),
-            Destination::Capture => Some(80),
+            #[cfg(feature = "capture")]
+            Destination::Capture
+            | Destination::CaptureNamed(_)
+            | Destination::CaptureBounded(_) => Some(80),
+            Destination::Custom(d) => d.width(),
         }
     }
 }
 
+/// Guess from the environment whether the terminal understands synchronized
+/// updates (mode 2026).
+///
+/// Asking the terminal would mean reading its reply from stdin, so instead
+/// this recognizes terminals known to support it. The environment is read
+/// once, and the result is remembered for the rest of the process.
+fn terminal_supports_synchronized_output() -> bool {
+    static SUPPORTED: OnceLock<bool> = OnceLock::new();
+    *SUPPORTED.get_or_init(|| {
+        let term_program = env::var("TERM_PROGRAM").unwrap_or_default();
+        let term = env::var("TERM").unwrap_or_default();
+        ["WezTerm", "ghostty", "contour", "iTerm.app"].contains(&term_program.as_str())
+            || ["kitty", "foot", "alacritty", "contour"]
+                .iter()
+                .any(|name| term.contains(name))
+    })
+}
+
+/// True if stdout (1) or stderr (2) is a terminal.
+#[cfg(feature = "ansi-terminal")]
+fn is_tty(fd: i32) -> bool {
+    atty::is(if fd == 1 {
+        atty::Stream::Stdout
+    } else {
+        atty::Stream::Stderr
+    })
+}
+
+/// Without the `ansi-terminal` feature, stdout and stderr are never treated
+/// as terminals, so progress bars are only drawn to other destinations.
+#[cfg(not(feature = "ansi-terminal"))]
+fn is_tty(_fd: i32) -> bool {
+    false
+}
+
+/// True unless the test harness was asked not to capture output, by
+/// `--nocapture` on the command line or `RUST_TEST_NOCAPTURE` in the
+/// environment.
+///
+/// This is only meaningful in a test binary: see [Options::in_captured_test](crate::Options::in_captured_test).
+/// There's no stable way to ask whether output is really captured, so only
+/// the harness's documented switches are checked.
+pub(crate) fn is_test_output_captured() -> bool {
+    static CAPTURED: OnceLock<bool> = OnceLock::new();
+    *CAPTURED.get_or_init(|| {
+        env::var_os("RUST_TEST_NOCAPTURE").is_none()
+            && !env::args().any(|a| a == "--nocapture" || a == "--no-capture")
+    })
+}
+
+/// Where the program's terminal is, as far as can be told from the
+/// environment, used to choose a default [Options::update_interval](crate::Options::update_interval).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TerminalContext {
+    /// A terminal on the local machine.
+    Local,
+    /// A terminal connected over SSH, detected by `SSH_CONNECTION`, `SSH_CLIENT`,
+    /// or `SSH_TTY` being set, where every frame crosses the network.
+    Remote,
+    /// A terminal connected over mosh, detected by a variable named `LC_MOSH`
+    /// or starting with `MOSH_`. Mosh sends only the latest state of the
+    /// screen, at a rate suited to the link, so frames drawn more often are
+    /// wasted.
+    Mosh,
+    /// A continuous integration job, detected by `CI` being set, whose output is
+    /// typically logged rather than watched.
+    Ci,
+}
+
+impl TerminalContext {
+    /// Detect the context from the environment.
+    ///
+    /// The environment is read once, and the result is remembered for the rest
+    /// of the process.
+    pub fn detect() -> TerminalContext {
+        static DETECTED: OnceLock<TerminalContext> = OnceLock::new();
+        *DETECTED.get_or_init(|| TerminalContext::from_vars(env::vars_os()))
+    }
+
+    /// Detect the context from a set of environment variables, given as
+    /// names and values.
+    ///
+    /// CI takes precedence over mosh, and mosh over SSH, since mosh sessions
+    /// are started over SSH.
+    ///
+    /// ```
+    /// use nutmeg::TerminalContext;
+    ///
+    /// assert_eq!(
+    ///     TerminalContext::from_vars([("SSH_TTY", "/dev/pts/1")]),
+    ///     TerminalContext::Remote
+    /// );
+    /// assert_eq!(TerminalContext::from_vars([("HOME", "/")]), TerminalContext::Local);
+    /// ```
+    pub fn from_vars<I, K, V>(vars: I) -> TerminalContext
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: AsRef<OsStr>,
+        V: AsRef<OsStr>,
+    {
+        let (mut ci, mut mosh, mut ssh) = (false, false, false);
+        for (name, value) in vars {
+            let Some(name) = name.as_ref().to_str() else {
+                continue;
+            };
+            match name {
+                "CI" => ci |= !value.as_ref().is_empty(),
+                "LC_MOSH" => mosh = true,
+                "SSH_CONNECTION" | "SSH_CLIENT" | "SSH_TTY" => ssh = true,
+                _ => mosh |= name.starts_with("MOSH_"),
+            }
+        }
+        if ci {
+            TerminalContext::Ci
+        } else if mosh {
+            TerminalContext::Mosh
+        } else if ssh {
+            TerminalContext::Remote
+        } else {
+            TerminalContext::Local
+        }
+    }
+
+    /// The update interval used by default in this context: 100ms locally,
+    /// 250ms over SSH, 500ms over mosh, and 1s in CI.
+    pub const fn default_update_interval(self) -> Duration {
+        match self {
+            TerminalContext::Local => Duration::from_millis(100),
+            TerminalContext::Remote => Duration::from_millis(250),
+            TerminalContext::Mosh => Duration::from_millis(500),
+            TerminalContext::Ci => Duration::from_secs(1),
+        }
+    }
+}
+
+thread_local! {
+    /// The height of the destination of the view that's rendering the model
+    /// on this thread.
+    static CURRENT_HEIGHT: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// The height in lines of the destination of the view that's currently
+/// rendering the model on this thread, or None if it's unknown or no view
+/// is rendering.
+///
+/// This lets models with many lines of detail, such as
+/// [Group](crate::models::Group), show less when the terminal is short.
+pub fn current_height() -> Option<usize> {
+    CURRENT_HEIGHT.with(Cell::get)
+}
+
+/// Makes a destination height current on this thread, until the scope is
+/// dropped.
+pub(crate) struct HeightScope {
+    previous: Option<usize>,
+}
+
+impl HeightScope {
+    pub(crate) fn enter(height: Option<usize>) -> HeightScope {
+        HeightScope {
+            previous: CURRENT_HEIGHT.with(|c| c.replace(height)),
+        }
+    }
+}
+
+impl Drop for HeightScope {
+    fn drop(&mut self) {
+        CURRENT_HEIGHT.with(|c| c.set(self.previous));
+    }
+}
+
 fn is_dumb_term() -> bool {
-    env::var("TERM").map_or(false, |s| s.eq_ignore_ascii_case("dumb"))
+    env::var("TERM").is_ok_and(|s| s.eq_ignore_ascii_case("dumb"))
 }
@@ -0,0 +1,81 @@
+// Copyright 2023 Martin Pool.
+
+//! Restore the terminal from contexts where it's not safe to lock or allocate,
+//! such as signal handlers.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::ansi;
+use crate::terminal::{self, Guard};
+
+/// A lock-free record of what's drawn on the terminal, kept up to date by
+/// the View whenever it releases its lock.
+pub(crate) struct DrawnState {
+    /// Zero if no progress bar is drawn; otherwise one more than the number of
+    /// lines the cursor is below the top of the progress bar.
+    lines: AtomicUsize,
+}
+
+impl DrawnState {
+    pub(crate) const fn new() -> DrawnState {
+        DrawnState {
+            lines: AtomicUsize::new(0),
+        }
+    }
+
+    /// Record that a bar is drawn with the cursor `cursor_y` lines below its top.
+    pub(crate) fn set_drawn(&self, cursor_y: usize) {
+        self.lines.store(cursor_y + 1, Ordering::SeqCst);
+    }
+
+    /// True if a bar is recorded as drawn.
+    pub(crate) fn is_drawn(&self) -> bool {
+        self.lines.load(Ordering::SeqCst) != 0
+    }
+
+    /// Record that nothing is drawn.
+    pub(crate) fn set_clear(&self) {
+        self.lines.store(0, Ordering::SeqCst);
+    }
+
+    /// Record the same state as `other`.
+    pub(crate) fn copy_from(&self, other: &DrawnState) {
+        self.lines
+            .store(other.lines.load(Ordering::SeqCst), Ordering::SeqCst);
+    }
+
+    /// Erase the progress bar, if drawn, and restore the modes recorded in `guard`.
+    ///
+    /// This does not lock or allocate, and is safe to call from a signal handler
+    /// on Unix.
+    pub(crate) fn restore(&self, guard: &Guard) {
+        let lines = self.lines.swap(0, Ordering::SeqCst);
+        let mut buf = [0u8; 96];
+        let mut len = 0;
+        let mut push = |b: &[u8]| {
+            buf[len..len + b.len()].copy_from_slice(b);
+            len += b.len();
+        };
+        if lines > 1 {
+            push(b"\x1b[");
+            let mut digits = [0u8; 20];
+            let mut n = lines - 1;
+            let mut i = digits.len();
+            while n > 0 {
+                i -= 1;
+                digits[i] = b'0' + (n % 10) as u8;
+                n /= 10;
+            }
+            push(&digits[i..]);
+            push(b"A");
+        }
+        if lines > 0 {
+            // Cursor up and carriage return, rather than the more concise
+            // sequences, since they're understood by every dialect.
+            push(b"\r");
+            push(ansi::CLEAR_TO_END_OF_SCREEN.as_bytes());
+        }
+        guard.take_restore_sequences(|s| push(s.as_bytes()));
+        terminal::write_raw(guard.fd(), &buf[..len]);
+    }
+}
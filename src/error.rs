@@ -0,0 +1,70 @@
+// Copyright 2023 Martin Pool.
+
+//! Errors from fallible view methods.
+
+use std::fmt;
+use std::io;
+
+use crate::OptionsWarning;
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// An error from a method such as [View::try_update] or [View::try_message].
+///
+/// The methods without `try_` panic on these errors instead.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum Error {
+    /// Writing to the destination failed.
+    Io(io::Error),
+
+    /// A thread panicked while it was using the view, so the view may be in
+    /// an inconsistent state and can't be used.
+    Poisoned,
+
+    /// The view's options have these problems, and [Options::strict] is set.
+    InvalidOptions(Vec<OptionsWarning>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Io(err) => write!(f, "failed to write progress: {err}"),
+            Error::Poisoned => f.write_str("progress view is poisoned by an earlier panic"),
+            Error::InvalidOptions(warnings) => {
+                f.write_str("invalid progress options: ")?;
+                for (i, warning) in warnings.iter().enumerate() {
+                    if i > 0 {
+                        f.write_str("; ")?;
+                    }
+                    write!(f, "{warning}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Io(err) => Some(err),
+            Error::Poisoned | Error::InvalidOptions(_) => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(err: io::Error) -> Error {
+        Error::Io(err)
+    }
+}
+
+impl From<Error> for io::Error {
+    fn from(err: Error) -> io::Error {
+        match err {
+            Error::Io(err) => err,
+            Error::Poisoned | Error::InvalidOptions(_) => io::Error::other(err),
+        }
+    }
+}
@@ -0,0 +1,263 @@
+// Copyright 2023 Martin Pool.
+
+//! Filters that transform or drop messages before they're printed.
+
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::ansi;
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// The importance of a message, for filtering by [MinLevel].
+///
+/// Messages printed by [View::message] have no level, and messages from
+/// [View::message_at] have the given level.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MessageLevel {
+    /// Very detailed tracing of the program's progress.
+    Trace,
+    /// Information useful when debugging.
+    Debug,
+    /// Normal progress.
+    Info,
+    /// A possible problem.
+    Warn,
+    /// A failure.
+    Error,
+}
+
+/// What a [MessageFilter] knows about the message it's given, besides the
+/// text.
+#[derive(Debug, Clone)]
+pub struct MessageContext {
+    pub(crate) level: Option<MessageLevel>,
+    pub(crate) line_start: bool,
+    pub(crate) is_terminal: bool,
+}
+
+impl MessageContext {
+    /// The level of the message, if it was printed with [View::message_at].
+    pub fn level(&self) -> Option<MessageLevel> {
+        self.level
+    }
+
+    /// True if the message starts at the beginning of a line, rather than
+    /// continuing a line that an earlier message left incomplete.
+    pub fn line_start(&self) -> bool {
+        self.line_start
+    }
+
+    /// True if the destination is a terminal.
+    pub fn is_terminal(&self) -> bool {
+        self.is_terminal
+    }
+}
+
+/// Transforms or drops messages printed by the view.
+///
+/// Filters are set by [Options::message_filters], and each message, including
+/// the final message, passes through them in order. A filter returns the
+/// text to pass to the next filter, or `None` to drop the message. Messages
+/// written through [std::io::Write] may be partial lines, so filters that act
+/// on lines should check [MessageContext::line_start].
+///
+/// After the filters, escape sequences are removed if the destination isn't
+/// a terminal, as by [StripEscapes], unless turned off by
+/// [Options::strip_escapes].
+///
+/// ```
+/// use nutmeg::{Destination, MessageContext, MessageFilter, Options, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// /// Print messages in upper case.
+/// #[derive(Debug)]
+/// struct Shout;
+///
+/// impl MessageFilter for Shout {
+///     fn filter(&self, message: String, _context: &MessageContext) -> Option<String> {
+///         Some(message.to_uppercase())
+///     }
+/// }
+///
+/// let options = Options::new()
+///     .destination(Destination::Capture)
+///     .message_filters(&[&Shout]);
+/// let view = View::new(DisplayModel("working"), options);
+/// let output = view.captured_output();
+/// view.message("hello\n");
+/// assert_eq!(*output.lock().unwrap(), "HELLO\n");
+/// ```
+pub trait MessageFilter: fmt::Debug + Send + Sync {
+    /// Return the message to print, or `None` to drop it.
+    fn filter(&self, message: String, context: &MessageContext) -> Option<String>;
+}
+
+/// Pass a message through a chain of filters, stopping if one drops it.
+pub(crate) fn apply_filters(
+    filters: &[&dyn MessageFilter],
+    message: String,
+    context: &MessageContext,
+) -> Option<String> {
+    filters
+        .iter()
+        .try_fold(message, |message, filter| filter.filter(message, context))
+        .filter(|message| !message.is_empty())
+}
+
+/// Insert `prefix` at the start of every line in `message`, including the
+/// first line only if the message starts at the beginning of a line.
+pub(crate) fn prefix_lines(message: &str, prefix: &str, line_start: bool) -> String {
+    let mut out = String::with_capacity(message.len() + prefix.len());
+    for (i, line) in message.split_inclusive('\n').enumerate() {
+        if i > 0 || line_start {
+            out.push_str(prefix);
+        }
+        out.push_str(line);
+    }
+    out
+}
+
+/// Put a fixed prefix at the start of every line of a message.
+///
+/// ```
+/// use nutmeg::{Destination, Options, Prefix, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// let options = Options::new()
+///     .destination(Destination::Capture)
+///     .message_filters(&[&Prefix("[build] ")]);
+/// let view = View::new(DisplayModel("working"), options);
+/// let output = view.captured_output();
+/// view.message("one\ntwo\n");
+/// assert_eq!(*output.lock().unwrap(), "[build] one\n[build] two\n");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Prefix(pub &'static str);
+
+impl MessageFilter for Prefix {
+    fn filter(&self, message: String, context: &MessageContext) -> Option<String> {
+        Some(prefix_lines(&message, self.0, context.line_start))
+    }
+}
+
+/// Put the local time of day, like `14:05 `, at the start of every line of a
+/// message.
+#[cfg(feature = "helpers")]
+#[derive(Debug, Clone, Copy)]
+pub struct Timestamp;
+
+#[cfg(feature = "helpers")]
+impl MessageFilter for Timestamp {
+    fn filter(&self, message: String, context: &MessageContext) -> Option<String> {
+        let (now, _) = crate::local_time::local_time(std::time::SystemTime::now());
+        let stamp = format!("{:02}:{:02} ", now.hour, now.minute);
+        Some(prefix_lines(&message, &stamp, context.line_start))
+    }
+}
+
+/// Remove escape sequences, such as colors, from messages written to a
+/// destination that isn't a terminal.
+///
+/// This is applied after all the other filters unless
+/// [Options::strip_escapes] is turned off, so it's only needed in the filter
+/// list to strip escapes before a later filter sees the message.
+#[derive(Debug, Clone, Copy)]
+pub struct StripEscapes;
+
+impl MessageFilter for StripEscapes {
+    fn filter(&self, message: String, context: &MessageContext) -> Option<String> {
+        if context.is_terminal {
+            Some(message)
+        } else {
+            Some(ansi::strip_sequences(&message))
+        }
+    }
+}
+
+/// Drop a message that's the same as the one before it.
+///
+/// This keeps a loop that repeatedly reports the same condition from
+/// filling the screen. `Coalesce::new` is `const`, so the filter can be a
+/// static value and be used in [Options::message_filters].
+///
+/// ```
+/// use nutmeg::{Coalesce, Destination, MessageFilter, Options, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// static COALESCE: Coalesce = Coalesce::new();
+/// static FILTERS: [&dyn MessageFilter; 1] = [&COALESCE];
+///
+/// let options = Options::new()
+///     .destination(Destination::Capture)
+///     .message_filters(&FILTERS);
+/// let view = View::new(DisplayModel("working"), options);
+/// let output = view.captured_output();
+/// for _ in 0..3 {
+///     view.message("retrying\n");
+/// }
+/// view.message("connected\n");
+/// assert_eq!(*output.lock().unwrap(), "retrying\nconnected\n");
+/// ```
+#[derive(Debug)]
+pub struct Coalesce {
+    /// The last message that passed through this filter.
+    last: Mutex<Option<String>>,
+}
+
+impl Coalesce {
+    /// Construct a filter that hasn't yet seen any messages.
+    pub const fn new() -> Coalesce {
+        Coalesce {
+            last: Mutex::new(None),
+        }
+    }
+}
+
+impl Default for Coalesce {
+    fn default() -> Coalesce {
+        Coalesce::new()
+    }
+}
+
+impl MessageFilter for Coalesce {
+    fn filter(&self, message: String, _context: &MessageContext) -> Option<String> {
+        let mut last = self.last.lock().expect("lock last message");
+        if last.as_deref() == Some(message.as_str()) {
+            None
+        } else {
+            *last = Some(message.clone());
+            Some(message)
+        }
+    }
+}
+
+/// Drop messages below a minimum [MessageLevel].
+///
+/// Messages without a level, such as those from [View::message], are always
+/// kept.
+///
+/// ```
+/// use nutmeg::{Destination, MessageLevel, MinLevel, Options, View};
+/// use nutmeg::models::DisplayModel;
+///
+/// let options = Options::new()
+///     .destination(Destination::Capture)
+///     .message_filters(&[&MinLevel(MessageLevel::Info)]);
+/// let view = View::new(DisplayModel("working"), options);
+/// let output = view.captured_output();
+/// view.message_at(MessageLevel::Debug, "opened cache\n");
+/// view.message_at(MessageLevel::Warn, "cache is stale\n");
+/// assert_eq!(*output.lock().unwrap(), "cache is stale\n");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct MinLevel(pub MessageLevel);
+
+impl MessageFilter for MinLevel {
+    fn filter(&self, message: String, context: &MessageContext) -> Option<String> {
+        match context.level {
+            Some(level) if level < self.0 => None,
+            _ => Some(message),
+        }
+    }
+}
@@ -0,0 +1,153 @@
+// Copyright 2023 Martin Pool.
+
+//! Hooks to localize the numbers and durations shown by helpers and built-in
+//! models.
+
+use std::cell::Cell;
+use std::fmt;
+use std::time::Duration;
+
+#[allow(unused)] // for docstrings
+use crate::{Model, Options, View};
+
+/// Formats numbers, percentages, and durations for display.
+///
+/// Set by [Options::formatter]. While the view calls the model, for example
+/// in [Model::render] or [Model::final_message], the formatter is available
+/// from [current_formatting], and it's used by helpers such as
+/// [percent_done](crate::percent_done) and by the built-in models.
+///
+/// Every method has a default matching the built-in English formatting, so
+/// implementations need only override what they want to change.
+///
+/// ```
+/// use nutmeg::{Formatting, Options, View};
+/// use nutmeg::models::LinearModel;
+///
+/// /// Use a decimal comma.
+/// #[derive(Debug)]
+/// struct German;
+///
+/// impl Formatting for German {
+///     fn decimal(&self, value: f64, precision: usize) -> String {
+///         format!("{value:.precision$}").replace('.', ",")
+///     }
+/// }
+///
+/// let view = View::new(
+///     LinearModel::new("Zählen", 3),
+///     Options::new().formatter(&German),
+/// );
+/// view.update(|model| model.increment(1));
+/// ```
+pub trait Formatting: fmt::Debug + Send + Sync {
+    /// Format a count of items.
+    ///
+    /// By default, this is the plain decimal number, like `12345`.
+    fn number(&self, n: u64) -> String {
+        n.to_string()
+    }
+
+    /// Format a fractional number with `precision` digits after the decimal
+    /// separator.
+    ///
+    /// By default, this uses a decimal point, like `12.5`.
+    fn decimal(&self, value: f64, precision: usize) -> String {
+        format!("{value:.precision$}")
+    }
+
+    /// Format a percentage, from 0.0 to 100.0.
+    ///
+    /// By default, this has one decimal place formatted by
+    /// [Formatting::decimal], followed by `%`, like `12.5%`.
+    fn percent(&self, percent: f64) -> String {
+        format!("{}%", self.decimal(percent, 1))
+    }
+
+    /// Format an approximate duration, such as an estimate of the time
+    /// remaining.
+    ///
+    /// By default, this is like `33 sec` or `12 min`.
+    fn duration(&self, duration: Duration) -> String {
+        let secs = duration.as_secs();
+        if secs >= 120 {
+            format!("{} min", secs / 60)
+        } else {
+            format!("{secs} sec")
+        }
+    }
+
+    /// The form of the noun `singular` to use for a count of `n`.
+    ///
+    /// By default, this uses simple English rules: "file" becomes "files",
+    /// "match" becomes "matches", and "directory" becomes "directories", for
+    /// any count except 1. Irregular nouns aren't handled.
+    fn plural(&self, n: u64, singular: &str) -> String {
+        if n == 1 {
+            return singular.to_owned();
+        }
+        let vowel_before_y = singular
+            .strip_suffix('y')
+            .and_then(|stem| stem.chars().last())
+            .is_some_and(|c| "aeiou".contains(c));
+        if ["s", "x", "z", "ch", "sh"]
+            .iter()
+            .any(|ending| singular.ends_with(ending))
+        {
+            format!("{singular}es")
+        } else if singular.ends_with('y') && !vowel_before_y {
+            format!("{}ies", &singular[..singular.len() - 1])
+        } else {
+            format!("{singular}s")
+        }
+    }
+
+    /// Format the time elapsed since work started.
+    ///
+    /// By default, this is minutes and seconds like `2:05`, or with hours
+    /// like `1:02:05`.
+    fn elapsed(&self, elapsed: Duration) -> String {
+        let secs = elapsed.as_secs();
+        if secs >= 3600 {
+            format!("{}:{:02}:{:02}", secs / 3600, (secs / 60) % 60, secs % 60)
+        } else {
+            format!("{}:{:02}", (secs / 60) % 60, secs % 60)
+        }
+    }
+}
+
+/// The built-in English formatting, which is the default.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DefaultFormatting;
+
+impl Formatting for DefaultFormatting {}
+
+thread_local! {
+    /// The formatter of the view that's calling the model on this thread.
+    static CURRENT: Cell<Option<&'static dyn Formatting>> = const { Cell::new(None) };
+}
+
+/// The formatter set by [Options::formatter] on the view that's currently
+/// calling the model on this thread, or [DefaultFormatting] if there is none.
+pub fn current_formatting() -> &'static dyn Formatting {
+    CURRENT.with(Cell::get).unwrap_or(&DefaultFormatting)
+}
+
+/// Makes a formatter current on this thread, until the scope is dropped.
+pub(crate) struct FormattingScope {
+    previous: Option<&'static dyn Formatting>,
+}
+
+impl FormattingScope {
+    pub(crate) fn enter(formatter: &'static dyn Formatting) -> FormattingScope {
+        FormattingScope {
+            previous: CURRENT.with(|c| c.replace(Some(formatter))),
+        }
+    }
+}
+
+impl Drop for FormattingScope {
+    fn drop(&mut self) {
+        CURRENT.with(|c| c.set(self.previous));
+    }
+}
@@ -2,16 +2,16 @@
 
 //! Helpful functions for drawing progress bars.
 
-use std::time::{Duration, Instant};
+use std::path::{self, Path};
+use std::time::{Duration, Instant, SystemTime};
 
-fn duration_brief(d: Duration) -> String {
-    let secs = d.as_secs();
-    if secs >= 120 {
-        format!("{} min", secs / 60)
-    } else {
-        format!("{secs} sec")
-    }
-}
+use unicode_width::UnicodeWidthChar;
+
+use crate::ansi::{self, Segment};
+use crate::formatting::current_formatting;
+use crate::local_time::local_time;
+use crate::pause::paused_since;
+use crate::width;
 
 /// Estimate by linear extrapolation the time remaining for a task with a given
 /// start time, number of completed items and number of total items.
@@ -20,18 +20,112 @@ fn duration_brief(d: Duration) -> String {
 /// future releases before 1.0.
 ///
 /// If the remaining time is not estimatable, returns "??".
+///
+/// When called while a view is rendering the model, time since `start` that the
+/// view was paused, as reported by [paused_since], doesn't count, so that the
+/// estimate isn't thrown off when work resumes after a long pause.
 pub fn estimate_remaining(start: &Instant, done: usize, total: usize) -> String {
-    let elapsed = start.elapsed();
+    estimate_remaining_excluding(start, done, total, paused_since(*start))
+}
+
+/// Estimate the time remaining like [estimate_remaining], but leaving out
+/// `excluded` time, such as time when the work was stalled, from the time
+/// elapsed since `start`.
+///
+/// ```
+/// use std::time::{Duration, Instant, SystemTime};
+/// use nutmeg::estimate_remaining_excluding;
+///
+/// // Half done after 100 seconds, 50 of which were spent waiting.
+/// let start = Instant::now() - Duration::from_secs(100);
+/// let estimate = estimate_remaining_excluding(&start, 50, 100, Duration::from_secs(50));
+/// assert_eq!(estimate, "50 sec");
+/// ```
+pub fn estimate_remaining_excluding(
+    start: &Instant,
+    done: usize,
+    total: usize,
+    excluded: Duration,
+) -> String {
+    match remaining_duration_excluding(start, done, total, excluded) {
+        Some(estimate) => current_formatting().duration(estimate),
+        None => "??".into(),
+    }
+}
+
+/// Estimate the time remaining by linear extrapolation, if it's estimatable,
+/// leaving out time when the view was paused.
+pub(crate) fn remaining_duration(start: &Instant, done: usize, total: usize) -> Option<Duration> {
+    remaining_duration_excluding(start, done, total, paused_since(*start))
+}
+
+fn remaining_duration_excluding(
+    start: &Instant,
+    done: usize,
+    total: usize,
+    excluded: Duration,
+) -> Option<Duration> {
+    let elapsed = start.elapsed().saturating_sub(excluded);
     if total == 0 || done == 0 || elapsed.is_zero() || done > total {
-        "??".into()
+        None
     } else {
         let done = done as f64;
         let total = total as f64;
-        let estimate = Duration::from_secs_f64(elapsed.as_secs_f64() * (total / done - 1.0));
-        duration_brief(estimate)
+        Some(Duration::from_secs_f64(
+            elapsed.as_secs_f64() * (total / done - 1.0),
+        ))
     }
 }
 
+/// Estimate the time of day when a task will finish, by linear extrapolation
+/// from its start time, number of completed items, and number of total items.
+///
+/// The result is in the local time zone, like `"~14:35"`. If the estimated
+/// finish is on a later day, the number of days is added, like `"~09:10 +1d"`.
+/// On platforms where the local time zone can't be found, the time is in UTC,
+/// like `"~14:35 UTC"`.
+///
+/// For multi-hour jobs, this can be more useful than the duration from
+/// [estimate_remaining]. As there, time when the view was paused isn't
+/// counted.
+///
+/// If the finish time is not estimatable, returns "??".
+///
+/// ```
+/// use std::time::{Duration, Instant, SystemTime};
+/// use nutmeg::eta_clock_time;
+///
+/// let start = Instant::now() - Duration::from_secs(3600);
+/// let eta = eta_clock_time(&start, 1, 3);
+/// assert!(eta.starts_with('~'));
+/// assert_eq!(eta_clock_time(&start, 0, 3), "??");
+/// ```
+pub fn eta_clock_time(start: &Instant, done: usize, total: usize) -> String {
+    match remaining_duration(start, done, total) {
+        Some(remaining) => {
+            let now = SystemTime::now();
+            format_clock_time(now + remaining, now)
+        }
+        None => "??".into(),
+    }
+}
+
+/// Format `time` as a time of day like `"~14:35"`, with the number of days
+/// after `now` if it's on a later day.
+fn format_clock_time(time: SystemTime, now: SystemTime) -> String {
+    let (finish, is_local) = local_time(time);
+    let (today, _) = local_time(now);
+    let mut out = format!("~{:02}:{:02}", finish.hour, finish.minute);
+    if !is_local {
+        out.push_str(" UTC");
+    }
+    let days = finish.day - today.day;
+    if days > 0 {
+        out.push_str(&format!(" +{days}d"));
+    }
+    out
+}
+
 /// Return a string representation of the percentage of work completed.
 ///
 /// ```
@@ -43,6 +137,445 @@ pub fn percent_done(done: usize, total: usize) -> String {
     if total == 0 || done > total {
         "??%".into()
     } else {
-        format!("{:.1}%", done as f64 * 100.0 / total as f64)
+        current_formatting().percent(done as f64 * 100.0 / total as f64)
+    }
+}
+
+/// Draw a small progress bar exactly `width` cells wide, filled in
+/// proportion to `fraction`, which is clamped between 0.0 and 1.0.
+///
+/// This is meant to be embedded in each line of a model showing several
+/// tasks, so that the progress of each is visible at a glance. Partly-filled
+/// cells are drawn with Unicode eighth blocks, and empty cells are spaces, so
+/// the caller may want to put the bar between brackets.
+///
+/// ```
+/// use nutmeg::micro_bar;
+///
+/// assert_eq!(micro_bar(0.0, 4), "    ");
+/// assert_eq!(micro_bar(0.5, 4), "██  ");
+/// assert_eq!(micro_bar(0.55, 10), "█████▌    ");
+/// assert_eq!(micro_bar(1.0, 4), "████");
+/// assert_eq!(format!("[{}] job 1", micro_bar(0.25, 8)), "[██      ] job 1");
+/// ```
+pub fn micro_bar(fraction: f64, width: usize) -> String {
+    const PARTIAL: [char; 8] = [' ', '▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+    let fraction = if fraction.is_nan() {
+        0.0
+    } else {
+        fraction.clamp(0.0, 1.0)
+    };
+    let eighths = (fraction * width as f64 * 8.0).round() as usize;
+    let (full, partial) = (eighths / 8, eighths % 8);
+    let mut bar = "█".repeat(full);
+    if full < width {
+        bar.push(PARTIAL[partial]);
+        bar.push_str(&" ".repeat(width - full - 1));
+    }
+    bar
+}
+
+/// Tracks the percentage of work shown by a model so that it never moves
+/// backwards, even when the total is revised upwards.
+///
+/// When the total grows so that the true fraction done falls below what was
+/// last shown, the shown fraction is held and then advances in proportion to
+/// the work done after that, reaching 100% when the work is complete. This
+/// absorbs the change in the total gradually, rather than jumping backwards,
+/// which users tend to see as a bug. If the total is revised down so that the
+/// true fraction is ahead, it's shown.
+///
+/// The built-in models that show a percentage use this when their
+/// `set_monotonic_percent` option is set.
+///
+/// ```
+/// use nutmeg::MonotonicPercent;
+///
+/// let mut percent = MonotonicPercent::new();
+/// assert_eq!(percent.percent(50, 100), "50.0%");
+/// // The total doubles, but the percentage doesn't go back to 25%.
+/// assert_eq!(percent.percent(50, 200), "50.0%");
+/// // Half of the remaining work is done, so half of the remaining bar is filled.
+/// assert_eq!(percent.percent(125, 200), "75.0%");
+/// assert_eq!(percent.percent(200, 200), "100.0%");
+/// ```
+#[derive(Debug, Clone)]
+pub struct MonotonicPercent {
+    /// The fraction most recently shown.
+    shown: f64,
+    /// The fraction shown and the amount done when the true fraction fell
+    /// behind, from which the shown fraction is interpolated.
+    anchor: Option<(f64, usize)>,
+}
+
+impl Default for MonotonicPercent {
+    fn default() -> Self {
+        MonotonicPercent::new()
+    }
+}
+
+impl MonotonicPercent {
+    /// Construct a tracker that hasn't shown any progress yet.
+    pub const fn new() -> MonotonicPercent {
+        MonotonicPercent {
+            shown: 0.0,
+            anchor: None,
+        }
+    }
+
+    /// Return the fraction of work to show, between 0.0 and 1.0, and remember
+    /// it, or None if it's not estimatable.
+    pub fn fraction(&mut self, done: usize, total: usize) -> Option<f64> {
+        let (fraction, anchor) = self.next(done, total)?;
+        self.shown = fraction;
+        self.anchor = anchor;
+        Some(fraction)
+    }
+
+    /// Return the fraction that [MonotonicPercent::fraction] would show,
+    /// without remembering it.
+    pub fn peek_fraction(&self, done: usize, total: usize) -> Option<f64> {
+        self.next(done, total).map(|(fraction, _)| fraction)
+    }
+
+    /// Return the percentage to show, formatted like [percent_done], and
+    /// remember it.
+    pub fn percent(&mut self, done: usize, total: usize) -> String {
+        match self.fraction(done, total) {
+            Some(fraction) => current_formatting().percent(fraction * 100.0),
+            None => "??%".into(),
+        }
+    }
+
+    fn next(&self, done: usize, total: usize) -> Option<(f64, Option<(f64, usize)>)> {
+        if total == 0 || done > total {
+            return None;
+        }
+        let actual = done as f64 / total as f64;
+        let interpolated = self.anchor.and_then(|(anchor_shown, anchor_done)| {
+            (done >= anchor_done && total > anchor_done).then(|| {
+                anchor_shown
+                    + (1.0 - anchor_shown) * (done - anchor_done) as f64
+                        / (total - anchor_done) as f64
+            })
+        });
+        match interpolated {
+            Some(fraction) if fraction >= self.shown => Some((fraction.max(actual), self.anchor)),
+            _ if actual >= self.shown => Some((actual, None)),
+            _ => Some((self.shown, Some((self.shown, done)))),
+        }
+    }
+}
+
+/// Format a number of bytes in decimal units, such as "12.3 MB".
+///
+/// ```
+/// use nutmeg::format_bytes;
+/// assert_eq!(format_bytes(999), "999 B");
+/// assert_eq!(format_bytes(1_500), "1.5 kB");
+/// assert_eq!(format_bytes(12_345_678), "12.3 MB");
+/// ```
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["kB", "MB", "GB", "TB", "PB", "EB"];
+    if bytes < 1000 {
+        return format!("{} B", current_formatting().number(bytes));
+    }
+    let mut value = bytes as f64 / 1000.0;
+    for unit in &UNITS[..UNITS.len() - 1] {
+        if value < 999.95 {
+            return format!("{} {unit}", current_formatting().decimal(value, 1));
+        }
+        value /= 1000.0;
+    }
+    format!(
+        "{} {}",
+        current_formatting().decimal(value, 1),
+        UNITS[UNITS.len() - 1]
+    )
+}
+
+/// Format a count followed by the singular or plural form of a noun, like
+/// "1 file" or "3 files".
+///
+/// The number and noun are formatted by the [current formatter](current_formatting).
+///
+/// ```
+/// use nutmeg::plural;
+/// assert_eq!(plural(1, "file"), "1 file");
+/// assert_eq!(plural(3, "file"), "3 files");
+/// assert_eq!(plural(0, "directory"), "0 directories");
+/// ```
+pub fn plural(n: u64, singular: &str) -> String {
+    let formatting = current_formatting();
+    format!(
+        "{} {}",
+        formatting.number(n),
+        formatting.plural(n, singular)
+    )
+}
+
+/// Fill in `{name}` placeholders in `template` with the matching count from
+/// `counts`.
+///
+/// A placeholder can also name a noun after a colon, like `{done:file}`, which
+/// is replaced by the count and the noun in the right number, as by [plural].
+/// Placeholders with names that aren't in `counts` are left unchanged.
+///
+/// ```
+/// use nutmeg::fill_template;
+///
+/// let counts = [("done", 1), ("total", 12)];
+/// assert_eq!(fill_template("Copied {done} of {total}", &counts), "Copied 1 of 12");
+/// assert_eq!(fill_template("Copied {done:file}", &counts), "Copied 1 file");
+/// assert_eq!(fill_template("{total:match} in {elapsed}", &counts), "12 matches in {elapsed}");
+/// ```
+pub fn fill_template(template: &str, counts: &[(&str, u64)]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(open) = rest.find('{') {
+        out.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let Some(close) = after.find('}') else {
+            out.push_str(&rest[open..]);
+            return out;
+        };
+        let placeholder = &after[..close];
+        let (name, noun) = match placeholder.split_once(':') {
+            Some((name, noun)) => (name, Some(noun)),
+            None => (placeholder, None),
+        };
+        match counts.iter().find(|(n, _)| *n == name) {
+            Some(&(_, count)) => match noun {
+                Some(noun) => out.push_str(&plural(count, noun)),
+                None => out.push_str(&current_formatting().number(count)),
+            },
+            None => out.push_str(&rest[open..open + close + 2]),
+        }
+        rest = &after[close + 1..];
+    }
+    out.push_str(rest);
+    out
+}
+
+/// Shorten a path to fit in `max_width` columns, by replacing directories in
+/// the middle with an ellipsis.
+///
+/// As many leading and trailing components are kept as will fit, preferring
+/// those nearest the end, and the file name is always kept if possible. If
+/// even the file name doesn't fit, its end is shown after an ellipsis.
+///
+/// Widths are measured in terminal columns, so wide characters count as two.
+///
+/// ```
+/// use nutmeg::abbreviate_path;
+///
+/// let path = "/home/u/src/project/deep/file.txt";
+/// assert_eq!(abbreviate_path(path, 40), path);
+/// assert_eq!(abbreviate_path(path, 24), "/home/u/…/deep/file.txt");
+/// assert_eq!(abbreviate_path(path, 12), "/…/file.txt");
+/// assert_eq!(abbreviate_path(path, 6), "…e.txt");
+/// ```
+pub fn abbreviate_path<P: AsRef<Path>>(path: P, max_width: usize) -> String {
+    let path = path.as_ref().to_string_lossy();
+    if width::display_width(&path) <= max_width {
+        return path.into_owned();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+    let parts: Vec<&str> = path.split(path::is_separator).collect();
+    let n = parts.len();
+    let join = |head: usize, tail: usize| {
+        let sep = path::MAIN_SEPARATOR_STR;
+        format!(
+            "{}{sep}…{sep}{}",
+            parts[..head].join(sep),
+            parts[n - tail..].join(sep)
+        )
+    };
+    let fits = |head: usize, tail: usize| width::display_width(&join(head, tail)) <= max_width;
+    let (mut head, mut tail) = (1, 1);
+    if n <= head + tail || !fits(head, tail) {
+        let end = width::suffix_with_width(&path, max_width - 1);
+        return format!("…{end}");
+    }
+    loop {
+        let mut grew = false;
+        if head + tail + 1 < n && fits(head, tail + 1) {
+            tail += 1;
+            grew = true;
+        }
+        if head + tail + 1 < n && fits(head + 1, tail) {
+            head += 1;
+            grew = true;
+        }
+        if !grew {
+            return join(head, tail);
+        }
+    }
+}
+
+/// Shorten a single line of text to fit in `width` columns, by replacing the
+/// middle with an ellipsis, so that both the beginning and the end remain
+/// visible.
+///
+/// This is useful for URLs, hashes, and other strings where the end is as
+/// informative as the start. Whole lines in a progress bar are instead cut off
+/// at the end.
+///
+/// ANSI escape sequences take no space and are all kept, even those in the
+/// part that's removed, so that styles are still turned off correctly. Wide
+/// characters count as two columns.
+///
+/// ```
+/// use nutmeg::truncate_middle;
+///
+/// assert_eq!(truncate_middle("0123456789abcdef", 9), "0123…cdef");
+/// assert_eq!(truncate_middle("short", 9), "short");
+/// assert_eq!(
+///     truncate_middle("\x1b[1m0123456789\x1b[0m", 5),
+///     "\x1b[1m01…89\x1b[0m"
+/// );
+/// ```
+pub fn truncate_middle(s: &str, width: usize) -> String {
+    let total = width::display_width(s);
+    if total <= width {
+        return s.to_owned();
+    }
+    let visible = || {
+        ansi::segments(s).flat_map(|seg| {
+            match seg {
+                Segment::Text(t) => t,
+                Segment::Control(_) => "",
+            }
+            .chars()
+        })
+    };
+    // Up to half the width for the start, one column for the ellipsis, and
+    // whatever remains for the end.
+    let mut head_width = 0;
+    for ch in visible() {
+        let ch_width = ch.width().unwrap_or(0);
+        if head_width + ch_width > width / 2 {
+            break;
+        }
+        head_width += ch_width;
+    }
+    let tail_budget = width.saturating_sub(1 + head_width);
+    let mut tail_start = total;
+    for ch in visible().collect::<Vec<char>>().into_iter().rev() {
+        let ch_width = ch.width().unwrap_or(0);
+        if total - tail_start + ch_width > tail_budget {
+            break;
+        }
+        tail_start -= ch_width;
+    }
+    let mut out = String::with_capacity(s.len());
+    let mut column = 0;
+    let mut elided = false;
+    for seg in ansi::segments(s) {
+        match seg {
+            Segment::Control(c) => out.push_str(c),
+            Segment::Text(t) => {
+                for ch in t.chars() {
+                    let ch_width = ch.width().unwrap_or(0);
+                    if column + ch_width <= head_width || column >= tail_start {
+                        out.push(ch);
+                    } else if !elided && width > 0 {
+                        out.push('…');
+                        elided = true;
+                    }
+                    column += ch_width;
+                }
+            }
+        }
+    }
+    out
+}
+
+/// Scrolls text that's too long for the available width, so that all of it
+/// can be read over successive frames.
+///
+/// Text that fits is shown unchanged. Longer text is shown from the start for
+/// [Marquee::PAUSE_FRAMES] frames, then scrolls left by one character per frame
+/// until its end is visible, pauses again, and starts over.
+///
+/// The marquee advances each time it's rendered, so it's typically kept in the
+/// model and rendered from [crate::Model::render]. [Marquee::advance] can be
+/// called from [crate::Model::on_skipped_frames] to keep pace with the clock.
+///
+/// The text should be plain, without ANSI sequences; the width is counted in
+/// terminal columns, so wide characters take two.
+///
+/// ```
+/// use nutmeg::Marquee;
+///
+/// let mut marquee = Marquee::new("a/very/long/path");
+/// assert_eq!(marquee.render(20), "a/very/long/path");
+/// marquee.advance(Marquee::PAUSE_FRAMES);
+/// assert_eq!(marquee.render(10), "a/very/lon");
+/// assert_eq!(marquee.render(10), "/very/long");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Marquee {
+    text: String,
+    frame: usize,
+}
+
+impl Marquee {
+    /// Number of frames to hold still at each end of the text.
+    pub const PAUSE_FRAMES: usize = 10;
+
+    /// Construct a marquee showing `text`, starting from the beginning.
+    pub fn new<S: Into<String>>(text: S) -> Marquee {
+        Marquee {
+            text: text.into(),
+            frame: 0,
+        }
+    }
+
+    /// Change the text, and start scrolling again from the beginning.
+    ///
+    /// If the text is unchanged, scrolling continues from where it was.
+    pub fn set_text<S: AsRef<str>>(&mut self, text: S) {
+        let text = text.as_ref();
+        if text != self.text {
+            self.text = text.to_owned();
+            self.frame = 0;
+        }
+    }
+
+    /// The full text.
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Skip forward by `frames` frames without rendering.
+    pub fn advance(&mut self, frames: usize) {
+        self.frame = self.frame.wrapping_add(frames);
+    }
+
+    /// Return the visible part of the text, at most `width` columns, and
+    /// advance to the next frame.
+    pub fn render(&mut self, width: usize) -> String {
+        if width::display_width(&self.text) <= width {
+            return self.text.clone();
+        }
+        // The number of characters to scroll past before the end is visible.
+        let tail = width::suffix_with_width(&self.text, width);
+        let overflow = self.text[..self.text.len() - tail.len()].chars().count();
+        let period = overflow + 2 * Marquee::PAUSE_FRAMES;
+        let phase = self.frame % period;
+        let offset = phase.saturating_sub(Marquee::PAUSE_FRAMES).min(overflow);
+        self.advance(1);
+        let mut used = 0;
+        self.text
+            .chars()
+            .skip(offset)
+            .take_while(|ch| {
+                used += ch.width().unwrap_or(0);
+                used <= width
+            })
+            .collect()
     }
 }
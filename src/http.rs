@@ -0,0 +1,242 @@
+// Copyright 2023 Martin Pool.
+
+//! Report progress over HTTP, so that headless jobs can be watched from a
+//! browser or `curl`.
+//!
+//! This module is only available with the `http` feature. It uses only the
+//! standard library, and is meant for listening on localhost, not as a
+//! general-purpose web server.
+//!
+//! An [HttpReporter] is attached to a view with [Options::http_reporter]. It
+//! serves:
+//!
+//! * `GET /`: the current state as JSON, like
+//!   `{"frame":"Copying: 12/40","fraction":0.3,"frames":7,"messages":2,"finished":false}`.
+//! * `GET /events`: the same JSON as a stream of server-sent events, one per
+//!   update.
+//!
+//! The frame is the one most recently drawn by the view. If progress bars are
+//! not drawn, for example because the destination is not a terminal, the model
+//! is instead rendered for the reporter at 80 columns, at most once per
+//! [Options::update_interval]. The fraction comes from [Model::fraction_done].
+//!
+//! Publishing never blocks the view: snapshots are sent to the reporter's
+//! thread, which writes them to the event streams. Each request is served on
+//! its own thread, and clients that stop reading are disconnected after
+//! [HttpReporter::TIMEOUT].
+//!
+//! ```no_run
+//! use nutmeg::http::HttpReporter;
+//! use nutmeg::models::LinearModel;
+//! use nutmeg::{Options, View};
+//!
+//! let reporter = HttpReporter::bind("127.0.0.1:7878").unwrap();
+//! let view = View::new(
+//!     LinearModel::new("Counting", 100),
+//!     Options::default().http_reporter(reporter),
+//! );
+//! view.update(|model| model.increment(1));
+//! ```
+
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Duration;
+
+use crate::json::{push_json_fraction, push_json_string};
+#[allow(unused)] // for docstrings
+use crate::{Model, Options};
+
+/// The width at which frames are rendered for the reporter.
+pub(crate) const WIDTH: usize = 80;
+
+/// The most recent state of a view, as served by an [HttpReporter].
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Snapshot {
+    frame: String,
+    fraction: Option<f64>,
+    frames: u64,
+    messages: u64,
+    finished: bool,
+}
+
+impl Snapshot {
+    fn to_json(&self) -> String {
+        let mut json = String::from("{\"frame\":");
+        push_json_string(&mut json, &self.frame);
+        json.push_str(",\"fraction\":");
+        push_json_fraction(&mut json, self.fraction);
+        write!(
+            json,
+            ",\"frames\":{},\"messages\":{},\"finished\":{}}}",
+            self.frames, self.messages, self.finished
+        )
+        .unwrap();
+        json
+    }
+}
+
+/// Work for the thread that writes server-sent events.
+enum Event {
+    /// Send a new snapshot to every subscriber.
+    Publish(Snapshot),
+    /// Send the latest snapshot to a new subscriber, and then subscribe it.
+    Subscribe(TcpStream),
+}
+
+/// Serves the state of a view as JSON and server-sent events over HTTP.
+///
+/// See the [module documentation](self).
+#[derive(Debug)]
+pub struct HttpReporter {
+    addr: SocketAddr,
+    snapshot: Mutex<Snapshot>,
+    /// Sends snapshots and new subscribers to the events thread.
+    events: Sender<Event>,
+}
+
+impl HttpReporter {
+    /// How long the reporter waits to read a request or write a response
+    /// before giving up on the client.
+    pub const TIMEOUT: Duration = Duration::from_secs(5);
+
+    /// Listen on `addr` and serve requests from background threads.
+    ///
+    /// The reporter lives for the rest of the process, so that it can be used
+    /// in [Options], which holds only static references.
+    ///
+    /// Port 0 picks an unused port, which can be found from [HttpReporter::local_addr].
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> io::Result<&'static HttpReporter> {
+        let listener = TcpListener::bind(addr)?;
+        let (events, events_rx) = channel();
+        let reporter: &'static HttpReporter = Box::leak(Box::new(HttpReporter {
+            addr: listener.local_addr()?,
+            snapshot: Mutex::new(Snapshot::default()),
+            events,
+        }));
+        thread::Builder::new()
+            .name("nutmeg-http-events".to_owned())
+            .spawn(move || send_events(events_rx))?;
+        thread::Builder::new()
+            .name("nutmeg-http".to_owned())
+            .spawn(move || {
+                for stream in listener.incoming().flatten() {
+                    let _ = thread::Builder::new()
+                        .name("nutmeg-http-request".to_owned())
+                        .spawn(move || reporter.serve(stream));
+                }
+            })?;
+        Ok(reporter)
+    }
+
+    /// The address the reporter is listening on.
+    pub fn local_addr(&self) -> SocketAddr {
+        self.addr
+    }
+
+    fn serve(&self, mut stream: TcpStream) -> io::Result<()> {
+        stream.set_read_timeout(Some(HttpReporter::TIMEOUT))?;
+        stream.set_write_timeout(Some(HttpReporter::TIMEOUT))?;
+        let mut request_line = String::new();
+        let mut reader = BufReader::new(stream.try_clone()?);
+        reader.read_line(&mut request_line)?;
+        // Skip the headers.
+        let mut header = String::new();
+        while reader.read_line(&mut header)? > 2 {
+            header.clear();
+        }
+        let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+        match path {
+            "/" => {
+                let body = self.snapshot.lock().unwrap().to_json();
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n\
+                    Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+                    body.len()
+                )
+            }
+            "/events" => {
+                // The events thread sends the current state, so that no
+                // update is missed before the stream is subscribed.
+                let _ = self.events.send(Event::Subscribe(stream));
+                Ok(())
+            }
+            _ => write!(
+                stream,
+                "HTTP/1.1 404 Not Found\r\nContent-Length: 0\r\nConnection: close\r\n\r\n"
+            ),
+        }
+    }
+
+    /// Update the snapshot and send it to subscribers.
+    ///
+    /// This only takes a lock on the snapshot: the events are written by
+    /// another thread.
+    fn publish(&self, update: impl FnOnce(&mut Snapshot)) {
+        let snapshot = {
+            let mut snapshot = self.snapshot.lock().unwrap();
+            let before = snapshot.clone();
+            update(&mut snapshot);
+            if *snapshot == before {
+                return;
+            }
+            snapshot.clone()
+        };
+        let _ = self.events.send(Event::Publish(snapshot));
+    }
+
+    pub(crate) fn publish_frame(&self, frame: &str, fraction: Option<f64>) {
+        let frame = crate::ansi::strip_sequences(frame);
+        self.publish(|s| {
+            if s.frame != frame || s.fraction != fraction {
+                s.frame = frame;
+                s.fraction = fraction;
+                s.frames += 1;
+            }
+        })
+    }
+
+    pub(crate) fn publish_message(&self) {
+        self.publish(|s| s.messages += 1)
+    }
+
+    pub(crate) fn publish_finished(&self) {
+        self.publish(|s| s.finished = true)
+    }
+}
+
+/// Write each published snapshot to the subscribed event streams, dropping
+/// any that fail or time out.
+fn send_events(events: Receiver<Event>) {
+    let mut latest = Snapshot::default();
+    let mut subscribers: Vec<TcpStream> = Vec::new();
+    for event in events {
+        match event {
+            Event::Publish(snapshot) => {
+                latest = snapshot;
+                let event = format!("data: {}\n\n", latest.to_json());
+                subscribers.retain_mut(|s| {
+                    s.write_all(event.as_bytes())
+                        .and_then(|_| s.flush())
+                        .is_ok()
+                });
+            }
+            Event::Subscribe(mut stream) => {
+                let sent = write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Type: text/event-stream\r\n\
+                    Cache-Control: no-cache\r\n\r\ndata: {}\n\n",
+                    latest.to_json()
+                )
+                .and_then(|_| stream.flush());
+                if sent.is_ok() {
+                    subscribers.push(stream);
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,277 @@
+// Copyright 2023 Martin Pool.
+
+//! Show progress of data transfers, such as downloads.
+//!
+//! [track_chunks] wraps an iterator of chunks of bytes, such as the blocks
+//! of a response body read by an HTTP client, counting the bytes in each
+//! chunk into a [BytesModel].
+//!
+//! [ProgressReader] and [ProgressWriter] wrap any [Read] or [Write], counting
+//! the bytes that pass through them, so that copying with
+//! [std::io::copy] shows the bytes transferred and the rate as it goes.
+//!
+//! Asynchronous HTTP clients such as `reqwest` and `hyper` yield the response
+//! body as a stream of chunks. With the `futures` feature, `track_stream`
+//! wraps such a stream:
+//!
+//! ```ignore
+//! let view = View::new(BytesModel::new("Downloading"), Options::default());
+//! let mut body = nutmeg::io::track_stream(response.bytes_stream(), &view);
+//! while let Some(chunk) = body.next().await {
+//!     file.write_all(&chunk?)?;
+//! }
+//! ```
+//!
+//! Otherwise, each chunk can be counted with [BytesModel::increment] as it
+//! arrives:
+//!
+//! ```ignore
+//! view.update(|model| model.set_total(response.content_length().unwrap_or(0)));
+//! while let Some(chunk) = response.chunk().await? {
+//!     view.update(|model| model.increment(chunk.len() as u64));
+//!     file.write_all(&chunk)?;
+//! }
+//! ```
+
+use std::io::{self, BufRead, Read, Write};
+#[cfg(feature = "futures")]
+use std::pin::Pin;
+#[cfg(feature = "futures")]
+use std::task::{Context, Poll};
+
+use crate::models::BytesModel;
+use crate::ProgressSink;
+#[allow(unused)] // for docstrings
+use crate::View;
+
+/// Wrap an iterator of results containing chunks of bytes, such as the body
+/// of a response read by an HTTP client, so that the size of each chunk is
+/// added to the [BytesModel] in `view` as it's taken.
+///
+/// Errors are passed through without being counted.
+///
+/// ```
+/// use nutmeg::models::BytesModel;
+/// use nutmeg::{Options, View};
+///
+/// let body: Vec<std::io::Result<Vec<u8>>> = vec![Ok(vec![0; 1000]), Ok(vec![0; 500])];
+/// let view = View::new(BytesModel::new("Downloading"), Options::default());
+/// let mut content = Vec::new();
+/// for chunk in nutmeg::io::track_chunks(body, &view) {
+///     content.extend_from_slice(&chunk?);
+/// }
+/// assert_eq!(view.finish().done(), 1500);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+pub fn track_chunks<'a, I, B, E, S>(chunks: I, view: &'a S) -> impl Iterator<Item = I::Item> + 'a
+where
+    I: IntoIterator<Item = Result<B, E>>,
+    I::IntoIter: 'a,
+    B: AsRef<[u8]>,
+    S: ProgressSink<BytesModel>,
+{
+    chunks.into_iter().inspect(move |chunk| {
+        if let Ok(chunk) = chunk {
+            let len = chunk.as_ref().len() as u64;
+            view.update(|model| model.increment(len))
+        }
+    })
+}
+
+/// Wrap an asynchronous stream of results containing chunks of bytes, such as
+/// the body of a response from an HTTP client, so that the size of each chunk
+/// is added to the [BytesModel] in `view` as it arrives.
+///
+/// This is the asynchronous equivalent of [track_chunks], and is only
+/// available with the `futures` feature. Errors are passed through without
+/// being counted.
+#[cfg(feature = "futures")]
+pub fn track_stream<St, S>(stream: St, view: &S) -> ProgressStream<'_, St, S>
+where
+    S: ProgressSink<BytesModel>,
+{
+    ProgressStream {
+        inner: stream,
+        view,
+    }
+}
+
+/// A stream that adds the size of each chunk to the [BytesModel] in a view,
+/// returned by [track_stream].
+#[cfg(feature = "futures")]
+#[derive(Debug)]
+pub struct ProgressStream<'a, St, S> {
+    inner: St,
+    view: &'a S,
+}
+
+#[cfg(feature = "futures")]
+impl<St, S> ProgressStream<'_, St, S> {
+    /// Return the inner stream.
+    pub fn into_inner(self) -> St {
+        self.inner
+    }
+}
+
+#[cfg(feature = "futures")]
+impl<St, B, E, S> futures_core::Stream for ProgressStream<'_, St, S>
+where
+    St: futures_core::Stream<Item = Result<B, E>>,
+    B: AsRef<[u8]>,
+    S: ProgressSink<BytesModel>,
+{
+    type Item = St::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<St::Item>> {
+        let view = self.view;
+        // Safety: `inner` is pinned whenever `self` is: it's never moved out
+        // of a pinned `ProgressStream`, which has no `Drop` and is only `Unpin`
+        // if the inner stream is.
+        let inner = unsafe { self.map_unchecked_mut(|s| &mut s.inner) };
+        let poll = inner.poll_next(cx);
+        if let Poll::Ready(Some(Ok(chunk))) = &poll {
+            let len = chunk.as_ref().len() as u64;
+            view.update(|model| model.increment(len))
+        }
+        poll
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.inner.size_hint()
+    }
+}
+
+/// A reader that adds the number of bytes read to the [BytesModel] in a view.
+///
+/// Bytes are counted as they're returned by [Read::read], or consumed through
+/// [BufRead] if the inner reader is buffered.
+///
+/// ```
+/// use nutmeg::io::ProgressReader;
+/// use nutmeg::models::BytesModel;
+/// use nutmeg::{Options, View};
+///
+/// let source: &[u8] = &[7; 5000];
+/// let view = View::new(BytesModel::new("Copying"), Options::default());
+/// view.update(|model| model.set_total(source.len() as u64));
+/// let mut dest = Vec::new();
+/// std::io::copy(&mut ProgressReader::new(source, &view), &mut dest)?;
+/// assert_eq!(view.finish().done(), 5000);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ProgressReader<'a, R, S> {
+    inner: R,
+    view: &'a S,
+}
+
+impl<'a, R, S: ProgressSink<BytesModel>> ProgressReader<'a, R, S> {
+    /// Wrap `inner` to count the bytes read into `view`.
+    pub fn new(inner: R, view: &'a S) -> ProgressReader<'a, R, S> {
+        ProgressReader { inner, view }
+    }
+
+    /// Return a reference to the inner reader.
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the inner reader.
+    ///
+    /// Bytes read directly from the inner reader aren't counted.
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Return the inner reader.
+    pub fn into_inner(self) -> R {
+        self.inner
+    }
+
+    fn count(&self, len: usize) {
+        if len > 0 {
+            self.view.update(|model| model.increment(len as u64))
+        }
+    }
+}
+
+impl<R: Read, S: ProgressSink<BytesModel>> Read for ProgressReader<'_, R, S> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.count(len);
+        Ok(len)
+    }
+}
+
+impl<R: BufRead, S: ProgressSink<BytesModel>> BufRead for ProgressReader<'_, R, S> {
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        self.inner.fill_buf()
+    }
+
+    fn consume(&mut self, amt: usize) {
+        self.inner.consume(amt);
+        self.count(amt);
+    }
+}
+
+/// A writer that adds the number of bytes written to the [BytesModel] in a
+/// view.
+///
+/// Bytes are counted when the inner writer accepts them, which for a
+/// buffered writer may be before they reach their destination.
+///
+/// ```
+/// use nutmeg::io::ProgressWriter;
+/// use nutmeg::models::BytesModel;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(BytesModel::new("Saving"), Options::default());
+/// let mut writer = ProgressWriter::new(Vec::new(), &view);
+/// std::io::copy(&mut &[7; 5000][..], &mut writer)?;
+/// assert_eq!(writer.into_inner().len(), 5000);
+/// assert_eq!(view.finish().done(), 5000);
+/// # Ok::<(), std::io::Error>(())
+/// ```
+#[derive(Debug)]
+pub struct ProgressWriter<'a, W, S> {
+    inner: W,
+    view: &'a S,
+}
+
+impl<'a, W, S: ProgressSink<BytesModel>> ProgressWriter<'a, W, S> {
+    /// Wrap `inner` to count the bytes written into `view`.
+    pub fn new(inner: W, view: &'a S) -> ProgressWriter<'a, W, S> {
+        ProgressWriter { inner, view }
+    }
+
+    /// Return a reference to the inner writer.
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    /// Return a mutable reference to the inner writer.
+    ///
+    /// Bytes written directly to the inner writer aren't counted.
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Return the inner writer.
+    pub fn into_inner(self) -> W {
+        self.inner
+    }
+}
+
+impl<W: Write, S: ProgressSink<BytesModel>> Write for ProgressWriter<'_, W, S> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let len = self.inner.write(buf)?;
+        if len > 0 {
+            self.view.update(|model| model.increment(len as u64))
+        }
+        Ok(len)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
@@ -0,0 +1,28 @@
+// Copyright 2023 Martin Pool.
+
+//! Write small JSON documents without a serialization library.
+
+use std::fmt::Write as _;
+
+/// Append `s` to `json` as a quoted JSON string.
+pub(crate) fn push_json_string(json: &mut String, s: &str) {
+    json.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => json.push_str("\\\""),
+            '\\' => json.push_str("\\\\"),
+            '\n' => json.push_str("\\n"),
+            c if (c as u32) < 0x20 => write!(json, "\\u{:04x}", c as u32).unwrap(),
+            c => json.push(c),
+        }
+    }
+    json.push('"');
+}
+
+/// Append `f` to `json` as a number, or as `null` if it's absent or not finite.
+pub(crate) fn push_json_fraction(json: &mut String, f: Option<f64>) {
+    match f {
+        Some(f) if f.is_finite() => write!(json, "{f}").unwrap(),
+        _ => json.push_str("null"),
+    }
+}
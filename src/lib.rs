@@ -37,7 +37,8 @@ Models can optionally provide a "final message" by implementing
 If one overall operation represents several concurrent operations then the
 application can, for example, represent them in a collection within the Model, and
 render them into multiple lines, or multiple sections in a single line.
-(See `examples/multithreaded.rs`.)
+Alternatively, each operation can be added as a task with [View::add_task],
+which is drawn as its own line below the model. (See `examples/multithreaded.rs`.)
 
 ## View
 
@@ -48,15 +49,24 @@ The application then updates the model state via [View::update], which may decid
 to paint the view to the terminal, subject to rate-limiting and other constraints.
 
 The view has an internal mutex and is `Send` and `Sync`,
-so it can be shared freely across threads.
-
-The view automatically erases itself from the screen when it is dropped.
+so it can be shared freely across threads. Single-threaded programs, or those
+with models that aren't `Send`, can instead use a [LocalView], which has no
+mutex.
+Threads that each count many small pieces of work can increment a [Counter]
+from [View::counter] rather than locking the view for every one, and can
+batch their counts with a [LocalCounter] from [Counter::local].
+Models that keep their state in atomics can use a [SyncView], whose updates
+take only a shared reference to the model and don't wait for the view's lock.
+
+The view automatically erases itself from the screen when it is dropped,
+unless configured otherwise by [Options::on_drop].
 
 While the view is on the screen, the application can print messages interleaved
 with the progress bar by either calling [View::message], or treating it as a [std::io::Write]
 destination, for example for [std::writeln].
 
-Errors in writing to the terminal cause a panic.
+Errors in writing to the terminal cause a panic, except in methods such as
+[View::try_update] and [View::try_message], which return an [Error].
 
 ## Options
 
@@ -181,6 +191,30 @@ For example:
 
 See `examples/tracing` for a runnable example.
 
+# Cargo features
+
+The default features keep all of the library; size-sensitive applications can
+turn them off to build only the core [Model] and [View] machinery, which
+depends only on `unicode-width`, and on `libc` on Unix.
+
+* `ansi-terminal`: draw progress bars on stdout and stderr when they're
+  terminals. Without this, [Destination::Stdout] and [Destination::Stderr]
+  only print messages, and progress is drawn only to other destinations.
+* `capture`: [Destination::Capture], [Destination::CaptureNamed] and
+  [Destination::CaptureBounded], for tests.
+* `helpers`: functions for formatting progress, such as [estimate_remaining].
+* `models`: ready-made models in [models], and the [adapters], [callback] and
+  [io] modules that use them. Implies `helpers`.
+* `windows-console`: enable escape sequences on the Windows console, so that
+  progress bars can be drawn there.
+
+These are on by default. These are off by default:
+
+* `futures`: `io::track_stream`, to count the bytes from an asynchronous
+  stream of chunks such as an HTTP response body. Implies `models`.
+* `http`: serve progress over HTTP with `http::HttpReporter`.
+* `tracing`: show open tracing spans as progress.
+
 # Project status
 
 Nutmeg is a young library. Although the API will not break gratuitously,
@@ -210,17 +244,58 @@ is welcome.
 
 #![warn(missing_docs)]
 
-use std::io::{self, Write};
-use std::sync::{Arc, Mutex};
-use std::time::Instant;
-
-mod ansi;
+use std::any::Any;
+use std::borrow::Cow;
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{mpsc, Arc, Mutex, MutexGuard, PoisonError, TryLockError};
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "models")]
+pub mod adapters;
+pub mod ansi;
+#[cfg(feature = "models")]
+pub mod callback;
+mod cancel;
+mod capture;
+mod clock;
+mod counter;
 mod destination;
+mod emergency;
+mod error;
+mod filter;
+mod formatting;
+#[cfg(feature = "helpers")]
 mod helpers;
+#[cfg(feature = "http")]
+pub mod http;
+#[cfg(feature = "models")]
+pub mod io;
+mod json;
+mod local;
+#[cfg(feature = "helpers")]
+mod local_time;
+mod model_guard;
+#[cfg(feature = "models")]
 pub mod models;
+mod multi;
+mod null;
 mod options;
+mod pause;
+mod reentry;
+mod resize;
+mod signal;
+mod stats;
+mod sync;
+mod task;
+pub mod terminal;
+pub mod test_util;
+pub mod theme;
+pub mod to_print;
+#[cfg(feature = "tracing")]
+pub mod tracing;
 mod width;
-#[cfg(windows)]
+#[cfg(all(windows, feature = "windows-console"))]
 mod windows;
 
 pub mod _changelog {
@@ -229,9 +304,46 @@ pub mod _changelog {
     use super::*; // so that hyperlinks work
 }
 
-pub use crate::destination::Destination;
+pub use crate::ansi::ControlDialect;
+pub use crate::cancel::request_cancellation;
+pub use crate::capture::{min_frame_interval, CapturedEvent, CapturedEventKind};
+pub use crate::clock::{frame_time, Clock, CoarseClock, SystemClock};
+use crate::clock::{FrameTimeScope, NextPaint, PaintTime};
+use crate::counter::Cells;
+pub use crate::counter::{Counter, CounterMode, LocalCounter};
+use crate::destination::HeightScope;
+pub use crate::destination::{
+    current_height, Destination, DestinationTrait, TerminalContext, WriterDestination,
+};
+pub use crate::error::Error;
+#[cfg(feature = "helpers")]
+pub use crate::filter::Timestamp;
+pub use crate::filter::{
+    Coalesce, MessageContext, MessageFilter, MessageLevel, MinLevel, Prefix, StripEscapes,
+};
+use crate::formatting::FormattingScope;
+pub use crate::formatting::{current_formatting, DefaultFormatting, Formatting};
+#[cfg(feature = "helpers")]
 pub use crate::helpers::*;
-pub use crate::options::Options;
+use crate::json::{push_json_fraction, push_json_string};
+pub use crate::local::LocalView;
+pub use crate::model_guard::ModelGuard;
+pub use crate::multi::{MultiView, Region};
+pub use crate::null::{NullView, ProgressSink};
+pub use crate::options::{
+    Accessibility, CursorPark, DropPolicy, FallbackInterval, Options, OptionsWarning, Pace, Preset,
+    TaskFinishPolicy,
+};
+pub use crate::pause::paused_since;
+use crate::pause::{PauseScope, Pauses};
+pub use crate::resize::notify_resize;
+pub use crate::signal::SignalCleanup;
+pub use crate::stats::Stats;
+pub use crate::sync::SyncView;
+use crate::task::Tasks;
+pub use crate::task::{Expansion, TaskGroup, TaskHandle, AUTO_GROUP_LINES};
+use crate::terminal::Mode;
+use crate::theme::ThemeScope;
 
 /// An application-defined type that holds whatever state is relevant to the
 /// progress bar, and that can render it into one or more lines of text.
@@ -282,12 +394,212 @@ pub trait Model {
     /// but it should not have a final newline, unless a trailing blank line
     /// is desired.
     ///
+    /// Unlike the progress bar, the final message is not truncated: lines longer
+    /// than the terminal width are wrapped. If the destination is not a terminal,
+    /// for example because output is redirected to a file, ANSI escape
+    /// sequences are removed.
+    ///
     /// This is called by [View::finish] or when the view is dropped.
     /// The final message is not printed when the view is abandoned by
     /// [View::abandon].
     fn final_message(&mut self) -> String {
         String::new()
     }
+
+    /// Render a message describing a failure, when the view is finished by
+    /// [View::finish_failure].
+    ///
+    /// By default this is "failed: " followed by the error, styled as a failure
+    /// by the [theme::current_theme], which is bold red by default.
+    ///
+    /// As for [Model::final_message], the message may contain ANSI styling
+    /// and multiple lines, and should not have a final newline.
+    fn failure_message(&mut self, error: &dyn Display) -> String {
+        theme::current_theme().failure(&format!("failed: {error}"))
+    }
+
+    /// Render a message saying that the work was cancelled, printed instead
+    /// of the [Model::final_message] when the view is finished or dropped after
+    /// cancellation was requested by [View::request_cancellation] or
+    /// [request_cancellation].
+    ///
+    /// By default this is "cancelled", styled as a warning by the
+    /// [theme::current_theme].
+    fn cancelled_message(&mut self) -> String {
+        theme::current_theme().warning("cancelled")
+    }
+
+    /// Called before rendering when the view was not redrawn for more than one
+    /// [Options::update_interval], with the number of intervals that passed
+    /// without a frame being drawn.
+    ///
+    /// This happens when the application pauses between updates, or sends a
+    /// burst of updates followed by a long pause. Models that animate on each
+    /// frame, such as spinners or scrolling text, can use this to advance by
+    /// the right amount rather than by one step per redraw.
+    ///
+    /// By default this does nothing.
+    ///
+    /// ```
+    /// struct Spinner {
+    ///     frame: usize,
+    /// }
+    ///
+    /// impl nutmeg::Model for Spinner {
+    ///     fn render(&mut self, _width: usize) -> String {
+    ///         self.frame += 1;
+    ///         ["|", "/", "-", "\\"][self.frame % 4].to_owned()
+    ///     }
+    ///
+    ///     fn on_skipped_frames(&mut self, skipped: usize) {
+    ///         self.frame += skipped;
+    ///     }
+    /// }
+    /// ```
+    fn on_skipped_frames(&mut self, skipped: usize) {
+        let _ = skipped;
+    }
+
+    /// The narrowest width at which [Model::render] gives a useful result.
+    ///
+    /// When the terminal is narrower than this, the view calls
+    /// [Model::render_degraded] instead.
+    ///
+    /// By default this is 0, so [Model::render] is always used.
+    fn min_width(&self) -> usize {
+        0
+    }
+
+    /// The width below which the view calls [Model::render_compact] rather than
+    /// [Model::render].
+    ///
+    /// Models are rendered in one of three forms depending on the width:
+    /// the full form from [Model::render]; a compact form from
+    /// [Model::render_compact] when the width is less than `compact_width`;
+    /// and a minimal form from [Model::render_degraded] when the width is less
+    /// than [Model::min_width].
+    ///
+    /// By default this is 0, so the compact form is never used.
+    fn compact_width(&self) -> usize {
+        0
+    }
+
+    /// Render a shorter form of the model, for terminals narrower than
+    /// [Model::compact_width].
+    ///
+    /// For example, this might leave out a description, and show only the
+    /// numbers, like `42% 3 min`.
+    ///
+    /// By default this calls [Model::render].
+    fn render_compact(&mut self, width: usize) -> String {
+        self.render(width)
+    }
+
+    /// The most lines this model expects to render, if it's known.
+    ///
+    /// When the progress bar is drawn after the screen was clear of it, the
+    /// view makes room for this many lines below the cursor, scrolling the
+    /// terminal if necessary. This way, a model that starts small and grows
+    /// doesn't scroll earlier output up the screen a line at a time as it
+    /// grows.
+    ///
+    /// Whatever this returns, frames are cut to the height of the terminal,
+    /// because lines that scroll off the top can't be erased.
+    ///
+    /// By default this is `None`, and no space is reserved.
+    fn max_height(&self) -> Option<usize> {
+        None
+    }
+
+    /// The fraction of the work that's done, from 0.0 to 1.0, if it's known.
+    ///
+    /// This is not used to draw the progress bar, but is reported to
+    /// observers that want a number rather than text, such as the `http`
+    /// reporter.
+    ///
+    /// By default this is `None`.
+    fn fraction_done(&self) -> Option<f64> {
+        None
+    }
+
+    /// Named counts describing the work done, such as `[("done", 12), ("total", 40)]`.
+    ///
+    /// These are included in the summary written by [Options::json_summary] when
+    /// the view is finished, so that scripts can read totals without parsing the
+    /// final message.
+    ///
+    /// By default this is empty.
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        Vec::new()
+    }
+
+    /// A short plain-text description of the progress, to be read out by a
+    /// screen reader, when the view is in [Accessibility::Announce] mode.
+    ///
+    /// By default, this is like "40 percent complete" if the
+    /// [Model::fraction_done] is known, or otherwise the first line of the
+    /// model rendered at 80 columns, without escape sequences.
+    fn announcement(&mut self) -> String {
+        match self.fraction_done() {
+            Some(fraction) => format!("{:.0} percent complete", fraction * 100.0),
+            None => {
+                let rendered = ansi::strip_sequences(&self.render(80));
+                rendered.lines().next().unwrap_or_default().to_owned()
+            }
+        }
+    }
+
+    /// Render a minimal form of the model, for terminals narrower than
+    /// [Model::min_width].
+    ///
+    /// This might be a single token such as a percentage. As for
+    /// [Model::render], lines wider than `width` are cut off.
+    ///
+    /// By default this calls [Model::render].
+    ///
+    /// ```
+    /// struct Copy {
+    ///     file: String,
+    ///     percent: usize,
+    /// }
+    ///
+    /// impl nutmeg::Model for Copy {
+    ///     fn render(&mut self, _width: usize) -> String {
+    ///         format!("Copying {}: {}%", self.file, self.percent)
+    ///     }
+    ///
+    ///     fn min_width(&self) -> usize {
+    ///         20
+    ///     }
+    ///
+    ///     fn render_degraded(&mut self, _width: usize) -> String {
+    ///         format!("{}%", self.percent)
+    ///     }
+    /// }
+    /// ```
+    fn render_degraded(&mut self, width: usize) -> String {
+        self.render(width)
+    }
+}
+
+/// Return the first `height` lines of `rendered`, so that drawing it doesn't
+/// scroll the top of the frame off the screen, where it can't be erased.
+fn limit_lines(rendered: &str, height: usize) -> &str {
+    match rendered.match_indices('\n').nth(height.saturating_sub(1)) {
+        Some((i, _)) => &rendered[..i],
+        None => rendered,
+    }
+}
+
+/// Render the model in the form suited to `width`.
+pub(crate) fn render_at<M: Model + ?Sized>(model: &mut M, width: usize) -> String {
+    if width < model.min_width() {
+        model.render_degraded(width)
+    } else if width < model.compact_width() {
+        model.render_compact(width)
+    } else {
+        model.render(width)
+    }
 }
 
 /// A view that draws and coordinates a progress bar on the terminal.
@@ -348,12 +660,50 @@ pub trait Model {
 /// }
 ///
 /// ```
-pub struct View<M: Model> {
+pub struct View<M: Model, D: DestinationTrait = Destination> {
     /// The real state of the view.
     ///
     /// The contents are always Some unless the View has been explicitly destroyed,
-    /// in which case this makes Drop a no-op.
-    inner: Mutex<Option<InnerView<M>>>,
+    /// in which case this makes Drop a no-op, or unless it was made by
+    /// [View::new_lazy] and hasn't been used yet.
+    inner: Mutex<Option<InnerView<M, D>>>,
+
+    /// Messages written while this thread held the inner lock, for example
+    /// from inside [Model::render], to be written once the lock is released.
+    deferred: Mutex<Vec<u8>>,
+
+    /// What's drawn on the terminal, readable without taking the lock.
+    drawn: emergency::DrawnState,
+
+    /// Terminal modes changed by the view, readable without taking the lock.
+    guard: terminal::Guard,
+
+    /// While the view is registered by [View::install_signal_cleanup], the
+    /// slot that holds its drawn state and guard in place of `drawn` and
+    /// `guard`.
+    signal_slot: signal::SlotIndex,
+
+    /// For views made by [View::new_lazy], how to build the inner view on
+    /// first use.
+    lazy: Option<LazyInit<M, D>>,
+
+    /// Set by [View::request_cancellation].
+    cancelled: AtomicBool,
+
+    /// When an update might next paint, readable without taking the lock.
+    next_paint: NextPaint,
+
+    /// The [Options::clock] the view was constructed with, used to check
+    /// `next_paint` without taking the lock.
+    clock: &'static dyn Clock,
+}
+
+/// The parts of a view constructed by [View::new_lazy] that are kept until
+/// it's first used.
+struct LazyInit<M, D> {
+    model: fn() -> M,
+    options: Options,
+    destination: fn(&Options) -> D,
 }
 
 impl<M: Model> View<M> {
@@ -372,39 +722,329 @@ impl<M: Model> View<M> {
     /// * If stdout is not a tty,
     /// * On Windows, if ANSI sequences cannot be enabled.
     /// * If the `$TERM` environment variable is `DUMB`.
+    /// * If [Options::in_captured_test] is set and the Rust test harness is
+    ///   capturing output, as it does by default under `cargo test`.
     ///
     /// This constructor arranges that output from the progress view will be
-    /// captured by the Rust test framework and not leak to stdout, but
-    /// detection of whether to show progress bars may not work correctly.
+    /// captured by the Rust test framework and not leak to stdout. To test
+    /// drawing progress bars, use [Destination::Capture].
     pub const fn new(model: M, options: Options) -> View<M> {
+        let destination = options.destination;
+        View {
+            guard: terminal::Guard::new(destination),
+            clock: options.clock,
+            inner: Mutex::new(Some(InnerView::new(model, options, destination))),
+            deferred: Mutex::new(Vec::new()),
+            drawn: emergency::DrawnState::new(),
+            signal_slot: signal::SlotIndex::new(),
+            lazy: None,
+            cancelled: AtomicBool::new(false),
+            next_paint: NextPaint::new(),
+        }
+    }
+
+    /// Construct a progress view, as for [View::new], and draw the initial
+    /// model straight away if [Options::paint_on_create] is set.
+    ///
+    /// Unlike [View::new] this isn't a `const fn`, so it can't be used to
+    /// construct a `static` view.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let options = Options::default().paint_on_create(true);
+    /// let view = View::start(LinearModel::new("Resolving dependencies", 3), options);
+    /// // The bar is already showing while the slow first step runs.
+    /// view.update(|model| model.increment(1));
+    /// ```
+    pub fn start(model: M, options: Options) -> View<M> {
+        View::new(model, options).paint_on_create()
+    }
+
+    /// Construct a progress view whose model is made by `model` when the view
+    /// is first used.
+    ///
+    /// This allows a `static` view with a model that can't be constructed in
+    /// a const context, for example because it holds a `String` or records
+    /// the time it was created. Nothing is drawn, and the model isn't
+    /// constructed, until the first update or message.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// static VIEW: View<LinearModel> =
+    ///     View::new_lazy(|| LinearModel::new(format!("Counting {}", "sheep"), 10), Options::new());
+    ///
+    /// VIEW.update(|model| model.increment(1));
+    /// assert_eq!(VIEW.inspect_model(|model| model.message().to_owned()), "Counting sheep");
+    /// ```
+    pub const fn new_lazy(model: fn() -> M, options: Options) -> View<M> {
+        View {
+            guard: terminal::Guard::new(options.destination),
+            clock: options.clock,
+            inner: Mutex::new(None),
+            deferred: Mutex::new(Vec::new()),
+            drawn: emergency::DrawnState::new(),
+            signal_slot: signal::SlotIndex::new(),
+            lazy: Some(LazyInit {
+                model,
+                options,
+                destination: |options| options.destination,
+            }),
+            cancelled: AtomicBool::new(false),
+            next_paint: NextPaint::new(),
+        }
+    }
+}
+
+impl<M: Model, W: std::io::Write + Send + 'static> View<M, WriterDestination<W>> {
+    /// Construct a progress view that draws to `writer`, which is `width`
+    /// columns wide.
+    ///
+    /// This is the same as [View::with_destination] with a
+    /// [WriterDestination]. Progress bars are drawn whether or not the writer
+    /// is a terminal.
+    ///
+    /// ```
+    /// use std::io::Write;
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let pty: Box<dyn Write + Send> = Box::new(std::io::sink());
+    /// let view = View::write_to(DisplayModel("working"), Options::new(), pty, 100);
+    /// view.message("hello\n");
+    /// ```
+    pub fn write_to(model: M, options: Options, writer: W, width: usize) -> Self {
+        View::with_destination(model, options, WriterDestination::new(writer, width))
+            .paint_on_create()
+    }
+}
+
+impl<M: Model, D: DestinationTrait> View<M, D> {
+    /// Construct a new progress view drawing to a destination of a specific type.
+    ///
+    /// The view is generic over the type of the destination, so writes to it
+    /// are statically dispatched and can be inlined, which may help performance
+    /// sensitive applications. The [Options::destination] setting is ignored.
+    ///
+    /// A [Destination] can also be passed here, in which case this is the same
+    /// as [View::new] with that destination.
+    ///
+    /// ```
+    /// use std::io;
+    /// use std::sync::Mutex;
+    /// use nutmeg::{DestinationTrait, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// #[derive(Debug, Default)]
+    /// struct Buffer(Mutex<String>);
+    ///
+    /// impl DestinationTrait for Buffer {
+    ///     fn init(&self) -> bool { true }
+    ///     fn width(&self) -> Option<usize> { Some(80) }
+    ///     fn write(&self, buf: &str) -> io::Result<()> {
+    ///         self.0.lock().unwrap().push_str(buf);
+    ///         Ok(())
+    ///     }
+    ///     fn flush(&self) -> io::Result<()> { Ok(()) }
+    /// }
+    ///
+    /// let view: View<_, Buffer> =
+    ///     View::with_destination(DisplayModel("hi"), Options::new(), Buffer::default());
+    /// view.message("hello\n");
+    /// ```
+    pub const fn with_destination(model: M, options: Options, destination: D) -> View<M, D> {
         View {
-            inner: Mutex::new(Some(InnerView::new(model, options))),
+            guard: terminal::Guard::without_fd(),
+            clock: options.clock,
+            inner: Mutex::new(Some(InnerView::new(model, options, destination))),
+            deferred: Mutex::new(Vec::new()),
+            drawn: emergency::DrawnState::new(),
+            signal_slot: signal::SlotIndex::new(),
+            lazy: None,
+            cancelled: AtomicBool::new(false),
+            next_paint: NextPaint::new(),
+        }
+    }
+
+    /// What's drawn on the terminal, from the signal slot if the view is
+    /// registered.
+    fn drawn(&self) -> &emergency::DrawnState {
+        self.signal_slot
+            .get()
+            .map_or(&self.drawn, |slot| &slot.drawn)
+    }
+
+    /// Terminal modes changed by the view, from the signal slot if the view
+    /// is registered.
+    fn guard(&self) -> &terminal::Guard {
+        self.signal_slot
+            .get()
+            .map_or(&self.guard, |slot| &slot.guard)
+    }
+
+    /// Lock the inner view to change its signal registration, unless this
+    /// thread already holds the lock.
+    fn lock_for_signal_slot(&self) -> Option<MutexGuard<'_, Option<InnerView<M, D>>>> {
+        if reentry::is_locked_by_current_thread(self.key()) {
+            None
+        } else {
+            Some(self.inner.lock().unwrap_or_else(PoisonError::into_inner))
         }
     }
 
+    /// A key identifying this view while it's borrowed.
+    fn key(&self) -> usize {
+        self as *const View<M, D> as usize
+    }
+
     /// Call this function on the locked inner view.
     ///
-    /// If the view has been destroyed, do nothing.
-    fn call_inner<F, R>(&self, f: F) -> R
+    /// `method` names the public method being called, for diagnostics.
+    ///
+    /// Panics if this thread already holds the lock, for example if `update`
+    /// is called from inside an update callback, since otherwise this
+    /// would deadlock.
+    fn call_inner<F, R>(&self, method: &str, f: F) -> R
+    where
+        F: FnOnce(&mut InnerView<M, D>) -> R,
+    {
+        match self.try_call_inner(method, f) {
+            Ok(r) => r,
+            Err(Error::Poisoned) => panic!("View mutex is not poisoned"),
+            Err(err) => panic!("{err}"),
+        }
+    }
+
+    /// Call this function on the locked inner view, or return
+    /// [Error::Poisoned] if another thread panicked while holding the lock.
+    ///
+    /// Panics if this thread already holds the lock, as for
+    /// [View::call_inner].
+    fn try_call_inner<F, R>(&self, method: &str, f: F) -> Result<R, Error>
+    where
+        F: FnOnce(&mut InnerView<M, D>) -> R,
+    {
+        self.check_not_reentrant(method);
+        let mut guard = self.inner.lock().map_err(|_| Error::Poisoned)?;
+        self.initialize_lazy(&mut guard);
+        if let Some(inner) = guard.as_mut() {
+            inner.check_options()?;
+        }
+        Ok(self.call_locked(guard, f))
+    }
+
+    /// Panic if this thread already holds the lock, naming the public
+    /// `method` being called.
+    fn check_not_reentrant(&self, method: &str) {
+        if reentry::is_locked_by_current_thread(self.key()) {
+            panic!(
+                "nutmeg::View::{method} called reentrantly from inside an update \
+                callback or Model::render on the same thread, which would deadlock"
+            );
+        }
+    }
+
+    /// Draw the initial model, if [Options::paint_on_create] is set.
+    fn paint_on_create(self) -> Self {
+        self.call_inner("paint_on_create", |v| {
+            if v.options.paint_on_create {
+                v.paint_progress().expect("paint_progress succeeds");
+            }
+        });
+        self
+    }
+
+    /// Call this function on the inner view, given the guard on its lock.
+    ///
+    /// Any messages deferred while `f` runs are written after it returns.
+    fn call_locked<F, R>(&self, mut guard: MutexGuard<Option<InnerView<M, D>>>, f: F) -> R
     where
-        F: FnOnce(&mut InnerView<M>) -> R,
+        F: FnOnce(&mut InnerView<M, D>) -> R,
     {
-        f(self
-            .inner
-            .lock()
-            .expect("View mutex is not poisoned")
-            .as_mut()
-            .expect("View is not already destroyed"))
+        self.initialize_lazy(&mut guard);
+        let inner = guard.as_mut().expect("View is not already destroyed");
+        self.notice_emergency_restore(inner);
+        if let Err(err) = inner.check_options() {
+            panic!("{err}");
+        }
+        let r = {
+            let _mark = reentry::LockMark::new(self.key());
+            let _scope = ModelScope::enter(&inner.options);
+            f(inner)
+        };
+        let deferred = std::mem::take(&mut *self.deferred.lock().expect("lock deferred"));
+        if !deferred.is_empty() {
+            inner.write(&deferred).expect("write deferred message");
+        }
+        match inner.state {
+            State::ProgressDrawn { cursor_y, .. } => self.drawn().set_drawn(cursor_y),
+            _ => self.drawn().set_clear(),
+        }
+        self.guard().set_fd(inner.fd());
+        self.guard().set_modes(inner.modes);
+        let (next_paint, resize_generation) = if std::ptr::eq(self.clock, inner.options.clock) {
+            inner.paint_schedule()
+        } else {
+            (PaintTime::Unknown, 0)
+        };
+        self.next_paint.store(next_paint, resize_generation);
+        r
+    }
+
+    /// If the progress bar was erased by [View::emergency_restore], perhaps
+    /// from a signal handler, since the view was last used, tell the inner
+    /// view that it's no longer on the screen.
+    fn notice_emergency_restore(&self, inner: &mut InnerView<M, D>) {
+        if !self.drawn().is_drawn() && matches!(inner.state, State::ProgressDrawn { .. }) {
+            inner.forget_drawn();
+        }
+    }
+
+    /// If this view was made by [View::new_lazy] and hasn't been used yet,
+    /// construct its model and inner view.
+    fn initialize_lazy(&self, inner: &mut Option<InnerView<M, D>>) {
+        if inner.is_none() {
+            if let Some(lazy) = &self.lazy {
+                let options = lazy.options.clone();
+                let destination = (lazy.destination)(&options);
+                *inner = Some(InnerView::new((lazy.model)(), options, destination));
+            }
+        }
+    }
+
+    /// Write a message, or, if this thread already holds the lock, defer
+    /// it until the current operation completes.
+    fn write_or_defer(&self, buf: &[u8]) -> Result<usize, Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+        if reentry::is_locked_by_current_thread(self.key()) {
+            self.deferred
+                .lock()
+                .expect("lock deferred")
+                .extend_from_slice(buf);
+            Ok(buf.len())
+        } else {
+            Ok(self.try_call_inner("write", |v| v.write(buf))??)
+        }
     }
 
     /// Extract the inner view, destroying this object: updates on it will
     /// no longer succeed.
-    fn take_inner(self) -> InnerView<M> {
-        self.inner
-            .lock()
-            .expect("View mutex is not poisoned")
-            .take()
-            .expect("View is not already destroyed")
+    fn take_inner(self) -> InnerView<M, D> {
+        let mut guard = self.inner.lock().expect("View mutex is not poisoned");
+        self.initialize_lazy(&mut guard);
+        let mut inner = guard.take().expect("View is not already destroyed");
+        drop(guard);
+        self.notice_emergency_restore(&mut inner);
+        // The inner view is now responsible for cleaning up.
+        self.drawn().set_clear();
+        self.guard().set_modes(0);
+        inner.apply_pending_updates();
+        inner
     }
 
     /// Stop using this progress view.
@@ -421,7 +1061,180 @@ impl<M: Model> View<M> {
 
     /// Erase the model from the screen (if drawn), destroy it, and return the model.
     pub fn finish(self) -> M {
-        self.take_inner().finish()
+        let cancelled = self.cancellation_requested();
+        self.take_inner().finish(cancelled)
+    }
+
+    /// Finish the view after the work succeeded.
+    ///
+    /// This is the same as [View::finish], printing the [Model::final_message],
+    /// except that it also rings the terminal bell if [Options::finish_bell] is set.
+    pub fn finish_success(self) -> M {
+        let mut inner = self.take_inner();
+        let message = {
+            let _scope = ModelScope::enter(&inner.options);
+            inner.model.final_message()
+        };
+        inner.finish_with(message, Outcome::Success)
+    }
+
+    /// Finish the view after the work failed.
+    ///
+    /// The progress bar is erased and, instead of the [Model::final_message],
+    /// the [Model::failure_message] for `error` is printed. The terminal bell
+    /// is rung if [Options::finish_bell] is set.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let view = View::new(LinearModel::new("Files copied", 10), Options::default());
+    /// match std::fs::read("/nonexistent") {
+    ///     Ok(_) => drop(view.finish_success()),
+    ///     Err(err) => drop(view.finish_failure(&err)),
+    /// }
+    /// ```
+    pub fn finish_failure<E: Display + ?Sized>(self, error: &E) -> M {
+        let mut inner = self.take_inner();
+        let message = {
+            let _scope = ModelScope::enter(&inner.options);
+            inner.model.failure_message(&error)
+        };
+        inner.finish_with(message, Outcome::Failure)
+    }
+
+    /// Ask the work shown by this view to stop.
+    ///
+    /// This only sets a flag, which the application's work loop can check
+    /// with [View::cancellation_requested]. When the view is then finished by
+    /// [View::finish] or dropped, the [Model::cancelled_message] is printed
+    /// instead of the final message.
+    ///
+    /// This doesn't lock or allocate, so it's safe to call from a signal
+    /// handler. To cancel every view in the process, use [request_cancellation].
+    pub fn request_cancellation(&self) {
+        self.cancelled.store(true, Ordering::SeqCst);
+    }
+
+    /// True if cancellation was requested for this view by
+    /// [View::request_cancellation], or for the whole process by
+    /// [request_cancellation], typically from a Ctrl-C handler.
+    ///
+    /// Worker loops that already use the view each iteration can check this to
+    /// stop early, without keeping a separate flag.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let options = Options::default().destination(Destination::Capture);
+    /// let view = View::new(LinearModel::new("Files", 100), options);
+    /// let output = view.captured_output();
+    /// for i in 0..100 {
+    ///     if view.cancellation_requested() {
+    ///         break;
+    ///     }
+    ///     if i == 5 {
+    ///         view.request_cancellation();
+    ///     }
+    ///     view.update(|model| model.increment(1));
+    /// }
+    /// view.finish();
+    /// assert!(output.lock().unwrap().ends_with("cancelled\n"));
+    /// ```
+    pub fn cancellation_requested(&self) -> bool {
+        self.cancelled.load(Ordering::SeqCst) || cancel::is_requested()
+    }
+
+    /// Erase the progress bar and restore the terminal, without locking
+    /// or allocating.
+    ///
+    /// This writes only a precomputed sequence to move to the top of the
+    /// progress bar, clear it, and undo the terminal modes the view changed
+    /// (as recorded in its [terminal::Guard]), using a raw `write(2)` call on the destination's file descriptor.
+    /// It is intended to be called from signal handlers or custom panic hooks,
+    /// where the view's lock might already be held and so other methods
+    /// could deadlock.
+    ///
+    /// If no progress bar is currently drawn, or the destination is
+    /// [Destination::Capture], this does nothing.
+    ///
+    /// If the view is used again afterwards, the progress bar is drawn afresh
+    /// below the cursor. On platforms other than Unix this uses the standard
+    /// library's locked streams, and so is suitable for panic hooks but not
+    /// for signal handlers.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    ///
+    /// struct Model(usize);
+    /// impl nutmeg::Model for Model {
+    ///     fn render(&mut self, _width: usize) -> String {
+    ///         format!("{} done", self.0)
+    ///     }
+    /// }
+    ///
+    /// static VIEW: View<Model> = View::new(Model(0), Options::new());
+    ///
+    /// let default_hook = std::panic::take_hook();
+    /// std::panic::set_hook(Box::new(move |info| {
+    ///     VIEW.emergency_restore();
+    ///     default_hook(info);
+    /// }));
+    /// VIEW.update(|model| model.0 += 1);
+    /// ```
+    pub fn emergency_restore(&self) {
+        match self.signal_slot.get() {
+            Some(slot) => slot.drawn.restore(&slot.guard),
+            None => self.drawn.restore(&self.guard),
+        }
+    }
+
+    /// Erase the progress bar and restore the terminal if the process is
+    /// killed by SIGINT or SIGTERM, such as when the user presses Ctrl-C,
+    /// until the returned [SignalCleanup] is dropped.
+    ///
+    /// The bar is also erased when the process is stopped by SIGTSTP, such as
+    /// when the user presses Ctrl-Z, so that the shell prompt isn't printed
+    /// over it. After the process continues, the bar is drawn again by the
+    /// next update, without waiting for the [Options::update_interval], or
+    /// by the next tick if the view is inside [View::with_ticker]. It's drawn
+    /// afresh, below anything the shell printed, even if another thread drew
+    /// it again just before the process stopped. Nothing is drawn from the
+    /// signal handler itself, so a view that's neither updated nor ticking
+    /// stays erased.
+    ///
+    /// The first call installs a handler for these signals. If the process
+    /// would otherwise have been terminated or stopped by the signal, the
+    /// handler cleans up every registered view, as if by
+    /// [View::emergency_restore], and then terminates or stops the process. If the application had
+    /// already installed its own handler, for example one that calls
+    /// [request_cancellation], that handler is called instead and the views
+    /// are left for the application to finish. Signals that were being ignored
+    /// are still ignored.
+    ///
+    /// Up to 8 views can be registered at once. This does nothing on
+    /// platforms other than Unix: [SignalCleanup::is_registered] says whether
+    /// the view was registered. If the [SignalCleanup] is forgotten rather
+    /// than dropped, the view stays registered until it's dropped.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let view = View::new(LinearModel::new("Files", 100), Options::default());
+    /// let _cleanup = view.install_signal_cleanup();
+    /// for _ in 0..100 {
+    ///     view.update(|model| model.increment(1));
+    /// }
+    /// ```
+    pub fn install_signal_cleanup(&self) -> SignalCleanup<'_> {
+        let _lock = self.lock_for_signal_slot();
+        if self.signal_slot.register(&self.drawn, &self.guard) {
+            SignalCleanup::new(Some(self))
+        } else {
+            SignalCleanup::new(None)
+        }
     }
 
     /// Update the model, and possibly redraw the screen to reflect the
@@ -446,29 +1259,356 @@ impl<M: Model> View<M> {
     ///
     /// The `update_fn` may return a value, and this is returned from
     /// `update`.
-    pub fn update<U, R>(&self, update_fn: U) -> R
+    ///
+    /// Every call takes the view's lock, since the model is inside it. Before
+    /// locking, the view compares [Clock::now_coarse] to an atomic record of
+    /// when the next frame is due, and if it's too soon to paint, it holds the
+    /// lock only while `update_fn` runs, skipping the other paint checks. To
+    /// update without taking the lock at all between frames, use a [Counter]
+    /// or a [SyncView].
+    ///
+    /// Calling `update` (or any other method that needs the model) from inside
+    /// `update_fn` or [Model::render] on the same thread panics, rather than
+    /// deadlocking. [View::message] may be called from those contexts.
+    pub fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        if self.next_paint.is_due(self.clock, self.drawn().is_drawn()) {
+            self.call_inner("update", |inner| inner.update(update_fn))
+                .expect("update progress view")
+        } else {
+            // It's too soon to paint, so only change the model.
+            self.call_inner("update", |inner| inner.update_without_paint(update_fn))
+        }
+    }
+
+    /// Update the model, and possibly redraw the screen, returning an error
+    /// rather than panicking if it can't be drawn.
+    ///
+    /// This is the same as [View::update], except that if writing to the
+    /// destination fails, or another thread panicked while using the view,
+    /// it returns an [Error]. If the error comes from drawing, `update_fn`
+    /// has already been applied to the model.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// fn copy_files(view: &View<LinearModel>) -> Result<(), nutmeg::Error> {
+    ///     for _ in 0..10 {
+    ///         view.try_update(|model| model.increment(1))?;
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// let view = View::new(LinearModel::new("Copying", 10), Options::default());
+    /// copy_files(&view).unwrap();
+    /// ```
+    pub fn try_update<U, R>(&self, update_fn: U) -> Result<R, Error>
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        Ok(self.try_call_inner("try_update", |inner| inner.update(update_fn))??)
+    }
+
+    /// Update the model, allowing the update to be replaced by a later one
+    /// with the same key if no frame is drawn in between.
+    ///
+    /// This is useful when several tasks each send a stream of updates, such
+    /// as the name of the file each is working on. If a frame is due, all
+    /// pending updates are applied in order and the frame is drawn. Otherwise,
+    /// `update_fn` is held, replacing any pending update with the same key, so
+    /// that only the last update per key between frames does any work.
+    ///
+    /// Pending updates are applied before the model is used in any other way,
+    /// for example by [View::update], [View::inspect_model], or when the view
+    /// is finished, so they're never lost except by being replaced. While
+    /// progress is disabled, or the view is suspended, no frame is ever due,
+    /// so pending updates wait for one of those calls.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::StringPair;
+    ///
+    /// let view = View::new(StringPair::new("Processing ", ""), Options::default());
+    /// for file in ["a.txt", "b.txt", "c.txt"] {
+    ///     view.update_keyed("worker-1", move |model| model.set_suffix(file));
+    /// }
+    /// assert_eq!(view.inspect_model(|model| model.suffix().to_owned()), "c.txt");
+    /// ```
+    pub fn update_keyed<U>(&self, key: &str, update_fn: U)
+    where
+        U: FnOnce(&mut M) + Send + 'static,
+    {
+        self.call_inner("update_keyed", |inner| {
+            inner.update_keyed(key, Box::new(update_fn))
+        })
+        .expect("update progress view")
+    }
+
+    /// Make a [Counter] that threads can increment without locking the view.
+    ///
+    /// Each time the view is about to use the model, such as to paint it, the
+    /// count accumulated since the last time is passed to `apply` along with
+    /// the model, and the count is reset.
+    ///
+    /// With [CounterMode::Sharded], each thread increments its own cell, so
+    /// that many threads counting very small pieces of work don't all contend
+    /// for the view's lock, or for one atomic value.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{CounterMode, Options, View};
+    ///
+    /// let view = View::new(LinearModel::new("Hashing blocks", 8000), Options::default());
+    /// let counter = view.counter(CounterMode::Sharded, |model, n| model.increment(n as usize));
+    /// view.with_ticker(|| {
+    ///     std::thread::scope(|scope| {
+    ///         for _ in 0..8 {
+    ///             scope.spawn(|| {
+    ///                 for _ in 0..1000 {
+    ///                     counter.increment(1);
+    ///                 }
+    ///             });
+    ///         }
+    ///     })
+    /// });
+    /// assert_eq!(view.finish().done(), 8000);
+    /// ```
+    pub fn counter<F>(&self, mode: CounterMode, apply: F) -> Counter
+    where
+        F: FnMut(&mut M, u64) + Send + 'static,
+    {
+        let cells = Arc::new(Cells::new(mode));
+        self.call_inner("counter", |inner| {
+            inner.counters.push((Arc::clone(&cells), Box::new(apply)))
+        });
+        Counter::new(cells)
+    }
+
+    /// Update the model if the view is not currently locked, and otherwise
+    /// return immediately.
+    ///
+    /// This is the same as [View::update] except that, if the view is locked
+    /// by another thread or by an enclosing call on this thread, it returns an
+    /// error of kind [std::io::ErrorKind::WouldBlock] rather than waiting, and
+    /// `update_fn` is not called.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let view = View::new(LinearModel::new("Things done", 100), Options::default());
+    /// view.update(|model| {
+    ///     model.increment(1);
+    ///     let err = view.try_update_nonblocking(|model| model.increment(1)).unwrap_err();
+    ///     assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+    /// });
+    /// assert_eq!(view.try_update_nonblocking(|model| model.done()).unwrap(), 1);
+    /// ```
+    pub fn try_update_nonblocking<U, R>(&self, update_fn: U) -> std::io::Result<R>
     where
         U: FnOnce(&mut M) -> R,
     {
-        self.call_inner(|inner| inner.update(update_fn))
+        match self.inner.try_lock() {
+            Ok(guard) => self.call_locked(guard, |inner| inner.update(update_fn)),
+            Err(TryLockError::WouldBlock) => Err(std::io::Error::new(
+                std::io::ErrorKind::WouldBlock,
+                "nutmeg::View is locked",
+            )),
+            Err(TryLockError::Poisoned(_)) => panic!("View mutex is poisoned"),
+        }
+    }
+
+    /// Draw `frame` in place of the progress bar, without calling the model.
+    ///
+    /// This is an escape hatch for applications that generate their progress
+    /// frames some other way, but still want the view to erase and redraw them
+    /// around messages, and to clean up when it's finished.
+    ///
+    /// The frame is drawn immediately, regardless of the update interval, but
+    /// otherwise it's treated like a frame rendered by the model: its lines are
+    /// truncated to the terminal width, it's not drawn while the view is
+    /// suspended or if progress is disabled, and it's erased before messages
+    /// are printed. If the model is later updated, the model's rendering
+    /// replaces the frame.
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Destination, Options, View};
+    ///
+    /// let options = Options::default().destination(Destination::Capture);
+    /// let view = View::new(DisplayModel("unused"), options);
+    /// let output = view.captured_output();
+    /// view.paint_raw("frame from elsewhere");
+    /// assert!(output.lock().unwrap().ends_with("\x1b[0Jframe from elsewhere"));
+    /// ```
+    pub fn paint_raw(&self, frame: &str) {
+        self.call_inner("paint_raw", |v| {
+            v.paint_raw(frame).expect("paint_raw succeeds")
+        })
+    }
+
+    /// Run `f` while a background thread repaints the progress bar every
+    /// [Options::tick_interval], and return its result.
+    ///
+    /// Normally the bar is only repainted when the model is updated, so times
+    /// and spinners shown by the model freeze while the application is busy
+    /// in a long operation between updates. The ticker repaints the current
+    /// model, subject to the usual [Options::update_interval], so that they
+    /// keep moving.
+    ///
+    /// The thread is stopped and joined before this returns, including if
+    /// `f` panics. Errors writing to the destination from the ticker are
+    /// ignored: they'll normally also be seen by the next update.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::UnboundedModel;
+    ///
+    /// let options = Options::default().tick_interval(Duration::from_millis(100));
+    /// let view = View::new(UnboundedModel::new("Fetching"), options);
+    /// let fetched = view.with_ticker(|| {
+    ///     // A slow operation with no updates, during which the elapsed time
+    ///     // keeps counting.
+    ///     std::thread::sleep(Duration::from_millis(300));
+    ///     view.update(|model| model.increment(1));
+    ///     42
+    /// });
+    /// assert_eq!(fetched, 42);
+    /// ```
+    pub fn with_ticker<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+        Self: Sync,
+    {
+        let tick_interval = self.call_inner("with_ticker", |v| {
+            v.ticker_started = true;
+            if v.options.fake_clock {
+                if let Err(err) = v.report_warnings(vec![OptionsWarning::TickerWithFakeClock]) {
+                    panic!("{err}");
+                }
+            }
+            v.options.tick_interval
+        });
+        let (stop, stopped) = mpsc::channel::<()>();
+        std::thread::scope(|scope| {
+            scope.spawn(move || {
+                while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(tick_interval)
+                {
+                    // Errors writing the frame are ignored, and a poisoned
+                    // view stops the ticker, so that this thread never panics
+                    // and loses the result of `f`.
+                    if self.try_call_inner("with_ticker", |v| v.tick()).is_err() {
+                        break;
+                    }
+                }
+            });
+            let r = f();
+            drop(stop);
+            r
+        })
     }
 
     /// Hide the progress bar if it's currently drawn, and leave it
     /// hidden until [View::resume] is called.
     pub fn suspend(&self) {
-        self.call_inner(|v| v.suspend().expect("suspend succeeds"))
+        self.call_inner("suspend", |v| v.suspend().expect("suspend succeeds"))
     }
 
     /// Remove the progress bar if it's currently drawn, but allow it
     /// to be redrawn when the model is next updated.
     pub fn clear(&self) {
-        self.call_inner(|v| v.clear().expect("clear succeeds"))
+        self.call_inner("clear", |v| v.clear().expect("clear succeeds"))
+    }
+
+    /// Tell the view that something else may have written over the progress
+    /// bar, so that the next update redraws all of it.
+    ///
+    /// This is useful when the application knows another component wrote to
+    /// the terminal without going through the view, for example a password
+    /// prompt from a subprocess. The next update erases everything from the
+    /// top of the bar down, and draws the whole bar again, even if the model
+    /// hasn't changed and even if the bar was drawn within the
+    /// [Options::update_interval].
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Destination, Options, View};
+    ///
+    /// let options = Options::default()
+    ///     .destination(Destination::Capture)
+    ///     .update_interval(Duration::from_secs(3600));
+    /// let view = View::new(DisplayModel("waiting"), options);
+    /// let events = view.captured_events();
+    /// view.update(|_| ());
+    /// // ... a subprocess prompts on the terminal ...
+    /// view.invalidate();
+    /// view.update(|_| ());
+    /// assert_eq!(events.lock().unwrap().iter().filter(|e| e.is_frame()).count(), 2);
+    /// ```
+    pub fn invalidate(&self) {
+        self.call_inner("invalidate", |v| v.invalidate())
     }
 
     /// Allow the progress bar to be drawn again, reversing the effect
     /// of [View::suspend].
     pub fn resume(&self) {
-        self.call_inner(|v| v.resume().expect("resume succeeds"))
+        self.call_inner("resume", |v| v.resume().expect("resume succeeds"))
+    }
+
+    /// Replace the view's options, for example to use a short update interval
+    /// during a phase of work with a lot of animation, and a longer one
+    /// during steady bulk work.
+    ///
+    /// Everything in `options` takes effect except for where output is
+    /// written and how time is measured: the destination,
+    /// [Options::also_emit], [Options::json_summary], the clock, and whether
+    /// the clock is fake are kept from the view's current options. If the new
+    /// options change how the progress bar is drawn, it's erased first and
+    /// drawn again on the next update.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let options = Options::default();
+    /// let view = View::new(LinearModel::new("Resolving", 10), options.clone());
+    /// view.replace_options(options.clone().update_interval(Duration::from_millis(20)));
+    /// for _ in 0..10 {
+    ///     view.update(|model| model.increment(1));
+    /// }
+    /// view.replace_options(options.update_interval(Duration::from_secs(1)));
+    /// ```
+    pub fn replace_options(&self, options: Options) {
+        self.call_inner("replace_options", |v| v.replace_options(options))
+    }
+
+    /// Change how often the view repaints, from a [Pace] preset, as for
+    /// [Options::pace].
+    ///
+    /// A ticker that's already running, from [View::with_ticker], keeps its
+    /// original interval.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, Pace, View};
+    ///
+    /// let view = View::new(LinearModel::new("Indexing", 1000), Options::default());
+    /// view.set_pace(Pace::Interactive);
+    /// view.update(|model| model.increment(10));
+    /// // Now settle in for the long haul.
+    /// view.set_pace(Pace::Background);
+    /// ```
+    pub fn set_pace(&self, pace: Pace) {
+        self.call_inner("set_pace", |v| {
+            v.options = v.options.clone().pace(pace);
+            v.recompute_intervals();
+        })
     }
 
     /// Set the value of the fake clock, for testing.
@@ -477,7 +1617,100 @@ impl<M: Model> View<M> {
     ///
     /// Moving the clock backwards in time may cause a panic.
     pub fn set_fake_clock(&self, fake_clock: Instant) {
-        self.call_inner(|v| v.set_fake_clock(fake_clock))
+        self.call_inner("set_fake_clock", |v| v.set_fake_clock(fake_clock))
+    }
+
+    /// Lock the model for updating, and return a guard through which it can be
+    /// changed.
+    ///
+    /// This is an alternative to [View::update] for code that needs to hold
+    /// the model across control flow that's awkward inside a closure, such as
+    /// `?` or early returns. When the guard is dropped, the progress bar is
+    /// repainted if it's time to, just as at the end of an update.
+    ///
+    /// While the guard is held, the view is locked: other threads updating it
+    /// will wait, and calling the view's other methods from the same thread
+    /// panics, except that messages are held until the guard is dropped.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// fn copy(view: &View<LinearModel>, names: &[&str]) -> std::io::Result<()> {
+    ///     let mut model = view.model_mut();
+    ///     for name in names {
+    ///         if name.is_empty() {
+    ///             return Err(std::io::ErrorKind::InvalidInput.into());
+    ///         }
+    ///         model.increment(1);
+    ///     }
+    ///     Ok(())
+    /// }
+    ///
+    /// let view = View::new(LinearModel::new("Copying", 2), Options::default());
+    /// copy(&view, &["a.txt", "b.txt"]).unwrap();
+    /// assert_eq!(view.inspect_model(|model| model.done()), 2);
+    /// ```
+    pub fn model_mut(&self) -> ModelGuard<'_, M, D> {
+        ModelGuard::new(self)
+    }
+
+    /// Add a task, which is drawn as a line below the model until the
+    /// returned handle is finished or dropped.
+    ///
+    /// Tasks suit work split across threads or jobs, where each should show
+    /// its own count and status, without the model keeping a list of them.
+    /// Each active task is drawn on its own line, in the order they were
+    /// added, as its name, its count, and its message, like
+    /// `fetch: 12 resolving deltas`. When a task finishes, the view can print
+    /// a summary of it above the bar, as set by [Options::on_task_finish].
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let view = View::new(DisplayModel("Downloading"), Options::default());
+    /// std::thread::scope(|scope| {
+    ///     for name in ["alpha", "beta", "gamma"] {
+    ///         let view = &view;
+    ///         scope.spawn(move || {
+    ///             let task = view.add_task(name);
+    ///             for _ in 0..10 {
+    ///                 task.set_message("fetching");
+    ///                 task.increment(1);
+    ///             }
+    ///         });
+    ///     }
+    /// });
+    /// assert_eq!(view.task_count(), 0);
+    /// view.finish();
+    /// ```
+    pub fn add_task<S: Into<Cow<'static, str>>>(&self, name: S) -> TaskHandle<'_, M, D> {
+        TaskHandle::new(self, name.into(), None)
+    }
+
+    /// Add a group of tasks, drawn below the ungrouped tasks as a summary
+    /// line followed, if there's room, by its members.
+    ///
+    /// Tasks are added to the group by [TaskGroup::add_task]. See [TaskGroup].
+    pub fn add_task_group<S: Into<Cow<'static, str>>>(&self, name: S) -> TaskGroup<'_, M, D> {
+        TaskGroup::new(self, name.into())
+    }
+
+    /// Return the number of tasks added by [View::add_task] that are not yet
+    /// complete.
+    pub fn task_count(&self) -> usize {
+        self.call_inner("task_count", |inner| inner.tasks.len())
+    }
+
+    /// Change the tasks and redraw if needed, panicking if the view can't be
+    /// drawn.
+    fn update_tasks<F, R>(&self, method: &str, f: F) -> R
+    where
+        F: FnOnce(&mut Tasks) -> R,
+    {
+        self.call_inner(method, |inner| inner.update_tasks(f))
+            .expect("update progress view")
     }
 
     /// Inspect the view's model.
@@ -499,7 +1732,10 @@ impl<M: Model> View<M> {
     where
         F: FnOnce(&mut M) -> R,
     {
-        self.call_inner(|v| f(&mut v.model))
+        self.call_inner("inspect_model", |v| {
+            v.apply_pending_updates();
+            f(&mut v.model)
+        })
     }
 
     /// Print a message to the view.
@@ -513,6 +1749,10 @@ impl<M: Model> View<M> {
     ///
     /// Typically the message should end with `\n`.
     ///
+    /// It's safe to call `message` from inside the callback passed to
+    /// [View::update], or from [Model::render]: the message is held
+    /// until that operation completes, and then printed.
+    ///
     /// If the last character of the message is *not* `\n` then the incomplete
     /// line remains on the terminal, and the progress bar will not be painted
     /// until it is completed by a message finishing in `\n`.
@@ -541,6 +1781,71 @@ impl<M: Model> View<M> {
         self.message_bytes(message.as_ref().as_bytes())
     }
 
+    /// Print a message with a level, which the [Options::message_filters]
+    /// can use to decide whether to print it, for example with [MinLevel].
+    ///
+    /// Otherwise this is the same as [View::message]. If called from inside an
+    /// update callback or [Model::render], the message is held until that
+    /// operation completes, and then filtered without its level.
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{MessageLevel, Options, View};
+    ///
+    /// let view = View::new(DisplayModel("working"), Options::default());
+    /// view.message_at(MessageLevel::Warn, "disk is nearly full\n");
+    /// ```
+    pub fn message_at<S: AsRef<str>>(&self, level: MessageLevel, message: S) {
+        let message = message.as_ref().as_bytes();
+        if message.is_empty() {
+            return;
+        }
+        if reentry::is_locked_by_current_thread(self.key()) {
+            self.write_or_defer(message).expect("write message");
+        } else {
+            self.call_inner("message", |inner| inner.write_message(message, Some(level)))
+                .expect("write message");
+        }
+    }
+
+    /// Print a line permanently above the progress bar, and redraw the bar
+    /// straight away.
+    ///
+    /// This is useful for leaving a record of each item as it's completed,
+    /// while the bar keeps running below. Unlike [View::message], the bar is
+    /// redrawn immediately rather than after [Options::print_holdoff], and the
+    /// line is terminated by a newline if it isn't already. Like any message,
+    /// the line remains on the screen when the view is finished.
+    ///
+    /// If called from inside an update callback or [Model::render], the line
+    /// is held until that operation completes, and then printed like a message.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let options = Options::default().destination(Destination::Capture);
+    /// let view = View::new(LinearModel::new("Files", 3), options);
+    /// let output = view.captured_output();
+    /// for name in ["a.txt", "b.txt", "c.txt"] {
+    ///     view.update(|model| model.increment(1));
+    ///     view.freeze_line(format!("copied {name}"));
+    /// }
+    /// view.finish();
+    /// assert!(output.lock().unwrap().contains("copied c.txt\n"));
+    /// ```
+    pub fn freeze_line<S: AsRef<str>>(&self, text: S) {
+        let mut line = text.as_ref().trim_end_matches('\n').to_owned();
+        line.push('\n');
+        if reentry::is_locked_by_current_thread(self.key()) {
+            self.message(line);
+        } else {
+            self.call_inner("freeze_line", |v| {
+                v.freeze_line(&line).expect("freeze_line succeeds")
+            })
+        }
+    }
+
     /// Print a message from a byte buffer.
     ///
     /// This is the same as [View::message] but takes an `AsRef<[u8]>`, such as a slice.
@@ -555,13 +1860,33 @@ impl<M: Model> View<M> {
     /// view.message_bytes(b"hello crow\n");
     /// ```
     pub fn message_bytes<S: AsRef<[u8]>>(&self, message: S) {
-        self.call_inner(|v| v.write(message.as_ref()).expect("write message"));
+        self.write_or_defer(message.as_ref())
+            .expect("write message");
+    }
+
+    /// Print a message, returning an error rather than panicking if it can't
+    /// be written.
+    ///
+    /// This is the same as [View::message], except that if writing to the
+    /// destination fails, or another thread panicked while using the view,
+    /// it returns an [Error].
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// let view = View::new(DisplayModel("working"), Options::default());
+    /// view.try_message("copied a.txt\n").unwrap();
+    /// ```
+    pub fn try_message<S: AsRef<str>>(&self, message: S) -> Result<(), Error> {
+        self.write_or_defer(message.as_ref().as_bytes()).map(drop)
     }
 
-    /// If the view's destination is [Destination::Capture], returns the buffer
-    /// of captured output.
+    /// If the view's destination is [Destination::Capture] or
+    /// [Destination::CaptureBounded], returns the buffer of captured output.
     ///
-    /// Panics if the destination is not [Destination::Capture].
+    /// If the destination is [Destination::CaptureNamed], this is the same
+    /// buffer as returned by [Destination::captured_output_named].
     ///
     /// The buffer is returned in an Arc so that it remains valid after the View
     /// is dropped.
@@ -582,55 +1907,176 @@ impl<M: Model> View<M> {
     /// drop(view);
     /// assert_eq!(output.lock().unwrap().as_str(), "Captured message\n");
     /// ```
+    #[cfg(feature = "capture")]
     pub fn captured_output(&self) -> Arc<Mutex<String>> {
-        self.call_inner(|v| v.captured_output())
+        self.call_inner("captured_output", |v| v.captured_output())
+    }
+
+    /// If the view's destination is [Destination::Capture],
+    /// [Destination::CaptureNamed] or [Destination::CaptureBounded], returns a
+    /// list of what was drawn and when.
+    ///
+    /// Each [CapturedEvent] records the time it happened, according to the
+    /// fake clock if [Options::fake_clock] is enabled. This allows tests to
+    /// check when frames were drawn, for example to check rate limiting,
+    /// without comparing escape sequences in [View::captured_output].
+    ///
+    /// For other destinations, the list remains empty.
+    ///
+    /// ```
+    /// use nutmeg::{CapturedEventKind, Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let view = View::new(DisplayModel(1), Options::default().destination(Destination::Capture));
+    /// let events = view.captured_events();
+    /// view.update(|_| ());
+    /// view.message("hi\n");
+    /// drop(view);
+    /// let kinds: Vec<_> = events.lock().unwrap().iter().map(|e| e.kind.clone()).collect();
+    /// assert_eq!(kinds, [
+    ///     CapturedEventKind::Frame("1".to_owned()),
+    ///     CapturedEventKind::Erase,
+    ///     CapturedEventKind::Message("hi\n".to_owned()),
+    /// ]);
+    /// ```
+    #[cfg(feature = "capture")]
+    pub fn captured_events(&self) -> Arc<Mutex<Vec<CapturedEvent>>> {
+        self.call_inner("captured_events", |v| v.captured_events())
+    }
+
+    /// Return counters describing the work the view has done, such as the
+    /// number of frames drawn and the time spent rendering them.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let view = View::new(DisplayModel(0), Options::default().destination(Destination::Capture));
+    /// view.update(|model| model.0 += 1);
+    /// assert_eq!(view.stats().frames, 1);
+    /// ```
+    pub fn stats(&self) -> Stats {
+        self.call_inner("stats", |v| v.stats.clone())
+    }
+
+    /// Return a channel that receives every subsequent event from this view:
+    /// frames drawn, erasures, messages, and the final message.
+    ///
+    /// This works with any destination, so several observers, such as a GUI
+    /// mirror, a logger, and a metrics collector, can each watch a single view.
+    /// Frames are only sent when they're drawn, so none are sent if the
+    /// destination is not a terminal.
+    ///
+    /// Events are sent without blocking the view. Dropping the receiver
+    /// unsubscribes it.
+    ///
+    /// ```
+    /// use nutmeg::{CapturedEventKind, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let view = View::new(DisplayModel("working"), Options::default());
+    /// let events = view.subscribe();
+    /// view.message("hello\n");
+    /// let event = events.recv().unwrap();
+    /// assert!(matches!(event.kind, CapturedEventKind::Message(m) if m == "hello\n"));
+    /// ```
+    pub fn subscribe(&self) -> mpsc::Receiver<CapturedEvent> {
+        let (sender, receiver) = mpsc::channel();
+        self.call_inner("subscribe", |v| v.subscribers.push(sender));
+        receiver
     }
 }
 
-impl<M: Model> io::Write for &View<M> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        if buf.is_empty() {
-            return Ok(0);
-        }
-        self.call_inner(|v| v.write(buf))
+impl<M: Model + Clone, D: DestinationTrait> View<M, D> {
+    /// Return a copy of the view's model.
+    ///
+    /// The lock on the view is only held while the model is cloned, so a
+    /// reporting thread can compute and format summaries from the copy
+    /// without holding up threads that are updating the view.
+    ///
+    /// ```
+    /// use nutmeg::{Options, View};
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let view = View::new(LinearModel::new("Things done", 100), Options::default());
+    /// view.update(|model| model.increment(3));
+    /// let snapshot = view.snapshot_model();
+    /// assert_eq!(snapshot.done(), 3);
+    /// ```
+    pub fn snapshot_model(&self) -> M {
+        self.inspect_model(|model| model.clone())
+    }
+}
+
+impl<M: Model, D: DestinationTrait> std::io::Write for &View<M, D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.write_or_defer(buf)?)
     }
 
-    fn flush(&mut self) -> io::Result<()> {
+    fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
-impl<M: Model> io::Write for View<M> {
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
-        if buf.is_empty() {
-            return Ok(0);
-        }
-        self.call_inner(|v| v.write(buf))
+impl<M: Model, D: DestinationTrait> std::io::Write for View<M, D> {
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        Ok(self.write_or_defer(buf)?)
     }
 
-    fn flush(&mut self) -> io::Result<()> {
+    fn flush(&mut self) -> std::io::Result<()> {
         Ok(())
     }
 }
 
-impl<M: Model> Drop for View<M> {
+impl<M: Model, D: DestinationTrait> Drop for View<M, D> {
     fn drop(&mut self) {
         // Only try lock here: don't hang if it's locked or panic
         // if it's poisoned. And, do nothing if the View has already been
         // finished, in which case the contents of the Mutex will be None.
         if let Ok(mut inner_guard) = self.inner.try_lock() {
-            if let Some(inner) = Option::take(&mut inner_guard) {
-                inner.finish();
+            if let Some(mut inner) = Option::take(&mut inner_guard) {
+                inner.apply_pending_updates();
+                self.notice_emergency_restore(&mut inner);
+                self.drawn().set_clear();
+                self.guard().set_modes(0);
+                match inner.options.on_drop {
+                    DropPolicy::Erase => drop(inner.finish_with(String::new(), Outcome::Finished)),
+                    DropPolicy::Abandon => drop(inner.abandon()),
+                    DropPolicy::FinishWithMessage => {
+                        drop(inner.finish(self.cancellation_requested()))
+                    }
+                }
             }
+        } else {
+            // Perhaps an update panicked: we can't safely get the model, but
+            // can still put the terminal back in order.
+            self.emergency_restore();
         }
+        self.signal_slot.release(&self.drawn, &self.guard);
     }
 }
 
+impl<M: Model, D: DestinationTrait> signal::Register for View<M, D> {
+    fn unregister_signal_cleanup(&self) {
+        let _lock = self.lock_for_signal_slot();
+        self.signal_slot.unregister(&self.drawn, &self.guard);
+    }
+}
+
+/// A function that adds a count from a [Counter] into the model.
+type CounterApply<M> = Box<dyn FnMut(&mut M, u64) + Send>;
+
+/// An update held by [View::update_keyed].
+type PendingUpdate<M> = Box<dyn FnOnce(&mut M) + Send>;
+
 /// The real contents of a View, inside a mutex.
-struct InnerView<M: Model> {
+struct InnerView<M: Model, D: DestinationTrait> {
     /// Current application model.
     model: M,
 
+    /// Where output is written.
+    destination: D,
+
     /// True if the progress bar is suspended, and should not be drawn.
     suspended: bool,
 
@@ -642,58 +2088,330 @@ struct InnerView<M: Model> {
     /// The current time on the fake clock, if it is enabled.
     fake_clock: Option<Instant>,
 
+    /// Bitset of the [terminal::Mode]s changed on the terminal.
+    modes: u8,
+
+    /// False if the destination turned out not to be a terminal.
+    is_terminal: bool,
+
+    /// True if the secondary destination, if any, is a terminal and so
+    /// receives progress bars as well as messages.
+    also_emit_is_terminal: bool,
+
+    /// When a frame was last published to the HTTP reporter.
+    #[cfg(feature = "http")]
+    last_reported: Option<Instant>,
+
     /// Captured output, if active.
     capture_buffer: Option<Arc<Mutex<String>>>,
-}
 
-#[derive(Debug, PartialEq, Eq, Clone)]
-enum State {
-    /// Nothing has ever been painted, and the screen has not yet been initialized.
-    New,
-    /// Progress is not visible and nothing was recently printed.
-    None,
-    /// Progress bar is currently displayed.
-    ProgressDrawn {
-        /// Last time it was drawn.
-        last_drawn_time: Instant,
-        /// Number of lines the cursor is below the line where the progress bar
-        /// should next be drawn.
-        cursor_y: usize,
-        /// The rendered string last drawn.
-        last_drawn_string: String,
-    },
-    /// Messages were written, and the progress bar is not visible.
+    /// Captured events, if the destination captures output.
+    captured_events: Option<Arc<Mutex<Vec<CapturedEvent>>>>,
+
+    /// Updates from [View::update_keyed] that haven't yet been applied, in the
+    /// order they were made, with at most one per key.
+    pending_updates: Vec<(String, PendingUpdate<M>)>,
+
+    /// Counters from [View::counter], with the functions that add their
+    /// counts into the model.
+    counters: Vec<(Arc<Cells>, CounterApply<M>)>,
+
+    /// Counters returned by [View::stats].
+    stats: Stats,
+
+    /// Each line of the last rendered frame, before and after checking and
+    /// truncation, so that unchanged lines needn't be processed again.
+    line_cache: Vec<(String, String)>,
+
+    /// The width at which `line_cache` was rendered.
+    line_cache_width: usize,
+
+    /// Channels receiving a copy of every event, from [View::subscribe].
+    subscribers: Vec<mpsc::Sender<CapturedEvent>>,
+
+    /// True if frames are drawn as synchronized updates.
+    synchronized_output: bool,
+
+    /// When progress was last announced, in [Accessibility::Announce] mode.
+    last_announced: Option<Instant>,
+
+    /// When a plain progress line was last printed for [Options::fallback_interval],
+    /// or when the first update happened.
+    last_fallback: Option<Instant>,
+
+    /// The number of [FallbackInterval::Percent] steps done when a plain
+    /// progress line was last printed.
+    last_fallback_step: u64,
+
+    /// When the view was paused, to be left out of estimates of time remaining.
+    pauses: Pauses,
+
+    /// When the view was suspended, if it is.
+    suspended_at: Option<Instant>,
+
+    /// When the model was last updated, to detect stalls.
+    last_update: Option<Instant>,
+
+    /// The value of [resize::generation] when the progress bar was last drawn.
+    resize_generation: usize,
+
+    /// True if [View::invalidate] was called since the last frame was drawn.
+    invalidated: bool,
+
+    /// Tasks added by [View::add_task] and drawn below the model.
+    tasks: Tasks,
+
+    /// True once the options have been checked and any warnings reported.
+    options_checked: bool,
+
+    /// True if [View::with_ticker] ever ran on this view.
+    ticker_started: bool,
+}
+
+/// When writes to the destination are slow, the update interval and print
+/// holdoff are lengthened to at least this many times the mean write latency,
+/// so that no more than about a tenth of the time is spent waiting on writes.
+const WRITE_LATENCY_FACTOR: u32 = 10;
+
+/// Settings from the [Options] made available to the model while the view
+/// calls it, until dropped.
+struct ModelScope {
+    _formatting: FormattingScope,
+    _theme: ThemeScope,
+}
+
+impl ModelScope {
+    fn enter(options: &Options) -> ModelScope {
+        ModelScope {
+            _formatting: FormattingScope::enter(options.formatter),
+            _theme: ThemeScope::enter(options.theme, options.monochrome),
+        }
+    }
+}
+
+/// The state of the view made available to the model while it's rendered,
+/// until dropped.
+struct RenderScope {
+    _height: HeightScope,
+    _pauses: PauseScope,
+    _frame_time: FrameTimeScope,
+}
+
+impl RenderScope {
+    fn enter<M: Model, D: DestinationTrait>(view: &InnerView<M, D>, now: Instant) -> RenderScope {
+        RenderScope {
+            _height: HeightScope::enter(view.destination.height()),
+            _pauses: PauseScope::enter(&view.pauses),
+            _frame_time: FrameTimeScope::enter(now),
+        }
+    }
+}
+
+/// How a view was finished, as reported in its JSON summary.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Outcome {
+    /// Finished without saying whether the work succeeded.
+    Finished,
+    /// Finished by [View::finish_success].
+    Success,
+    /// Finished by [View::finish_failure].
+    Failure,
+    /// Finished by [View::finish] or dropped after cancellation was requested.
+    Cancelled,
+}
+
+impl Outcome {
+    fn name(self) -> &'static str {
+        match self {
+            Outcome::Finished => "finished",
+            Outcome::Success => "success",
+            Outcome::Failure => "failure",
+            Outcome::Cancelled => "cancelled",
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum State {
+    /// Nothing has ever been painted, and the screen has not yet been initialized.
+    New,
+    /// Progress is not visible and nothing was recently printed.
+    None,
+    /// Progress bar is currently displayed.
+    ProgressDrawn {
+        /// Last time it was drawn.
+        last_drawn_time: Instant,
+        /// Number of lines the cursor is below the line where the progress bar
+        /// should next be drawn.
+        cursor_y: usize,
+        /// The rendered string last drawn.
+        last_drawn_string: String,
+        /// The width of the terminal when it was drawn.
+        width: usize,
+    },
+    /// Messages were written, and the progress bar is not visible.
     Printed { last_printed: Instant },
     /// An incomplete message line has been printed, so the progress bar can't
     /// be drawn until it's removed.
     IncompleteLine,
 }
 
-impl<M: Model> InnerView<M> {
-    const fn new(model: M, options: Options) -> InnerView<M> {
+impl<M: Model, D: DestinationTrait> InnerView<M, D> {
+    const fn new(model: M, options: Options, destination: D) -> InnerView<M, D> {
         InnerView {
             capture_buffer: None,
+            captured_events: None,
+            pending_updates: Vec::new(),
+            counters: Vec::new(),
+            line_cache: Vec::new(),
+            line_cache_width: 0,
+            stats: Stats {
+                renders: 0,
+                frames: 0,
+                render_time: Duration::ZERO,
+                slowest_render: Duration::ZERO,
+                slow_renders: 0,
+                effective_update_interval: options.update_interval,
+                effective_print_holdoff: options.print_holdoff,
+                writes: 0,
+                write_time: Duration::ZERO,
+            },
+            subscribers: Vec::new(),
+            synchronized_output: false,
+            last_announced: None,
+            last_fallback: None,
+            last_fallback_step: 0,
+            pauses: Pauses::new(),
+            suspended_at: None,
+            last_update: None,
+            resize_generation: 0,
+            invalidated: false,
+            tasks: Tasks::new(),
+            options_checked: false,
+            ticker_started: false,
+            destination,
             fake_clock: None,
             model,
+            modes: 0,
+            is_terminal: true,
+            also_emit_is_terminal: false,
+            #[cfg(feature = "http")]
+            last_reported: None,
             options,
             state: State::New,
             suspended: false,
         }
     }
 
-    fn finish(mut self) -> M {
+    /// Print the final message, or the cancelled message if `cancelled`, and
+    /// finish.
+    fn finish(mut self, cancelled: bool) -> M {
+        let _scope = ModelScope::enter(&self.options);
+        if cancelled {
+            let message = self.model.cancelled_message();
+            self.finish_with(message, Outcome::Cancelled)
+        } else {
+            let message = self.model.final_message();
+            self.finish_with(message, Outcome::Finished)
+        }
+    }
+
+    /// Erase the progress bar, print a final message if it's not empty, ring
+    /// the bell if the outcome is success or failure, and write the JSON summary.
+    fn finish_with(mut self, final_message: String, outcome: Outcome) -> M {
+        self.warn_unused_tick_interval();
+        if outcome != Outcome::Cancelled {
+            self.linger().expect("draw final frame");
+        }
         let _ = self.clear();
-        let final_message = self.model.final_message();
+        self.init_destination();
+        let final_message = if final_message.is_empty() {
+            final_message
+        } else {
+            self.filter_message(final_message, None).unwrap_or_default()
+        };
+        let mut buf = String::new();
+        if !final_message.is_empty() {
+            let (marker_start, marker_end) =
+                self.markers(test_util::MESSAGE_START, test_util::MESSAGE_END);
+            buf.push_str(marker_start);
+            buf.push_str(&self.layout_final_message(&final_message));
+            self.record_event(|| CapturedEventKind::FinalMessage(final_message.clone()));
+            buf.push('\n');
+            buf.push_str(marker_end);
+        }
+        if matches!(outcome, Outcome::Success | Outcome::Failure)
+            && self.options.finish_bell
+            && self.is_terminal
+        {
+            buf.push_str(ansi::BELL);
+        }
+        if !buf.is_empty() {
+            self.write_output(&buf).expect("write final message");
+        }
         if !final_message.is_empty() {
-            self.write_output(&format!("{final_message}\n"));
+            self.write_secondary_text(&format!("{final_message}\n"))
+                .expect("write final message");
+        }
+        #[cfg(feature = "http")]
+        if let Some(reporter) = self.options.http_reporter {
+            reporter.publish_finished();
+        }
+        if let Some(destination) = self.options.json_summary {
+            let summary = self.json_summary(&final_message, outcome);
+            destination.write(&summary).expect("write JSON summary");
+            destination.flush().expect("write JSON summary");
         }
         self.model
     }
 
-    fn abandon(mut self) -> io::Result<M> {
+    /// Describe the finished view as a line of JSON, for [Options::json_summary].
+    fn json_summary(&self, final_message: &str, outcome: Outcome) -> String {
+        let mut json = String::from("{\"outcome\":");
+        push_json_string(&mut json, outcome.name());
+        json.push_str(",\"message\":");
+        push_json_string(&mut json, &ansi::strip_sequences(final_message));
+        json.push_str(",\"fraction\":");
+        push_json_fraction(&mut json, self.model.fraction_done());
+        for (name, count) in self.model.summary_counts() {
+            json.push(',');
+            push_json_string(&mut json, name);
+            json.push(':');
+            json.push_str(&count.to_string());
+        }
+        json.push_str("}\n");
+        json
+    }
+
+    /// Fit a final message to the destination.
+    ///
+    /// On a terminal, long lines are wrapped to the terminal width. Otherwise,
+    /// for example when output is redirected to a file, escape sequences are
+    /// removed, unless turned off by [Options::strip_escapes].
+    fn layout_final_message(&mut self, message: &str) -> String {
+        self.init_destination();
+        if !self.is_terminal {
+            self.plain_text(message).into_owned()
+        } else if let Some(width) = self.destination.width() {
+            width::wrap_lines(message, width)
+        } else {
+            message.to_owned()
+        }
+    }
+
+    fn abandon(mut self) -> std::io::Result<M> {
+        self.warn_unused_tick_interval();
         match self.state {
-            State::ProgressDrawn { .. } => {
-                self.write_output("\n");
+            State::ProgressDrawn {
+                cursor_y,
+                ref last_drawn_string,
+                ..
+            } => {
+                // Leave the cursor below the bar, wherever it was parked.
+                let last_line = last_drawn_string.split('\n').count() - 1;
+                let mut buf = ansi::cursor_down(last_line.saturating_sub(cursor_y)).into_owned();
+                buf.push('\n');
+                self.write_output(&buf)?;
             }
             State::New | State::IncompleteLine | State::None | State::Printed { .. } => (),
         }
@@ -703,159 +2421,1013 @@ impl<M: Model> InnerView<M> {
 
     /// Return the real or fake clock.
     fn clock(&self) -> Instant {
-        self.fake_clock.unwrap_or_else(Instant::now)
+        self.fake_clock.unwrap_or_else(|| self.options.clock.now())
     }
 
     fn init_destination(&mut self) {
         if self.state == State::New {
-            if self.options.destination.initalize().is_err() {
+            let captured = self.options.in_captured_test
+                && matches!(
+                    self.options.destination,
+                    Destination::Stdout | Destination::Stderr
+                )
+                && destination::is_test_output_captured();
+            if captured || !self.destination.init() {
                 // This destination doesn't want to draw progress bars, so stay off forever.
                 self.options.progress_enabled = false;
+                self.is_terminal = false;
+            }
+            if let Some(secondary) = self.options.also_emit {
+                self.also_emit_is_terminal = secondary.init();
             }
+            self.apply_environment();
             self.state = State::None;
         }
     }
 
-    fn paint_progress(&mut self) -> io::Result<()> {
+    /// Adjust the options for the environment: the accessibility mode
+    /// requested by environment variables, an update interval suited to the
+    /// terminal unless one was set explicitly, and whether frames are drawn
+    /// as synchronized updates.
+    fn apply_environment(&mut self) {
+        if let Some(accessibility) = Accessibility::from_env() {
+            self.options.accessibility = accessibility;
+        }
+        if self.options.auto_update_interval
+            && matches!(
+                self.builtin_destination(),
+                Some(Destination::Stdout | Destination::Stderr)
+            )
+        {
+            self.options.update_interval = TerminalContext::detect().default_update_interval();
+        }
+        self.synchronized_output = self
+            .options
+            .synchronized_output
+            .unwrap_or_else(|| self.destination.synchronized_output());
+        self.recompute_intervals();
+    }
+
+    fn replace_options(&mut self, options: Options) {
+        let old = &self.options;
+        let options = Options {
+            destination: old.destination,
+            also_emit: old.also_emit,
+            json_summary: old.json_summary,
+            clock: old.clock,
+            fake_clock: old.fake_clock,
+            ..options
+        };
+        if options.dialect != old.dialect
+            || options.toggle_line_wrap != old.toggle_line_wrap
+            || options.progress_enabled != old.progress_enabled
+            || options.accessibility != old.accessibility
+        {
+            self.clear().expect("clear succeeds");
+        }
+        self.options = options;
+        if let Err(err) =
+            self.report_warnings(self.options.warnings_for(self.builtin_destination()))
+        {
+            panic!("{err}");
+        }
+        if self.state != State::New {
+            // Keep the decisions made when the destination was initialized.
+            self.options.progress_enabled &= self.is_terminal;
+            self.apply_environment();
+        }
+    }
+
+    /// Check the options when the view is first used, as for
+    /// [InnerView::report_warnings].
+    fn check_options(&mut self) -> Result<(), Error> {
+        if self.options_checked {
+            return Ok(());
+        }
+        self.report_warnings(self.options.warnings_for(self.builtin_destination()))
+    }
+
+    /// Now that the view is finishing, warn if [Options::tick_interval] was set
+    /// but [View::with_ticker] never ran.
+    fn warn_unused_tick_interval(&mut self) {
+        if self.options.tick_interval_set && !self.ticker_started {
+            self.record_event(|| {
+                CapturedEventKind::OptionsWarning(OptionsWarning::TickIntervalWithoutTicker)
+            });
+        }
+    }
+
+    /// Send each warning as an event, or if [Options::strict] is set and there
+    /// are any, return them as an error.
+    fn report_warnings(&mut self, warnings: Vec<OptionsWarning>) -> Result<(), Error> {
+        if self.options.strict && !warnings.is_empty() {
+            return Err(Error::InvalidOptions(warnings));
+        }
+        for warning in warnings {
+            self.record_event(|| CapturedEventKind::OptionsWarning(warning));
+        }
+        self.options_checked = true;
+        Ok(())
+    }
+
+    /// How often to print plain progress lines, if they're printed instead of
+    /// drawing a progress bar, because the destination isn't a terminal or
+    /// progress bars are disabled.
+    fn fallback_interval(&self) -> Option<FallbackInterval> {
+        if self.is_terminal && self.options.progress_enabled {
+            None
+        } else {
+            self.options.fallback_interval
+        }
+    }
+
+    /// Instead of drawing a progress bar, print the model as a plain line if
+    /// enough time has passed, or enough work has been done, since the last
+    /// one, as set by [Options::fallback_interval].
+    fn print_fallback(&mut self, interval: FallbackInterval) -> std::io::Result<()> {
+        if self.state == State::IncompleteLine {
+            return Ok(());
+        }
+        let now = self.clock();
+        match interval {
+            FallbackInterval::Time(interval) => match self.last_fallback {
+                None => {
+                    self.last_fallback = Some(now);
+                    return Ok(());
+                }
+                Some(last) if now - last < interval => return Ok(()),
+                Some(_) => self.last_fallback = Some(now),
+            },
+            FallbackInterval::Percent(percent) => {
+                self.apply_pending_updates();
+                let Some(fraction) = self.model.fraction_done() else {
+                    return Ok(());
+                };
+                let step = (fraction.clamp(0.0, 1.0) * 100.0 / percent.max(1) as f64) as u64;
+                if step <= self.last_fallback_step {
+                    return Ok(());
+                }
+                self.last_fallback_step = step;
+            }
+        }
+        self.apply_pending_updates();
+        let width = self.destination.width().unwrap_or(80);
+        let scope = RenderScope::enter(self, now);
+        let mut rendered = render_at(&mut self.model, width);
+        self.tasks.append_to(&mut rendered);
+        drop(scope);
+        #[cfg(feature = "http")]
+        self.publish_http_frame(&rendered, now);
+        let mut buf = String::new();
+        for line in ansi::strip_sequences(&rendered).lines() {
+            let line = line.trim_end();
+            if !line.is_empty() {
+                buf.push_str(line);
+                buf.push('\n');
+            }
+        }
+        if !buf.is_empty() {
+            self.write_output(&buf)?;
+            self.state = State::Printed { last_printed: now };
+        }
+        Ok(())
+    }
+
+    /// In [Accessibility::Announce] mode, print the model's announcement if
+    /// enough time has passed since the last one, or since the first update.
+    fn announce(&mut self, now: Instant) -> std::io::Result<()> {
+        if self.state == State::IncompleteLine {
+            return Ok(());
+        }
+        match self.last_announced {
+            None => {
+                self.last_announced = Some(now);
+                return Ok(());
+            }
+            Some(last) if now - last < self.options.announce_interval => return Ok(()),
+            Some(_) => (),
+        }
+        self.last_announced = Some(now);
+        self.apply_pending_updates();
+        let scope = RenderScope::enter(self, now);
+        let announcement = ansi::strip_sequences(&self.model.announcement());
+        drop(scope);
+        if !announcement.is_empty() {
+            self.write_output(&format!("{announcement}\n"))?;
+            self.state = State::Printed { last_printed: now };
+        }
+        Ok(())
+    }
+
+    fn paint_progress(&mut self) -> std::io::Result<()> {
         self.init_destination();
-        if !self.options.progress_enabled || self.suspended {
+        if self.suspended {
+            return Ok(());
+        }
+        if let Some(interval) = self.fallback_interval() {
+            return self.print_fallback(interval);
+        }
+        if !self.options.progress_enabled {
             return Ok(());
         }
         let now = self.clock();
+        if self.options.accessibility == Accessibility::Announce {
+            return self.announce(now);
+        }
         match self.state {
             State::IncompleteLine => return Ok(()),
             State::New | State::None => (),
             State::Printed { last_printed } => {
-                if now - last_printed < self.options.print_holdoff {
+                if now - last_printed < self.stats.effective_print_holdoff {
                     return Ok(());
                 }
             }
             State::ProgressDrawn {
                 last_drawn_time, ..
             } => {
-                if now - last_drawn_time < self.options.update_interval {
+                let elapsed = now - last_drawn_time;
+                if elapsed < self.stats.effective_update_interval
+                    && self.resize_generation == resize::generation()
+                    && !self.invalidated
+                {
                     return Ok(());
                 }
+                if !self.options.update_interval.is_zero() {
+                    let frames = elapsed.as_nanos() / self.options.update_interval.as_nanos();
+                    let skipped = frames.saturating_sub(1);
+                    if skipped > 0 {
+                        self.model
+                            .on_skipped_frames(skipped.try_into().unwrap_or(usize::MAX));
+                    }
+                }
             }
         }
-        if let Some(width) = self.options.destination.width() {
-            let mut rendered = self.model.render(width);
+        self.draw_model(now)
+    }
+
+    /// Render the model and draw it, regardless of when it was last drawn.
+    fn draw_model(&mut self, now: Instant) -> std::io::Result<()> {
+        if let Some(width) = self.destination.width() {
+            self.apply_pending_updates();
+            let render_start = Instant::now();
+            let scope = RenderScope::enter(self, now);
+            let mut rendered = render_at(&mut self.model, width);
+            self.tasks.append_to(&mut rendered);
+            drop(scope);
+            self.account_model_render(render_start.elapsed());
+            #[cfg(feature = "http")]
+            self.publish_http_frame(&rendered, now);
             if rendered.ends_with('\n') {
                 // Handle models that incorrectly add a trailing newline, rather than
                 // leaving a blank line. (Maybe we should just let them fix it, and
                 // be simpler?)
                 rendered.pop();
             }
-            let mut buf = String::new();
-            if let State::ProgressDrawn {
+            self.draw_frame(&rendered, width, now, render_start, true)?;
+        }
+        Ok(())
+    }
+
+    /// If the bar is drawn and [Options::finish_linger] is set, draw the
+    /// final state of the model and leave it on the screen for a moment.
+    fn linger(&mut self) -> std::io::Result<()> {
+        let linger = self.options.finish_linger;
+        if linger.is_zero() || !matches!(self.state, State::ProgressDrawn { .. }) {
+            return Ok(());
+        }
+        let now = self.clock();
+        self.draw_model(now)?;
+        if !self.options.fake_clock {
+            std::thread::sleep(linger);
+        }
+        Ok(())
+    }
+
+    /// Draw a frame supplied by the application instead of rendering the model.
+    fn paint_raw(&mut self, frame: &str) -> std::io::Result<()> {
+        self.init_destination();
+        if !self.options.progress_enabled
+            || self.suspended
+            || self.state == State::IncompleteLine
+            || self.options.accessibility == Accessibility::Announce
+        {
+            return Ok(());
+        }
+        if let Some(width) = self.destination.width() {
+            let render_start = Instant::now();
+            let frame = frame.strip_suffix('\n').unwrap_or(frame);
+            self.draw_frame(frame, width, self.clock(), render_start, false)?;
+        }
+        Ok(())
+    }
+
+    /// Erase the previously drawn frame, if any, and draw `rendered` in its
+    /// place, after checking and truncating its lines to `width`.
+    ///
+    /// `from_model` is false if the frame came from [View::paint_raw] rather
+    /// than the model.
+    fn draw_frame(
+        &mut self,
+        rendered: &str,
+        width: usize,
+        now: Instant,
+        render_start: Instant,
+        from_model: bool,
+    ) -> std::io::Result<()> {
+        let rendered = match self.destination.height() {
+            Some(height) => limit_lines(rendered, height),
+            None => rendered,
+        };
+        let rendered = self.process_lines(rendered, width, from_model);
+        self.resize_generation = resize::generation();
+        self.invalidated = false;
+        let (marker_start, marker_end) = self.markers(test_util::FRAME_START, test_util::FRAME_END);
+        let mut buf = marker_start.to_owned();
+        if self.synchronized_output {
+            buf.push_str(ansi::BEGIN_SYNCHRONIZED_UPDATE);
+        }
+        // Index of the first line to redraw.
+        let mut first_changed = 0;
+        if let Some(lines) = self.reflowed_lines_above_cursor(width) {
+            // The terminal got narrower, so lines of the old frame may have
+            // wrapped: erase all of them and redraw everything.
+            buf.push_str(&self.options.dialect.up_n_lines_and_home(lines));
+        } else if let State::ProgressDrawn {
+            ref last_drawn_string,
+            cursor_y,
+            ..
+        } = self.state
+        {
+            if *last_drawn_string == rendered {
+                self.account_render(render_start.elapsed(), false);
+                return Ok(());
+            }
+            // Lines above the first change are left alone on the screen, which
+            // saves a lot of output for large frames where few lines change.
+            let line_count = rendered.split('\n').count();
+            first_changed = rendered
+                .split('\n')
+                .zip(last_drawn_string.split('\n'))
+                .take_while(|(new, old)| new == old)
+                .count()
+                .min(line_count - 1)
+                .min(cursor_y);
+            buf.push_str(
+                &self
+                    .options
+                    .dialect
+                    .up_n_lines_and_home(cursor_y - first_changed),
+            );
+        } else if let Some(reserve) = self.lines_to_reserve(from_model) {
+            // Move down and back up, scrolling the terminal if needed, so that
+            // the frame can grow without scrolling.
+            buf.push_str(&"\n".repeat(reserve));
+            buf.push_str(&self.options.dialect.up_n_lines_and_home(reserve));
+        }
+        if self.toggles_line_wrap() {
+            buf.push_str(Mode::LineWrapDisabled.set_sequence());
+            self.modes |= Mode::LineWrapDisabled.bit();
+        }
+        buf.push_str(self.options.dialect.clear_to_end_of_screen());
+        let changed_start = rendered
+            .split('\n')
+            .take(first_changed)
+            .map(|line| line.len() + 1)
+            .sum::<usize>();
+        buf.push_str(&rendered[changed_start..]);
+        let last_line = rendered.as_bytes().iter().filter(|b| **b == b'\n').count();
+        let cursor_y = match self.options.cursor_park {
+            CursorPark::EndOfBar => last_line,
+            CursorPark::StartOfBar => {
+                buf.push_str(&self.options.dialect.up_n_lines_and_home(last_line));
+                0
+            }
+            CursorPark::BottomLeft => {
+                buf.push_str(&self.options.dialect.up_n_lines_and_home(0));
+                last_line
+            }
+        };
+        if self.synchronized_output {
+            buf.push_str(ansi::END_SYNCHRONIZED_UPDATE);
+        }
+        buf.push_str(marker_end);
+        self.write_output(&buf)?;
+        self.record_event(|| CapturedEventKind::Frame(rendered.clone()));
+        self.account_render(render_start.elapsed(), true);
+        self.state = State::ProgressDrawn {
+            last_drawn_time: now,
+            last_drawn_string: rendered,
+            cursor_y,
+            width,
+        };
+        Ok(())
+    }
+
+    /// The number of lines below the first line of the frame to make room
+    /// for before drawing it on a clear screen, from [Options::reserve_lines]
+    /// and [Model::max_height].
+    fn lines_to_reserve(&self, from_model: bool) -> Option<usize> {
+        let mut lines = self.options.reserve_lines;
+        if from_model {
+            lines = lines.max(self.model.max_height().unwrap_or(0));
+        }
+        if let Some(height) = self.destination.height() {
+            lines = lines.min(height);
+        }
+        Some(lines.saturating_sub(1)).filter(|n| *n > 0)
+    }
+
+    /// If the terminal is now `width` columns, narrower than when the progress
+    /// bar was drawn, the number of lines to move up to reach the top of the
+    /// bar, supposing the terminal wrapped its lines to the new width.
+    ///
+    /// Returns None if the bar isn't drawn or the terminal isn't narrower.
+    fn reflowed_lines_above_cursor(&self, width: usize) -> Option<usize> {
+        match self.state {
+            State::ProgressDrawn {
                 ref last_drawn_string,
-                cursor_y,
+                width: drawn_width,
                 ..
-            } = self.state
-            {
-                if *last_drawn_string == rendered {
-                    return Ok(());
-                }
-                buf.push_str(&ansi::up_n_lines_and_home(cursor_y));
-            }
-            buf.push_str(ansi::DISABLE_LINE_WRAP);
-            buf.push_str(ansi::CLEAR_TO_END_OF_SCREEN);
-            buf.push_str(&rendered);
-            self.write_output(&buf);
-            let cursor_y = rendered.as_bytes().iter().filter(|b| **b == b'\n').count();
-            self.state = State::ProgressDrawn {
-                last_drawn_time: now,
-                last_drawn_string: rendered,
-                cursor_y,
-            };
+            } if width < drawn_width && width > 0 => {
+                let rows: Vec<usize> = last_drawn_string
+                    .split('\n')
+                    .map(|line| {
+                        // Lines were cut to the old width, by us or by the
+                        // terminal with line wrapping turned off.
+                        let columns = width::display_width(line).min(drawn_width).max(1);
+                        columns.div_ceil(width)
+                    })
+                    .collect();
+                let total: usize = rows.iter().sum();
+                Some(match self.options.cursor_park {
+                    CursorPark::EndOfBar => total - 1,
+                    CursorPark::StartOfBar => 0,
+                    CursorPark::BottomLeft => total - rows.last().expect("at least one line"),
+                })
+            }
+            _ => None,
         }
-        Ok(())
+    }
+
+    /// True if the line wrap mode should be turned off while the bar is drawn.
+    fn toggles_line_wrap(&self) -> bool {
+        self.options.toggle_line_wrap && self.options.dialect.supports_line_wrap()
     }
 
     /// Hide the progress bar and leave it hidden until it is resumed.
-    fn suspend(&mut self) -> io::Result<()> {
+    fn suspend(&mut self) -> std::io::Result<()> {
         self.suspended = true;
+        self.suspended_at.get_or_insert_with(Instant::now);
         self.clear()
     }
 
-    fn resume(&mut self) -> io::Result<()> {
+    fn resume(&mut self) -> std::io::Result<()> {
         self.suspended = false;
+        if let Some(suspended_at) = self.suspended_at.take() {
+            self.pauses.push(suspended_at, Instant::now());
+        }
         self.paint_progress()
     }
 
     /// Clear the progress bars off the screen, leaving it ready to
     /// print other output.
-    fn clear(&mut self) -> io::Result<()> {
+    fn clear(&mut self) -> std::io::Result<()> {
+        let buf = self.erase_sequence();
+        if !buf.is_empty() {
+            self.write_output(&buf)?;
+        }
+        Ok(())
+    }
+
+    /// Forget what's on the screen, so that the next frame erases and redraws
+    /// the whole bar without waiting for the update interval.
+    fn invalidate(&mut self) {
+        if let State::ProgressDrawn {
+            last_drawn_string, ..
+        } = &mut self.state
+        {
+            last_drawn_string.clear();
+            self.invalidated = true;
+        }
+        self.line_cache.clear();
+    }
+
+    /// Record that the progress bar was erased, and the terminal modes
+    /// restored, by something other than this view.
+    fn forget_drawn(&mut self) {
+        self.state = State::None;
+        self.modes = 0;
+    }
+
+    /// If the progress bar is drawn, return the sequence to erase it, and
+    /// mark it as erased; otherwise return an empty string.
+    ///
+    /// The caller must write the sequence.
+    fn erase_sequence(&mut self) -> String {
         match self.state {
             State::ProgressDrawn { cursor_y, .. } => {
-                self.write_output(&format!(
-                    "{}{}{}",
-                    ansi::up_n_lines_and_home(cursor_y),
-                    ansi::CLEAR_TO_END_OF_SCREEN,
-                    ansi::ENABLE_LINE_WRAP,
-                ));
+                let lines = self
+                    .destination
+                    .width()
+                    .and_then(|width| self.reflowed_lines_above_cursor(width))
+                    .unwrap_or(cursor_y);
+                let mut buf = format!(
+                    "{}{}",
+                    self.options.dialect.up_n_lines_and_home(lines),
+                    self.options.dialect.clear_to_end_of_screen(),
+                );
+                terminal::push_restore_sequences(self.modes, |s| buf.push_str(s));
+                self.modes = 0;
+                self.record_event(|| CapturedEventKind::Erase);
                 self.state = State::None;
+                buf
+            }
+            State::None | State::New | State::IncompleteLine | State::Printed { .. } => {
+                String::new()
             }
-            State::None | State::New | State::IncompleteLine | State::Printed { .. } => {}
         }
-        Ok(())
     }
 
-    fn update<U, R>(&mut self, update_fn: U) -> R
+    fn update<U, R>(&mut self, update_fn: U) -> std::io::Result<R>
     where
         U: FnOnce(&mut M) -> R,
     {
+        self.begin_update();
         let r = update_fn(&mut self.model);
-        self.paint_progress().unwrap();
-        r
+        self.end_update()?;
+        Ok(r)
+    }
+
+    /// Change the tasks, and then redraw if it's time to, as after an update.
+    fn update_tasks<F, R>(&mut self, f: F) -> std::io::Result<R>
+    where
+        F: FnOnce(&mut Tasks) -> R,
+    {
+        self.begin_update();
+        let r = f(&mut self.tasks);
+        self.end_update()?;
+        Ok(r)
+    }
+
+    /// Remove a task, printing a summary of it if [Options::on_task_finish]
+    /// asks for one, and return its count.
+    fn finish_task(&mut self, id: u64) -> std::io::Result<u64> {
+        self.begin_update();
+        let task = self.tasks.remove(id);
+        match self.options.on_task_finish {
+            TaskFinishPolicy::Remove => self.end_update()?,
+            TaskFinishPolicy::Summarize => {
+                let summary =
+                    task.summary(self.clock(), self.options.formatter, self.options.theme);
+                self.freeze_line(&format!("{summary}\n"))?;
+            }
+        }
+        Ok(task.done)
+    }
+
+    /// Update the model when [InnerView::paint_schedule] says it's too soon
+    /// to paint.
+    fn update_without_paint<U, R>(&mut self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        self.begin_update();
+        update_fn(&mut self.model)
+    }
+
+    /// Prepare for the model to be updated.
+    fn begin_update(&mut self) {
+        self.apply_pending_updates();
+        let Some(threshold) = self.options.stall_threshold else {
+            return;
+        };
+        let now = Instant::now();
+        if let Some(last_update) = self.last_update {
+            if now.saturating_duration_since(last_update) > threshold {
+                self.pauses.push(last_update, now);
+            }
+        }
+        self.last_update = Some(now);
+    }
+
+    /// When an update might next paint, and the [resize::generation] after
+    /// which a resize should cause an earlier paint.
+    ///
+    /// This is conservative: it gives [PaintTime::Unknown] whenever the answer
+    /// depends on more than the time.
+    fn paint_schedule(&self) -> (PaintTime, usize) {
+        #[cfg(feature = "http")]
+        if self.options.http_reporter.is_some() {
+            return (PaintTime::Unknown, 0);
+        }
+        if self.fake_clock.is_some() || self.state == State::New {
+            (PaintTime::Unknown, 0)
+        } else if self.suspended
+            || (!self.options.progress_enabled && self.fallback_interval().is_none())
+        {
+            (PaintTime::Never, resize::generation())
+        } else if let State::ProgressDrawn {
+            last_drawn_time, ..
+        } = self.state
+        {
+            if self.invalidated
+                || self.options.accessibility != Accessibility::Visual
+                || self.options.update_interval.is_zero()
+            {
+                // With no update interval every update paints, which a lagging
+                // coarse clock could otherwise delay.
+                (PaintTime::Unknown, 0)
+            } else {
+                (
+                    PaintTime::After(last_drawn_time + self.stats.effective_update_interval),
+                    self.resize_generation,
+                )
+            }
+        } else {
+            (PaintTime::Unknown, 0)
+        }
+    }
+
+    /// Repaint, if it's time to, after the model was updated.
+    fn end_update(&mut self) -> std::io::Result<()> {
+        self.paint_progress()?;
+        #[cfg(feature = "http")]
+        self.report_http();
+        Ok(())
+    }
+
+    /// Check and truncate each line of a rendered frame, reusing the results
+    /// for lines that are unchanged since the previous frame.
+    ///
+    /// Panics if validation is enabled and a line contains a forbidden
+    /// control sequence.
+    fn process_lines(&mut self, rendered: &str, width: usize, from_model: bool) -> String {
+        let mut previous = std::mem::take(&mut self.line_cache).into_iter();
+        if self.line_cache_width != width {
+            previous = Vec::new().into_iter();
+            self.line_cache_width = width;
+        }
+        let mut out = String::with_capacity(rendered.len());
+        for (i, line) in rendered.split('\n').enumerate() {
+            if i > 0 {
+                out.push('\n');
+            }
+            let (raw, processed) = match previous.next() {
+                Some((raw, processed)) if raw == line => (raw, processed),
+                _ => {
+                    if self.options.validate_render {
+                        if let Some(seq) = ansi::find_forbidden_sequence(line) {
+                            let source = if from_model {
+                                format!("{}::render", std::any::type_name::<M>())
+                            } else {
+                                "View::paint_raw".to_owned()
+                            };
+                            panic!(
+                                "nutmeg: {source} returned the control sequence {seq:?}, \
+                                which moves the cursor or clears the screen and would \
+                                corrupt the progress display"
+                            );
+                        }
+                    }
+                    let processed = width::truncate_lines(line, width);
+                    (line.to_owned(), processed)
+                }
+            };
+            out.push_str(&processed);
+            self.line_cache.push((raw, processed));
+        }
+        out
+    }
+
+    /// Record the time taken to render and perhaps draw a frame, and lengthen
+    /// the update interval if that's needed to keep within the budget.
+    fn account_render(&mut self, elapsed: Duration, drawn: bool) {
+        self.stats.renders += 1;
+        if drawn {
+            self.stats.frames += 1;
+        }
+        self.stats.render_time += elapsed;
+        self.recompute_intervals();
     }
 
-    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+    /// Set the effective update interval and print holdoff from the options,
+    /// lengthened if rendering would take more than the allowed share of time,
+    /// or if writes to the destination are slow.
+    fn recompute_intervals(&mut self) {
+        let mut update_interval = self.options.update_interval;
+        let mut print_holdoff = self.options.print_holdoff;
+        if let (Some(share), Some(mean)) = (
+            self.options.max_render_time_share,
+            self.stats.mean_render_time(),
+        ) {
+            if share > 0.0 {
+                let min_interval = Duration::from_secs_f64(mean.as_secs_f64() / share as f64);
+                update_interval = update_interval.max(min_interval);
+            }
+        }
+        if let Some(latency) = self.stats.mean_write_latency() {
+            let min_interval = latency * WRITE_LATENCY_FACTOR;
+            update_interval = update_interval.max(min_interval);
+            print_holdoff = print_holdoff.max(min_interval);
+        }
+        self.stats.effective_update_interval = update_interval;
+        self.stats.effective_print_holdoff = print_holdoff;
+    }
+
+    /// Record the time taken by one call to [Model::render], and report it if
+    /// it's slow.
+    fn account_model_render(&mut self, elapsed: Duration) {
+        self.stats.slowest_render = self.stats.slowest_render.max(elapsed);
+        if let Some(threshold) = self.options.slow_render_threshold {
+            if elapsed > threshold {
+                self.stats.slow_renders += 1;
+                self.record_event(|| CapturedEventKind::SlowRender(elapsed));
+            }
+        }
+    }
+
+    fn update_keyed(&mut self, key: &str, update_fn: PendingUpdate<M>) -> std::io::Result<()> {
+        if let Some(pos) = self.pending_updates.iter().position(|(k, _)| k == key) {
+            // The earlier update is superseded, so it's dropped without being run.
+            drop(self.pending_updates.remove(pos));
+        }
+        self.pending_updates.push((key.to_owned(), update_fn));
+        self.paint_progress()
+    }
+
+    fn apply_pending_updates(&mut self) {
+        for (_key, update_fn) in std::mem::take(&mut self.pending_updates) {
+            update_fn(&mut self.model);
+        }
+        let model = &mut self.model;
+        self.counters.retain_mut(|(cells, apply)| {
+            // Once every handle is dropped, the counter can't count any more.
+            // This is checked before taking the count, so that the count from
+            // a handle dropped in between is taken now rather than lost.
+            let alive = Arc::strong_count(cells) > 1;
+            if !alive {
+                // Make sure that increments before the last handle was
+                // dropped are seen by the take.
+                std::sync::atomic::fence(std::sync::atomic::Ordering::Acquire);
+            }
+            let n = cells.take();
+            if n > 0 {
+                apply(model, n);
+            }
+            alive
+        });
+    }
+
+    fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+        self.write_message(buf, None)
+    }
+
+    /// Print a message with an optional level, after passing it through the
+    /// [Options::message_filters].
+    fn write_message(&mut self, buf: &[u8], level: Option<MessageLevel>) -> std::io::Result<usize> {
         if buf.is_empty() {
             return Ok(0);
         }
         self.init_destination();
-        self.clear()?;
-        self.state = if buf.ends_with(b"\n") {
+        let message = std::str::from_utf8(buf).expect("message is not UTF-8");
+        let Some(message) = self.filter_message(message.to_owned(), level) else {
+            return Ok(buf.len());
+        };
+        // Erase the bar and write the message in one write, which matters on
+        // slow remote terminals.
+        let mut output = self.erase_sequence();
+        self.state = if message.ends_with('\n') {
             State::Printed {
                 last_printed: self.clock(),
             }
         } else {
             State::IncompleteLine
         };
-        self.write_output(std::str::from_utf8(buf).expect("message is not UTF-8"));
+        let (marker_start, marker_end) =
+            self.markers(test_util::MESSAGE_START, test_util::MESSAGE_END);
+        output.push_str(marker_start);
+        output.push_str(&self.plain_text(&message));
+        output.push_str(marker_end);
+        self.write_output(&output)?;
+        self.write_secondary_text(&message)?;
+        #[cfg(feature = "http")]
+        if let Some(reporter) = self.options.http_reporter {
+            reporter.publish_message();
+        }
+        self.record_event(|| CapturedEventKind::Message(message));
         Ok(buf.len())
     }
 
+    /// Pass a message through the [Options::message_filters], returning
+    /// `None` if it's dropped.
+    fn filter_message(&self, message: String, level: Option<MessageLevel>) -> Option<String> {
+        let context = MessageContext {
+            level,
+            line_start: !matches!(self.state, State::IncompleteLine),
+            is_terminal: self.is_terminal,
+        };
+        filter::apply_filters(self.options.message_filters, message, &context)
+    }
+
+    /// Remove escape sequences from text going to a destination that isn't a
+    /// terminal, if [Options::strip_escapes] is on.
+    fn plain_text<'s>(&self, text: &'s str) -> Cow<'s, str> {
+        if self.options.strip_escapes && !self.is_terminal {
+            Cow::Owned(ansi::strip_sequences(text))
+        } else {
+            Cow::Borrowed(text)
+        }
+    }
+
+    /// Repaint the model from the background ticker.
+    fn tick(&mut self) -> std::io::Result<()> {
+        self.paint_progress()?;
+        #[cfg(feature = "http")]
+        self.report_http();
+        Ok(())
+    }
+
+    /// Write a complete line and then redraw the progress bar without waiting
+    /// for the print holdoff.
+    fn freeze_line(&mut self, line: &str) -> std::io::Result<()> {
+        self.write(line.as_bytes())?;
+        if let State::Printed { .. } = self.state {
+            self.state = State::None;
+        }
+        self.paint_progress()
+    }
+
     /// Set the value of the fake clock, for testing.
     fn set_fake_clock(&mut self, fake_clock: Instant) {
         assert!(self.options.fake_clock, "Options.fake_clock is not enabled");
         self.fake_clock = Some(fake_clock);
     }
 
-    fn write_output(&mut self, buf: &str) {
-        match &mut self.options.destination {
-            Destination::Stdout => {
-                print!("{buf}");
-                io::stdout().flush().unwrap();
+    /// The destination, if it's the built-in [Destination] enum.
+    fn builtin_destination(&self) -> Option<Destination> {
+        (&self.destination as &dyn Any)
+            .downcast_ref::<Destination>()
+            .copied()
+    }
+
+    /// True if output is captured into a buffer for testing.
+    #[cfg(feature = "capture")]
+    fn is_capture(&self) -> bool {
+        matches!(
+            self.builtin_destination(),
+            Some(
+                Destination::Capture
+                    | Destination::CaptureNamed(_)
+                    | Destination::CaptureBounded(_)
+            )
+        )
+    }
+
+    #[cfg(not(feature = "capture"))]
+    fn is_capture(&self) -> bool {
+        false
+    }
+
+    /// The markers to write around some output, if they're turned on by
+    /// [Options::capture_markers], or otherwise empty strings.
+    #[cfg(feature = "capture")]
+    fn markers(&self, start: &'static str, end: &'static str) -> (&'static str, &'static str) {
+        if self.options.capture_markers && self.is_capture() {
+            (start, end)
+        } else {
+            ("", "")
+        }
+    }
+
+    #[cfg(not(feature = "capture"))]
+    fn markers(&self, _start: &'static str, _end: &'static str) -> (&'static str, &'static str) {
+        ("", "")
+    }
+
+    /// The most output to keep in the capture buffer, if the destination is
+    /// [Destination::CaptureBounded].
+    fn capture_limit(&self) -> Option<usize> {
+        match self.builtin_destination() {
+            #[cfg(feature = "capture")]
+            Some(Destination::CaptureBounded(limit)) => Some(limit),
+            _ => None,
+        }
+    }
+
+    /// The file descriptor of the destination, if it has one.
+    fn fd(&self) -> Option<i32> {
+        self.builtin_destination().and_then(|d| d.fd())
+    }
+
+    fn write_output(&mut self, buf: &str) -> std::io::Result<()> {
+        if self.is_capture() {
+            let limit = self.capture_limit();
+            let output = self.captured_output();
+            let mut output = output.lock().expect("lock capture_buffer");
+            output.push_str(buf);
+            if let Some(limit) = limit {
+                capture::trim_output(&mut output, limit);
+            }
+        } else {
+            let start = Instant::now();
+            self.destination.write(buf)?;
+            self.destination.flush()?;
+            self.stats.writes += 1;
+            self.stats.write_time += start.elapsed();
+            self.recompute_intervals();
+        }
+        if self.also_emit_is_terminal {
+            if let Some(secondary) = self.options.also_emit {
+                secondary.write(buf)?;
+                secondary.flush()?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Render the model for the HTTP reporter, if there is one, the view
+    /// doesn't render frames itself, and the reporter's not been updated
+    /// within the update interval.
+    ///
+    /// Frames that the view renders are published by [InnerView::publish_http_frame],
+    /// so that stateful models are not rendered twice.
+    #[cfg(feature = "http")]
+    fn report_http(&mut self) {
+        if self.options.http_reporter.is_none()
+            || (self.options.progress_enabled
+                && self.options.accessibility == Accessibility::Visual)
+            || self.fallback_interval().is_some()
+        {
+            return;
+        }
+        let now = self.clock();
+        if let Some(last) = self.last_reported {
+            if now - last < self.options.update_interval {
+                return;
             }
-            Destination::Stderr => {
-                eprint!("{buf}");
-                io::stderr().flush().unwrap();
+        }
+        let frame = render_at(&mut self.model, http::WIDTH);
+        self.publish_http_frame(&frame, now);
+    }
+
+    /// Send a frame rendered from the model to the HTTP reporter, if there is one.
+    #[cfg(feature = "http")]
+    fn publish_http_frame(&mut self, frame: &str, now: Instant) {
+        if let Some(reporter) = self.options.http_reporter {
+            self.last_reported = Some(now);
+            reporter.publish_frame(frame, self.model.fraction_done());
+        }
+    }
+
+    /// Write text to the secondary destination, if it's not a terminal and so
+    /// doesn't get a copy of everything from [InnerView::write_output].
+    fn write_secondary_text(&mut self, text: &str) -> std::io::Result<()> {
+        if let Some(secondary) = self.options.also_emit {
+            if !self.also_emit_is_terminal {
+                secondary.write(&ansi::strip_sequences(text))?;
+                secondary.flush()?;
             }
-            Destination::Capture => {
-                self.capture_buffer
-                    .get_or_insert_with(|| Arc::new(Mutex::new(String::new())))
-                    .lock()
-                    .expect("lock capture_buffer")
-                    .push_str(buf);
+        }
+        Ok(())
+    }
+
+    /// If the destination captures output, record an event.
+    fn record_event<F: FnOnce() -> CapturedEventKind>(&mut self, kind: F) {
+        let is_capture = self.is_capture();
+        if !is_capture && self.subscribers.is_empty() {
+            return;
+        }
+        let event = CapturedEvent {
+            time: self.clock(),
+            kind: kind(),
+        };
+        self.subscribers
+            .retain(|subscriber| subscriber.send(event.clone()).is_ok());
+        if is_capture {
+            let limit = self.capture_limit();
+            let events = self.captured_events();
+            let mut events = events.lock().expect("lock captured events");
+            events.push(event);
+            if let Some(limit) = limit {
+                capture::trim_events(&mut events, limit);
             }
         }
     }
 
+    fn captured_events(&mut self) -> Arc<Mutex<Vec<CapturedEvent>>> {
+        self.captured_events
+            .get_or_insert_with(Default::default)
+            .clone()
+    }
+
     fn captured_output(&mut self) -> Arc<Mutex<String>> {
+        let destination = self.builtin_destination();
         self.capture_buffer
-            .get_or_insert_with(|| Arc::new(Mutex::new(String::new())))
+            .get_or_insert_with(|| match destination {
+                #[cfg(feature = "capture")]
+                Some(Destination::CaptureNamed(name)) => Destination::captured_output_named(name),
+                _ => Arc::new(Mutex::new(String::new())),
+            })
             .clone()
     }
 }
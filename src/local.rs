@@ -0,0 +1,205 @@
+// Copyright 2023 Martin Pool.
+
+//! A view for single-threaded programs, without a mutex.
+
+use std::cell::RefCell;
+#[cfg(feature = "capture")]
+use std::sync::{Arc, Mutex};
+
+#[allow(unused)] // for docstrings
+use crate::View;
+use crate::{
+    Destination, DestinationTrait, DropPolicy, InnerView, Model, ModelScope, Options, Outcome,
+    ProgressSink,
+};
+
+/// A progress view for use on a single thread.
+///
+/// This works like [View], but keeps its state in a [RefCell] rather than a
+/// mutex, so there's no locking, and the model doesn't need to be [Send]:
+/// it can hold an `Rc`, or handles that must stay on one thread. In turn, a
+/// `LocalView` can't be shared between threads.
+///
+/// As with [View], messages printed from inside an update callback or
+/// [Model::render] are held until the callback returns, and calling other
+/// methods from there panics.
+///
+/// ```
+/// use std::rc::Rc;
+/// use nutmeg::models::DisplayModel;
+/// use nutmeg::{LocalView, Options};
+///
+/// let name: Rc<str> = Rc::from("counting");
+/// let view = LocalView::new(DisplayModel(Rc::clone(&name)), Options::default());
+/// view.update(|model| model.0 = Rc::from("still counting"));
+/// view.message("counted\n");
+/// view.finish();
+/// ```
+pub struct LocalView<M: Model, D: DestinationTrait = Destination> {
+    /// The state of the view; None once it's been finished or abandoned.
+    inner: RefCell<Option<InnerView<M, D>>>,
+
+    /// Messages written while the view was in use, to be written once it's
+    /// released.
+    deferred: RefCell<Vec<u8>>,
+}
+
+impl<M: Model> LocalView<M> {
+    /// Construct a view drawn to the [Options::destination], as for
+    /// [View::new].
+    pub const fn new(model: M, options: Options) -> LocalView<M> {
+        let destination = options.destination;
+        LocalView::with_destination(model, options, destination)
+    }
+}
+
+impl<M: Model, D: DestinationTrait> LocalView<M, D> {
+    /// Construct a view drawing to a destination of a specific type, as for
+    /// [View::with_destination].
+    pub const fn with_destination(model: M, options: Options, destination: D) -> LocalView<M, D> {
+        LocalView {
+            inner: RefCell::new(Some(InnerView::new(model, options, destination))),
+            deferred: RefCell::new(Vec::new()),
+        }
+    }
+
+    /// Call `f` on the inner view, and then write any messages deferred while
+    /// it ran.
+    ///
+    /// Panics if the view is already in use, since it can't be borrowed twice.
+    fn call_inner<F, R>(&self, method: &str, f: F) -> R
+    where
+        F: FnOnce(&mut InnerView<M, D>) -> R,
+    {
+        let Ok(mut guard) = self.inner.try_borrow_mut() else {
+            panic!(
+                "nutmeg::LocalView::{method} called reentrantly from inside an update \
+                callback or Model::render"
+            );
+        };
+        let inner = guard.as_mut().expect("LocalView is not already destroyed");
+        if let Err(err) = inner.check_options() {
+            panic!("{err}");
+        }
+        let r = {
+            let _scope = ModelScope::enter(&inner.options);
+            f(inner)
+        };
+        let deferred = std::mem::take(&mut *self.deferred.borrow_mut());
+        if !deferred.is_empty() {
+            inner.write(&deferred).expect("write deferred message");
+        }
+        r
+    }
+
+    fn take_inner(&self) -> InnerView<M, D> {
+        self.inner
+            .borrow_mut()
+            .take()
+            .expect("LocalView is not already destroyed")
+    }
+
+    /// Update the model, and redraw the progress bar if it's time to, as for
+    /// [View::update].
+    pub fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        self.call_inner("update", |inner| inner.update(update_fn))
+            .expect("update progress view")
+    }
+
+    /// Inspect the model without redrawing, as for [View::inspect_model].
+    pub fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        self.call_inner("inspect_model", |inner| f(&mut inner.model))
+    }
+
+    /// Print a message above the progress bar, as for [View::message].
+    pub fn message<S: AsRef<str>>(&self, message: S) {
+        self.message_bytes(message.as_ref().as_bytes())
+    }
+
+    /// Print a message from a byte buffer, as for [View::message_bytes].
+    pub fn message_bytes<S: AsRef<[u8]>>(&self, message: S) {
+        let message = message.as_ref();
+        if message.is_empty() {
+            return;
+        }
+        if self.inner.try_borrow_mut().is_err() {
+            self.deferred.borrow_mut().extend_from_slice(message);
+        } else {
+            self.call_inner("message", |inner| inner.write(message))
+                .expect("write message");
+        }
+    }
+
+    /// Hide the progress bar until [LocalView::resume] is called, as for
+    /// [View::suspend].
+    pub fn suspend(&self) {
+        self.call_inner("suspend", |v| v.suspend().expect("suspend succeeds"))
+    }
+
+    /// Remove the progress bar if it's drawn, as for [View::clear].
+    pub fn clear(&self) {
+        self.call_inner("clear", |v| v.clear().expect("clear succeeds"))
+    }
+
+    /// Allow the progress bar to be drawn again, as for [View::resume].
+    pub fn resume(&self) {
+        self.call_inner("resume", |v| v.resume().expect("resume succeeds"))
+    }
+
+    /// Return the buffer of output captured when the destination is
+    /// [Destination::Capture], as for [View::captured_output].
+    #[cfg(feature = "capture")]
+    pub fn captured_output(&self) -> Arc<Mutex<String>> {
+        self.call_inner("captured_output", |v| v.captured_output())
+    }
+
+    /// Stop using this view, leaving the progress bar on the screen, and
+    /// return the model.
+    pub fn abandon(self) -> M {
+        self.take_inner().abandon().expect("Abandoned view")
+    }
+
+    /// Erase the progress bar, print the [Model::final_message], and return
+    /// the model.
+    pub fn finish(self) -> M {
+        self.take_inner().finish(false)
+    }
+}
+
+impl<M: Model, D: DestinationTrait> ProgressSink<M> for LocalView<M, D> {
+    fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        LocalView::update(self, update_fn)
+    }
+
+    fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        LocalView::inspect_model(self, f)
+    }
+
+    fn message<S: AsRef<str>>(&self, message: S) {
+        LocalView::message(self, message)
+    }
+}
+
+impl<M: Model, D: DestinationTrait> Drop for LocalView<M, D> {
+    fn drop(&mut self) {
+        if let Some(inner) = self.inner.get_mut().take() {
+            match inner.options.on_drop {
+                DropPolicy::Erase => drop(inner.finish_with(String::new(), Outcome::Finished)),
+                DropPolicy::Abandon => drop(inner.abandon()),
+                DropPolicy::FinishWithMessage => drop(inner.finish(false)),
+            }
+        }
+    }
+}
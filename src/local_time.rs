@@ -0,0 +1,86 @@
+// Copyright 2023 Martin Pool.
+
+//! Format times of day in the local time zone, without depending on a date
+//! and time library.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// A date and time of day.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct LocalTime {
+    /// Days since 1970-01-01 in the same time zone.
+    pub(crate) day: i64,
+    pub(crate) hour: u32,
+    pub(crate) minute: u32,
+}
+
+/// Convert `time` to the local time zone, or to UTC if the local time zone
+/// can't be found on this platform, in which case the second value is false.
+pub(crate) fn local_time(time: SystemTime) -> (LocalTime, bool) {
+    let secs = match time.duration_since(UNIX_EPOCH) {
+        Ok(d) => d.as_secs() as i64,
+        Err(e) => -(e.duration().as_secs() as i64),
+    };
+    match platform::local_time(secs) {
+        Some(local) => (local, true),
+        None => (utc_time(secs), false),
+    }
+}
+
+fn utc_time(secs: i64) -> LocalTime {
+    let seconds_of_day = secs.rem_euclid(86400);
+    LocalTime {
+        day: secs.div_euclid(86400),
+        hour: (seconds_of_day / 3600) as u32,
+        minute: (seconds_of_day / 60 % 60) as u32,
+    }
+}
+
+/// Days since 1970-01-01 of a date in the proleptic Gregorian calendar.
+///
+/// This is Howard Hinnant's `days_from_civil` algorithm.
+#[allow(dead_code)] // Not used on all platforms.
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year =
+        (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+#[cfg(unix)]
+mod platform {
+    use super::{days_from_civil, LocalTime};
+
+    pub(super) fn local_time(secs: i64) -> Option<LocalTime> {
+        let time = libc::time_t::try_from(secs).ok()?;
+        // Safety: `tm` is plain data, for which all zeroes is a valid value.
+        let mut tm: libc::tm = unsafe { std::mem::zeroed() };
+        // Safety: both pointers are valid for the duration of the call.
+        let result = unsafe { libc::localtime_r(&time, &mut tm) };
+        if result.is_null() {
+            return None;
+        }
+        Some(LocalTime {
+            day: days_from_civil(
+                tm.tm_year as i64 + 1900,
+                tm.tm_mon as u32 + 1,
+                tm.tm_mday as u32,
+            ),
+            hour: tm.tm_hour as u32,
+            minute: tm.tm_min as u32,
+        })
+    }
+}
+
+#[cfg(not(unix))]
+mod platform {
+    use super::LocalTime;
+
+    pub(super) fn local_time(_secs: i64) -> Option<LocalTime> {
+        None
+    }
+}
@@ -0,0 +1,86 @@
+// Copyright 2023 Martin Pool.
+
+//! A guard giving access to a view's model outside of an update closure.
+
+use std::ops::{Deref, DerefMut};
+use std::sync::MutexGuard;
+
+use crate::reentry::LockMark;
+use crate::{Destination, DestinationTrait, InnerView, Model, ModelScope, View};
+
+/// Mutable access to the model of a [View], returned by [View::model_mut].
+///
+/// The view is locked while the guard exists. When it's dropped, the
+/// progress bar is repainted if it's time to, as at the end of
+/// [View::update].
+pub struct ModelGuard<'v, M: Model, D: DestinationTrait = Destination> {
+    view: &'v View<M, D>,
+    /// The lock on the inner view; always Some until the guard is dropped.
+    inner: Option<MutexGuard<'v, Option<InnerView<M, D>>>>,
+    /// Present while the guard is held, so that reentrant calls are noticed.
+    mark: Option<(LockMark, ModelScope)>,
+}
+
+impl<'v, M: Model, D: DestinationTrait> ModelGuard<'v, M, D> {
+    pub(crate) fn new(view: &'v View<M, D>) -> ModelGuard<'v, M, D> {
+        view.check_not_reentrant("model_mut");
+        let mut inner = view.inner.lock().expect("View mutex is not poisoned");
+        view.initialize_lazy(&mut inner);
+        let inner_view = inner.as_mut().expect("View is not already destroyed");
+        if let Err(err) = inner_view.check_options() {
+            panic!("{err}");
+        }
+        inner_view.begin_update();
+        let mark = (
+            LockMark::new(view.key()),
+            ModelScope::enter(&inner_view.options),
+        );
+        ModelGuard {
+            view,
+            inner: Some(inner),
+            mark: Some(mark),
+        }
+    }
+
+    fn inner_view(&self) -> &InnerView<M, D> {
+        self.inner
+            .as_ref()
+            .and_then(|inner| inner.as_ref())
+            .expect("ModelGuard holds the view")
+    }
+
+    fn inner_view_mut(&mut self) -> &mut InnerView<M, D> {
+        self.inner
+            .as_mut()
+            .and_then(|inner| inner.as_mut())
+            .expect("ModelGuard holds the view")
+    }
+}
+
+impl<M: Model, D: DestinationTrait> Deref for ModelGuard<'_, M, D> {
+    type Target = M;
+
+    fn deref(&self) -> &M {
+        &self.inner_view().model
+    }
+}
+
+impl<M: Model, D: DestinationTrait> DerefMut for ModelGuard<'_, M, D> {
+    fn deref_mut(&mut self) -> &mut M {
+        &mut self.inner_view_mut().model
+    }
+}
+
+impl<M: Model, D: DestinationTrait> Drop for ModelGuard<'_, M, D> {
+    fn drop(&mut self) {
+        self.mark = None;
+        if std::thread::panicking() {
+            return;
+        }
+        if let Some(inner) = self.inner.take() {
+            self.view
+                .call_locked(inner, |inner| inner.end_update())
+                .expect("update progress view");
+        }
+    }
+}
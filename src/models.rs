@@ -10,15 +10,31 @@ use std::borrow::Cow;
 use std::fmt::{Debug, Display};
 use std::time::{Duration, Instant};
 
+use crate::formatting::current_formatting;
+use crate::helpers::remaining_duration;
+use crate::theme::current_theme;
 #[allow(unused)] // For docstrings
 use crate::View;
-use crate::{estimate_remaining, percent_done, Model};
+use crate::{
+    estimate_remaining, eta_clock_time, fill_template, format_bytes, frame_time, percent_done,
+    plural, render_at, Model, MonotonicPercent, ProgressSink,
+};
+
+pub use crate::task::{Expansion, AUTO_GROUP_LINES};
+
+/// The width below which the built-in models use their compact form.
+///
+/// See [Model::compact_width].
+pub const COMPACT_WIDTH: usize = 40;
 
 /// A Nutmeg progress model that concatenates a pair of strings to render
 /// the progress bar.
 ///
 /// For example, the prefix could be a description of the operation, and the
 /// suffix could be the name of the file or object that's being processed.
+///
+/// On terminals narrower than [COMPACT_WIDTH], only the suffix is shown, or the
+/// prefix if the suffix is empty.
 pub struct StringPair {
     prefix: Cow<'static, str>,
     suffix: Cow<'static, str>,
@@ -47,6 +63,23 @@ impl StringPair {
         }
     }
 
+    /// Construct a new StringPair in a `const` context, such as the initial
+    /// model of a `static` [View].
+    ///
+    /// ```
+    /// use nutmeg::models::StringPair;
+    /// use nutmeg::{Options, View};
+    ///
+    /// static VIEW: View<StringPair> =
+    ///     View::new(StringPair::new_const("Copying: ", ""), Options::new());
+    /// ```
+    pub const fn new_const(prefix: &'static str, suffix: &'static str) -> StringPair {
+        StringPair {
+            prefix: Cow::Borrowed(prefix),
+            suffix: Cow::Borrowed(suffix),
+        }
+    }
+
     /// Update the second string.
     ///
     /// Typically this should be called from a callback passed to [View::update].
@@ -56,12 +89,42 @@ impl StringPair {
     {
         self.suffix = suffix.into();
     }
+
+    /// Get the first string.
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    /// Get the second string.
+    ///
+    /// ```
+    /// use nutmeg::models::StringPair;
+    ///
+    /// let view = nutmeg::View::new(StringPair::new("Copying: ", ""), nutmeg::Options::default());
+    /// view.update(|model| model.set_suffix("/etc/hostname"));
+    /// assert_eq!(view.inspect_model(|model| model.suffix().to_owned()), "/etc/hostname");
+    /// ```
+    pub fn suffix(&self) -> &str {
+        &self.suffix
+    }
 }
 
 impl Model for StringPair {
     fn render(&mut self, _width: usize) -> String {
         format!("{}{}", self.prefix, self.suffix)
     }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        if self.suffix.is_empty() {
+            self.prefix.to_string()
+        } else {
+            self.suffix.to_string()
+        }
+    }
 }
 
 /// A model for completion of a number of approximately equal-sized tasks,
@@ -73,7 +136,14 @@ impl Model for StringPair {
 /// Counting raindrops: 68/99, 68.7%, 3 sec remaining
 /// ```
 ///
-/// /// Run `cargo run --examples linear_model` in the Nutmeg source tree to see this in action.
+/// On terminals narrower than [COMPACT_WIDTH], the message and counts are left
+/// out:
+///
+/// ```text
+/// 68.7% 3 sec
+/// ```
+///
+/// Run `cargo run --examples linear_model` in the Nutmeg source tree to see this in action.
 ///
 /// # Example
 ///
@@ -87,12 +157,20 @@ impl Model for StringPair {
 ///     progress.update(|model| model.increment(1));
 /// }
 /// ```
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct LinearModel {
     done: usize,
     total: usize,
     message: Cow<'static, str>,
-    start: Instant,
+    /// When the work started, or None if it's not yet been rendered.
+    start: Option<Instant>,
+    /// Template for the final message, filled in by [fill_template].
+    final_message: Cow<'static, str>,
+    /// Show the estimated finish as a time of day when at least this much
+    /// time remains.
+    clock_time_after: Option<Duration>,
+    /// Set by [LinearModel::set_monotonic_percent].
+    monotonic: Option<MonotonicPercent>,
 }
 
 impl LinearModel {
@@ -102,7 +180,36 @@ impl LinearModel {
             done: 0,
             total,
             message: message.into(),
-            start: Instant::now(),
+            start: Some(Instant::now()),
+            final_message: Cow::Borrowed(""),
+            clock_time_after: None,
+            monotonic: None,
+        }
+    }
+
+    /// Construct a new model in a `const` context, such as the initial model
+    /// of a `static` [View].
+    ///
+    /// The start time, from which the remaining time is extrapolated, is set
+    /// when the model is first rendered.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::{Options, View};
+    ///
+    /// static VIEW: View<LinearModel> =
+    ///     View::new(LinearModel::new_const("Counting raindrops", 99), Options::new());
+    /// VIEW.update(|model| model.increment(1));
+    /// ```
+    pub const fn new_const(message: &'static str, total: usize) -> LinearModel {
+        LinearModel {
+            done: 0,
+            total,
+            message: Cow::Borrowed(message),
+            start: None,
+            final_message: Cow::Borrowed(""),
+            clock_time_after: None,
+            monotonic: None,
         }
     }
 
@@ -111,6 +218,30 @@ impl LinearModel {
         self.total = total
     }
 
+    /// Revise the total amount of expected work up or down by `delta`, for
+    /// example when some work turns out to be unnecessary part-way through.
+    ///
+    /// The total is never reduced below the amount of work already done, so
+    /// the percentage and time remaining stay estimatable.
+    ///
+    /// The time remaining is extrapolated from the rate of work done so far,
+    /// which doesn't depend on the total, so revising the total changes the
+    /// estimate only by the time to do the added or removed work.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let mut model = LinearModel::new("Backing up", 1000);
+    /// model.set_done(400);
+    /// model.adjust_total(-300);
+    /// assert_eq!(model.total(), 700);
+    /// model.adjust_total(-1000);
+    /// assert_eq!(model.total(), 400);
+    /// ```
+    pub fn adjust_total(&mut self, delta: isize) {
+        self.total = self.total.saturating_add_signed(delta).max(self.done)
+    }
+
     /// Get the total number of things.
     pub fn total(&self) -> usize {
         self.total
@@ -121,6 +252,99 @@ impl LinearModel {
         self.done
     }
 
+    /// Get the message describing the work.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the time from which the remaining time is extrapolated.
+    ///
+    /// This is when the model was constructed by [LinearModel::new], or when it
+    /// was first rendered if it was constructed by [LinearModel::new_const].
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start
+    }
+
+    /// Set a template for the message printed when the view is finished.
+    ///
+    /// The template can use the placeholders `{done}` and `{total}`, optionally
+    /// with a noun, as described in [fill_template]: for example,
+    /// `"Copied {done:file}"`. The message is styled as a success by the
+    /// [current_theme](crate::theme::current_theme). By default there's no
+    /// final message.
+    pub fn set_final_message<S: Into<Cow<'static, str>>>(&mut self, template: S) {
+        self.final_message = template.into()
+    }
+
+    /// Show the estimated finish as a time of day, like `done ~14:35`, rather
+    /// than as a duration, when at least `after` remains.
+    ///
+    /// For multi-hour jobs, an absolute time is easier to understand than
+    /// something like `190 min remaining`. `Duration::ZERO` always shows the
+    /// time of day. See [eta_clock_time].
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::models::LinearModel;
+    ///
+    /// let mut model = LinearModel::new("Rendering frames", 100_000);
+    /// model.set_clock_time_after(Duration::from_secs(3600));
+    /// ```
+    pub fn set_clock_time_after(&mut self, after: Duration) {
+        self.clock_time_after = Some(after)
+    }
+
+    /// Never show a lower percentage than was shown before, even if the total
+    /// is revised upwards: see [MonotonicPercent].
+    ///
+    /// The time remaining is still estimated from the true total.
+    ///
+    /// ```
+    /// use nutmeg::models::LinearModel;
+    /// use nutmeg::Model;
+    ///
+    /// let mut model = LinearModel::new("Files", 100);
+    /// model.set_monotonic_percent(true);
+    /// model.set_done(50);
+    /// assert!(model.render(80).starts_with("Files: 50/100, 50.0%"));
+    /// model.adjust_total(100);
+    /// assert!(model.render(80).starts_with("Files: 50/200, 50.0%"));
+    /// ```
+    pub fn set_monotonic_percent(&mut self, monotonic: bool) {
+        self.monotonic = monotonic.then(MonotonicPercent::new)
+    }
+
+    /// The percentage done, as shown.
+    fn percent(&mut self) -> String {
+        match &mut self.monotonic {
+            Some(monotonic) => monotonic.percent(self.done, self.total),
+            None => percent_done(self.done, self.total),
+        }
+    }
+
+    /// The estimated time remaining, or the time of day when the work will
+    /// finish if that's enabled and enough time remains.
+    fn remaining(&mut self) -> String {
+        let start = self.start();
+        match (
+            self.clock_time_after,
+            remaining_duration(&start, self.done, self.total),
+        ) {
+            (Some(after), Some(remaining)) if remaining >= after => {
+                format!("done {}", eta_clock_time(&start, self.done, self.total))
+            }
+            _ => format!(
+                "{} remaining",
+                estimate_remaining(&start, self.done, self.total)
+            ),
+        }
+    }
+
+    /// The start time, setting it to now if it's not yet set.
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+
     /// Update the amount of work done.
     ///
     /// This should normally be called from a callback passed to [View::update].
@@ -139,19 +363,57 @@ impl LinearModel {
 
 impl Model for LinearModel {
     fn render(&mut self, _width: usize) -> String {
+        let remaining = self.remaining();
+        let percent = self.percent();
         format!(
-            "{}: {}/{}, {}, {} remaining",
+            "{}: {}/{}, {percent}, {remaining}",
             self.message,
-            self.done,
-            self.total,
-            percent_done(self.done, self.total),
-            estimate_remaining(&self.start, self.done, self.total)
+            number(self.done),
+            number(self.total),
+        )
+    }
+
+    fn final_message(&mut self) -> String {
+        success_message(&self.final_message, &self.summary_counts())
+    }
+
+    fn announcement(&mut self) -> String {
+        let start = self.start();
+        announce_progress(&start, self.done, self.total)
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        if let Some(monotonic) = &self.monotonic {
+            monotonic.peek_fraction(self.done, self.total)
+        } else if self.total == 0 {
+            None
+        } else {
+            Some((self.done as f64 / self.total as f64).min(1.0))
+        }
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![("done", self.done as u64), ("total", self.total as u64)]
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        let percent = self.percent();
+        format!(
+            "{percent} {}",
+            estimate_remaining(&self.start(), self.done, self.total)
         )
     }
 }
 
 /// A model that counts up the amount of work done, with no known total, showing the elapsed time.
 ///
+/// On terminals narrower than [COMPACT_WIDTH], only the count and elapsed time
+/// are shown.
+///
 /// Run `cargo run --examples unbounded_model` in the Nutmeg source tree to see this in action.
 ///
 /// # Example
@@ -167,7 +429,10 @@ impl Model for LinearModel {
 pub struct UnboundedModel {
     message: Cow<'static, str>,
     done: usize,
-    start: Instant,
+    /// When the work started, or None if it's not yet been rendered.
+    start: Option<Instant>,
+    /// Template for the final message, filled in by [fill_template].
+    final_message: Cow<'static, str>,
 }
 
 impl UnboundedModel {
@@ -176,7 +441,22 @@ impl UnboundedModel {
         UnboundedModel {
             done: 0,
             message: message.into(),
-            start: Instant::now(),
+            start: Some(Instant::now()),
+            final_message: Cow::Borrowed(""),
+        }
+    }
+
+    /// Construct a model in a `const` context, such as the initial model of a
+    /// `static` [View].
+    ///
+    /// The start time, from which the elapsed time is measured, is set when
+    /// the model is first rendered.
+    pub const fn new_const(message: &'static str) -> UnboundedModel {
+        UnboundedModel {
+            done: 0,
+            message: Cow::Borrowed(message),
+            start: None,
+            final_message: Cow::Borrowed(""),
         }
     }
 
@@ -194,30 +474,1186 @@ impl UnboundedModel {
     pub fn increment(&mut self, i: usize) {
         self.done += i
     }
+
+    /// Get the number of things done so far.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// Get the message describing the work.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the time from which the elapsed time is measured.
+    ///
+    /// This is when the model was constructed by [UnboundedModel::new], or when
+    /// it was first rendered if it was constructed by [UnboundedModel::new_const].
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start
+    }
+
+    /// Set a template for the message printed when the view is finished.
+    ///
+    /// The template can use the placeholders `{done}`, optionally
+    /// with a noun, as described in [fill_template]: for example,
+    /// `"Found {done:match}"`. The message is styled as a success by the
+    /// [current_theme](crate::theme::current_theme). By default there's no
+    /// final message.
+    pub fn set_final_message<S: Into<Cow<'static, str>>>(&mut self, template: S) {
+        self.final_message = template.into()
+    }
+
+    /// The start time, setting it to now if it's not yet set.
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
 }
 
 impl Model for UnboundedModel {
     fn render(&mut self, _width: usize) -> String {
+        let start = self.start();
         format!(
             "{}: {} in {}",
             self.message,
-            self.done,
-            format_duration(self.start.elapsed())
+            number(self.done),
+            format_duration(start.elapsed())
         )
     }
+
+    fn final_message(&mut self) -> String {
+        success_message(&self.final_message, &self.summary_counts())
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        let start = self.start();
+        format!("{} {}", number(self.done), format_duration(start.elapsed()))
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![("done", self.done as u64)]
+    }
 }
 
 fn format_duration(d: Duration) -> String {
-    let elapsed_secs = d.as_secs();
-    if elapsed_secs >= 3600 {
-        format!(
-            "{}:{:02}:{:02}",
-            elapsed_secs / 3600,
-            (elapsed_secs / 60) % 60,
-            elapsed_secs % 60
-        )
+    current_formatting().elapsed(d)
+}
+
+/// Fill in a final message template, and style it as a success, unless it's empty.
+fn success_message(template: &str, counts: &[(&str, u64)]) -> String {
+    if template.is_empty() {
+        String::new()
     } else {
-        format!("{}:{:02}", (elapsed_secs / 60) % 60, elapsed_secs % 60)
+        current_theme().success(&fill_template(template, counts))
+    }
+}
+
+/// Describe progress for a screen reader, like "40 percent complete, 2 minutes
+/// remaining".
+fn announce_progress(start: &Instant, done: usize, total: usize) -> String {
+    if total == 0 || done > total {
+        return format!("{} done", plural(done as u64, "item"));
+    }
+    let percent = (done as f64 * 100.0 / total as f64).floor() as u64;
+    let mut announcement = format!("{percent} percent complete");
+    if let Some(remaining) = remaining_duration(start, done, total) {
+        let secs = remaining.as_secs();
+        let remaining = if secs >= 120 {
+            plural(secs / 60, "minute")
+        } else {
+            plural(secs, "second")
+        };
+        announcement.push_str(&format!(", {remaining} remaining"));
+    }
+    announcement
+}
+
+/// Format a count using the current formatter.
+fn number(n: usize) -> String {
+    current_formatting().number(n as u64)
+}
+
+/// A model for work done in two phases: first scanning to count the items,
+/// and then processing them, such as walking a directory tree and then
+/// copying the files found.
+///
+/// While scanning, the number of items found so far and the elapsed time are
+/// shown. After [ScanThenWork::start_work] the number found becomes the total,
+/// and the model shows the count done, percentage, and remaining time, like a
+/// [LinearModel].
+///
+/// [ScanThenWork::run] drives both phases from an iterator of items.
+///
+/// ```
+/// use nutmeg::models::ScanThenWork;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(
+///     ScanThenWork::new("Scanning", "Copying"),
+///     Options::default(),
+/// );
+/// let mut copied = Vec::new();
+/// ScanThenWork::run(&view, ["a", "b", "c"], |name| copied.push(name));
+/// assert_eq!(copied, ["a", "b", "c"]);
+/// let model = view.finish();
+/// assert_eq!((model.done(), model.total()), (3, 3));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ScanThenWork {
+    scan_message: Cow<'static, str>,
+    work_message: Cow<'static, str>,
+    scanning: bool,
+    found: usize,
+    done: usize,
+    /// When the current phase started, or None if it's not yet been rendered.
+    start: Option<Instant>,
+    /// Template for the final message, filled in by [fill_template].
+    final_message: Cow<'static, str>,
+    /// Set by [ScanThenWork::set_monotonic_percent].
+    monotonic: Option<MonotonicPercent>,
+}
+
+impl ScanThenWork {
+    /// Construct a model in the scanning phase, with messages describing each
+    /// phase.
+    pub fn new<S1, S2>(scan_message: S1, work_message: S2) -> ScanThenWork
+    where
+        S1: Into<Cow<'static, str>>,
+        S2: Into<Cow<'static, str>>,
+    {
+        ScanThenWork {
+            scan_message: scan_message.into(),
+            work_message: work_message.into(),
+            scanning: true,
+            found: 0,
+            done: 0,
+            start: Some(Instant::now()),
+            final_message: Cow::Borrowed(""),
+            monotonic: None,
+        }
+    }
+
+    /// Count more items found while scanning.
+    pub fn increment_found(&mut self, i: usize) {
+        self.found += i
+    }
+
+    /// Finish scanning and start the work phase, with the number of items
+    /// found as the total.
+    pub fn start_work(&mut self) {
+        self.scanning = false;
+        self.done = 0;
+        self.start = Some(Instant::now());
+    }
+
+    /// Update the amount of work done.
+    pub fn set_done(&mut self, done: usize) {
+        self.done = done
+    }
+
+    /// Update the amount of work done by an increment (typically 1).
+    pub fn increment(&mut self, i: usize) {
+        self.done += i
+    }
+
+    /// True if the model is still in the scanning phase.
+    pub fn is_scanning(&self) -> bool {
+        self.scanning
+    }
+
+    /// Get the number of items found so far, which is the total for the work
+    /// phase.
+    pub fn total(&self) -> usize {
+        self.found
+    }
+
+    /// Get the number of items processed so far in the work phase.
+    pub fn done(&self) -> usize {
+        self.done
+    }
+
+    /// Set a template for the message printed when the view is finished.
+    ///
+    /// The template can use the placeholders `{done}` and `{total}`, optionally
+    /// with a noun, as described in [fill_template]: for example,
+    /// `"Copied {done} of {total:file}"`. The message is styled as a success by the
+    /// [current_theme](crate::theme::current_theme). By default there's no
+    /// final message.
+    pub fn set_final_message<S: Into<Cow<'static, str>>>(&mut self, template: S) {
+        self.final_message = template.into()
+    }
+
+    /// Never show a lower percentage than was shown before, even if more items
+    /// are found during the work phase: see [MonotonicPercent].
+    ///
+    /// The time remaining is still estimated from the true total.
+    pub fn set_monotonic_percent(&mut self, monotonic: bool) {
+        self.monotonic = monotonic.then(MonotonicPercent::new)
+    }
+
+    /// The percentage done in the work phase, as shown.
+    fn percent(&mut self) -> String {
+        match &mut self.monotonic {
+            Some(monotonic) => monotonic.percent(self.done, self.found),
+            None => percent_done(self.done, self.found),
+        }
+    }
+
+    /// The start time of the current phase, setting it to now if it's not yet
+    /// set.
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+
+    /// Scan `items`, counting them in the model in `view`, and then call
+    /// `work` on each one, counting them as done.
+    ///
+    /// The items are collected in memory during the scan.
+    pub fn run<I, F, S>(view: &S, items: I, mut work: F)
+    where
+        I: IntoIterator,
+        F: FnMut(I::Item),
+        S: ProgressSink<ScanThenWork>,
+    {
+        let items: Vec<I::Item> = items
+            .into_iter()
+            .inspect(|_| view.update(|model| model.increment_found(1)))
+            .collect();
+        view.update(|model| model.start_work());
+        for item in items {
+            work(item);
+            view.update(|model| model.increment(1));
+        }
+    }
+}
+
+impl Model for ScanThenWork {
+    fn render(&mut self, _width: usize) -> String {
+        let start = self.start();
+        if self.scanning {
+            format!(
+                "{}: {} found in {}",
+                self.scan_message,
+                number(self.found),
+                format_duration(start.elapsed())
+            )
+        } else {
+            let percent = self.percent();
+            format!(
+                "{}: {}/{}, {percent}, {} remaining",
+                self.work_message,
+                number(self.done),
+                number(self.found),
+                estimate_remaining(&start, self.done, self.found)
+            )
+        }
+    }
+
+    fn final_message(&mut self) -> String {
+        success_message(&self.final_message, &self.summary_counts())
+    }
+
+    fn announcement(&mut self) -> String {
+        let start = self.start();
+        if self.scanning {
+            format!(
+                "{}, {} found",
+                self.scan_message,
+                plural(self.found as u64, "item")
+            )
+        } else {
+            announce_progress(&start, self.done, self.found)
+        }
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        let start = self.start();
+        if self.scanning {
+            format!(
+                "{} {}",
+                number(self.found),
+                format_duration(start.elapsed())
+            )
+        } else {
+            format!(
+                "{} {}",
+                self.percent(),
+                estimate_remaining(&start, self.done, self.found)
+            )
+        }
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        if self.scanning {
+            None
+        } else if let Some(monotonic) = &self.monotonic {
+            monotonic.peek_fraction(self.done, self.found)
+        } else if self.found == 0 || self.done > self.found {
+            None
+        } else {
+            Some(self.done as f64 / self.found as f64)
+        }
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![("done", self.done as u64), ("total", self.found as u64)]
+    }
+}
+
+/// A model counting bytes transferred, such as by a download or copy, with an
+/// optional total.
+///
+/// Sizes and the average rate are shown in decimal units by [format_bytes].
+/// If the total is known the percentage and remaining time are also shown.
+///
+/// On terminals narrower than [COMPACT_WIDTH], only the percentage (or the
+/// count, if there's no total) and the rate are shown.
+///
+/// ```
+/// use nutmeg::models::BytesModel;
+///
+/// let view = nutmeg::View::new(BytesModel::new("Downloading"), nutmeg::Options::default());
+/// view.update(|model| model.set_total(1 << 20));
+/// for _chunk in 0..16 {
+///     view.update(|model| model.increment(1 << 16));
+/// }
+/// assert_eq!(view.finish().done(), 1 << 20);
+/// ```
+#[derive(Debug, Clone)]
+pub struct BytesModel {
+    message: Cow<'static, str>,
+    done: u64,
+    total: Option<u64>,
+    /// When the transfer started, or None if it's not yet been rendered.
+    start: Option<Instant>,
+    /// Set by [BytesModel::set_monotonic_percent].
+    monotonic: Option<MonotonicPercent>,
+}
+
+impl BytesModel {
+    /// Construct a model with a message describing the transfer, and no
+    /// total.
+    pub fn new<S: Into<Cow<'static, str>>>(message: S) -> BytesModel {
+        BytesModel {
+            message: message.into(),
+            done: 0,
+            total: None,
+            start: Some(Instant::now()),
+            monotonic: None,
+        }
+    }
+
+    /// Construct a model in a `const` context, such as the initial model of a
+    /// `static` [View].
+    ///
+    /// The start time, from which the rate is measured, is set when the model
+    /// is first rendered.
+    pub const fn new_const(message: &'static str) -> BytesModel {
+        BytesModel {
+            message: Cow::Borrowed(message),
+            done: 0,
+            total: None,
+            start: None,
+            monotonic: None,
+        }
+    }
+
+    /// Set the total number of bytes expected, such as from a `Content-Length`
+    /// header.
+    pub fn set_total(&mut self, total: u64) {
+        self.total = Some(total)
+    }
+
+    /// Get the total number of bytes expected, if known.
+    pub fn total(&self) -> Option<u64> {
+        self.total
+    }
+
+    /// Update the number of bytes transferred.
+    pub fn set_done(&mut self, done: u64) {
+        self.done = done
+    }
+
+    /// Add to the number of bytes transferred.
+    pub fn increment(&mut self, bytes: u64) {
+        self.done += bytes
+    }
+
+    /// Get the number of bytes transferred so far.
+    pub fn done(&self) -> u64 {
+        self.done
+    }
+
+    /// Get the message describing the transfer.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the time from which the rate is measured.
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start
+    }
+
+    /// Never show a lower percentage than was shown before, even if the total
+    /// is revised upwards: see [MonotonicPercent].
+    ///
+    /// The time remaining is still estimated from the true total.
+    pub fn set_monotonic_percent(&mut self, monotonic: bool) {
+        self.monotonic = monotonic.then(MonotonicPercent::new)
+    }
+
+    /// The percentage done of a known total, as shown.
+    fn percent(&mut self, total: u64) -> String {
+        let (done, total) = (saturating_usize(self.done), saturating_usize(total));
+        match &mut self.monotonic {
+            Some(monotonic) => monotonic.percent(done, total),
+            None => percent_done(done, total),
+        }
+    }
+
+    /// The start time, setting it to now if it's not yet set.
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+
+    /// The average rate since the start, like "1.2 MB/s".
+    fn rate(&mut self) -> String {
+        let secs = self.start().elapsed().as_secs_f64();
+        let rate = if secs > 0.0 {
+            (self.done as f64 / secs) as u64
+        } else {
+            0
+        };
+        format!("{}/s", format_bytes(rate))
+    }
+}
+
+impl Model for BytesModel {
+    fn render(&mut self, _width: usize) -> String {
+        let rate = self.rate();
+        let start = self.start();
+        match self.total {
+            Some(total) => {
+                let (done, total_items) = (saturating_usize(self.done), saturating_usize(total));
+                let percent = self.percent(total);
+                format!(
+                    "{}: {} of {}, {percent}, {}, {} remaining",
+                    self.message,
+                    format_bytes(self.done),
+                    format_bytes(total),
+                    rate,
+                    estimate_remaining(&start, done, total_items)
+                )
+            }
+            None => format!(
+                "{}: {} in {}, {}",
+                self.message,
+                format_bytes(self.done),
+                format_duration(start.elapsed()),
+                rate
+            ),
+        }
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        let rate = self.rate();
+        match self.total {
+            Some(total) => format!("{} {}", self.percent(total), rate),
+            None => format!("{} {}", format_bytes(self.done), rate),
+        }
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        match (self.total, &self.monotonic) {
+            (Some(total), Some(monotonic)) => {
+                monotonic.peek_fraction(saturating_usize(self.done), saturating_usize(total))
+            }
+            (Some(total), None) if total > 0 && self.done <= total => {
+                Some(self.done as f64 / total as f64)
+            }
+            _ => None,
+        }
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        let mut counts = vec![("done", self.done)];
+        counts.extend(self.total.map(|total| ("total", total)));
+        counts
+    }
+}
+
+/// A model counting both items and bytes done, such as files and their
+/// contents copied, each with an optional total.
+///
+/// Copy tools usually know both the number of files and their total size,
+/// and neither alone tells the full story: a few large files may account for
+/// most of the bytes, or many small files for most of the time. This model
+/// shows both, like `Copying: 12/40, 1.2 MB of 3.4 MB, 35.3%, 1.2 MB/s, 12 sec remaining`.
+///
+/// The percentage and time remaining come from bytes when their total is
+/// known, because the time to copy something depends more closely on its size
+/// than on the number of items. Otherwise, they come from the items if their
+/// total is known.
+///
+/// On terminals narrower than [COMPACT_WIDTH], only the percentage and time
+/// remaining are shown, or the counts if neither total is known.
+///
+/// ```
+/// use nutmeg::models::ItemsAndBytesModel;
+/// use nutmeg::Model;
+///
+/// let mut model = ItemsAndBytesModel::new("Copying");
+/// model.set_total_items(4);
+/// model.set_total_bytes(4000);
+/// model.increment(1, 3000);
+/// assert!(model.render(100).starts_with("Copying: 1/4, 3.0 kB of 4.0 kB, 75.0%, "));
+/// assert_eq!(model.fraction_done(), Some(0.75));
+/// ```
+#[derive(Debug, Clone)]
+pub struct ItemsAndBytesModel {
+    message: Cow<'static, str>,
+    items: usize,
+    total_items: Option<usize>,
+    bytes: u64,
+    total_bytes: Option<u64>,
+    /// When the work started, or None if it's not yet been rendered.
+    start: Option<Instant>,
+    /// Template for the final message, filled in by [fill_template].
+    final_message: Cow<'static, str>,
+    /// Set by [ItemsAndBytesModel::set_monotonic_percent].
+    monotonic: Option<MonotonicPercent>,
+}
+
+impl ItemsAndBytesModel {
+    /// Construct a model with a message describing the work, and no totals.
+    pub fn new<S: Into<Cow<'static, str>>>(message: S) -> ItemsAndBytesModel {
+        ItemsAndBytesModel {
+            message: message.into(),
+            items: 0,
+            total_items: None,
+            bytes: 0,
+            total_bytes: None,
+            start: Some(Instant::now()),
+            final_message: Cow::Borrowed(""),
+            monotonic: None,
+        }
+    }
+
+    /// Construct a model in a `const` context, such as the initial model of a
+    /// `static` [View].
+    ///
+    /// The start time, from which the rate and remaining time are
+    /// extrapolated, is set when the model is first rendered.
+    pub const fn new_const(message: &'static str) -> ItemsAndBytesModel {
+        ItemsAndBytesModel {
+            message: Cow::Borrowed(message),
+            items: 0,
+            total_items: None,
+            bytes: 0,
+            total_bytes: None,
+            start: None,
+            final_message: Cow::Borrowed(""),
+            monotonic: None,
+        }
+    }
+
+    /// Set the total number of items expected.
+    pub fn set_total_items(&mut self, total: usize) {
+        self.total_items = Some(total)
+    }
+
+    /// Set the total number of bytes expected.
+    pub fn set_total_bytes(&mut self, total: u64) {
+        self.total_bytes = Some(total)
+    }
+
+    /// Count `items` more items and `bytes` more bytes done.
+    ///
+    /// This should normally be called from a callback passed to [View::update].
+    pub fn increment(&mut self, items: usize, bytes: u64) {
+        self.items += items;
+        self.bytes += bytes;
+    }
+
+    /// Count more bytes done, such as part-way through a large item.
+    pub fn increment_bytes(&mut self, bytes: u64) {
+        self.bytes += bytes
+    }
+
+    /// Update the number of items and bytes done.
+    pub fn set_done(&mut self, items: usize, bytes: u64) {
+        self.items = items;
+        self.bytes = bytes;
+    }
+
+    /// Get the number of items done so far.
+    pub fn items(&self) -> usize {
+        self.items
+    }
+
+    /// Get the number of bytes done so far.
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Get the total number of items expected, if known.
+    pub fn total_items(&self) -> Option<usize> {
+        self.total_items
+    }
+
+    /// Get the total number of bytes expected, if known.
+    pub fn total_bytes(&self) -> Option<u64> {
+        self.total_bytes
+    }
+
+    /// Get the message describing the work.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the time from which the rate and remaining time are extrapolated.
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start
+    }
+
+    /// Set a template for the message printed when the view is finished.
+    ///
+    /// The template can use the placeholders `{done}` and `{total}` for items,
+    /// and `{bytes}` and `{total_bytes}`, as described in [fill_template]:
+    /// for example, `"Copied {done:file}"`. The message is styled as a success
+    /// by the [current_theme](crate::theme::current_theme). By default there's
+    /// no final message.
+    pub fn set_final_message<S: Into<Cow<'static, str>>>(&mut self, template: S) {
+        self.final_message = template.into()
+    }
+
+    /// Never show a lower percentage than was shown before, even if a total
+    /// is revised upwards: see [MonotonicPercent].
+    ///
+    /// The time remaining is still estimated from the true total.
+    pub fn set_monotonic_percent(&mut self, monotonic: bool) {
+        self.monotonic = monotonic.then(MonotonicPercent::new)
+    }
+
+    /// The amount done and total of the measure the percentage and remaining
+    /// time are estimated from: bytes if their total is known, and otherwise
+    /// items.
+    fn measure(&self) -> Option<(usize, usize)> {
+        match (self.total_bytes, self.total_items) {
+            (Some(total), _) => Some((saturating_usize(self.bytes), saturating_usize(total))),
+            (None, Some(total)) => Some((self.items, total)),
+            (None, None) => None,
+        }
+    }
+
+    /// The percentage done and time remaining, like "35.3%" and "12 sec",
+    /// if either total is known.
+    fn estimate(&mut self) -> Option<(String, String)> {
+        let (done, total) = self.measure()?;
+        let start = self.start();
+        let percent = match &mut self.monotonic {
+            Some(monotonic) => monotonic.percent(done, total),
+            None => percent_done(done, total),
+        };
+        Some((percent, estimate_remaining(&start, done, total)))
+    }
+
+    /// The start time, setting it to now if it's not yet set.
+    fn start(&mut self) -> Instant {
+        *self.start.get_or_insert_with(Instant::now)
+    }
+
+    /// The average rate of bytes since the start, like "1.2 MB/s".
+    fn rate(&mut self) -> String {
+        let secs = self.start().elapsed().as_secs_f64();
+        let rate = if secs > 0.0 {
+            (self.bytes as f64 / secs) as u64
+        } else {
+            0
+        };
+        format!("{}/s", format_bytes(rate))
+    }
+}
+
+impl Model for ItemsAndBytesModel {
+    fn render(&mut self, _width: usize) -> String {
+        let rate = self.rate();
+        let items = match self.total_items {
+            Some(total) => format!("{}/{}", number(self.items), number(total)),
+            None => number(self.items),
+        };
+        let bytes = match self.total_bytes {
+            Some(total) => format!("{} of {}", format_bytes(self.bytes), format_bytes(total)),
+            None => format_bytes(self.bytes),
+        };
+        match self.estimate() {
+            Some((percent, remaining)) => format!(
+                "{}: {items}, {bytes}, {percent}, {rate}, {remaining} remaining",
+                self.message
+            ),
+            None => {
+                let elapsed = format_duration(self.start().elapsed());
+                format!("{}: {items}, {bytes} in {elapsed}, {rate}", self.message)
+            }
+        }
+    }
+
+    fn final_message(&mut self) -> String {
+        success_message(&self.final_message, &self.summary_counts())
+    }
+
+    fn announcement(&mut self) -> String {
+        let start = self.start();
+        match self.measure() {
+            Some((done, total)) => announce_progress(&start, done, total),
+            None => format!("{} done", plural(self.items as u64, "item")),
+        }
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        match self.estimate() {
+            Some((percent, remaining)) => format!("{percent} {remaining}"),
+            None => format!("{} {}", number(self.items), format_bytes(self.bytes)),
+        }
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        let (done, total) = self.measure()?;
+        if let Some(monotonic) = &self.monotonic {
+            monotonic.peek_fraction(done, total)
+        } else if total == 0 || done > total {
+            None
+        } else {
+            Some(done as f64 / total as f64)
+        }
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        let mut counts = vec![("done", self.items as u64)];
+        counts.extend(self.total_items.map(|total| ("total", total as u64)));
+        counts.push(("bytes", self.bytes));
+        counts.extend(self.total_bytes.map(|total| ("total_bytes", total)));
+        counts
+    }
+}
+
+fn saturating_usize(n: u64) -> usize {
+    usize::try_from(n).unwrap_or(usize::MAX)
+}
+
+/// A model that groups several member models under a summary line.
+///
+/// Depending on its [Expansion] policy and the height of the terminal, the
+/// group shows either just the summary, which counts the members that are
+/// complete and their overall completion, or the summary followed by each
+/// member, indented. This way hundreds of members degrade gracefully to a
+/// one-line rollup, while a few are shown in detail.
+///
+/// Members are complete when their [Model::fraction_done] is 1.0, and the
+/// group's fraction done is the mean of the fractions its members report.
+/// Groups can be nested.
+///
+/// ```
+/// use nutmeg::models::{Expansion, Group, LinearModel};
+/// use nutmeg::Model;
+///
+/// let mut group = Group::new("Downloads");
+/// group.push(LinearModel::new("a.tar", 10));
+/// group.push(LinearModel::new("b.tar", 10));
+/// group.members_mut()[0].set_done(10);
+/// assert_eq!(group.render(80).lines().count(), 3);
+///
+/// group.set_expansion(Expansion::Collapsed);
+/// assert_eq!(group.render(80), "Downloads: 1/2 complete, 50.0%");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Group<M: Model> {
+    name: Cow<'static, str>,
+    members: Vec<M>,
+    expansion: Expansion,
+}
+
+impl<M: Model> Group<M> {
+    /// Construct an empty group, with the default [Expansion::Auto] policy.
+    pub fn new<S: Into<Cow<'static, str>>>(name: S) -> Group<M> {
+        Group {
+            name: name.into(),
+            members: Vec::new(),
+            expansion: Expansion::default(),
+        }
+    }
+
+    /// Construct an empty group in a `const` context, such as the initial
+    /// model of a `static` [View].
+    pub const fn new_const(name: &'static str) -> Group<M> {
+        Group {
+            name: Cow::Borrowed(name),
+            members: Vec::new(),
+            expansion: Expansion::Auto,
+        }
+    }
+
+    /// Set when the members are shown.
+    pub fn set_expansion(&mut self, expansion: Expansion) {
+        self.expansion = expansion;
+    }
+
+    /// Add a member to the end of the group.
+    pub fn push(&mut self, member: M) {
+        self.members.push(member);
+    }
+
+    /// The members of the group.
+    pub fn members(&self) -> &[M] {
+        &self.members
+    }
+
+    /// The members of the group, which can be updated, added, or removed.
+    pub fn members_mut(&mut self) -> &mut Vec<M> {
+        &mut self.members
+    }
+
+    /// The number of members whose fraction done is 1.0.
+    pub fn complete(&self) -> usize {
+        self.members
+            .iter()
+            .filter(|m| m.fraction_done().is_some_and(|f| f >= 1.0))
+            .count()
+    }
+
+    fn summary(&self) -> String {
+        let formatting = current_formatting();
+        let mut summary = format!(
+            "{}: {}/{} complete",
+            self.name,
+            number(self.complete()),
+            number(self.members.len())
+        );
+        if let Some(fraction) = self.fraction_done() {
+            summary.push_str(", ");
+            summary.push_str(&formatting.percent(fraction * 100.0));
+        }
+        summary
+    }
+}
+
+impl<M: Model> Model for Group<M> {
+    fn render(&mut self, width: usize) -> String {
+        let mut out = self.summary();
+        let max_lines = self.expansion.max_lines();
+        if 1 + self.members.len() > max_lines {
+            return out;
+        }
+        let member_width = width.saturating_sub(2).max(1);
+        let mut detail = String::new();
+        let mut lines = 1;
+        for member in &mut self.members {
+            for line in render_at(member, member_width).lines() {
+                lines += 1;
+                if lines > max_lines {
+                    return out;
+                }
+                detail.push_str("\n  ");
+                detail.push_str(line);
+            }
+        }
+        out.push_str(&detail);
+        out
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        let fractions: Vec<f64> = self
+            .members
+            .iter()
+            .filter_map(|m| m.fraction_done())
+            .collect();
+        if fractions.is_empty() {
+            None
+        } else {
+            Some(fractions.iter().sum::<f64>() / fractions.len() as f64)
+        }
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![
+            ("complete", self.complete() as u64),
+            ("members", self.members.len() as u64),
+        ]
+    }
+}
+
+/// A model that shows a spinner followed by a message, for work whose
+/// progress can't be measured.
+///
+/// The spinner's frame is chosen from the time since the model was first
+/// rendered, as given by [frame_time], rather than from the number of
+/// updates, so it turns at a steady rate however often the model is updated.
+/// To keep it turning while the application isn't updating the view, use
+/// [View::with_ticker].
+///
+/// ```
+/// use std::time::Duration;
+/// use nutmeg::models::SpinnerModel;
+/// use nutmeg::{Options, View};
+///
+/// let options = Options::default().tick_interval(SpinnerModel::FRAME_INTERVAL);
+/// let view = View::new(SpinnerModel::new("Resolving dependencies"), options);
+/// view.with_ticker(|| {
+///     std::thread::sleep(Duration::from_millis(300));
+///     view.update(|model| model.set_message("Downloading"));
+/// });
+/// ```
+#[derive(Debug, Clone)]
+pub struct SpinnerModel {
+    message: Cow<'static, str>,
+    frames: &'static [&'static str],
+    frame_interval: Duration,
+    /// When the model was first rendered, or None if it hasn't been yet.
+    start: Option<Instant>,
+}
+
+impl SpinnerModel {
+    /// The default frames: a dot rotating in a braille cell.
+    pub const FRAMES: &'static [&'static str] = &["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"];
+
+    /// The default time each frame is shown.
+    pub const FRAME_INTERVAL: Duration = Duration::from_millis(100);
+
+    /// Construct a spinner showing `message`.
+    pub fn new<S: Into<Cow<'static, str>>>(message: S) -> SpinnerModel {
+        SpinnerModel {
+            message: message.into(),
+            ..SpinnerModel::new_const("")
+        }
+    }
+
+    /// Construct a spinner in a `const` context, such as the initial model of
+    /// a `static` [View].
+    pub const fn new_const(message: &'static str) -> SpinnerModel {
+        SpinnerModel {
+            message: Cow::Borrowed(message),
+            frames: SpinnerModel::FRAMES,
+            frame_interval: SpinnerModel::FRAME_INTERVAL,
+            start: None,
+        }
+    }
+
+    /// Use different frames, each shown for `frame_interval`.
+    ///
+    /// Panics if `frames` is empty or `frame_interval` is zero.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::models::SpinnerModel;
+    ///
+    /// let mut spinner = SpinnerModel::new("Thinking");
+    /// spinner.set_frames(&["-", "\\", "|", "/"], Duration::from_millis(250));
+    /// ```
+    pub fn set_frames(&mut self, frames: &'static [&'static str], frame_interval: Duration) {
+        assert!(!frames.is_empty(), "spinner frames are empty");
+        assert!(!frame_interval.is_zero(), "spinner frame interval is zero");
+        self.frames = frames;
+        self.frame_interval = frame_interval;
+    }
+
+    /// Change the message shown after the spinner.
+    pub fn set_message<S: Into<Cow<'static, str>>>(&mut self, message: S) {
+        self.message = message.into();
+    }
+
+    /// Get the message shown after the spinner.
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Get the time from which the spinner turns, which is when it was first
+    /// rendered.
+    pub fn start_time(&self) -> Option<Instant> {
+        self.start
+    }
+
+    /// The frame to show at `now`.
+    fn frame(&mut self, now: Instant) -> &'static str {
+        let start = *self.start.get_or_insert(now);
+        let ticks =
+            now.saturating_duration_since(start).as_nanos() / self.frame_interval.as_nanos();
+        self.frames[(ticks % self.frames.len() as u128) as usize]
+    }
+}
+
+impl Model for SpinnerModel {
+    fn render(&mut self, _width: usize) -> String {
+        format!("{} {}", self.frame(frame_time()), self.message)
+    }
+}
+
+/// A model that measures how often the application updates the view, and how
+/// often the view paints, for tuning how often a hot loop should call
+/// [View::update].
+///
+/// Call [ThroughputProbe::record_update] from each update. Each call to
+/// [Model::render] counts as one paint. Once a measurement window has passed,
+/// by [frame_time], the probe shows the rates over that window, like
+/// `Hashing: 48213 updates/s, 20 paints/s, 2411 updates per paint`.
+///
+/// A high ratio of updates to paints means most updates are only taking the
+/// view's lock to be discarded, so the loop could batch its work and update
+/// less often.
+///
+/// ```
+/// use nutmeg::models::ThroughputProbe;
+/// use nutmeg::{Options, View};
+///
+/// let view = View::new(ThroughputProbe::new("Hashing"), Options::default());
+/// for _ in 0..1000 {
+///     view.update(|model| model.record_update());
+/// }
+/// let probe = view.finish();
+/// assert_eq!(probe.updates(), 1000);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ThroughputProbe {
+    message: Cow<'static, str>,
+    updates: u64,
+    paints: u64,
+    window: Duration,
+    /// The time the current window started, and the counts at that time, or
+    /// None if the model hasn't been rendered yet.
+    window_start: Option<(Instant, u64, u64)>,
+    /// Updates and paints per second over the last complete window.
+    rates: Option<(f64, f64)>,
+}
+
+impl ThroughputProbe {
+    /// The default length of the window over which rates are measured.
+    pub const WINDOW: Duration = Duration::from_secs(1);
+
+    /// Construct a probe showing `message` before the rates.
+    pub fn new<S: Into<Cow<'static, str>>>(message: S) -> ThroughputProbe {
+        ThroughputProbe {
+            message: message.into(),
+            updates: 0,
+            paints: 0,
+            window: ThroughputProbe::WINDOW,
+            window_start: None,
+            rates: None,
+        }
+    }
+
+    /// Measure rates over windows of this length, rather than
+    /// [ThroughputProbe::WINDOW].
+    ///
+    /// Panics if `window` is zero.
+    pub fn set_window(&mut self, window: Duration) {
+        assert!(!window.is_zero(), "throughput window is zero");
+        self.window = window;
+    }
+
+    /// Count one update from the application.
+    pub fn record_update(&mut self) {
+        self.updates += 1;
+    }
+
+    /// The number of updates recorded so far.
+    pub fn updates(&self) -> u64 {
+        self.updates
+    }
+
+    /// The number of times the model has been painted so far.
+    pub fn paints(&self) -> u64 {
+        self.paints
+    }
+
+    /// Updates per second over the last complete window, or None if no window
+    /// has completed yet.
+    pub fn update_rate(&self) -> Option<f64> {
+        self.rates.map(|(updates, _)| updates)
+    }
+
+    /// Paints per second over the last complete window, or None if no window
+    /// has completed yet.
+    pub fn paint_rate(&self) -> Option<f64> {
+        self.rates.map(|(_, paints)| paints)
+    }
+
+    /// Count a paint at `now`, and measure the rates if a window has passed.
+    fn record_paint(&mut self, now: Instant) {
+        self.paints += 1;
+        let (start, start_updates, start_paints) =
+            *self
+                .window_start
+                .get_or_insert((now, self.updates, self.paints));
+        let elapsed = now.saturating_duration_since(start);
+        if elapsed >= self.window {
+            let secs = elapsed.as_secs_f64();
+            self.rates = Some((
+                (self.updates - start_updates) as f64 / secs,
+                (self.paints - start_paints) as f64 / secs,
+            ));
+            self.window_start = Some((now, self.updates, self.paints));
+        }
+    }
+
+    /// The rates, or the counts if no window has completed yet.
+    fn render_rates(&self) -> String {
+        let formatting = current_formatting();
+        match self.rates {
+            Some((updates, paints)) => {
+                let mut s = format!(
+                    "{} updates/s, {} paints/s",
+                    formatting.number(updates.round() as u64),
+                    formatting.number(paints.round() as u64),
+                );
+                if paints > 0.0 {
+                    s.push_str(&format!(
+                        ", {} updates per paint",
+                        formatting.number((updates / paints).round() as u64)
+                    ));
+                }
+                s
+            }
+            None => format!(
+                "{} updates, {} paints",
+                formatting.number(self.updates),
+                formatting.number(self.paints),
+            ),
+        }
+    }
+}
+
+impl Model for ThroughputProbe {
+    fn render(&mut self, _width: usize) -> String {
+        self.record_paint(frame_time());
+        format!("{}: {}", self.message, self.render_rates())
+    }
+
+    fn compact_width(&self) -> usize {
+        COMPACT_WIDTH
+    }
+
+    fn render_compact(&mut self, _width: usize) -> String {
+        self.record_paint(frame_time());
+        self.render_rates()
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        vec![("updates", self.updates), ("paints", self.paints)]
     }
 }
 
@@ -227,7 +1663,6 @@ fn format_duration(d: Duration) -> String {
 /// For many simple cases this avoids any need to explicitly declare a model
 /// class: instead the [View::new] call can, in-line, construct a BasicView
 /// giving an initial value and a render function.
-
 ///
 /// # Example
 /// ```
@@ -284,7 +1719,7 @@ where
 }
 
 /// A model that holds a single value and renders it using its `Display` implementation.
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub struct DisplayModel<T: Display + Debug>(pub T);
 
 impl<T: Display + Debug> DisplayModel<T> {
@@ -299,3 +1734,129 @@ impl<T: Display + Debug> Model for DisplayModel<T> {
         format!("{}", self.0)
     }
 }
+
+/// One rendering of a built-in model, returned by [gallery].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GalleryFrame {
+    /// The name of the model type, such as `"LinearModel"`.
+    pub model: &'static str,
+    /// A description of the state of the model when it was rendered, such as `"50/100"`.
+    pub state: String,
+    /// The width passed to [Model::render].
+    pub width: usize,
+    /// The rendered text.
+    pub rendered: String,
+}
+
+/// The terminal widths at which [gallery] renders each model.
+pub const GALLERY_WIDTHS: [usize; 3] = [20, 40, 80];
+
+/// Render every built-in model in several states and at each of [GALLERY_WIDTHS].
+///
+/// This lets applications preview how the built-in models look, and gives
+/// tests a simple way to check that changes to the models, or new models,
+/// render sensibly.
+///
+/// Models that show elapsed or remaining time are rendered immediately after
+/// they're constructed, so the times will be close to zero.
+///
+/// ```
+/// for frame in nutmeg::models::gallery() {
+///     println!("{:>14} {:>8} {:>3}: {}", frame.model, frame.state, frame.width, frame.rendered);
+/// }
+/// ```
+pub fn gallery() -> Vec<GalleryFrame> {
+    let mut frames = Vec::new();
+    let mut add = |model: &'static str, state: String, m: &mut dyn Model| {
+        for width in GALLERY_WIDTHS {
+            frames.push(GalleryFrame {
+                model,
+                state: state.clone(),
+                width,
+                rendered: render_at(m, width),
+            });
+        }
+    };
+    for suffix in ["", "/etc/hostname"] {
+        add(
+            "StringPair",
+            format!("{suffix:?}"),
+            &mut StringPair::new("Copying: ", suffix),
+        );
+    }
+    for done in [0, 50, 100] {
+        let mut model = LinearModel::new("Counting raindrops", 100);
+        model.set_done(done);
+        add("LinearModel", format!("{done}/100"), &mut model);
+    }
+    for done in [0, 12345] {
+        let mut model = UnboundedModel::new("Counting raindrops");
+        model.set_done(done);
+        add("UnboundedModel", format!("{done}"), &mut model);
+    }
+    for (found, done) in [(1234, None), (1234, Some(617))] {
+        let mut model = ScanThenWork::new("Scanning", "Copying");
+        model.increment_found(found);
+        if let Some(done) = done {
+            model.start_work();
+            model.set_done(done);
+        }
+        add("ScanThenWork", format!("{found}/{done:?}"), &mut model);
+    }
+    for (done, total) in [
+        (0, None),
+        (12_345_678, None),
+        (12_345_678, Some(98_765_432)),
+    ] {
+        let mut model = BytesModel::new("Downloading");
+        model.set_done(done);
+        if let Some(total) = total {
+            model.set_total(total);
+        }
+        add("BytesModel", format!("{done}/{total:?}"), &mut model);
+    }
+    for (total_items, total_bytes) in [(None, None), (Some(40), Some(3_456_789))] {
+        let mut model = ItemsAndBytesModel::new("Copying");
+        model.set_done(12, 1_234_567);
+        if let (Some(items), Some(bytes)) = (total_items, total_bytes) {
+            model.set_total_items(items);
+            model.set_total_bytes(bytes);
+        }
+        add(
+            "ItemsAndBytesModel",
+            format!("{total_items:?}/{total_bytes:?}"),
+            &mut model,
+        );
+    }
+    add(
+        "SpinnerModel",
+        "new".to_owned(),
+        &mut SpinnerModel::new("Resolving dependencies"),
+    );
+    add(
+        "ThroughputProbe",
+        "new".to_owned(),
+        &mut ThroughputProbe::new("Hashing"),
+    );
+    for expansion in [Expansion::Collapsed, Expansion::Expanded] {
+        let mut group = Group::new("Downloads");
+        for (name, done) in [("a.tar", 100), ("b.tar", 40)] {
+            let mut member = LinearModel::new(name, 100);
+            member.set_done(done);
+            group.push(member);
+        }
+        group.set_expansion(expansion);
+        add("Group", format!("{expansion:?}"), &mut group);
+    }
+    add(
+        "BasicModel",
+        "(3, 10)".to_owned(),
+        &mut BasicModel::new((3, 10), |(a, b)| format!("{a}/{b} complete")),
+    );
+    add(
+        "DisplayModel",
+        "42".to_owned(),
+        &mut DisplayModel("the answer is 42"),
+    );
+    frames
+}
@@ -0,0 +1,299 @@
+// Copyright 2023 Martin Pool.
+
+//! Several independent progress regions stacked on one terminal.
+
+use std::any::Any;
+use std::marker::PhantomData;
+#[cfg(feature = "capture")]
+use std::sync::{Arc, Mutex};
+
+use crate::{render_at, Destination, DestinationTrait, Model, Options, View};
+
+/// Several progress regions, each with its own [Model], drawn one above
+/// the other on a single terminal.
+///
+/// Only one view should be active on a terminal at a time, so an application
+/// that wants to show progress for several independent pieces of work can
+/// make one `MultiView` and [add](MultiView::add) a region for each. The
+/// `MultiView` owns the terminal: it erases and redraws all the regions
+/// together, and messages printed through it appear above all of them.
+///
+/// Each region is updated through the [Region] handle returned when it's
+/// added. When the handle is finished or dropped, the region is removed and
+/// its [Model::final_message] is printed above the remaining regions.
+///
+/// Regions borrow the `MultiView`, so to update them from other threads, use
+/// scoped threads or keep the `MultiView` in a `static`.
+///
+/// ```
+/// use nutmeg::{MultiView, Options};
+/// use nutmeg::models::{DisplayModel, LinearModel};
+///
+/// let multi = MultiView::new(Options::default());
+/// std::thread::scope(|scope| {
+///     for name in ["fetch", "build"] {
+///         let multi = &multi;
+///         scope.spawn(move || {
+///             let region = multi.add(LinearModel::new(name, 10));
+///             for _ in 0..10 {
+///                 region.update(|model| model.increment(1));
+///             }
+///         });
+///     }
+///     let status = multi.add(DisplayModel("waiting for jobs"));
+///     status.update(|model| model.0 = "still waiting");
+/// });
+/// multi.finish();
+/// ```
+pub struct MultiView<D: DestinationTrait = Destination> {
+    view: View<Regions, D>,
+}
+
+/// A handle to update one region of a [MultiView].
+///
+/// Dropping the handle, or calling [Region::finish], removes the region and
+/// prints its [Model::final_message].
+pub struct Region<'v, M: Model + Send + 'static, D: DestinationTrait = Destination> {
+    multi: &'v MultiView<D>,
+    id: u64,
+    _model: PhantomData<fn() -> M>,
+}
+
+/// The model of the underlying view: all the regions, in the order they
+/// were added.
+struct Regions {
+    members: Vec<(u64, Box<dyn Member>)>,
+    next_id: u64,
+}
+
+/// A model that can be stored in a [MultiView] and later recovered as its
+/// concrete type.
+trait Member: Model + Send {
+    fn as_any_mut(&mut self) -> &mut dyn Any;
+    fn into_any(self: Box<Self>) -> Box<dyn Any>;
+}
+
+impl<M: Model + Send + 'static> Member for M {
+    fn as_any_mut(&mut self) -> &mut dyn Any {
+        self
+    }
+
+    fn into_any(self: Box<Self>) -> Box<dyn Any> {
+        self
+    }
+}
+
+impl Regions {
+    fn get_mut<M: Model + Send + 'static>(&mut self, id: u64) -> &mut M {
+        let (_, member) = self
+            .members
+            .iter_mut()
+            .find(|(member_id, _)| *member_id == id)
+            .expect("Region is still present");
+        member
+            .as_any_mut()
+            .downcast_mut()
+            .expect("Region has the model type it was added with")
+    }
+
+    fn remove(&mut self, id: u64) -> Option<Box<dyn Member>> {
+        let i = self
+            .members
+            .iter()
+            .position(|(member_id, _)| *member_id == id)?;
+        Some(self.members.remove(i).1)
+    }
+}
+
+impl Model for Regions {
+    fn render(&mut self, width: usize) -> String {
+        let mut s = String::new();
+        for (_, member) in &mut self.members {
+            let rendered = render_at(member.as_mut(), width);
+            let rendered = rendered.strip_suffix('\n').unwrap_or(&rendered);
+            if rendered.is_empty() {
+                continue;
+            }
+            if !s.is_empty() {
+                s.push('\n');
+            }
+            s.push_str(rendered);
+        }
+        s
+    }
+
+    fn max_height(&self) -> Option<usize> {
+        self.members
+            .iter()
+            .map(|(_, member)| member.max_height())
+            .sum()
+    }
+
+    fn final_message(&mut self) -> String {
+        self.members
+            .iter_mut()
+            .map(|(_, member)| member.final_message())
+            .filter(|message| !message.is_empty())
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl MultiView {
+    /// Construct a multi-region view with no regions, drawn to the
+    /// [Options::destination].
+    pub const fn new(options: Options) -> MultiView {
+        MultiView {
+            view: View::new(
+                Regions {
+                    members: Vec::new(),
+                    next_id: 0,
+                },
+                options,
+            ),
+        }
+    }
+}
+
+impl<D: DestinationTrait> MultiView<D> {
+    /// Construct a multi-region view drawing to a destination of a specific
+    /// type, as for [View::with_destination].
+    pub const fn with_destination(options: Options, destination: D) -> MultiView<D> {
+        MultiView {
+            view: View::with_destination(
+                Regions {
+                    members: Vec::new(),
+                    next_id: 0,
+                },
+                options,
+                destination,
+            ),
+        }
+    }
+
+    /// Add a region showing `model` below all the existing regions.
+    ///
+    /// Returns a handle through which the region's model is updated.
+    pub fn add<M: Model + Send + 'static>(&self, model: M) -> Region<'_, M, D> {
+        let id = self.view.update(|regions| {
+            let id = regions.next_id;
+            regions.next_id += 1;
+            regions.members.push((id, Box::new(model)));
+            id
+        });
+        Region {
+            multi: self,
+            id,
+            _model: PhantomData,
+        }
+    }
+
+    /// Return the number of regions currently shown.
+    pub fn region_count(&self) -> usize {
+        self.view.inspect_model(|regions| regions.members.len())
+    }
+
+    /// Print a message above all the regions, as for [View::message].
+    pub fn message<S: AsRef<str>>(&self, message: S) {
+        self.view.message(message)
+    }
+
+    /// Hide all the regions until [MultiView::resume] is called, as for
+    /// [View::suspend].
+    pub fn suspend(&self) {
+        self.view.suspend()
+    }
+
+    /// Allow the regions to be drawn again, as for [View::resume].
+    pub fn resume(&self) {
+        self.view.resume()
+    }
+
+    /// Return the buffer of output captured when the destination is
+    /// [Destination::Capture], as for [View::captured_output].
+    #[cfg(feature = "capture")]
+    pub fn captured_output(&self) -> Arc<Mutex<String>> {
+        self.view.captured_output()
+    }
+
+    /// Erase all the regions and print the final messages of any that are
+    /// still present, in order.
+    pub fn finish(self) {
+        self.view.finish();
+    }
+
+    /// Stop using this view, leaving the regions on the screen.
+    pub fn abandon(self) {
+        self.view.abandon();
+    }
+}
+
+impl<M: Model + Send + 'static, D: DestinationTrait> Region<'_, M, D> {
+    /// Update this region's model, and redraw all the regions if needed, as
+    /// for [View::update].
+    pub fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        let id = self.id;
+        self.multi
+            .view
+            .update(|regions| update_fn(regions.get_mut(id)))
+    }
+
+    /// Inspect this region's model without redrawing, as for
+    /// [View::inspect_model].
+    pub fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        let id = self.id;
+        self.multi
+            .view
+            .inspect_model(|regions| f(regions.get_mut(id)))
+    }
+
+    /// Print a message above all the regions, as for [View::message].
+    pub fn message<S: AsRef<str>>(&self, message: S) {
+        self.multi.message(message)
+    }
+
+    /// Remove this region, print its final message, and return the model.
+    pub fn finish(self) -> M {
+        let this = std::mem::ManuallyDrop::new(self);
+        *this
+            .remove()
+            .expect("Region is still present")
+            .into_any()
+            .downcast()
+            .expect("Region has the model type it was added with")
+    }
+
+    /// Remove the region from the view and print its final message, if it's
+    /// still present.
+    fn remove(&self) -> Option<Box<dyn Member>> {
+        let id = self.id;
+        // The final message is rendered inside the update so that it uses the
+        // view's formatting and theme.
+        let (member, message) = self.multi.view.update(|regions| {
+            let mut member = regions.remove(id)?;
+            let message = member.final_message();
+            Some((member, message))
+        })?;
+        if !message.is_empty() {
+            // Print the message and redraw the remaining regions below it.
+            self.multi.view.freeze_line(message);
+        }
+        Some(member)
+    }
+}
+
+impl<M: Model + Send + 'static, D: DestinationTrait> Drop for Region<'_, M, D> {
+    fn drop(&mut self) {
+        // Leave the region while unwinding, rather than risk panicking again.
+        if std::thread::panicking() {
+            return;
+        }
+        self.remove();
+    }
+}
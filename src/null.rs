@@ -0,0 +1,151 @@
+// Copyright 2023 Martin Pool.
+
+//! A view that never draws progress, for when progress is statically disabled.
+
+use std::sync::Mutex;
+
+use crate::to_print::WriteToPrint;
+use crate::{DestinationTrait, Model};
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// Operations shared by [View] and [NullView], so that libraries can accept
+/// either.
+///
+/// A library function can take `&impl ProgressSink<M>`, and callers who don't
+/// want progress can pass a [NullView], which costs little more than calling
+/// the update closures.
+///
+/// ```
+/// use nutmeg::{NullView, Options, ProgressSink, View};
+/// use nutmeg::models::LinearModel;
+///
+/// fn reticulate(progress: &impl ProgressSink<LinearModel>) {
+///     for _ in 0..10 {
+///         progress.update(|model| model.increment(1));
+///     }
+///     progress.message("splines reticulated\n");
+/// }
+///
+/// let view = View::new(LinearModel::new("Splines", 10), Options::default());
+/// reticulate(&view);
+///
+/// let null = NullView::new(LinearModel::new("Splines", 10));
+/// reticulate(&null);
+/// assert_eq!(null.finish().done(), 10);
+/// ```
+pub trait ProgressSink<M: Model> {
+    /// Update the model, as for [View::update].
+    fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R;
+
+    /// Inspect the model without redrawing, as for [View::inspect_model].
+    fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R;
+
+    /// Print a message, as for [View::message].
+    fn message<S: AsRef<str>>(&self, message: S);
+}
+
+impl<M: Model, D: DestinationTrait> ProgressSink<M> for View<M, D> {
+    fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        View::update(self, update_fn)
+    }
+
+    fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        View::inspect_model(self, f)
+    }
+
+    fn message<S: AsRef<str>>(&self, message: S) {
+        View::message(self, message)
+    }
+}
+
+/// A view that never draws a progress bar or renders the model.
+///
+/// Updates only call the closure on the model, without reading the clock or
+/// checking the terminal, so this is suitable for callers that have opted out
+/// of progress entirely. Messages are printed to stdout, as they would be by a
+/// [View] with [Options::progress_enabled] false.
+#[derive(Debug)]
+pub struct NullView<M: Model> {
+    model: Mutex<M>,
+}
+
+impl<M: Model> NullView<M> {
+    /// Construct a view that holds the model but never draws it.
+    pub const fn new(model: M) -> NullView<M> {
+        NullView {
+            model: Mutex::new(model),
+        }
+    }
+
+    /// Update the model.
+    pub fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        update_fn(&mut self.model.lock().unwrap())
+    }
+
+    /// Inspect the model.
+    ///
+    /// This is the same as [NullView::update], since nothing is ever drawn.
+    pub fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        f(&mut self.model.lock().unwrap())
+    }
+
+    /// Print a message to stdout.
+    pub fn message<S: AsRef<str>>(&self, message: S) {
+        use std::io::Write;
+        WriteToPrint
+            .write_all(message.as_ref().as_bytes())
+            .expect("write message");
+    }
+
+    /// Stop using this view and return the model.
+    ///
+    /// Nothing is printed, since nothing was drawn. This is the same as
+    /// [NullView::finish].
+    pub fn abandon(self) -> M {
+        self.finish()
+    }
+
+    /// Stop using this view and return the model.
+    ///
+    /// Unlike [View::finish], the [Model::final_message] is not printed.
+    pub fn finish(self) -> M {
+        self.model.into_inner().unwrap()
+    }
+}
+
+impl<M: Model> ProgressSink<M> for NullView<M> {
+    fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&mut M) -> R,
+    {
+        NullView::update(self, update_fn)
+    }
+
+    fn inspect_model<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce(&mut M) -> R,
+    {
+        NullView::inspect_model(self, f)
+    }
+
+    fn message<S: AsRef<str>>(&self, message: S) {
+        NullView::message(self, message)
+    }
+}
@@ -1,12 +1,21 @@
 // Copyright 2022-2023 Martin Pool.
 
+use std::fmt;
 use std::time::Duration;
 
-use crate::Destination;
+use crate::theme::Theme;
+#[allow(unused)] // for docstrings
+use crate::{CapturedEventKind, Model, TerminalContext, View};
+use crate::{
+    Clock, ControlDialect, DefaultFormatting, Destination, Error, Formatting, MessageFilter,
+    SystemClock,
+};
 
 /// Options controlling a View.
 ///
-/// These are supplied to a constructor like [View::new], and cannot be changed after the view is created.
+/// These are supplied to a constructor like [View::new]. The timing can be
+/// changed later through [View::set_pace], or all the options except the
+/// destination through [View::replace_options].
 ///
 /// The default options created by [Options::default] should be reasonable
 /// for most applications.
@@ -34,31 +43,406 @@ pub struct Options {
     /// Target interval to repaint the progress bar.
     pub(crate) update_interval: Duration,
 
+    /// Choose the update interval from the [TerminalContext] when the view
+    /// is first drawn, because it wasn't set explicitly?
+    pub(crate) auto_update_interval: bool,
+
     /// How long to wait after printing output before drawing the progress bar again.
     pub(crate) print_holdoff: Duration,
 
+    /// The largest share of time that may be spent rendering, if limited.
+    pub(crate) max_render_time_share: Option<f32>,
+
+    /// Report calls to render that take longer than this.
+    pub(crate) slow_render_threshold: Option<Duration>,
+
     /// Is the progress bar drawn at all?
     pub(crate) progress_enabled: bool,
 
+    /// Draw the initial model as soon as the view is constructed?
+    pub(crate) paint_on_create: bool,
+
     /// Use a fake clock for testing.
     pub(crate) fake_clock: bool,
 
+    /// Source of the current time, when the fake clock is not in use.
+    pub(crate) clock: &'static dyn Clock,
+
     /// Write progress and messages to stdout, stderr, or a capture buffer for tests?
     pub(crate) destination: Destination,
+
+    /// Is the view used inside a Rust test whose output may be captured?
+    pub(crate) in_captured_test: bool,
+
+    /// Another destination that receives a copy of the output.
+    pub(crate) also_emit: Option<Destination>,
+
+    /// How numbers and durations are formatted by helpers and built-in models.
+    pub(crate) formatter: &'static dyn Formatting,
+
+    /// Styles for success and failure.
+    pub(crate) theme: Theme,
+
+    /// Advise models to use bold and underline rather than color?
+    pub(crate) monochrome: bool,
+
+    /// Remove escape sequences from messages when the destination isn't a terminal?
+    pub(crate) strip_escapes: bool,
+
+    /// Filters applied to each message before it's printed.
+    pub(crate) message_filters: &'static [&'static dyn MessageFilter],
+
+    /// Delimit frames and messages in captured output?
+    #[cfg(feature = "capture")]
+    pub(crate) capture_markers: bool,
+
+    /// Where to write a JSON summary when the view is finished.
+    pub(crate) json_summary: Option<Destination>,
+
+    /// Serve the view's state over HTTP.
+    #[cfg(feature = "http")]
+    pub(crate) http_reporter: Option<&'static crate::http::HttpReporter>,
+
+    /// Disable line wrapping while the progress bar is drawn?
+    pub(crate) toggle_line_wrap: bool,
+
+    /// Which control sequences to use to draw the progress bar.
+    pub(crate) dialect: ControlDialect,
+
+    /// Check rendered output for control sequences that would corrupt the display?
+    pub(crate) validate_render: bool,
+
+    /// Ring the bell when finished by `finish_success` or `finish_failure`?
+    pub(crate) finish_bell: bool,
+
+    /// How long to leave the final frame on the screen when finishing.
+    pub(crate) finish_linger: Duration,
+
+    /// What to do when the view is dropped without being finished or abandoned.
+    pub(crate) on_drop: DropPolicy,
+
+    /// What's printed when a task added by [View::add_task] is finished.
+    pub(crate) on_task_finish: TaskFinishPolicy,
+
+    /// Whether progress is drawn or announced.
+    pub(crate) accessibility: Accessibility,
+
+    /// How often to print plain progress lines when the destination isn't a terminal.
+    pub(crate) fallback_interval: Option<FallbackInterval>,
+
+    /// The minimum interval between announcements.
+    pub(crate) announce_interval: Duration,
+
+    /// How often the background thread started by [View::with_ticker] repaints.
+    pub(crate) tick_interval: Duration,
+
+    /// True if [Options::tick_interval] was called, rather than the interval
+    /// coming from the default or a [Pace].
+    pub(crate) tick_interval_set: bool,
+
+    /// Gaps between updates longer than this are left out of time estimates.
+    pub(crate) stall_threshold: Option<Duration>,
+
+    /// Wrap each frame in a synchronized update, or None to detect whether
+    /// the destination supports it.
+    pub(crate) synchronized_output: Option<bool>,
+
+    /// Make room for a frame of this many lines before drawing on a clear screen.
+    pub(crate) reserve_lines: usize,
+
+    /// Where the cursor is left after each frame is drawn.
+    pub(crate) cursor_park: CursorPark,
+
+    /// Treat [OptionsWarning]s as errors?
+    pub(crate) strict: bool,
+}
+
+/// What a [View] does when it's dropped without being explicitly finished or abandoned.
+///
+/// Set by [Options::on_drop].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DropPolicy {
+    /// Erase the progress bar, and print nothing.
+    Erase,
+    /// Leave the progress bar on the screen, as for [View::abandon].
+    Abandon,
+    /// Erase the progress bar and print the [Model::final_message], as for [View::finish].
+    ///
+    /// This is the default.
+    FinishWithMessage,
+}
+
+/// What a [View] does when a task added by [View::add_task] is finished, or
+/// its handle is dropped.
+///
+/// Set by [Options::on_task_finish].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TaskFinishPolicy {
+    /// Remove the task's line from the progress bar, and print nothing.
+    ///
+    /// This is the default.
+    Remove,
+    /// Remove the task's line, and print a summary of the task above the
+    /// progress bar, as for [View::freeze_line], with its name, its count,
+    /// and how long it was active, like `✔ fetch 12 in 1.2s`.
+    ///
+    /// The count is replaced by the size given to `TaskHandle::set_size`, if
+    /// any, like `✔ file.txt 12 MB in 1.2s`. The summary is styled by
+    /// [Theme::completed](crate::theme::Theme::completed) from the
+    /// [Options::theme].
+    Summarize,
+}
+
+/// Where the cursor rests between frames of the progress bar, set by
+/// [Options::cursor_park].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CursorPark {
+    /// After the last character of the last line of the bar, where the text
+    /// left it.
+    ///
+    /// This is the default.
+    EndOfBar,
+    /// At the start of the first line of the bar.
+    ///
+    /// Each frame is then redrawn in full, rather than only from the first
+    /// line that changed.
+    StartOfBar,
+    /// At the start of the last line of the bar.
+    BottomLeft,
+}
+
+/// A combination of [Options] that probably doesn't do what was intended,
+/// returned by [Options::warnings].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OptionsWarning {
+    /// [View::with_ticker] was called on a view using the fake clock, which
+    /// the ticker doesn't advance, so it never repaints.
+    TickerWithFakeClock,
+    /// [Options::paint_on_create] is set, but progress bars are disabled.
+    PaintOnCreateWithoutProgress,
+    /// [Accessibility::Announce] is set, but progress is disabled, so nothing
+    /// is announced.
+    AnnounceWithoutProgress,
+    /// [Options::also_emit] sends a second copy of the output to the same
+    /// stream as the destination.
+    AlsoEmitToDestination,
+    /// [Options::capture_markers] is set, but the destination doesn't capture
+    /// output.
+    CaptureMarkersWithoutCapture,
+    /// [FallbackInterval::Percent] is 0, which is treated as 1.
+    FallbackPercentZero,
+    /// [Options::max_render_time_share] is not between 0 and 1.
+    RenderTimeShareOutOfRange,
+    /// [Options::tick_interval] was set, but the view finished without
+    /// [View::with_ticker] ever running, so the interval had no effect.
+    ///
+    /// This is only found when the view finishes, so it's reported as an
+    /// event but isn't an error in strict mode.
+    TickIntervalWithoutTicker,
+}
+
+impl fmt::Display for OptionsWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            OptionsWarning::TickerWithFakeClock => {
+                "with_ticker doesn't repaint with the fake clock"
+            }
+            OptionsWarning::PaintOnCreateWithoutProgress => {
+                "paint_on_create has no effect when progress is disabled"
+            }
+            OptionsWarning::AnnounceWithoutProgress => {
+                "nothing is announced when progress is disabled"
+            }
+            OptionsWarning::AlsoEmitToDestination => {
+                "also_emit writes a second copy of the output to the destination"
+            }
+            OptionsWarning::CaptureMarkersWithoutCapture => {
+                "capture_markers has no effect when the destination isn't captured"
+            }
+            OptionsWarning::FallbackPercentZero => "a fallback interval of 0 percent is used as 1",
+            OptionsWarning::RenderTimeShareOutOfRange => {
+                "max_render_time_share should be between 0 and 1"
+            }
+            OptionsWarning::TickIntervalWithoutTicker => {
+                "tick_interval has no effect unless with_ticker is running"
+            }
+        })
+    }
+}
+
+/// How often plain progress lines are printed when the destination isn't a
+/// terminal, set by [Options::fallback_interval].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackInterval {
+    /// Print a line at most this often.
+    Time(Duration),
+    /// Print a line each time the [Model::fraction_done] passes a multiple
+    /// of this many percent.
+    ///
+    /// Nothing is printed for models that don't know how much is done.
+    Percent(u32),
+}
+
+/// How often a [View] repaints, as a preset for [Options::update_interval],
+/// [Options::print_holdoff], and [Options::tick_interval].
+///
+/// Set by [Options::pace], or while the view is running by [View::set_pace],
+/// so that an application can say what kind of work it's doing rather than
+/// tuning each interval.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pace {
+    /// Smooth animation while the user is watching something short: repaint
+    /// every 50ms, and tick 10 times a second.
+    Interactive,
+    /// Steady progress through a long job: repaint every 250ms, and tick
+    /// every second.
+    Bulk,
+    /// Work the user is unlikely to be watching closely: repaint every
+    /// second, and tick every 5 seconds.
+    Background,
+}
+
+impl Pace {
+    pub(crate) const fn update_interval(self) -> Duration {
+        match self {
+            Pace::Interactive => Duration::from_millis(50),
+            Pace::Bulk => Duration::from_millis(250),
+            Pace::Background => Duration::from_secs(1),
+        }
+    }
+
+    pub(crate) const fn print_holdoff(self) -> Duration {
+        match self {
+            Pace::Interactive => Duration::from_millis(50),
+            Pace::Bulk => Duration::from_millis(200),
+            Pace::Background => Duration::from_millis(500),
+        }
+    }
+
+    pub(crate) const fn tick_interval(self) -> Duration {
+        match self {
+            Pace::Interactive => Duration::from_millis(100),
+            Pace::Bulk => Duration::from_secs(1),
+            Pace::Background => Duration::from_secs(5),
+        }
+    }
+}
+
+/// A bundle of options suited to one kind of program, set by
+/// [Options::preset].
+///
+/// Each preset sets the destination, the timing, what's printed when the
+/// destination isn't a terminal, and what happens if the view is dropped
+/// without being finished, for example while unwinding from an error. Options
+/// set after the preset override it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Preset {
+    /// An interactive command-line tool: draw on stderr, leaving stdout for
+    /// the program's output, at [Pace::Interactive]; print nothing extra when
+    /// stderr isn't a terminal; and print the final message if the view is
+    /// dropped.
+    Cli,
+    /// A long-running service whose output goes to a log: never draw a
+    /// progress bar, but print a plain progress line to stderr every minute,
+    /// at [Pace::Background]; and print nothing if the view is dropped.
+    Daemon,
+    /// A job in continuous integration, whose output is usually a log but
+    /// may be a terminal: draw on stderr at [Pace::Bulk] if it's a terminal,
+    /// and otherwise print a plain progress line every 10 percent; and print
+    /// the final message if the view is dropped.
+    CiJob,
+    /// Tests of the application's progress output: capture output in memory
+    /// as for [Destination::Capture], paint on every update without holding
+    /// off after messages, check every frame with [Options::validate_render],
+    /// and leave the last frame in the captured output if the view is dropped.
+    #[cfg(feature = "capture")]
+    TestHarness,
+}
+
+/// How a [View] presents progress, set by [Options::accessibility].
+///
+/// The mode can also be chosen by the user, overriding the application, by
+/// setting the environment variable `NUTMEG_ACCESSIBILITY` to `announce` or
+/// `visual`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Accessibility {
+    /// Draw the progress bar and repaint it in place.
+    ///
+    /// This is the default.
+    Visual,
+    /// Don't draw the progress bar; instead, print a plain line from
+    /// [Model::announcement], such as "40 percent complete, 2 minutes
+    /// remaining", at most every [Options::announce_interval].
+    ///
+    /// This suits terminals used with screen readers, which read out
+    /// repainted text, or can't follow it.
+    Announce,
+}
+
+impl Accessibility {
+    /// The mode requested by the `NUTMEG_ACCESSIBILITY` environment variable,
+    /// if it's set to a recognized value.
+    pub(crate) fn from_env() -> Option<Accessibility> {
+        let value = std::env::var("NUTMEG_ACCESSIBILITY").ok()?;
+        if value.eq_ignore_ascii_case("announce") {
+            Some(Accessibility::Announce)
+        } else if value.eq_ignore_ascii_case("visual") {
+            Some(Accessibility::Visual)
+        } else {
+            None
+        }
+    }
 }
 
 impl Options {
     /// Return some reasonable default options.
     ///
-    /// The update interval and print holdoff are 100ms, the progress bar is enabled,
-    /// and output is sent to stdout.
+    /// The update interval is chosen from the [TerminalContext] (100ms on a local
+    /// terminal) and the print holdoff is 100ms, the progress bar is enabled,
+    /// line wrapping is toggled off while it's drawn, and output is sent to stdout.
+    /// Rendered output is validated in debug builds.
     pub const fn new() -> Options {
         Options {
             update_interval: Duration::from_millis(100),
+            auto_update_interval: true,
             print_holdoff: Duration::from_millis(100),
+            max_render_time_share: None,
+            slow_render_threshold: None,
             progress_enabled: true,
+            paint_on_create: false,
             fake_clock: false,
+            clock: &SystemClock,
             destination: Destination::Stdout,
+            in_captured_test: false,
+            also_emit: None,
+            formatter: &DefaultFormatting,
+            theme: Theme::Default,
+            monochrome: false,
+            strip_escapes: true,
+            message_filters: &[],
+            #[cfg(feature = "capture")]
+            capture_markers: false,
+            json_summary: None,
+            #[cfg(feature = "http")]
+            http_reporter: None,
+            toggle_line_wrap: true,
+            dialect: ControlDialect::XTERM,
+            validate_render: cfg!(debug_assertions),
+            finish_bell: false,
+            finish_linger: Duration::ZERO,
+            on_drop: DropPolicy::FinishWithMessage,
+            on_task_finish: TaskFinishPolicy::Remove,
+            accessibility: Accessibility::Visual,
+            announce_interval: Duration::from_secs(10),
+            fallback_interval: None,
+            tick_interval: Duration::from_secs(1),
+            tick_interval_set: false,
+            stall_threshold: None,
+            reserve_lines: 0,
+            synchronized_output: None,
+            cursor_park: CursorPark::EndOfBar,
+            strict: false,
         }
     }
 
@@ -72,12 +456,70 @@ impl Options {
         }
     }
 
+    /// Draw the initial model as soon as the view is constructed by
+    /// [View::start] or [View::write_to], rather than waiting for the first
+    /// update.
+    ///
+    /// This is useful when the first piece of work might be slow, so that
+    /// the user sees what's happening straight away. The frame is only drawn
+    /// if progress bars would otherwise be drawn, for example if the
+    /// destination is a terminal.
+    ///
+    /// [View::new] is a `const fn`, so that views can be `static`, and so it
+    /// can't draw anything and ignores this option.
+    ///
+    /// Off by default.
+    pub const fn paint_on_create(self, paint_on_create: bool) -> Options {
+        Options {
+            paint_on_create,
+            ..self
+        }
+    }
+
     /// Set the minimal interval to repaint the progress bar.
     ///
     /// `Duration::ZERO` can be used to cause the bar to repaint on every update.
+    ///
+    /// If this isn't set, views drawing to stdout or stderr choose an interval
+    /// suited to the [TerminalContext] detected from the environment, so that
+    /// they're well-behaved over SSH or mosh, or in CI logs; other views use 100ms.
     pub const fn update_interval(self, update_interval: Duration) -> Options {
         Options {
             update_interval,
+            auto_update_interval: false,
+            ..self
+        }
+    }
+
+    /// Limit the share of time spent rendering and drawing frames, by
+    /// lengthening the update interval if rendering is slow.
+    ///
+    /// For example, with a share of `0.05`, if rendering and writing a frame
+    /// takes 10ms on average, frames are drawn at most every 200ms, even if the
+    /// [Options::update_interval] is shorter. This protects the throughput of
+    /// the application from an expensive [Model::render]. The interval in use
+    /// is reported by [View::stats].
+    ///
+    /// By default there is no limit.
+    pub const fn max_render_time_share(self, share: f32) -> Options {
+        Options {
+            max_render_time_share: Some(share),
+            ..self
+        }
+    }
+
+    /// Report calls to [Model::render] that take longer than `threshold`.
+    ///
+    /// Each slow render is counted in [View::stats] and sent as a
+    /// [CapturedEventKind::SlowRender](crate::CapturedEventKind::SlowRender)
+    /// event to [View::subscribe] and capture destinations. This can help find
+    /// models that do filesystem calls or heavy formatting on every frame.
+    ///
+    /// The slowest render is always recorded in the stats, but by default none
+    /// are reported as slow.
+    pub const fn slow_render_threshold(self, threshold: Duration) -> Options {
+        Options {
+            slow_render_threshold: Some(threshold),
             ..self
         }
     }
@@ -96,6 +538,178 @@ impl Options {
         }
     }
 
+    /// Set how often the progress bar is repainted by the background thread
+    /// started by [View::with_ticker], so that elapsed times and spinners
+    /// keep moving while the application isn't updating the model.
+    ///
+    /// This only has an effect while [View::with_ticker] is running: outside
+    /// of it, the bar is repainted only when the view is updated. The ticker
+    /// is a scoped runner, rather than a thread owned by the view, because
+    /// views can be constructed in `const` contexts and `static`s, which can't
+    /// start threads, and because a scoped thread can borrow a view that's
+    /// not `'static`.
+    ///
+    /// If the view finishes without [View::with_ticker] ever running, it
+    /// reports [OptionsWarning::TickIntervalWithoutTicker].
+    ///
+    /// The default is one second.
+    pub const fn tick_interval(self, tick_interval: Duration) -> Options {
+        Options {
+            tick_interval,
+            tick_interval_set: true,
+            ..self
+        }
+    }
+
+    /// Set the update interval, print holdoff, and tick interval together
+    /// from a [Pace] preset.
+    ///
+    /// Like [Options::update_interval], this turns off choosing the update
+    /// interval from the [TerminalContext].
+    ///
+    /// ```
+    /// use nutmeg::{Options, Pace};
+    ///
+    /// let options = Options::new().pace(Pace::Bulk);
+    /// ```
+    pub const fn pace(self, pace: Pace) -> Options {
+        Options {
+            update_interval: pace.update_interval(),
+            auto_update_interval: false,
+            print_holdoff: pace.print_holdoff(),
+            tick_interval: pace.tick_interval(),
+            ..self
+        }
+    }
+
+    /// Set the destination, timing, non-terminal output, and drop policy
+    /// together from a [Preset] for a kind of program.
+    ///
+    /// Options set after the preset override it.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, Preset};
+    ///
+    /// let options = Options::new().preset(Preset::CiJob);
+    /// let options = Options::new().preset(Preset::Cli).destination(Destination::Stdout);
+    /// ```
+    pub const fn preset(self, preset: Preset) -> Options {
+        match preset {
+            Preset::Cli => self
+                .destination(Destination::Stderr)
+                .pace(Pace::Interactive)
+                .progress_enabled(true)
+                .no_fallback_interval()
+                .on_drop(DropPolicy::FinishWithMessage),
+            Preset::Daemon => self
+                .destination(Destination::Stderr)
+                .pace(Pace::Background)
+                .progress_enabled(false)
+                .fallback_interval(FallbackInterval::Time(Duration::from_secs(60)))
+                .on_drop(DropPolicy::Erase),
+            Preset::CiJob => self
+                .destination(Destination::Stderr)
+                .pace(Pace::Bulk)
+                .progress_enabled(true)
+                .fallback_interval(FallbackInterval::Percent(10))
+                .on_drop(DropPolicy::FinishWithMessage),
+            #[cfg(feature = "capture")]
+            Preset::TestHarness => self
+                .destination(Destination::Capture)
+                .update_interval(Duration::ZERO)
+                .print_holdoff(Duration::ZERO)
+                .progress_enabled(true)
+                .no_fallback_interval()
+                .validate_render(true)
+                .on_drop(DropPolicy::Abandon),
+        }
+    }
+
+    /// Turn off plain progress lines for destinations that aren't terminals.
+    const fn no_fallback_interval(self) -> Options {
+        Options {
+            fallback_interval: None,
+            ..self
+        }
+    }
+
+    /// Treat gaps between updates longer than `threshold` as stalls, which
+    /// are left out of the time elapsed when estimating the time remaining,
+    /// like the time when the view is suspended.
+    ///
+    /// This suits applications that sometimes wait for reasons unrelated to
+    /// the rate of work, such as for the user, so that the estimate is
+    /// sensible again as soon as work resumes. By default, stalls aren't
+    /// detected. See [paused_since](crate::paused_since).
+    pub const fn stall_threshold(self, threshold: Duration) -> Options {
+        Options {
+            stall_threshold: Some(threshold),
+            ..self
+        }
+    }
+
+    /// Make room for a progress bar of at least `lines` lines before drawing
+    /// it on a clear screen, scrolling the terminal if necessary.
+    ///
+    /// Without this, the first frame of a tall model drawn near the bottom of
+    /// the screen, or a model that grows, scrolls earlier output up
+    /// unexpectedly. Models can also give their height through
+    /// [Model::max_height](crate::Model::max_height), and the larger of the
+    /// two is used. The space is never more than the height of the terminal.
+    ///
+    /// The default is 0, reserving no space.
+    pub const fn reserve_lines(self, lines: usize) -> Options {
+        Options {
+            reserve_lines: lines,
+            ..self
+        }
+    }
+
+    /// Set where the cursor rests between frames of the progress bar.
+    ///
+    /// By default it's left at the end of the bar, where drawing the text
+    /// left it. On terminals that show a block cursor, that can look like part
+    /// of the bar; and screen readers that follow the cursor may read from
+    /// there. [CursorPark::StartOfBar] or [CursorPark::BottomLeft] move it to
+    /// the first column instead.
+    ///
+    /// ```
+    /// use nutmeg::{CursorPark, Destination, Options, View};
+    /// use nutmeg::models::DisplayModel;
+    ///
+    /// let options = Options::default()
+    ///     .destination(Destination::Capture)
+    ///     .cursor_park(CursorPark::StartOfBar);
+    /// let view = View::new(DisplayModel("one\ntwo"), options);
+    /// let output = view.captured_output();
+    /// view.update(|_| ());
+    /// assert_eq!(*output.lock().unwrap(), "\x1b[?7l\x1b[0Jone\ntwo\x1b[1F");
+    /// ```
+    pub const fn cursor_park(self, cursor_park: CursorPark) -> Options {
+        Options {
+            cursor_park,
+            ..self
+        }
+    }
+
+    /// Set whether each frame is drawn as a synchronized update (mode 2026),
+    /// so that the terminal shows it all at once, without flickering, even
+    /// when it's many lines tall.
+    ///
+    /// By default, synchronized updates are used if the destination reports
+    /// that it supports them: see
+    /// [DestinationTrait::synchronized_output](crate::DestinationTrait::synchronized_output).
+    /// For stdout and stderr, this is guessed from the `TERM` and
+    /// `TERM_PROGRAM` environment variables. Terminals that don't understand
+    /// synchronized updates ignore them, so it's generally safe to turn this
+    /// on.
+    pub const fn synchronized_output(self, enabled: bool) -> Options {
+        Options {
+            synchronized_output: Some(enabled),
+            ..self
+        }
+    }
+
     /// Enable use of a fake clock, for testing.
     ///
     /// When true, all calculations of when to repaint use the fake
@@ -110,6 +724,124 @@ impl Options {
         Options { fake_clock, ..self }
     }
 
+    /// Set the clock used to decide when to repaint.
+    ///
+    /// [SystemClock] is the default. [CoarseClock](crate::CoarseClock) is
+    /// cheaper to read, which may help programs that send many updates.
+    ///
+    /// This is ignored if [Options::fake_clock] is enabled.
+    pub const fn clock(self, clock: &'static dyn Clock) -> Options {
+        Options { clock, ..self }
+    }
+
+    /// Set how numbers, percentages, and durations are formatted by helpers
+    /// and built-in models, for example to localize the decimal separator.
+    ///
+    /// [DefaultFormatting] is the default. See [Formatting].
+    pub const fn formatter(self, formatter: &'static dyn Formatting) -> Options {
+        Options { formatter, ..self }
+    }
+
+    /// Set the styles used for success and failure by the default
+    /// [Model::failure_message] and by the built-in models.
+    ///
+    /// [Theme::Default] is the default. [Theme::Accessible] uses colors that
+    /// can be distinguished with color-vision deficiencies, and symbols.
+    pub const fn theme(self, theme: Theme) -> Options {
+        Options { theme, ..self }
+    }
+
+    /// Advise the theme and models to use bold and underline rather than color,
+    /// for high contrast on projectors and terminals with poor contrast.
+    ///
+    /// Models can check the advice with
+    /// [monochrome_advised](crate::theme::monochrome_advised). It's also
+    /// given if the user sets the environment variable `NO_COLOR` or
+    /// `NUTMEG_MONOCHROME` to a non-empty value.
+    pub const fn monochrome(self, monochrome: bool) -> Options {
+        Options { monochrome, ..self }
+    }
+
+    /// Set whether escape sequences, such as colors, are removed from messages
+    /// and final messages when the destination isn't a terminal, for example
+    /// when output is redirected to a file, a pipe, or a CI log.
+    ///
+    /// This is on by default, so that applications can style their messages
+    /// without leaving escape codes in logs. Turn it off to pass messages
+    /// through unchanged, for example to a pager that understands colors.
+    pub const fn strip_escapes(self, strip_escapes: bool) -> Options {
+        Options {
+            strip_escapes,
+            ..self
+        }
+    }
+
+    /// Set filters that transform or drop messages and final messages before
+    /// they're printed, applied in order.
+    ///
+    /// There are no filters by default. See [MessageFilter] for the built-in
+    /// filters and how to write one.
+    ///
+    /// ```
+    /// use nutmeg::{Coalesce, MessageLevel, MinLevel, Options, Prefix};
+    ///
+    /// static COALESCE: Coalesce = Coalesce::new();
+    /// static OPTIONS: Options = Options::new().message_filters(&[
+    ///     &MinLevel(MessageLevel::Info),
+    ///     &COALESCE,
+    ///     &Prefix("[sync] "),
+    /// ]);
+    /// ```
+    pub const fn message_filters(
+        self,
+        message_filters: &'static [&'static dyn MessageFilter],
+    ) -> Options {
+        Options {
+            message_filters,
+            ..self
+        }
+    }
+
+    /// Set whether output captured by [Destination::Capture],
+    /// [Destination::CaptureNamed] or [Destination::CaptureBounded] has markers
+    /// around each frame and each message.
+    ///
+    /// Frames are delimited by [FRAME_START](crate::test_util::FRAME_START)
+    /// and [FRAME_END](crate::test_util::FRAME_END), and messages, including
+    /// the final message, by
+    /// [MESSAGE_START](crate::test_util::MESSAGE_START) and
+    /// [MESSAGE_END](crate::test_util::MESSAGE_END). This lets tests that
+    /// check the captured text split it into the operations that wrote it,
+    /// without recognizing escape sequences. Erasing the progress bar before a
+    /// message is outside the message's markers.
+    ///
+    /// This is off by default, and has no effect on other destinations.
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::test_util::{MESSAGE_END, MESSAGE_START};
+    /// use nutmeg::{Destination, Options, View};
+    ///
+    /// let options = Options::default()
+    ///     .destination(Destination::Capture)
+    ///     .capture_markers(true);
+    /// let view = View::new(DisplayModel("working"), options);
+    /// let output = view.captured_output();
+    /// view.message("hello\n");
+    /// drop(view);
+    /// assert_eq!(
+    ///     *output.lock().unwrap(),
+    ///     format!("{MESSAGE_START}hello\n{MESSAGE_END}")
+    /// );
+    /// ```
+    #[cfg(feature = "capture")]
+    pub const fn capture_markers(self, capture_markers: bool) -> Options {
+        Options {
+            capture_markers,
+            ..self
+        }
+    }
+
     /// Set whether progress bars are drawn to stdout, stderr, or an internal capture buffer.
     ///
     /// [Destination::Stdout] is the default.
@@ -123,6 +855,375 @@ impl Options {
             ..self
         }
     }
+
+    /// Declare that this view is used inside a Rust test, where the test
+    /// harness captures stdout and stderr.
+    ///
+    /// The harness captures output written by `print!` and `eprint!` even if
+    /// the real stdout is a terminal, so progress bars drawn there would be
+    /// mixed into the captured output rather than shown. When this is set,
+    /// progress bars are not drawn to [Destination::Stdout] or
+    /// [Destination::Stderr], and only messages are written, unless the
+    /// harness was run with `--nocapture` or `RUST_TEST_NOCAPTURE` set.
+    ///
+    /// Nutmeg doesn't try to guess whether it's running in a test, since there's
+    /// no reliable way to tell. To test drawing progress bars, use
+    /// [Destination::Capture].
+    ///
+    /// ```
+    /// let options = nutmeg::Options::default().in_captured_test(cfg!(test));
+    /// ```
+    pub const fn in_captured_test(self, in_captured_test: bool) -> Options {
+        Options {
+            in_captured_test,
+            ..self
+        }
+    }
+
+    /// Also send output to a second destination.
+    ///
+    /// For example, progress could be drawn on the terminal and also recorded
+    /// by a [Destination::Custom] that writes to an asciinema file.
+    ///
+    /// If the secondary destination is a terminal, as reported by
+    /// [DestinationTrait::init](crate::DestinationTrait::init), it receives
+    /// exactly the same output as the primary destination, including progress
+    /// bars drawn for the primary's width. Otherwise, it receives only messages
+    /// and the final message, with escape sequences removed.
+    ///
+    /// Terminal modes are only restored on the primary destination by
+    /// [View::emergency_restore].
+    ///
+    /// # Panics
+    ///
+    /// [Destination::Capture] and [Destination::CaptureBounded] can't be used as
+    /// a secondary destination, since their buffer belongs to the view; use
+    /// [Destination::CaptureNamed] instead.
+    pub const fn also_emit(self, secondary: Destination) -> Options {
+        #[cfg(feature = "capture")]
+        if matches!(
+            secondary,
+            Destination::Capture | Destination::CaptureBounded(_)
+        ) {
+            panic!("Destination::Capture can't be a secondary destination");
+        }
+        Options {
+            also_emit: Some(secondary),
+            ..self
+        }
+    }
+
+    /// When the view is finished, also write a summary as one line of JSON to
+    /// `destination`, so that scripts wrapping the program can read the outcome
+    /// without parsing styled terminal text.
+    ///
+    /// The summary is an object with these fields:
+    ///
+    /// * `outcome`: `"success"` or `"failure"` if finished by
+    ///   [View::finish_success] or [View::finish_failure], `"cancelled"` if
+    ///   finished after [View::cancellation_requested], otherwise `"finished"`.
+    /// * `message`: the final message, with escape sequences removed.
+    /// * `fraction`: the [Model::fraction_done], or `null`.
+    /// * Any counts from [Model::summary_counts], such as `done` and `total`.
+    ///
+    /// For example,
+    /// `{"outcome":"success","message":"Copied 40 files","fraction":1,"done":40,"total":40}`.
+    ///
+    /// No summary is written if the view is abandoned.
+    ///
+    /// # Panics
+    ///
+    /// [Destination::Capture] and [Destination::CaptureBounded] can't be used
+    /// for the summary, since their buffer belongs to the view; use
+    /// [Destination::CaptureNamed] instead. This is checked here, rather than
+    /// when the view finishes.
+    ///
+    /// Finishing the view panics if the summary can't be written.
+    pub const fn json_summary(self, destination: Destination) -> Options {
+        #[cfg(feature = "capture")]
+        if matches!(
+            destination,
+            Destination::Capture | Destination::CaptureBounded(_)
+        ) {
+            panic!("Destination::Capture can't receive a JSON summary");
+        }
+        Options {
+            json_summary: Some(destination),
+            ..self
+        }
+    }
+
+    /// Serve the state of the view over HTTP, through a reporter created by
+    /// [HttpReporter::bind](crate::http::HttpReporter::bind).
+    ///
+    /// Only available with the `http` feature.
+    #[cfg(feature = "http")]
+    pub const fn http_reporter(self, reporter: &'static crate::http::HttpReporter) -> Options {
+        Options {
+            http_reporter: Some(reporter),
+            ..self
+        }
+    }
+
+    /// Set whether line wrapping is turned off while the progress bar is drawn.
+    ///
+    /// Each line of the rendered progress bar is truncated to the terminal width,
+    /// counting the columns taken by wide characters and skipping escape sequences.
+    /// As a further guard against a line wrapping and confusing the count of lines
+    /// to erase, for example if the terminal's idea of character widths differs,
+    /// Nutmeg by default also disables the terminal's automatic line wrapping
+    /// (DECAWM) while the progress bar is drawn. Some terminals, and some `screen`
+    /// configurations, handle this badly and can be left with wrapping turned off.
+    ///
+    /// If this is false, the wrap mode is never changed.
+    pub const fn toggle_line_wrap(self, toggle_line_wrap: bool) -> Options {
+        Options {
+            toggle_line_wrap,
+            ..self
+        }
+    }
+
+    /// Set the dialect of control sequences used to draw the progress bar.
+    ///
+    /// [ControlDialect::XTERM] is the default, and works on most modern terminals.
+    /// Users of unusual terminals may choose a more conservative dialect.
+    ///
+    /// If the dialect doesn't support changing the line wrap mode, lines are
+    /// truncated to the terminal width, as if [Options::toggle_line_wrap]
+    /// was false.
+    pub const fn control_dialect(self, dialect: ControlDialect) -> Options {
+        Options { dialect, ..self }
+    }
+
+    /// Set whether combinations of options that probably don't do what was
+    /// intended are treated as errors.
+    ///
+    /// The view checks its options when it's first used. By default, each
+    /// [OptionsWarning] is sent as a [CapturedEventKind::OptionsWarning] event
+    /// to subscribers and captured output, and otherwise ignored. In strict
+    /// mode, using the view instead fails: methods such as [View::try_update]
+    /// return [Error::InvalidOptions], and the others panic.
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Error, Options, View};
+    ///
+    /// let options = Options::default()
+    ///     .progress_enabled(false)
+    ///     .paint_on_create(true)
+    ///     .strict(true);
+    /// assert!(matches!(options.validate(), Err(Error::InvalidOptions(_))));
+    /// let view = View::new(DisplayModel("working"), options);
+    /// assert!(view.try_update(|_| ()).is_err());
+    /// # view.abandon();
+    /// ```
+    pub const fn strict(self, strict: bool) -> Options {
+        Options { strict, ..self }
+    }
+
+    /// Return warnings about combinations of these options that probably
+    /// don't do what was intended.
+    ///
+    /// ```
+    /// use nutmeg::{Destination, Options, OptionsWarning};
+    ///
+    /// let options = Options::default().also_emit(Destination::Stdout);
+    /// assert_eq!(options.warnings(), [OptionsWarning::AlsoEmitToDestination]);
+    /// ```
+    pub fn warnings(&self) -> Vec<OptionsWarning> {
+        self.warnings_for(Some(self.destination))
+    }
+
+    /// Return an error listing the [Options::warnings] if [Options::strict]
+    /// is set and there are any.
+    pub fn validate(&self) -> Result<(), Error> {
+        self.validate_for(Some(self.destination))
+    }
+
+    /// Return warnings for a view drawing to `destination`, or to some other
+    /// type of destination if it's None.
+    pub(crate) fn warnings_for(&self, destination: Option<Destination>) -> Vec<OptionsWarning> {
+        let mut warnings = Vec::new();
+        if !self.progress_enabled && self.paint_on_create {
+            warnings.push(OptionsWarning::PaintOnCreateWithoutProgress);
+        }
+        if !self.progress_enabled && self.accessibility == Accessibility::Announce {
+            warnings.push(OptionsWarning::AnnounceWithoutProgress);
+        }
+        if matches!(
+            (destination, self.also_emit),
+            (Some(Destination::Stdout), Some(Destination::Stdout))
+                | (Some(Destination::Stderr), Some(Destination::Stderr))
+        ) {
+            warnings.push(OptionsWarning::AlsoEmitToDestination);
+        }
+        #[cfg(feature = "capture")]
+        if self.capture_markers
+            && !matches!(
+                destination,
+                Some(
+                    Destination::Capture
+                        | Destination::CaptureNamed(_)
+                        | Destination::CaptureBounded(_)
+                )
+            )
+        {
+            warnings.push(OptionsWarning::CaptureMarkersWithoutCapture);
+        }
+        if matches!(self.fallback_interval, Some(FallbackInterval::Percent(0))) {
+            warnings.push(OptionsWarning::FallbackPercentZero);
+        }
+        if let Some(share) = self.max_render_time_share {
+            if !(share > 0.0 && share <= 1.0) {
+                warnings.push(OptionsWarning::RenderTimeShareOutOfRange);
+            }
+        }
+        warnings
+    }
+
+    /// Check the options for a view drawing to `destination`, as for
+    /// [Options::validate].
+    pub(crate) fn validate_for(&self, destination: Option<Destination>) -> Result<(), Error> {
+        let warnings = self.warnings_for(destination);
+        if self.strict && !warnings.is_empty() {
+            Err(Error::InvalidOptions(warnings))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Set whether rendered progress bars are checked for control sequences that
+    /// would corrupt the display.
+    ///
+    /// The view keeps track of where the cursor is so that it can later erase
+    /// the progress bar. If [Model::render] returns sequences that move the
+    /// cursor up or down, scroll, or clear the screen, the bar will be erased
+    /// from the wrong place. When validation is on, rendering such a sequence
+    /// panics with a message naming the model type and the sequence.
+    ///
+    /// By default this is on in builds with `debug_assertions`, and off otherwise.
+    pub const fn validate_render(self, validate_render: bool) -> Options {
+        Options {
+            validate_render,
+            ..self
+        }
+    }
+
+    /// Set whether to ring the terminal bell when the view is finished by
+    /// [View::finish_success] or [View::finish_failure].
+    ///
+    /// This can draw the user's attention back to a long-running job. The bell
+    /// is only rung if the destination is a terminal. By default it is off.
+    pub const fn finish_bell(self, finish_bell: bool) -> Options {
+        Options {
+            finish_bell,
+            ..self
+        }
+    }
+
+    /// Set how long the final state of the progress bar stays on the screen
+    /// when the view is finished, before it's erased and the final message is
+    /// printed.
+    ///
+    /// Work that finishes very quickly otherwise flashes the bar up and away
+    /// before the user can read it. When the view is finished, the bar is
+    /// redrawn with the latest state of the model, even if it was last drawn
+    /// within the [Options::update_interval], and the view waits this long
+    /// before erasing it.
+    ///
+    /// There's no wait if the bar isn't drawn, or if the work was cancelled.
+    /// With [Options::fake_clock] the final state is drawn but there's no
+    /// wait, so tests aren't slowed down.
+    ///
+    /// By default this is zero.
+    pub const fn finish_linger(self, finish_linger: Duration) -> Options {
+        Options {
+            finish_linger,
+            ..self
+        }
+    }
+
+    /// Set what the view does when it's dropped without being finished or abandoned.
+    ///
+    /// By default, [DropPolicy::FinishWithMessage], the view behaves as if
+    /// [View::finish] was called.
+    pub const fn on_drop(self, on_drop: DropPolicy) -> Options {
+        Options { on_drop, ..self }
+    }
+
+    /// Set what the view does when a task added by [View::add_task] is
+    /// finished.
+    ///
+    /// By default, [TaskFinishPolicy::Remove], the task's line just disappears.
+    ///
+    /// ```
+    /// use nutmeg::models::DisplayModel;
+    /// use nutmeg::{Destination, Options, TaskFinishPolicy, View};
+    ///
+    /// let options = Options::default()
+    ///     .destination(Destination::Capture)
+    ///     .on_task_finish(TaskFinishPolicy::Summarize);
+    /// let view = View::new(DisplayModel("Building"), options);
+    /// let task = view.add_task("compile");
+    /// task.increment(12);
+    /// task.finish();
+    /// assert!(view.captured_output().lock().unwrap().contains("✔ compile 12 in "));
+    /// ```
+    pub const fn on_task_finish(self, on_task_finish: TaskFinishPolicy) -> Options {
+        Options {
+            on_task_finish,
+            ..self
+        }
+    }
+
+    /// Print the progress as plain lines when the destination isn't a
+    /// terminal, for example when output is redirected to a CI log or piped
+    /// through `tee`, rather than showing nothing until the view finishes.
+    ///
+    /// The lines are also printed to a terminal if progress bars are turned
+    /// off by [Options::progress_enabled], as by [Preset::Daemon].
+    ///
+    /// Each line is the model rendered at the destination's width, or 80
+    /// columns, without escape sequences and followed by a newline. The first
+    /// line is printed once the first interval has passed, so short jobs
+    /// print nothing extra. By default no lines are printed.
+    ///
+    /// ```
+    /// use std::time::Duration;
+    /// use nutmeg::{FallbackInterval, Options};
+    ///
+    /// let options = Options::new().fallback_interval(FallbackInterval::Time(Duration::from_secs(30)));
+    /// let options = Options::new().fallback_interval(FallbackInterval::Percent(10));
+    /// ```
+    pub const fn fallback_interval(self, interval: FallbackInterval) -> Options {
+        Options {
+            fallback_interval: Some(interval),
+            ..self
+        }
+    }
+
+    /// Set whether progress is drawn or announced in plain lines for screen
+    /// readers.
+    ///
+    /// By default, [Accessibility::Visual], progress is drawn. The user's
+    /// `NUTMEG_ACCESSIBILITY` environment variable, if set, takes precedence.
+    pub const fn accessibility(self, accessibility: Accessibility) -> Options {
+        Options {
+            accessibility,
+            ..self
+        }
+    }
+
+    /// Set the minimum interval between announcements in
+    /// [Accessibility::Announce] mode.
+    ///
+    /// The default is 10 seconds.
+    pub const fn announce_interval(self, announce_interval: Duration) -> Options {
+        Options {
+            announce_interval,
+            ..self
+        }
+    }
 }
 
 impl Default for Options {
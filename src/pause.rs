@@ -0,0 +1,83 @@
+// Copyright 2023 Martin Pool.
+
+//! Intervals when the view was paused, which are left out of estimates of the
+//! time remaining.
+//!
+//! The view records when it was suspended, and, if
+//! [Options::stall_threshold] is set, long gaps between updates. While the
+//! view renders the model, these are available from [paused_since], and
+//! they're subtracted by [estimate_remaining](crate::estimate_remaining).
+
+use std::cell::RefCell;
+use std::time::{Duration, Instant};
+
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// The most intervals kept by a view; older intervals are forgotten.
+const MAX_PAUSES: usize = 64;
+
+/// Intervals when a view was paused, oldest first.
+#[derive(Debug)]
+pub(crate) struct Pauses {
+    intervals: Vec<(Instant, Instant)>,
+}
+
+impl Pauses {
+    pub(crate) const fn new() -> Pauses {
+        Pauses {
+            intervals: Vec::new(),
+        }
+    }
+
+    /// Record that the view was paused from `start` to `end`.
+    pub(crate) fn push(&mut self, start: Instant, end: Instant) {
+        if end > start {
+            if self.intervals.len() == MAX_PAUSES {
+                self.intervals.remove(0);
+            }
+            self.intervals.push((start, end));
+        }
+    }
+}
+
+thread_local! {
+    /// Pauses of the view that's rendering the model on this thread.
+    static CURRENT: RefCell<Vec<(Instant, Instant)>> = const { RefCell::new(Vec::new()) };
+}
+
+/// The total time since `start` that the view currently rendering the model on
+/// this thread was paused, because it was suspended by [View::suspend] or
+/// stalled for longer than [Options::stall_threshold].
+///
+/// Models that estimate rates or times from the elapsed time can subtract
+/// this so that their estimates aren't thrown off by a long pause. Outside of
+/// rendering, this is zero.
+pub fn paused_since(start: Instant) -> Duration {
+    CURRENT.with(|current| {
+        current
+            .borrow()
+            .iter()
+            .map(|&(a, b)| b.saturating_duration_since(a.max(start)))
+            .sum()
+    })
+}
+
+/// Makes a view's pauses current on this thread, until the scope is dropped.
+pub(crate) struct PauseScope {
+    previous: Vec<(Instant, Instant)>,
+}
+
+impl PauseScope {
+    pub(crate) fn enter(pauses: &Pauses) -> PauseScope {
+        PauseScope {
+            previous: CURRENT.with(|c| c.replace(pauses.intervals.clone())),
+        }
+    }
+}
+
+impl Drop for PauseScope {
+    fn drop(&mut self) {
+        CURRENT.with(|c| *c.borrow_mut() = std::mem::take(&mut self.previous));
+    }
+}
@@ -0,0 +1,42 @@
+// Copyright 2023 Martin Pool.
+
+//! Track which views are locked by the current thread.
+//!
+//! This lets a View notice when it's called again from inside its own
+//! `update` callback or [crate::Model::render], where taking the lock
+//! again would deadlock.
+
+use std::cell::RefCell;
+
+thread_local! {
+    /// Keys of the views whose inner lock is held by this thread.
+    static LOCKED_VIEWS: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+}
+
+/// Marks a view as locked by this thread, until the mark is dropped.
+pub(crate) struct LockMark {
+    key: usize,
+}
+
+impl LockMark {
+    pub(crate) fn new(key: usize) -> LockMark {
+        LOCKED_VIEWS.with(|v| v.borrow_mut().push(key));
+        LockMark { key }
+    }
+}
+
+impl Drop for LockMark {
+    fn drop(&mut self) {
+        LOCKED_VIEWS.with(|v| {
+            let mut v = v.borrow_mut();
+            if let Some(pos) = v.iter().rposition(|k| *k == self.key) {
+                v.remove(pos);
+            }
+        });
+    }
+}
+
+/// True if the view with this key is currently locked by this thread.
+pub(crate) fn is_locked_by_current_thread(key: usize) -> bool {
+    LOCKED_VIEWS.with(|v| v.borrow().contains(&key))
+}
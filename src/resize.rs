@@ -0,0 +1,53 @@
+// Copyright 2023 Martin Pool.
+
+//! Notices that the terminal was resized, for example from a `SIGWINCH` handler.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[allow(unused)] // for docstrings
+use crate::{Options, View};
+
+/// Incremented by [notify_resize].
+static GENERATION: AtomicUsize = AtomicUsize::new(0);
+
+/// Tell every view in this process that the terminal may have been resized.
+///
+/// Views measure the terminal each time they draw, and if it's narrower than
+/// when the progress bar was last drawn they erase the lines that the terminal
+/// may have wrapped. However, drawing is limited by
+/// [Options::update_interval], and an unchanged frame isn't redrawn. After
+/// this is called, the next [View::update] of each view redraws the progress
+/// bar at the new size, without waiting.
+///
+/// Nutmeg doesn't install a signal handler itself: this is intended to be
+/// called from a `SIGWINCH` handler installed by the application, or on
+/// Windows when a console window buffer size event is read. It doesn't lock
+/// or allocate, so it's safe to call from a signal handler.
+///
+/// ```
+/// use std::time::Duration;
+/// use nutmeg::models::DisplayModel;
+/// use nutmeg::{Destination, Options, View};
+///
+/// let options = Options::default()
+///     .destination(Destination::Capture)
+///     .update_interval(Duration::from_secs(3600));
+/// let view = View::new(DisplayModel(1), options);
+/// let events = view.captured_events();
+/// view.update(|model| model.0 = 2);
+/// view.update(|model| model.0 = 3);
+/// assert_eq!(events.lock().unwrap().len(), 1);
+///
+/// // Typically from the SIGWINCH handler.
+/// nutmeg::notify_resize();
+/// view.update(|model| model.0 = 4);
+/// assert_eq!(events.lock().unwrap().len(), 2);
+/// ```
+pub fn notify_resize() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// A number that changes each time [notify_resize] is called.
+pub(crate) fn generation() -> usize {
+    GENERATION.load(Ordering::SeqCst)
+}
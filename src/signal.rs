@@ -0,0 +1,333 @@
+// Copyright 2023 Martin Pool.
+
+//! Erase progress bars and restore the terminal when the process is
+//! interrupted or stopped by a signal.
+//!
+//! While a view is registered, its lock-free record of what's drawn and which
+//! modes to restore lives in a static slot rather than in the view, so the
+//! handler never reads memory that the view owns: the view can be moved,
+//! dropped, or leaked, and its [SignalCleanup] forgotten, without leaving the
+//! handler a dangling pointer.
+
+use std::fmt;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+use crate::emergency::DrawnState;
+use crate::terminal::Guard;
+#[allow(unused)] // for docstrings
+use crate::View;
+
+/// The most views that can be registered for cleanup at once.
+const SLOTS: usize = 8;
+
+/// Stored in a view's [SlotIndex] when it's not registered.
+const NO_SLOT: usize = usize::MAX;
+
+/// The state of a registered view, readable from the signal handler.
+pub(crate) struct Slot {
+    /// True while the slot belongs to a view.
+    claimed: AtomicBool,
+    /// The number of [SignalCleanup]s for the view that have not been dropped.
+    ///
+    /// Only changed while holding the view's lock.
+    registrations: AtomicUsize,
+    pub(crate) drawn: DrawnState,
+    pub(crate) guard: Guard,
+}
+
+impl Slot {
+    const fn new() -> Slot {
+        Slot {
+            claimed: AtomicBool::new(false),
+            registrations: AtomicUsize::new(0),
+            drawn: DrawnState::new(),
+            guard: Guard::without_fd(),
+        }
+    }
+}
+
+#[allow(clippy::declare_interior_mutable_const)]
+const FREE_SLOT: Slot = Slot::new();
+
+static SLOT_TABLE: [Slot; SLOTS] = [FREE_SLOT; SLOTS];
+
+/// Which slot, if any, holds a view's state.
+///
+/// The view reads its state from the slot while it's registered, and from its
+/// own fields otherwise.
+pub(crate) struct SlotIndex(AtomicUsize);
+
+impl SlotIndex {
+    pub(crate) const fn new() -> SlotIndex {
+        SlotIndex(AtomicUsize::new(NO_SLOT))
+    }
+
+    /// The slot holding the view's state, if it's registered.
+    pub(crate) fn get(&self) -> Option<&'static Slot> {
+        SLOT_TABLE.get(self.0.load(Ordering::SeqCst))
+    }
+
+    /// Register the view, moving its state from `drawn` and `guard` into a
+    /// slot, unless it's already registered. Returns false if no slot is free
+    /// or signals can't be handled on this platform.
+    ///
+    /// Must be called holding the view's lock.
+    pub(crate) fn register(&self, drawn: &DrawnState, guard: &Guard) -> bool {
+        if let Some(slot) = self.get() {
+            slot.registrations.fetch_add(1, Ordering::SeqCst);
+            return true;
+        }
+        if !handler::install() {
+            return false;
+        }
+        let Some(index) = SLOT_TABLE.iter().position(|slot| {
+            slot.claimed
+                .compare_exchange(false, true, Ordering::SeqCst, Ordering::SeqCst)
+                .is_ok()
+        }) else {
+            return false;
+        };
+        let slot = &SLOT_TABLE[index];
+        slot.registrations.store(1, Ordering::SeqCst);
+        slot.drawn.copy_from(drawn);
+        slot.guard.copy_from(guard);
+        self.0.store(index, Ordering::SeqCst);
+        // The view's own fields are unused until it's unregistered, and
+        // must not restore the modes again when they're dropped.
+        drawn.set_clear();
+        guard.set_modes(0);
+        true
+    }
+
+    /// Drop one registration, and if it was the last, move the view's state
+    /// back into `drawn` and `guard` and free the slot.
+    ///
+    /// Must be called holding the view's lock.
+    pub(crate) fn unregister(&self, drawn: &DrawnState, guard: &Guard) {
+        if let Some(slot) = self.get() {
+            if slot.registrations.fetch_sub(1, Ordering::SeqCst) == 1 {
+                self.release(drawn, guard);
+            }
+        }
+    }
+
+    /// Free the slot regardless of how many registrations remain, for example
+    /// because the view is being dropped, moving its state back into `drawn`
+    /// and `guard`.
+    pub(crate) fn release(&self, drawn: &DrawnState, guard: &Guard) {
+        let index = self.0.swap(NO_SLOT, Ordering::SeqCst);
+        if let Some(slot) = SLOT_TABLE.get(index) {
+            drawn.copy_from(&slot.drawn);
+            guard.copy_from(&slot.guard);
+            slot.drawn.set_clear();
+            slot.guard.set_modes(0);
+            slot.guard.set_fd(None);
+            slot.registrations.store(0, Ordering::SeqCst);
+            slot.claimed.store(false, Ordering::SeqCst);
+        }
+    }
+}
+
+/// A view that can be registered for cleanup on signals.
+pub(crate) trait Register {
+    /// Drop one registration made by [View::install_signal_cleanup].
+    fn unregister_signal_cleanup(&self);
+}
+
+/// Keeps a view registered to be cleaned up if the process is interrupted,
+/// until it's dropped.
+///
+/// Returned by [View::install_signal_cleanup].
+///
+/// It's safe to forget a `SignalCleanup` with [std::mem::forget]: the view
+/// then stays registered until it's dropped.
+#[must_use = "the view is only cleaned up on a signal until the SignalCleanup is dropped"]
+pub struct SignalCleanup<'v> {
+    view: Option<&'v (dyn Register + 'v)>,
+}
+
+impl SignalCleanup<'_> {
+    pub(crate) fn new(view: Option<&dyn Register>) -> SignalCleanup<'_> {
+        SignalCleanup { view }
+    }
+
+    /// True if the view will be cleaned up on a signal.
+    ///
+    /// This is false on platforms other than Unix, or if too many views are
+    /// already registered.
+    pub fn is_registered(&self) -> bool {
+        self.view.is_some()
+    }
+}
+
+impl fmt::Debug for SignalCleanup<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("SignalCleanup")
+            .field("registered", &self.is_registered())
+            .finish()
+    }
+}
+
+impl Drop for SignalCleanup<'_> {
+    fn drop(&mut self) {
+        if let Some(view) = self.view {
+            view.unregister_signal_cleanup();
+        }
+    }
+}
+
+/// Erase the progress bars of all registered views, and restore their
+/// terminal modes.
+///
+/// This doesn't lock or allocate.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn restore_all() {
+    for slot in &SLOT_TABLE {
+        if slot.claimed.load(Ordering::SeqCst) {
+            slot.drawn.restore(&slot.guard)
+        }
+    }
+}
+
+/// Record that no registered view's progress bar is on the screen, without
+/// writing anything, so that each view draws its bar afresh at its next update
+/// or tick.
+///
+/// This doesn't lock or allocate.
+#[cfg_attr(not(unix), allow(dead_code))]
+fn forget_all() {
+    for slot in &SLOT_TABLE {
+        if slot.claimed.load(Ordering::SeqCst) {
+            slot.drawn.set_clear();
+        }
+    }
+}
+
+#[cfg(unix)]
+mod handler {
+    use std::cell::UnsafeCell;
+    use std::mem::{self, MaybeUninit};
+    use std::ptr;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::Once;
+
+    use libc::{c_int, c_void, siginfo_t};
+
+    /// The signals that are handled: the first two terminate the process and
+    /// the last stops it.
+    const SIGNALS: [c_int; 3] = [libc::SIGINT, libc::SIGTERM, libc::SIGTSTP];
+
+    /// The action for a signal before our handler was installed.
+    struct Previous {
+        /// Set once `action` is written.
+        ready: AtomicBool,
+        action: UnsafeCell<MaybeUninit<libc::sigaction>>,
+    }
+
+    // Safety: `action` is only written once, before `ready` is set, and only
+    // read after `ready` is seen.
+    unsafe impl Sync for Previous {}
+
+    impl Previous {
+        fn get(&self) -> Option<&libc::sigaction> {
+            if self.ready.load(Ordering::Acquire) {
+                // Safety: see the Sync impl.
+                Some(unsafe { (*self.action.get()).assume_init_ref() })
+            } else {
+                None
+            }
+        }
+    }
+
+    #[allow(clippy::declare_interior_mutable_const)]
+    const NO_PREVIOUS: Previous = Previous {
+        ready: AtomicBool::new(false),
+        action: UnsafeCell::new(MaybeUninit::uninit()),
+    };
+
+    static PREVIOUS: [Previous; 3] = [NO_PREVIOUS; 3];
+
+    fn previous(signum: c_int) -> Option<&'static libc::sigaction> {
+        let i = SIGNALS.iter().position(|&s| s == signum)?;
+        PREVIOUS[i].get()
+    }
+
+    /// Install the handler for SIGINT, SIGTERM and SIGTSTP, once per process.
+    pub(super) fn install() -> bool {
+        static INSTALL: Once = Once::new();
+        INSTALL.call_once(|| {
+            for (&signum, previous) in SIGNALS.iter().zip(&PREVIOUS) {
+                // Safety: the structs are plain data, for which zero is a
+                // valid value, and `handle` only does things that are safe in
+                // a signal handler.
+                unsafe {
+                    let mut old: libc::sigaction = mem::zeroed();
+                    if libc::sigaction(signum, ptr::null(), &mut old) != 0
+                        || old.sa_sigaction == libc::SIG_IGN
+                    {
+                        // The signal was ignored, so leave it that way.
+                        continue;
+                    }
+                    (*previous.action.get()).write(old);
+                    previous.ready.store(true, Ordering::Release);
+                    let mut action: libc::sigaction = mem::zeroed();
+                    action.sa_sigaction = handle
+                        as extern "C" fn(c_int, *mut siginfo_t, *mut c_void)
+                        as libc::sighandler_t;
+                    action.sa_flags = libc::SA_SIGINFO | libc::SA_RESTART;
+                    libc::sigemptyset(&mut action.sa_mask);
+                    libc::sigaction(signum, &action, ptr::null_mut());
+                }
+            }
+        });
+        true
+    }
+
+    extern "C" fn handle(signum: c_int, info: *mut siginfo_t, context: *mut c_void) {
+        if let Some(previous) = previous(signum) {
+            if previous.sa_sigaction != libc::SIG_DFL {
+                // The application has its own handler, which might let it
+                // finish its views cleanly, so leave them alone.
+                //
+                // Safety: this handler was installed with `sigaction`, and
+                // SA_SIGINFO says which signature it has.
+                unsafe {
+                    if previous.sa_flags & libc::SA_SIGINFO != 0 {
+                        let previous: extern "C" fn(c_int, *mut siginfo_t, *mut c_void) =
+                            mem::transmute(previous.sa_sigaction);
+                        previous(signum, info, context);
+                    } else {
+                        let previous: extern "C" fn(c_int) = mem::transmute(previous.sa_sigaction);
+                        previous(signum);
+                    }
+                }
+                return;
+            }
+        }
+        super::restore_all();
+        if signum == libc::SIGTSTP {
+            // Stop as the default action would have, with SIGSTOP since
+            // SIGTSTP is blocked while it's being handled.
+            unsafe { libc::kill(libc::getpid(), libc::SIGSTOP) };
+            // The process was continued. Another thread may have drawn a bar
+            // again before it stopped, and the shell has written below it
+            // since, so rather than erasing lines that may no longer be the
+            // bar's, every view draws afresh at its next update or tick.
+            super::forget_all();
+        } else {
+            // Terminate the process by the signal, as it would have been
+            // without our handler, so the parent sees how it ended.
+            unsafe {
+                libc::signal(signum, libc::SIG_DFL);
+                libc::raise(signum);
+            }
+        }
+    }
+}
+
+#[cfg(not(unix))]
+mod handler {
+    pub(super) fn install() -> bool {
+        false
+    }
+}
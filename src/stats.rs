@@ -0,0 +1,72 @@
+// Copyright 2023 Martin Pool.
+
+//! Statistics about the work done by a view.
+
+use std::time::Duration;
+
+#[allow(unused)] // for docstrings
+use crate::{Model, Options, View};
+
+/// Counters describing the work done by a view, returned by [View::stats].
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct Stats {
+    /// Number of times [Model::render] was called to draw the progress bar.
+    pub renders: u64,
+    /// Number of frames written to the destination.
+    ///
+    /// This is less than `renders` when the rendered text was unchanged.
+    pub frames: u64,
+    /// Total time spent rendering and writing frames.
+    pub render_time: Duration,
+    /// The longest time taken by a single call to [Model::render].
+    pub slowest_render: Duration,
+    /// Number of calls to [Model::render] that took longer than
+    /// [Options::slow_render_threshold].
+    pub slow_renders: u64,
+    /// The interval actually used between frames.
+    ///
+    /// This is the [Options::update_interval], unless rendering is so slow that
+    /// it was lengthened to keep within [Options::max_render_time_share], or
+    /// writing to the terminal is so slow that it was lengthened to reduce the
+    /// number of writes.
+    pub effective_update_interval: Duration,
+    /// The time actually waited after a message before drawing the progress bar.
+    ///
+    /// This is the [Options::print_holdoff], unless writing to the terminal is
+    /// slow, in which case it's lengthened in the same way as the update interval.
+    pub effective_print_holdoff: Duration,
+    /// Number of writes to the destination, excluding capture buffers.
+    pub writes: u64,
+    /// Total time spent writing to and flushing the destination.
+    pub write_time: Duration,
+}
+
+impl Stats {
+    /// The mean time to render and write a frame, or None if nothing has been
+    /// rendered.
+    pub fn mean_render_time(&self) -> Option<Duration> {
+        if self.renders == 0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                self.render_time.as_secs_f64() / self.renders as f64,
+            ))
+        }
+    }
+
+    /// The mean time to write to and flush the destination, or None if
+    /// nothing has been written.
+    ///
+    /// A long latency suggests the terminal is remote, for example over SSH;
+    /// applications might show a hint that progress updates will be slow.
+    pub fn mean_write_latency(&self) -> Option<Duration> {
+        if self.writes == 0 {
+            None
+        } else {
+            Some(Duration::from_secs_f64(
+                self.write_time.as_secs_f64() / self.writes as f64,
+            ))
+        }
+    }
+}
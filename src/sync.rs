@@ -0,0 +1,245 @@
+// Copyright 2023 Martin Pool.
+
+//! A view whose model is updated through a shared reference, without waiting
+//! for the view's lock.
+
+use std::fmt::Display;
+#[cfg(feature = "capture")]
+use std::sync::Mutex;
+use std::sync::{Arc, RwLock};
+
+#[cfg(feature = "capture")]
+use crate::CapturedEvent;
+use crate::{Destination, DestinationTrait, Model, Options, View};
+
+/// A progress view for models that synchronize their own state, for example
+/// with atomics.
+///
+/// [View::update] locks the whole view, so that it can give the update
+/// function a `&mut` reference to the model. When the model's fields are
+/// atomics, that lock isn't needed to change them, but threads still wait for
+/// each other, and for any thread that's drawing the progress bar.
+///
+/// A `SyncView` instead keeps the model behind a read-write lock, and
+/// [SyncView::update] passes it a shared reference while holding only the
+/// read lock, so any number of threads can update the model at once. After
+/// the update, the progress bar is redrawn if it's time to, unless another
+/// thread is already using the view, in which case the update returns
+/// without waiting. Updates that come too soon after the last frame don't
+/// touch the view's lock at all. The write lock is taken only briefly, to
+/// render the model.
+///
+/// `SyncView::new` is not `const`, so to keep one in a static variable, wrap
+/// it in a [std::sync::OnceLock] or [std::sync::LazyLock].
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering::Relaxed};
+/// use nutmeg::{Options, SyncView};
+///
+/// #[derive(Default)]
+/// struct Model {
+///     files: AtomicUsize,
+/// }
+///
+/// impl nutmeg::Model for Model {
+///     fn render(&mut self, _width: usize) -> String {
+///         format!("{} files", self.files.load(Relaxed))
+///     }
+/// }
+///
+/// let view = SyncView::new(Model::default(), Options::default());
+/// std::thread::scope(|scope| {
+///     for _ in 0..4 {
+///         scope.spawn(|| {
+///             for _ in 0..100 {
+///                 view.update(|model| model.files.fetch_add(1, Relaxed));
+///             }
+///         });
+///     }
+/// });
+/// assert_eq!(view.finish().files.into_inner(), 400);
+/// ```
+pub struct SyncView<M: Model + Send + Sync, D: DestinationTrait = Destination> {
+    /// The model, shared with the view, which takes the write lock to render it.
+    model: Arc<RwLock<M>>,
+
+    view: View<Shared<M>, D>,
+}
+
+impl<M: Model + Send + Sync> SyncView<M> {
+    /// Construct a view drawn to the [Options::destination], as for
+    /// [View::new].
+    pub fn new(model: M, options: Options) -> SyncView<M> {
+        let destination = options.destination;
+        SyncView::with_destination(model, options, destination)
+    }
+}
+
+impl<M: Model + Send + Sync, D: DestinationTrait> SyncView<M, D> {
+    /// Construct a view drawing to a destination of a specific type, as for
+    /// [View::with_destination].
+    pub fn with_destination(model: M, options: Options, destination: D) -> SyncView<M, D> {
+        let model = Arc::new(RwLock::new(model));
+        let view = View::with_destination(Shared(Arc::clone(&model)), options, destination);
+        SyncView { model, view }
+    }
+
+    /// Update the model through a shared reference, and then redraw the
+    /// progress bar if it's time to and no other thread is using the view.
+    ///
+    /// `update_fn` runs while holding only the read lock on the model, so it
+    /// doesn't wait for other threads' updates, and they don't wait for it.
+    ///
+    /// Calling `update` from inside `update_fn` or [Model::render] panics or
+    /// deadlocks.
+    pub fn update<U, R>(&self, update_fn: U) -> R
+    where
+        U: FnOnce(&M) -> R,
+    {
+        self.view.check_not_reentrant("update");
+        let r = update_fn(&self.model.read().expect("lock model"));
+        if !self
+            .view
+            .next_paint
+            .is_due(self.view.clock, self.view.drawn().is_drawn())
+        {
+            return r;
+        }
+        match self.view.try_update_nonblocking(|_| ()) {
+            Err(err) if err.kind() != std::io::ErrorKind::WouldBlock => {
+                panic!("update progress view: {err}")
+            }
+            _ => r,
+        }
+    }
+
+    /// Print a message above the progress bar, as for [View::message].
+    pub fn message<S: AsRef<str>>(&self, message: S) {
+        self.view.message(message)
+    }
+
+    /// Print a message from a byte buffer, as for [View::message_bytes].
+    pub fn message_bytes<S: AsRef<[u8]>>(&self, message: S) {
+        self.view.message_bytes(message)
+    }
+
+    /// Hide the progress bar until [SyncView::resume] is called, as for
+    /// [View::suspend].
+    pub fn suspend(&self) {
+        self.view.suspend()
+    }
+
+    /// Remove the progress bar if it's drawn, as for [View::clear].
+    pub fn clear(&self) {
+        self.view.clear()
+    }
+
+    /// Allow the progress bar to be drawn again, as for [View::resume].
+    pub fn resume(&self) {
+        self.view.resume()
+    }
+
+    /// Return the buffer of output captured when the destination is
+    /// [Destination::Capture], as for [View::captured_output].
+    #[cfg(feature = "capture")]
+    pub fn captured_output(&self) -> Arc<Mutex<String>> {
+        self.view.captured_output()
+    }
+
+    /// Return the events captured when the destination is
+    /// [Destination::Capture], as for [View::captured_events].
+    #[cfg(feature = "capture")]
+    pub fn captured_events(&self) -> Arc<Mutex<Vec<CapturedEvent>>> {
+        self.view.captured_events()
+    }
+
+    /// Stop using this view, leaving the progress bar on the screen, and
+    /// return the model.
+    pub fn abandon(self) -> M {
+        drop(self.view.abandon());
+        unwrap_model(self.model)
+    }
+
+    /// Erase the progress bar, print the [Model::final_message], and return
+    /// the model.
+    pub fn finish(self) -> M {
+        drop(self.view.finish());
+        unwrap_model(self.model)
+    }
+}
+
+/// Take the model back once the view holding the other reference is gone.
+fn unwrap_model<M>(model: Arc<RwLock<M>>) -> M {
+    Arc::into_inner(model)
+        .expect("view has released the model")
+        .into_inner()
+        .expect("lock model")
+}
+
+/// The model as seen by the [View] inside a [SyncView], taking the write lock
+/// on the shared model whenever the view calls it.
+struct Shared<M>(Arc<RwLock<M>>);
+
+impl<M> Shared<M> {
+    fn get(&self) -> std::sync::RwLockReadGuard<'_, M> {
+        self.0.read().expect("lock model")
+    }
+
+    fn get_mut(&mut self) -> std::sync::RwLockWriteGuard<'_, M> {
+        self.0.write().expect("lock model")
+    }
+}
+
+impl<M: Model> Model for Shared<M> {
+    fn render(&mut self, width: usize) -> String {
+        self.get_mut().render(width)
+    }
+
+    fn final_message(&mut self) -> String {
+        self.get_mut().final_message()
+    }
+
+    fn failure_message(&mut self, error: &dyn Display) -> String {
+        self.get_mut().failure_message(error)
+    }
+
+    fn cancelled_message(&mut self) -> String {
+        self.get_mut().cancelled_message()
+    }
+
+    fn on_skipped_frames(&mut self, skipped: usize) {
+        self.get_mut().on_skipped_frames(skipped)
+    }
+
+    fn min_width(&self) -> usize {
+        self.get().min_width()
+    }
+
+    fn compact_width(&self) -> usize {
+        self.get().compact_width()
+    }
+
+    fn render_compact(&mut self, width: usize) -> String {
+        self.get_mut().render_compact(width)
+    }
+
+    fn max_height(&self) -> Option<usize> {
+        self.get().max_height()
+    }
+
+    fn fraction_done(&self) -> Option<f64> {
+        self.get().fraction_done()
+    }
+
+    fn summary_counts(&self) -> Vec<(&'static str, u64)> {
+        self.get().summary_counts()
+    }
+
+    fn announcement(&mut self) -> String {
+        self.get_mut().announcement()
+    }
+
+    fn render_degraded(&mut self, width: usize) -> String {
+        self.get_mut().render_degraded(width)
+    }
+}
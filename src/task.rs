@@ -0,0 +1,386 @@
+// Copyright 2023 Martin Pool.
+
+//! Lightweight tasks shown as lines below the model of a view, optionally
+//! in groups.
+
+use std::borrow::Cow;
+use std::fmt::Write as _;
+use std::time::Instant;
+
+use crate::theme::Theme;
+use crate::{
+    current_formatting, current_height, Destination, DestinationTrait, Formatting, Model, View,
+};
+
+/// How a group chooses between its one-line summary and the lines of its
+/// members.
+///
+/// This is used both by a [TaskGroup] and by the `models::Group` model.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Expansion {
+    /// Show only the summary line.
+    Collapsed,
+
+    /// Show the summary line followed by every member.
+    Expanded,
+
+    /// Show the members only if the whole group fits in this many lines.
+    UpTo(usize),
+
+    /// Show the members only if the whole group fits in half the height of
+    /// the terminal, as given by [current_height], or in [AUTO_GROUP_LINES]
+    /// if the height is unknown.
+    ///
+    /// This is the default.
+    #[default]
+    Auto,
+}
+
+impl Expansion {
+    /// The most lines, including the summary, that a group may take while
+    /// showing its members.
+    pub(crate) fn max_lines(self) -> usize {
+        match self {
+            Expansion::Collapsed => 1,
+            Expansion::Expanded => usize::MAX,
+            Expansion::UpTo(lines) => lines,
+            Expansion::Auto => current_height().map_or(AUTO_GROUP_LINES, |h| h / 2),
+        }
+    }
+}
+
+/// The most lines shown by a group with [Expansion::Auto] when the height
+/// of the terminal is unknown.
+pub const AUTO_GROUP_LINES: usize = 12;
+
+/// A handle to update one task of a [View], returned by [View::add_task].
+///
+/// While the handle exists, the task is drawn as a line below the view's
+/// model, showing its name, its count, and its message, like
+/// `fetch: 12 resolving deltas`. Dropping the handle, or calling
+/// [TaskHandle::finish], marks the task complete and removes the line,
+/// and then prints a summary if [Options::on_task_finish](crate::Options::on_task_finish)
+/// asks for one.
+///
+/// The handle borrows the view, so to update tasks from other threads, use
+/// scoped threads or keep the view in an `Arc` or `static`.
+pub struct TaskHandle<'v, M: Model, D: DestinationTrait = Destination> {
+    view: &'v View<M, D>,
+    id: u64,
+}
+
+/// A handle to a group of tasks in a [View], returned by [View::add_task_group].
+///
+/// The group is drawn below the view's model and any tasks that aren't in a
+/// group, as a summary line counting its complete members, like
+/// `tests: 3/40 complete`, followed by a line for each active member,
+/// indented. Depending on the group's [Expansion] and the height of the
+/// terminal, only the summary may be shown, so that hundreds of members
+/// degrade to a one-line rollup.
+///
+/// Dropping the handle, or calling [TaskGroup::finish], removes the group.
+/// Members that are still active are then drawn as ungrouped tasks.
+///
+/// ```
+/// use nutmeg::models::DisplayModel;
+/// use nutmeg::{Expansion, Options, View};
+///
+/// let view = View::new(DisplayModel("Testing"), Options::default());
+/// let group = view.add_task_group("tests");
+/// group.set_expansion(Expansion::UpTo(5));
+/// std::thread::scope(|scope| {
+///     for i in 0..20 {
+///         let task = group.add_task(format!("test {i}"));
+///         scope.spawn(move || task.increment(1));
+///     }
+/// });
+/// group.finish();
+/// view.finish();
+/// ```
+pub struct TaskGroup<'v, M: Model, D: DestinationTrait = Destination> {
+    view: &'v View<M, D>,
+    id: u64,
+}
+
+/// The tasks added to a view and not yet complete, in the order they were
+/// added, and the groups they belong to.
+pub(crate) struct Tasks {
+    active: Vec<Task>,
+    groups: Vec<Group>,
+    next_id: u64,
+}
+
+struct Group {
+    id: u64,
+    name: Cow<'static, str>,
+    expansion: Expansion,
+    /// The number of members that have finished.
+    complete: usize,
+}
+
+pub(crate) struct Task {
+    id: u64,
+    name: Cow<'static, str>,
+    pub(crate) done: u64,
+    message: String,
+    /// Shown in place of the count in the summary, if set by
+    /// [TaskHandle::set_size].
+    size: Option<String>,
+    /// When the task was added, on the view's clock.
+    started: Instant,
+    /// The id of the group containing this task, if any.
+    group: Option<u64>,
+}
+
+impl Task {
+    /// A line summarizing the finished task, like `✔ fetch 12 in 1.2s`.
+    pub(crate) fn summary(&self, now: Instant, formatter: &dyn Formatting, theme: Theme) -> String {
+        let secs = now.saturating_duration_since(self.started).as_secs_f64();
+        let size = match &self.size {
+            Some(size) => size.clone(),
+            None => formatter.number(self.done),
+        };
+        theme.completed(&format!(
+            "{} {size} in {}s",
+            self.name,
+            formatter.decimal(secs, 1)
+        ))
+    }
+}
+
+impl Tasks {
+    pub(crate) const fn new() -> Tasks {
+        Tasks {
+            active: Vec::new(),
+            groups: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    fn add(&mut self, name: Cow<'static, str>, started: Instant, group: Option<u64>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.active.push(Task {
+            id,
+            name,
+            done: 0,
+            message: String::new(),
+            size: None,
+            started,
+            group,
+        });
+        id
+    }
+
+    fn add_group(&mut self, name: Cow<'static, str>) -> u64 {
+        let id = self.next_id;
+        self.next_id += 1;
+        self.groups.push(Group {
+            id,
+            name,
+            expansion: Expansion::default(),
+            complete: 0,
+        });
+        id
+    }
+
+    fn group_mut(&mut self, id: u64) -> &mut Group {
+        self.groups
+            .iter_mut()
+            .find(|group| group.id == id)
+            .expect("Group is still active")
+    }
+
+    fn remove_group(&mut self, id: u64) {
+        self.groups.retain(|group| group.id != id);
+    }
+
+    fn get_mut(&mut self, id: u64) -> &mut Task {
+        self.active
+            .iter_mut()
+            .find(|task| task.id == id)
+            .expect("Task is still active")
+    }
+
+    pub(crate) fn remove(&mut self, id: u64) -> Task {
+        let i = self
+            .active
+            .iter()
+            .position(|task| task.id == id)
+            .expect("Task is still active");
+        let task = self.active.remove(i);
+        if let Some(group) = self.groups.iter_mut().find(|g| Some(g.id) == task.group) {
+            group.complete += 1;
+        }
+        task
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.active.len()
+    }
+
+    /// Append a line for each active task to a rendered frame, followed by
+    /// each group.
+    ///
+    /// This should be called while the view's formatter and height are in
+    /// scope, so that counts are formatted by it and groups can collapse to
+    /// fit.
+    pub(crate) fn append_to(&self, rendered: &mut String) {
+        let is_grouped = |task: &Task| self.groups.iter().any(|g| Some(g.id) == task.group);
+        for task in self.active.iter().filter(|task| !is_grouped(task)) {
+            push_line(rendered, "");
+            task.append_to(rendered);
+        }
+        for group in &self.groups {
+            let members: Vec<&Task> = self
+                .active
+                .iter()
+                .filter(|task| task.group == Some(group.id))
+                .collect();
+            let total = current_formatting().number((group.complete + members.len()) as u64);
+            let complete = current_formatting().number(group.complete as u64);
+            push_line(rendered, "");
+            write!(rendered, "{}: {complete}/{total} complete", group.name).unwrap();
+            if members.len() < group.expansion.max_lines() {
+                for task in members {
+                    push_line(rendered, "  ");
+                    task.append_to(rendered);
+                }
+            }
+        }
+    }
+}
+
+/// Start a new line of a frame, if it's not empty, and then push `indent`.
+fn push_line(rendered: &mut String, indent: &str) {
+    if !rendered.is_empty() && !rendered.ends_with('\n') {
+        rendered.push('\n');
+    }
+    rendered.push_str(indent);
+}
+
+impl Task {
+    /// Append the task's name, count, and message.
+    fn append_to(&self, rendered: &mut String) {
+        let done = current_formatting().number(self.done);
+        write!(rendered, "{}: {done}", self.name).unwrap();
+        if !self.message.is_empty() {
+            write!(rendered, " {}", self.message).unwrap();
+        }
+    }
+}
+
+impl<'v, M: Model, D: DestinationTrait> TaskHandle<'v, M, D> {
+    pub(crate) fn new(
+        view: &'v View<M, D>,
+        name: Cow<'static, str>,
+        group: Option<u64>,
+    ) -> TaskHandle<'v, M, D> {
+        let id = view
+            .call_inner("add_task", |inner| {
+                let started = inner.clock();
+                inner.update_tasks(|tasks| tasks.add(name, started, group))
+            })
+            .expect("update progress view");
+        TaskHandle { view, id }
+    }
+
+    /// Add `n` to the task's count, and redraw the view if needed, as for
+    /// [View::update].
+    pub fn increment(&self, n: u64) {
+        let id = self.id;
+        self.view
+            .update_tasks("increment", |tasks| tasks.get_mut(id).done += n)
+    }
+
+    /// Set the message shown after the task's count.
+    pub fn set_message<S: Into<String>>(&self, message: S) {
+        let id = self.id;
+        let message = message.into();
+        self.view
+            .update_tasks("set_message", |tasks| tasks.get_mut(id).message = message)
+    }
+
+    /// Set what the summary printed when the task finishes shows in place of
+    /// its count, such as a size with its unit, like `12 MB`.
+    ///
+    /// This only matters if [Options::on_task_finish](crate::Options::on_task_finish)
+    /// asks for summaries.
+    pub fn set_size<S: Into<String>>(&self, size: S) {
+        let id = self.id;
+        let size = size.into();
+        self.view
+            .update_tasks("set_size", |tasks| tasks.get_mut(id).size = Some(size))
+    }
+
+    /// Return the task's count.
+    pub fn done(&self) -> u64 {
+        let id = self.id;
+        self.view
+            .call_inner("done", |inner| inner.tasks.get_mut(id).done)
+    }
+
+    /// Mark the task complete, removing it from the view, and return its
+    /// count.
+    pub fn finish(self) -> u64 {
+        let this = std::mem::ManuallyDrop::new(self);
+        let id = this.id;
+        this.view
+            .call_inner("finish", |inner| inner.finish_task(id))
+            .expect("update progress view")
+    }
+}
+
+impl<M: Model, D: DestinationTrait> Drop for TaskHandle<'_, M, D> {
+    fn drop(&mut self) {
+        // Leave the task while unwinding, rather than risk panicking again.
+        if std::thread::panicking() {
+            return;
+        }
+        let id = self.id;
+        let _ = self
+            .view
+            .try_call_inner("finish", |inner| inner.finish_task(id));
+    }
+}
+
+impl<'v, M: Model, D: DestinationTrait> TaskGroup<'v, M, D> {
+    pub(crate) fn new(view: &'v View<M, D>, name: Cow<'static, str>) -> TaskGroup<'v, M, D> {
+        let id = view.update_tasks("add_task_group", |tasks| tasks.add_group(name));
+        TaskGroup { view, id }
+    }
+
+    /// Add a task to the group, as for [View::add_task].
+    pub fn add_task<S: Into<Cow<'static, str>>>(&self, name: S) -> TaskHandle<'v, M, D> {
+        TaskHandle::new(self.view, name.into(), Some(self.id))
+    }
+
+    /// Set when the members are shown: by default, [Expansion::Auto].
+    pub fn set_expansion(&self, expansion: Expansion) {
+        let id = self.id;
+        self.view.update_tasks("set_expansion", |tasks| {
+            tasks.group_mut(id).expansion = expansion
+        })
+    }
+
+    /// Remove the group from the view.
+    pub fn finish(self) {
+        let this = std::mem::ManuallyDrop::new(self);
+        let id = this.id;
+        this.view
+            .update_tasks("finish", |tasks| tasks.remove_group(id))
+    }
+}
+
+impl<M: Model, D: DestinationTrait> Drop for TaskGroup<'_, M, D> {
+    fn drop(&mut self) {
+        if std::thread::panicking() {
+            return;
+        }
+        let id = self.id;
+        let _ = self.view.try_call_inner("finish", |inner| {
+            inner.update_tasks(|tasks| tasks.remove_group(id))
+        });
+    }
+}
@@ -0,0 +1,220 @@
+// Copyright 2023 Martin Pool.
+
+//! Record and restore terminal modes.
+//!
+//! A [Guard] remembers which terminal modes have been changed, and on drop
+//! (or on [Guard::restore]) writes exactly the sequences needed to undo them.
+//!
+//! Every [View] keeps a `Guard` for its destination, which it uses both for
+//! normal cleanup and for [View::emergency_restore]. Applications that change
+//! other modes themselves, for example hiding the cursor, can record those
+//! changes in their own `Guard` so that they are undone on every exit path.
+//!
+//! ```
+//! use nutmeg::terminal::{Guard, Mode};
+//!
+//! let guard = Guard::new(nutmeg::Destination::Stdout);
+//! print!("{}", Mode::CursorHidden.set_sequence());
+//! guard.record(Mode::CursorHidden);
+//! // ... the cursor is shown again when the guard is dropped.
+//! ```
+
+use std::sync::atomic::{AtomicI32, AtomicU8, Ordering};
+
+#[allow(unused)] // for docstrings
+use crate::View;
+use crate::{ansi, Destination};
+
+/// A terminal mode that may be changed while drawing progress bars.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Mode {
+    /// Automatic line wrapping (DECAWM) is disabled.
+    LineWrapDisabled,
+    /// The cursor is hidden.
+    CursorHidden,
+    /// A scrolling region has been set.
+    ScrollRegion,
+    /// The alternate screen buffer is active.
+    AlternateScreen,
+}
+
+/// All modes, in the order in which they are restored.
+const ALL_MODES: [Mode; 4] = [
+    Mode::ScrollRegion,
+    Mode::LineWrapDisabled,
+    Mode::CursorHidden,
+    Mode::AlternateScreen,
+];
+
+impl Mode {
+    pub(crate) const fn bit(self) -> u8 {
+        match self {
+            Mode::LineWrapDisabled => 1,
+            Mode::CursorHidden => 2,
+            Mode::ScrollRegion => 4,
+            Mode::AlternateScreen => 8,
+        }
+    }
+
+    /// The escape sequence that enters this mode.
+    ///
+    /// For [Mode::ScrollRegion] this resets the region to the whole screen,
+    /// since the real region depends on the application.
+    pub const fn set_sequence(self) -> &'static str {
+        match self {
+            Mode::LineWrapDisabled => ansi::DISABLE_LINE_WRAP,
+            Mode::CursorHidden => ansi::HIDE_CURSOR,
+            Mode::ScrollRegion => ansi::RESET_SCROLL_REGION,
+            Mode::AlternateScreen => ansi::ENTER_ALTERNATE_SCREEN,
+        }
+    }
+
+    /// The escape sequence that undoes this mode.
+    pub const fn restore_sequence(self) -> &'static str {
+        match self {
+            Mode::LineWrapDisabled => ansi::ENABLE_LINE_WRAP,
+            Mode::CursorHidden => ansi::SHOW_CURSOR,
+            Mode::ScrollRegion => ansi::RESET_SCROLL_REGION,
+            Mode::AlternateScreen => ansi::EXIT_ALTERNATE_SCREEN,
+        }
+    }
+}
+
+/// Call `push` with the restore sequence for each mode set in `bits`.
+pub(crate) fn push_restore_sequences(bits: u8, mut push: impl FnMut(&'static str)) {
+    for mode in ALL_MODES {
+        if bits & mode.bit() != 0 {
+            push(mode.restore_sequence());
+        }
+    }
+}
+
+/// Records which terminal modes have been changed on a destination, and
+/// restores them when dropped.
+///
+/// The guard uses only atomics, so it can be shared between threads and
+/// [Guard::restore] can be called from a signal handler.
+#[derive(Debug)]
+pub struct Guard {
+    /// File descriptor of the terminal, or -1 if there's none.
+    fd: AtomicI32,
+    /// Bitset of [Mode::bit] for the modes that need to be restored.
+    modes: AtomicU8,
+}
+
+impl Guard {
+    /// Construct a guard for a destination, with no modes recorded.
+    ///
+    /// A guard for [Destination::Capture] records modes but never writes anything.
+    pub const fn new(destination: Destination) -> Guard {
+        Guard {
+            fd: AtomicI32::new(match destination.fd() {
+                Some(fd) => fd,
+                None => -1,
+            }),
+            modes: AtomicU8::new(0),
+        }
+    }
+
+    /// Construct a guard that doesn't yet know its file descriptor.
+    pub(crate) const fn without_fd() -> Guard {
+        Guard {
+            fd: AtomicI32::new(-1),
+            modes: AtomicU8::new(0),
+        }
+    }
+
+    /// Record that a mode has been changed and should be restored.
+    pub fn record(&self, mode: Mode) {
+        self.modes.fetch_or(mode.bit(), Ordering::SeqCst);
+    }
+
+    /// Record that a mode has already been restored by some other means.
+    pub fn forget(&self, mode: Mode) {
+        self.modes.fetch_and(!mode.bit(), Ordering::SeqCst);
+    }
+
+    /// True if this mode is recorded as needing to be restored.
+    pub fn is_recorded(&self, mode: Mode) -> bool {
+        self.modes.load(Ordering::SeqCst) & mode.bit() != 0
+    }
+
+    /// Write the sequences to restore all recorded modes, and forget them.
+    ///
+    /// This does not lock or allocate, and on Unix it is safe to call from a
+    /// signal handler.
+    pub fn restore(&self) {
+        let mut buf = [0u8; 64];
+        let mut len = 0;
+        self.take_restore_sequences(|s| {
+            buf[len..len + s.len()].copy_from_slice(s.as_bytes());
+            len += s.len();
+        });
+        write_raw(self.fd.load(Ordering::SeqCst), &buf[..len]);
+    }
+
+    pub(crate) fn fd(&self) -> i32 {
+        self.fd.load(Ordering::SeqCst)
+    }
+
+    pub(crate) fn set_fd(&self, fd: Option<i32>) {
+        self.fd.store(fd.unwrap_or(-1), Ordering::SeqCst);
+    }
+
+    /// Replace the whole set of recorded modes.
+    pub(crate) fn set_modes(&self, bits: u8) {
+        self.modes.store(bits, Ordering::SeqCst);
+    }
+
+    /// Record the same file descriptor and modes as `other`.
+    pub(crate) fn copy_from(&self, other: &Guard) {
+        self.fd.store(other.fd(), Ordering::SeqCst);
+        self.set_modes(other.modes.load(Ordering::SeqCst));
+    }
+
+    /// Forget all recorded modes, passing the sequences to restore them to `push`.
+    pub(crate) fn take_restore_sequences(&self, push: impl FnMut(&'static str)) {
+        push_restore_sequences(self.modes.swap(0, Ordering::SeqCst), push)
+    }
+}
+
+impl Drop for Guard {
+    fn drop(&mut self) {
+        self.restore()
+    }
+}
+
+/// Write directly to a file descriptor, without locking or allocating.
+///
+/// Errors are ignored, since there is nothing more that can safely be done.
+#[cfg(unix)]
+pub(crate) fn write_raw(fd: i32, buf: &[u8]) {
+    if fd < 0 {
+        return;
+    }
+    let mut rest = buf;
+    while !rest.is_empty() {
+        // Safety: `rest` is a valid slice for the duration of the call.
+        let n = unsafe { libc::write(fd, rest.as_ptr().cast(), rest.len()) };
+        if n <= 0 {
+            return;
+        }
+        rest = &rest[n as usize..];
+    }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn write_raw(fd: i32, buf: &[u8]) {
+    // There's no portable unlocked write here, so this is only suitable for
+    // panic hooks, not for signal handlers.
+    use std::io::Write;
+    if buf.is_empty() {
+        return;
+    }
+    let _ = match fd {
+        1 => std::io::stdout().write_all(buf),
+        2 => std::io::stderr().write_all(buf),
+        _ => Ok(()),
+    };
+}
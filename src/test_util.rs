@@ -0,0 +1,214 @@
+// Copyright 2023 Martin Pool.
+
+//! Helpers for testing application-defined models.
+
+use std::any::type_name;
+use std::fmt;
+use std::ops::RangeInclusive;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use crate::width::display_width;
+use crate::{ansi, render_at, Model};
+
+/// Written to captured output before each frame, if
+/// [Options::capture_markers](crate::Options::capture_markers) is on.
+pub const FRAME_START: &str = "\u{27e6}frame\u{27e7}";
+
+/// Written to captured output after each frame, if
+/// [Options::capture_markers](crate::Options::capture_markers) is on.
+pub const FRAME_END: &str = "\u{27e6}/frame\u{27e7}";
+
+/// Written to captured output before each message, if
+/// [Options::capture_markers](crate::Options::capture_markers) is on.
+pub const MESSAGE_START: &str = "\u{27e6}message\u{27e7}";
+
+/// Written to captured output after each message, if
+/// [Options::capture_markers](crate::Options::capture_markers) is on.
+pub const MESSAGE_END: &str = "\u{27e6}/message\u{27e7}";
+
+/// The widths tried by [stress_widths].
+pub const STRESS_WIDTHS: RangeInclusive<usize> = 1..=200;
+
+/// Render `model` at every width in [STRESS_WIDTHS], and panic if it panics
+/// or produces any line wider than the width it was given.
+///
+/// Widths of lines are measured ignoring ANSI escape sequences, and counting
+/// wide Unicode characters as two columns.
+///
+/// This catches, for example, subtraction overflows in models that compute
+/// the space left for a bar on very narrow terminals.
+///
+/// ```
+/// struct Model;
+/// impl nutmeg::Model for Model {
+///     fn render(&mut self, width: usize) -> String {
+///         let label = "progress: ";
+///         let bar_width = width.saturating_sub(label.len());
+///         format!("{}{}", &label[..label.len().min(width)], "#".repeat(bar_width))
+///     }
+/// }
+///
+/// nutmeg::test_util::stress_widths(&mut Model);
+/// ```
+pub fn stress_widths<M: Model>(model: &mut M) {
+    check_widths(model, STRESS_WIDTHS)
+}
+
+/// Render `model` at every width in `widths`, and panic if it panics or
+/// produces any line wider than the width it was given.
+///
+/// See [stress_widths].
+pub fn check_widths<M: Model>(model: &mut M, widths: RangeInclusive<usize>) {
+    for width in widths {
+        let rendered = catch_unwind(AssertUnwindSafe(|| render_at(model, width)))
+            .unwrap_or_else(|_| panic!("{}::render panicked at width {width}", type_name::<M>()));
+        for line in rendered.lines() {
+            let line_width = display_width(line);
+            assert!(
+                line_width <= width,
+                "{}::render at width {width} produced a line {line_width} columns wide: {line:?}",
+                type_name::<M>()
+            );
+        }
+    }
+}
+
+/// The widths at which [check_model_contract] renders the model.
+pub const CONTRACT_WIDTHS: [usize; 5] = [10, 40, 80, 120, 200];
+
+/// A way in which a model's rendering breaks the rules documented on [Model::render].
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ContractViolation {
+    /// The rendered text ends with more than one newline, which would leave a blank
+    /// line at the bottom of the progress bar.
+    TrailingBlankLine {
+        /// The width passed to render.
+        width: usize,
+    },
+    /// The rendered text contains a control sequence that moves the cursor vertically,
+    /// scrolls, or clears the screen.
+    ForbiddenSequence {
+        /// The width passed to render.
+        width: usize,
+        /// The offending sequence.
+        sequence: String,
+    },
+    /// Rendering twice in a row, with no update in between, gave different results.
+    Unstable {
+        /// The width passed to render.
+        width: usize,
+        /// The first rendering.
+        first: String,
+        /// The second rendering.
+        second: String,
+    },
+    /// A rendered line was wider than the width passed to render.
+    TooWide {
+        /// The width passed to render.
+        width: usize,
+        /// The width of the line, ignoring escape sequences.
+        line_width: usize,
+        /// The line that was too wide.
+        line: String,
+    },
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ContractViolation::TrailingBlankLine { width } => {
+                write!(f, "at width {width}: output ends with a blank line")
+            }
+            ContractViolation::ForbiddenSequence { width, sequence } => write!(
+                f,
+                "at width {width}: output contains cursor-moving sequence {sequence:?}"
+            ),
+            ContractViolation::Unstable {
+                width,
+                first,
+                second,
+            } => write!(
+                f,
+                "at width {width}: rendering twice gave {first:?} and then {second:?}"
+            ),
+            ContractViolation::TooWide {
+                width,
+                line_width,
+                line,
+            } => write!(
+                f,
+                "at width {width}: line is {line_width} columns wide: {line:?}"
+            ),
+        }
+    }
+}
+
+/// Render `model` at each of [CONTRACT_WIDTHS] and return all the ways it
+/// breaks the documented rules for [Model::render].
+///
+/// The model is rendered twice at each width, to check its output is stable
+/// when its state doesn't change. Models whose output depends on the clock,
+/// such as those showing elapsed time, may occasionally be reported as unstable.
+pub fn model_contract_violations<M: Model>(model: &mut M) -> Vec<ContractViolation> {
+    let mut violations = Vec::new();
+    for width in CONTRACT_WIDTHS {
+        let first = render_at(model, width);
+        let second = render_at(model, width);
+        if first != second {
+            violations.push(ContractViolation::Unstable {
+                width,
+                first: first.clone(),
+                second,
+            });
+        }
+        if first.strip_suffix('\n').unwrap_or(&first).ends_with('\n') {
+            violations.push(ContractViolation::TrailingBlankLine { width });
+        }
+        if let Some(sequence) = ansi::find_forbidden_sequence(&first) {
+            violations.push(ContractViolation::ForbiddenSequence {
+                width,
+                sequence: sequence.to_owned(),
+            });
+        }
+        for line in first.lines() {
+            let line_width = display_width(line);
+            if line_width > width {
+                violations.push(ContractViolation::TooWide {
+                    width,
+                    line_width,
+                    line: line.to_owned(),
+                });
+            }
+        }
+    }
+    violations
+}
+
+/// Check that `model` follows the documented rules for [Model::render], and
+/// panic listing every violation if it doesn't.
+///
+/// See [model_contract_violations] for the checks.
+///
+/// ```
+/// struct Model { done: usize }
+/// impl nutmeg::Model for Model {
+///     fn render(&mut self, width: usize) -> String {
+///         let s = format!("{} done", self.done);
+///         s[..s.len().min(width)].to_owned()
+///     }
+/// }
+///
+/// nutmeg::test_util::check_model_contract(&mut Model { done: 12 });
+/// ```
+pub fn check_model_contract<M: Model>(model: &mut M) {
+    let violations = model_contract_violations(model);
+    if !violations.is_empty() {
+        let list: Vec<String> = violations.iter().map(|v| format!("  {v}")).collect();
+        panic!(
+            "{} breaks the Model contract:\n{}",
+            type_name::<M>(),
+            list.join("\n")
+        );
+    }
+}
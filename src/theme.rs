@@ -0,0 +1,171 @@
+// Copyright 2023 Martin Pool.
+
+//! Styles for success and failure in messages and built-in models.
+//!
+//! The [Theme] is set by [Options::theme]. While the view calls the model,
+//! the theme is available from [current_theme], and it's used by the default
+//! [Model::failure_message] and by the final messages of the built-in models.
+//! Applications can also use a theme directly to style their own messages.
+
+use std::cell::Cell;
+use std::env;
+use std::sync::OnceLock;
+
+use crate::ansi;
+#[allow(unused)] // for docstrings
+use crate::{Model, Options, View};
+
+/// A palette and set of symbols for showing success, warning, and failure.
+///
+/// ```
+/// use nutmeg::theme::Theme;
+///
+/// let theme = Theme::Accessible;
+/// let message = theme.failure("copy failed");
+/// assert!(message.contains("✖ copy failed"));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Theme {
+    /// Failures are bold red; successes and warnings are not styled.
+    ///
+    /// This is the default.
+    #[default]
+    Default,
+
+    /// Colors that can be told apart with the common forms of color-vision
+    /// deficiency, plus a symbol before each message so that it doesn't rely
+    /// on color at all.
+    ///
+    /// The colors are from the Okabe-Ito palette: blue for success, orange
+    /// for warnings, and vermilion for failures, marked by `✔`, `!`, and `✖`
+    /// respectively.
+    Accessible,
+}
+
+impl Theme {
+    /// Style a message reporting success.
+    ///
+    /// When [monochrome_advised], this is bold rather than colored.
+    pub fn success(self, text: &str) -> String {
+        match self {
+            Theme::Default => text.to_owned(),
+            Theme::Accessible => styled(format!("✔ {text}"), BLUE, Kind::Success),
+        }
+    }
+
+    /// Style a line reporting that a piece of work completed, marked with `✔`
+    /// in every theme, and otherwise styled as for [Theme::success].
+    pub fn completed(self, text: &str) -> String {
+        match self {
+            Theme::Default => format!("✔ {text}"),
+            Theme::Accessible => self.success(text),
+        }
+    }
+
+    /// Style a message reporting something that might need attention.
+    ///
+    /// When [monochrome_advised], this is underlined rather than colored.
+    pub fn warning(self, text: &str) -> String {
+        match self {
+            Theme::Default => text.to_owned(),
+            Theme::Accessible => styled(format!("! {text}"), ORANGE, Kind::Warning),
+        }
+    }
+
+    /// Style a message reporting failure.
+    ///
+    /// When [monochrome_advised], this is bold and underlined rather than
+    /// colored.
+    pub fn failure(self, text: &str) -> String {
+        match self {
+            Theme::Default => styled(text.to_owned(), RED, Kind::Failure),
+            Theme::Accessible => styled(format!("✖ {text}"), VERMILION, Kind::Failure),
+        }
+    }
+}
+
+/// The kinds of message that a theme styles.
+#[derive(Clone, Copy)]
+enum Kind {
+    Success,
+    Warning,
+    Failure,
+}
+
+/// Foreground colors, as SGR parameters.
+const RED: &str = "31";
+const BLUE: &str = "38;2;0;114;178";
+const ORANGE: &str = "38;2;230;159;0";
+const VERMILION: &str = "38;2;213;94;0";
+
+/// Paint `text` in `color`, or, if monochrome output is advised, with bold
+/// and underline distinguishing the kind of message instead.
+fn styled(text: String, color: &str, kind: Kind) -> String {
+    let params = match (monochrome_advised(), kind) {
+        (true, Kind::Success) => "1".to_owned(),
+        (true, Kind::Warning) => "4".to_owned(),
+        (true, Kind::Failure) => "1;4".to_owned(),
+        (false, Kind::Success) => color.to_owned(),
+        (false, Kind::Warning | Kind::Failure) => format!("1;{color}"),
+    };
+    format!("\x1b[{params}m{text}{}", ansi::RESET_STYLE)
+}
+
+thread_local! {
+    /// The theme of the view that's calling the model on this thread, and
+    /// whether it advises monochrome output.
+    static CURRENT: Cell<Option<(Theme, bool)>> = const { Cell::new(None) };
+}
+
+/// The theme set by [Options::theme] on the view that's currently calling
+/// the model on this thread, or [Theme::Default] if there is none.
+pub fn current_theme() -> Theme {
+    CURRENT
+        .with(Cell::get)
+        .map(|(theme, _)| theme)
+        .unwrap_or_default()
+}
+
+/// True if the view that's currently calling the model on this thread
+/// advises monochrome, high-contrast output, using bold and underline rather
+/// than color.
+///
+/// This is set by [Options::monochrome], or by the user setting the
+/// environment variable `NO_COLOR` or `NUTMEG_MONOCHROME` to a non-empty
+/// value. Models that use color should check this while rendering.
+pub fn monochrome_advised() -> bool {
+    CURRENT
+        .with(Cell::get)
+        .is_some_and(|(_, monochrome)| monochrome)
+}
+
+/// True if the user asked for monochrome output through the environment.
+fn monochrome_from_env() -> bool {
+    static FROM_ENV: OnceLock<bool> = OnceLock::new();
+    *FROM_ENV.get_or_init(|| {
+        ["NO_COLOR", "NUTMEG_MONOCHROME"]
+            .iter()
+            .any(|name| env::var_os(name).is_some_and(|value| !value.is_empty()))
+    })
+}
+
+/// Makes a theme current on this thread, until the scope is dropped.
+pub(crate) struct ThemeScope {
+    previous: Option<(Theme, bool)>,
+}
+
+impl ThemeScope {
+    pub(crate) fn enter(theme: Theme, monochrome: bool) -> ThemeScope {
+        let monochrome = monochrome || monochrome_from_env();
+        ThemeScope {
+            previous: CURRENT.with(|c| c.replace(Some((theme, monochrome)))),
+        }
+    }
+}
+
+impl Drop for ThemeScope {
+    fn drop(&mut self) {
+        CURRENT.with(|c| c.set(self.previous));
+    }
+}
@@ -0,0 +1,61 @@
+// Copyright 2022-2023 Martin Pool.
+
+//! Writers that send output through the `print!` and `eprint!` macros.
+//!
+//! The Rust test harness captures output written by `print!` and `eprint!`,
+//! but not output written directly to [std::io::stdout] or [std::io::stderr].
+//! Nutmeg writes to stdout and stderr through these writers so that progress
+//! bars and messages from views inside tests don't leak onto the terminal
+//! running `cargo test`.
+//!
+//! Applications and libraries can use the same writers for their own
+//! output, for example as the writer for a logging framework, to get the same
+//! behavior.
+//!
+//! ```
+//! use std::io::Write;
+//! use nutmeg::to_print::WriteToPrint;
+//!
+//! writeln!(WriteToPrint, "this is captured in tests").unwrap();
+//! ```
+
+use std::io::{self, Write};
+use std::str;
+
+/// Writes to stdout using `print!`, so that output is captured in tests.
+///
+/// Each write must be valid UTF-8.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteToPrint;
+
+/// Writes to stderr using `eprint!`, so that output is captured in tests.
+///
+/// Each write must be valid UTF-8.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WriteToStderr;
+
+impl Write for WriteToPrint {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        print!("{}", to_str(buf)?);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stdout().flush()
+    }
+}
+
+impl Write for WriteToStderr {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        eprint!("{}", to_str(buf)?);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        io::stderr().flush()
+    }
+}
+
+fn to_str(buf: &[u8]) -> io::Result<&str> {
+    str::from_utf8(buf).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+}
@@ -0,0 +1,244 @@
+// Copyright 2023 Martin Pool.
+
+//! Show the open spans of a program instrumented with
+//! [tracing](https://docs.rs/tracing) as progress, and print its events as
+//! messages.
+//!
+//! This module is only available with the `tracing` feature.
+//!
+//! A [ProgressLayer] is a [tracing_subscriber::Layer] that owns a [View].
+//! While a span is entered, on any thread, it's shown as a line of the
+//! progress bar, with its fields, indented below its parent span. Events are
+//! printed above the progress bar, like `INFO copied file=a.txt`, using
+//! [View::message_at] with the event's level, so they can be filtered by
+//! [MinLevel](crate::MinLevel).
+//!
+//! For more control over the format of events, the view can instead be used
+//! as the writer for a `tracing_subscriber::fmt` layer, as shown in
+//! `examples/tracing`.
+//!
+//! ```
+//! use nutmeg::tracing::ProgressLayer;
+//! use nutmeg::Options;
+//! use tracing_subscriber::prelude::*;
+//!
+//! let subscriber = tracing_subscriber::registry().with(ProgressLayer::new(Options::default()));
+//! tracing::subscriber::with_default(subscriber, || {
+//!     let _span = tracing::info_span!("copy", file = "a.txt").entered();
+//!     tracing::info!(bytes = 1234, "copied");
+//! });
+//! ```
+
+use std::collections::HashMap;
+use std::fmt::{self, Write as _};
+#[cfg(feature = "capture")]
+use std::sync::{Arc, Mutex};
+
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::{Attributes, Id, Record};
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+
+#[allow(unused)] // for docstrings
+use crate::View;
+use crate::{Destination, DestinationTrait, MessageLevel, Model, Options};
+
+/// A tracing layer that shows entered spans as progress, and prints events
+/// as messages.
+///
+/// See the [module documentation](self).
+pub struct ProgressLayer<D: DestinationTrait = Destination> {
+    view: View<ActiveSpans, D>,
+}
+
+/// The model of the view: spans that are open, in the order they were
+/// created.
+#[derive(Default)]
+struct ActiveSpans {
+    spans: HashMap<u64, SpanState>,
+    order: Vec<u64>,
+}
+
+struct SpanState {
+    name: &'static str,
+    /// Formatted fields, like `file=a.txt size=3`.
+    fields: String,
+    /// The number of parents this span has that are known to the layer.
+    depth: usize,
+    /// The number of times the span is currently entered, across all threads.
+    entered: usize,
+}
+
+impl Model for ActiveSpans {
+    fn render(&mut self, _width: usize) -> String {
+        let mut s = String::new();
+        for state in self
+            .order
+            .iter()
+            .filter_map(|id| self.spans.get(id))
+            .filter(|state| state.entered > 0)
+        {
+            if !s.is_empty() {
+                s.push('\n');
+            }
+            s.push_str(&"  ".repeat(state.depth));
+            s.push_str(state.name);
+            if !state.fields.is_empty() {
+                write!(s, ": {}", state.fields).unwrap();
+            }
+        }
+        s
+    }
+}
+
+impl ProgressLayer {
+    /// Construct a layer drawing progress to the [Options::destination].
+    pub fn new(options: Options) -> ProgressLayer {
+        ProgressLayer {
+            view: View::new(ActiveSpans::default(), options),
+        }
+    }
+}
+
+impl<D: DestinationTrait> ProgressLayer<D> {
+    /// Construct a layer drawing to a destination of a specific type, as for
+    /// [View::with_destination].
+    pub fn with_destination(options: Options, destination: D) -> ProgressLayer<D> {
+        ProgressLayer {
+            view: View::with_destination(ActiveSpans::default(), options, destination),
+        }
+    }
+
+    /// Return the buffer of output captured when the destination is
+    /// [Destination::Capture], as for [View::captured_output].
+    ///
+    /// This should be called before the layer is given to the subscriber.
+    #[cfg(feature = "capture")]
+    pub fn captured_output(&self) -> Arc<Mutex<String>> {
+        self.view.captured_output()
+    }
+
+    fn update_span<F: FnOnce(&mut SpanState)>(&self, id: &Id, f: F) {
+        self.view.update(|model| {
+            if let Some(state) = model.spans.get_mut(&id.into_u64()) {
+                f(state)
+            }
+        })
+    }
+}
+
+impl<S: Subscriber, D: DestinationTrait> Layer<S> for ProgressLayer<D> {
+    fn on_new_span(&self, attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        let mut fields = String::new();
+        attrs.record(&mut FieldVisitor::new(&mut fields));
+        let parent = if let Some(parent) = attrs.parent() {
+            Some(parent.clone())
+        } else if attrs.is_contextual() {
+            ctx.current_span().id().cloned()
+        } else {
+            None
+        };
+        self.view.update(|model| {
+            let depth = parent
+                .and_then(|parent| model.spans.get(&parent.into_u64()))
+                .map_or(0, |parent| parent.depth + 1);
+            model.spans.insert(
+                id.into_u64(),
+                SpanState {
+                    name: attrs.metadata().name(),
+                    fields,
+                    depth,
+                    entered: 0,
+                },
+            );
+            model.order.push(id.into_u64());
+        })
+    }
+
+    fn on_record(&self, id: &Id, values: &Record<'_>, _ctx: Context<'_, S>) {
+        self.update_span(id, |state| {
+            values.record(&mut FieldVisitor::new(&mut state.fields))
+        })
+    }
+
+    fn on_enter(&self, id: &Id, _ctx: Context<'_, S>) {
+        self.update_span(id, |state| state.entered += 1)
+    }
+
+    fn on_exit(&self, id: &Id, _ctx: Context<'_, S>) {
+        self.update_span(id, |state| state.entered = state.entered.saturating_sub(1))
+    }
+
+    fn on_close(&self, id: Id, _ctx: Context<'_, S>) {
+        let id = id.into_u64();
+        self.view.update(|model| {
+            model.spans.remove(&id);
+            model.order.retain(|i| *i != id);
+        })
+    }
+
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        let mut line = format!("{:>5}", event.metadata().level());
+        let mut fields = String::new();
+        let mut visitor = FieldVisitor::new(&mut fields);
+        event.record(&mut visitor);
+        if let Some(message) = visitor.message {
+            write!(line, " {message}").unwrap();
+        }
+        if !fields.is_empty() {
+            write!(line, " {fields}").unwrap();
+        }
+        line.push('\n');
+        self.view
+            .message_at(message_level(event.metadata().level()), line)
+    }
+}
+
+/// The [MessageLevel] corresponding to a tracing level, for filtering by
+/// [Options::message_filters](crate::Options::message_filters).
+fn message_level(level: &Level) -> MessageLevel {
+    match *level {
+        Level::TRACE => MessageLevel::Trace,
+        Level::DEBUG => MessageLevel::Debug,
+        Level::INFO => MessageLevel::Info,
+        Level::WARN => MessageLevel::Warn,
+        Level::ERROR => MessageLevel::Error,
+    }
+}
+
+/// Formats fields like `name=value`, separated by spaces, except for the
+/// `message` field of events, which is kept separately.
+struct FieldVisitor<'s> {
+    fields: &'s mut String,
+    message: Option<String>,
+}
+
+impl<'s> FieldVisitor<'s> {
+    fn new(fields: &'s mut String) -> FieldVisitor<'s> {
+        FieldVisitor {
+            fields,
+            message: None,
+        }
+    }
+
+    fn push(&mut self, field: &Field, value: fmt::Arguments) {
+        if field.name() == "message" {
+            self.message = Some(value.to_string());
+        } else {
+            if !self.fields.is_empty() {
+                self.fields.push(' ');
+            }
+            write!(self.fields, "{}={value}", field.name()).unwrap();
+        }
+    }
+}
+
+impl Visit for FieldVisitor<'_> {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.push(field, format_args!("{value}"))
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.push(field, format_args!("{value:?}"))
+    }
+}
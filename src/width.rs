@@ -2,25 +2,171 @@
 
 //! Measure terminal width.
 
-use terminal_size::Width;
-#[cfg(unix)]
+#[cfg(feature = "ansi-terminal")]
+use terminal_size::{Height, Width};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
+
+use crate::ansi::{self, Segment};
+#[cfg(all(unix, feature = "ansi-terminal"))]
 pub(crate) fn stdout_width() -> Option<usize> {
     terminal_size::terminal_size_using_fd(1).map(|(Width(w), _)| w as usize)
 }
 
-#[cfg(windows)]
+#[cfg(all(windows, feature = "ansi-terminal"))]
 pub(crate) fn stdout_width() -> Option<usize> {
     // TODO: We could get the handle for stderr to make this more precise...
     terminal_size::terminal_size().map(|(Width(w), _)| w as usize)
 }
 
-#[cfg(unix)]
+#[cfg(all(unix, feature = "ansi-terminal"))]
 pub(crate) fn stderr_width() -> Option<usize> {
     terminal_size::terminal_size_using_fd(2).map(|(Width(w), _)| w as usize)
 }
 
-#[cfg(windows)]
+#[cfg(all(windows, feature = "ansi-terminal"))]
 pub(crate) fn stderr_width() -> Option<usize> {
     // TODO: We could get the handle for stderr to make this more precise...
     terminal_size::terminal_size().map(|(Width(w), _)| w as usize)
 }
+
+#[cfg(all(unix, feature = "ansi-terminal"))]
+pub(crate) fn stdout_height() -> Option<usize> {
+    terminal_size::terminal_size_using_fd(1).map(|(_, Height(h))| h as usize)
+}
+
+#[cfg(all(windows, feature = "ansi-terminal"))]
+pub(crate) fn stdout_height() -> Option<usize> {
+    terminal_size::terminal_size().map(|(_, Height(h))| h as usize)
+}
+
+#[cfg(all(unix, feature = "ansi-terminal"))]
+pub(crate) fn stderr_height() -> Option<usize> {
+    terminal_size::terminal_size_using_fd(2).map(|(_, Height(h))| h as usize)
+}
+
+#[cfg(all(windows, feature = "ansi-terminal"))]
+pub(crate) fn stderr_height() -> Option<usize> {
+    terminal_size::terminal_size().map(|(_, Height(h))| h as usize)
+}
+
+/// Without the `ansi-terminal` feature, the size of the terminal is unknown.
+#[cfg(not(feature = "ansi-terminal"))]
+pub(crate) fn stdout_width() -> Option<usize> {
+    None
+}
+
+#[cfg(not(feature = "ansi-terminal"))]
+pub(crate) fn stderr_width() -> Option<usize> {
+    None
+}
+
+#[cfg(not(feature = "ansi-terminal"))]
+pub(crate) fn stdout_height() -> Option<usize> {
+    None
+}
+
+#[cfg(not(feature = "ansi-terminal"))]
+pub(crate) fn stderr_height() -> Option<usize> {
+    None
+}
+
+/// Truncate each line of `s` to at most `width` terminal columns.
+///
+/// Wide characters take two columns, and one that would cross the edge is left
+/// out rather than drawn in half. Escape sequences take no space, and are
+/// never split: those after the cut are kept, and a line that was cut and
+/// doesn't already end by resetting the style ends with [ansi::RESET_STYLE],
+/// so that its colors don't bleed into the following lines.
+pub(crate) fn truncate_lines(s: &str, width: usize) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut used = 0;
+    let mut full = false;
+    let mut styled = false;
+    for seg in ansi::segments(s) {
+        match seg {
+            Segment::Control(c) => {
+                out.push_str(c);
+                styled = !matches!(c, ansi::RESET_STYLE | "\x1b[m");
+            }
+            Segment::Text(t) => {
+                for ch in t.chars() {
+                    if ch == '\n' {
+                        if full && styled {
+                            out.push_str(ansi::RESET_STYLE);
+                        }
+                        out.push(ch);
+                        used = 0;
+                        full = false;
+                        styled = false;
+                    } else if !full {
+                        let w = ch.width().unwrap_or(0);
+                        if used + w > width {
+                            full = true;
+                        } else {
+                            out.push(ch);
+                            used += w;
+                        }
+                    }
+                }
+            }
+        }
+    }
+    if full && styled {
+        out.push_str(ansi::RESET_STYLE);
+    }
+    out
+}
+
+/// Wrap each line of `s` so that it's at most `width` visible characters,
+/// passing escape sequences through without counting them.
+pub(crate) fn wrap_lines(s: &str, width: usize) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut column = 0;
+    for seg in ansi::segments(s) {
+        match seg {
+            Segment::Control(c) => out.push_str(c),
+            Segment::Text(t) => {
+                for ch in t.chars() {
+                    if ch == '\n' {
+                        column = 0;
+                    } else {
+                        if column == width && width > 0 {
+                            out.push('\n');
+                            column = 0;
+                        }
+                        column += 1;
+                    }
+                    out.push(ch);
+                }
+            }
+        }
+    }
+    out
+}
+
+/// The number of terminal columns taken by `s`, which should be a single line.
+///
+/// Escape sequences take no space, and wide characters take two columns.
+pub(crate) fn display_width(s: &str) -> usize {
+    ansi::segments(s)
+        .map(|seg| match seg {
+            Segment::Text(t) => t.width(),
+            Segment::Control(_) => 0,
+        })
+        .sum()
+}
+
+/// The longest suffix of `s` that takes at most `width` columns.
+///
+/// `s` should be plain text without escape sequences.
+#[cfg(feature = "helpers")]
+pub(crate) fn suffix_with_width(s: &str, width: usize) -> &str {
+    let mut used = 0;
+    for (pos, ch) in s.char_indices().rev() {
+        used += ch.width().unwrap_or(0);
+        if used > width {
+            return &s[pos + ch.len_utf8()..];
+        }
+    }
+    s
+}
@@ -0,0 +1,38 @@
+//! Test shortening paths to fit a width.
+
+use nutmeg::abbreviate_path;
+
+#[test]
+fn short_path_is_unchanged() {
+    assert_eq!(abbreviate_path("src/lib.rs", 10), "src/lib.rs");
+}
+
+#[cfg(unix)]
+#[test]
+fn relative_path_keeps_first_and_last_components() {
+    assert_eq!(abbreviate_path("a/bbbbbbbb/cccccccc/d", 12), "a/…/d");
+    assert_eq!(
+        abbreviate_path("a/bbbbbbbb/cccccccc/d", 14),
+        "a/…/cccccccc/d"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn wide_characters_count_double() {
+    let path = "/data/写真/旅行/東京/画像.jpg";
+    for width in 0..30 {
+        let short = abbreviate_path(path, width);
+        assert!(
+            unicode_width::UnicodeWidthStr::width(short.as_str()) <= width,
+            "{short:?} is wider than {width}"
+        );
+    }
+    assert_eq!(abbreviate_path(path, 18), "/…/東京/画像.jpg");
+}
+
+#[test]
+fn single_long_component_is_cut_from_the_start() {
+    assert_eq!(abbreviate_path("abcdefghij", 5), "…ghij");
+    assert_eq!(abbreviate_path("abcdefghij", 0), "");
+}
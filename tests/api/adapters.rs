@@ -0,0 +1,73 @@
+//! Test adapters feeding other libraries' progress into a view.
+
+use nutmeg::adapters::{count_iter, track_iter, transfer_progress, ProgressIterExt};
+use nutmeg::models::{LinearModel, UnboundedModel};
+use nutmeg::{Destination, NullView, Options, View};
+
+#[test]
+fn transfer_progress_sets_done_and_total() {
+    let view = View::new(
+        LinearModel::new("Receiving objects", 0),
+        Options::default().destination(Destination::Capture),
+    );
+    let feed = transfer_progress(&view);
+    feed(10, 40);
+    feed(25, 40);
+    drop(feed);
+    let model = view.finish();
+    assert_eq!((model.done(), model.total()), (25, 40));
+}
+
+#[test]
+fn track_iter_counts_items_of_known_length() {
+    let view = NullView::new(LinearModel::new("Extracting", 99));
+    let mut iter = track_iter(0..5, &view);
+    assert_eq!(view.inspect_model(|m| (m.done(), m.total())), (0, 5));
+    assert_eq!(iter.next(), Some(0));
+    assert_eq!(iter.next(), Some(1));
+    assert_eq!(view.inspect_model(|m| (m.done(), m.total())), (2, 5));
+    assert_eq!(iter.count(), 3);
+    assert_eq!(view.inspect_model(|m| m.done()), 5);
+}
+
+#[test]
+fn progress_with_tracks_any_iterator() {
+    let view = NullView::new(LinearModel::new("Reading", 0));
+    let names = vec!["a", "b", "c", "d"];
+    let joined: String = names.into_iter().progress_with(&view).collect();
+    assert_eq!(joined, "abcd");
+    assert_eq!(view.inspect_model(|m| (m.done(), m.total())), (4, 4));
+}
+
+#[test]
+fn progress_with_draws_the_count() {
+    let view = View::new(
+        LinearModel::new("Reading", 0),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(std::time::Duration::ZERO),
+    );
+    let output = view.captured_output();
+    for _ in [1, 2, 3].iter().progress_with(&view) {}
+    assert!(
+        output.lock().unwrap().contains("Reading: 3/3"),
+        "{output:?}"
+    );
+    view.abandon();
+}
+
+#[test]
+fn track_iter_of_unknown_length_has_zero_total() {
+    let view = NullView::new(LinearModel::new("Extracting", 99));
+    let n = track_iter((0..10).filter(|i| i % 2 == 0), &view).count();
+    assert_eq!(n, 5);
+    assert_eq!(view.inspect_model(|m| (m.done(), m.total())), (5, 0));
+}
+
+#[test]
+fn count_iter_increments_unbounded_model() {
+    let view = NullView::new(UnboundedModel::new("Unpacking"));
+    let names: Vec<String> = count_iter(vec!["a".to_owned(), "b".to_owned()], &view).collect();
+    assert_eq!(names, ["a", "b"]);
+    assert_eq!(view.finish().done(), 2);
+}
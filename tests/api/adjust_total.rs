@@ -0,0 +1,42 @@
+//! Test revising the total of a `LinearModel` part-way through the work.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::estimate_remaining_excluding;
+use nutmeg::models::LinearModel;
+use nutmeg::Model;
+
+#[test]
+fn adjust_total_up_and_down() {
+    let mut model = LinearModel::new("Files", 100);
+    model.set_done(50);
+    model.adjust_total(100);
+    assert_eq!(model.total(), 200);
+    assert!(model.render(80).starts_with("Files: 50/200, 25.0%, "));
+    model.adjust_total(-140);
+    assert_eq!(model.total(), 60);
+    assert!(model.render(80).starts_with("Files: 50/60, 83.3%, "));
+}
+
+#[test]
+fn total_is_not_reduced_below_done() {
+    let mut model = LinearModel::new("Files", 100);
+    model.set_done(50);
+    model.adjust_total(-80);
+    assert_eq!(model.total(), 50);
+    let rendered = model.render(80);
+    assert!(rendered.starts_with("Files: 50/50, 100.0%, "), "{rendered}");
+    assert!(!rendered.contains("??"), "{rendered}");
+    model.adjust_total(isize::MIN);
+    assert_eq!(model.total(), 50);
+}
+
+#[test]
+fn estimate_changes_only_by_time_for_added_work() {
+    // 50 items in 100 seconds is 2 seconds per item, whatever the total.
+    let start = Instant::now() - Duration::from_secs(100);
+    let estimate = |total| estimate_remaining_excluding(&start, 50, total, Duration::ZERO);
+    assert_eq!(estimate(100), "100 sec");
+    assert_eq!(estimate(105), "110 sec");
+    assert_eq!(estimate(60), "20 sec");
+}
@@ -0,0 +1,81 @@
+//! Test sending output to a secondary destination.
+
+use std::io;
+use std::sync::Mutex;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, DestinationTrait, Options, View};
+
+#[test]
+fn terminal_secondary_gets_same_output() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default()
+            .destination(Destination::Capture)
+            .also_emit(Destination::capture_named("also_emit/terminal")),
+    );
+    view.update(|_| ());
+    view.message("hello\n");
+    let primary = view.captured_output();
+    view.finish();
+    let secondary = Destination::captured_output_named("also_emit/terminal");
+    assert_eq!(*primary.lock().unwrap(), *secondary.lock().unwrap());
+    assert!(secondary.lock().unwrap().contains("working"));
+}
+
+#[derive(Debug)]
+struct Log(Mutex<String>);
+
+impl DestinationTrait for Log {
+    fn init(&self) -> bool {
+        false
+    }
+
+    fn width(&self) -> Option<usize> {
+        None
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+static LOG: Log = Log(Mutex::new(String::new()));
+
+struct Job;
+
+impl nutmeg::Model for Job {
+    fn render(&mut self, _width: usize) -> String {
+        "working".to_owned()
+    }
+
+    fn final_message(&mut self) -> String {
+        "\x1b[1mdone\x1b[0m".to_owned()
+    }
+}
+
+#[test]
+fn non_terminal_secondary_gets_only_plain_messages() {
+    let view = View::new(
+        Job,
+        Options::default()
+            .destination(Destination::Capture)
+            .also_emit(Destination::Custom(&LOG)),
+    );
+    view.update(|_| ());
+    view.message("\x1b[32mhello\x1b[0m\n");
+    view.update(|_| ());
+    view.finish();
+    assert_eq!(LOG.0.lock().unwrap().as_str(), "hello\ndone\n");
+}
+
+#[test]
+#[should_panic(expected = "can't be a secondary destination")]
+fn capture_is_not_a_secondary_destination() {
+    let _ = Options::default().also_emit(Destination::Capture);
+}
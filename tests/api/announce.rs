@@ -0,0 +1,66 @@
+//! Test announcing progress in plain lines for screen readers.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::{DisplayModel, LinearModel};
+use nutmeg::{Accessibility, Destination, Model, Options, View};
+
+fn announce_options() -> Options {
+    Options::default()
+        .destination(Destination::Capture)
+        .fake_clock(true)
+        .accessibility(Accessibility::Announce)
+}
+
+#[test]
+fn announcements_are_infrequent_plain_lines() {
+    let view = View::new(LinearModel::new("Copying", 10), announce_options());
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|model| model.set_done(1));
+    view.set_fake_clock(start + Duration::from_secs(5));
+    view.update(|model| model.set_done(4));
+    let output = view.captured_output();
+    assert_eq!(*output.lock().unwrap(), "");
+
+    view.set_fake_clock(start + Duration::from_secs(10));
+    view.update(|model| model.set_done(5));
+    {
+        let output = output.lock().unwrap();
+        assert!(output.starts_with("50 percent complete"), "{output:?}");
+        assert!(output.ends_with('\n'));
+        assert!(!output.contains('\x1b'), "{output:?}");
+    }
+
+    view.set_fake_clock(start + Duration::from_secs(12));
+    view.update(|model| model.set_done(6));
+    assert_eq!(output.lock().unwrap().lines().count(), 1);
+    view.abandon();
+}
+
+#[test]
+fn default_announcement_is_plain_rendering() {
+    let mut model = DisplayModel("\x1b[1mworking\x1b[0m");
+    assert_eq!(model.announcement(), "working");
+}
+
+#[test]
+fn announcement_interval_is_configurable() {
+    let view = View::new(
+        DisplayModel("working"),
+        announce_options().announce_interval(Duration::from_secs(1)),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    view.set_fake_clock(start + Duration::from_secs(1));
+    view.update(|_| ());
+    view.message("hello\n");
+    view.set_fake_clock(start + Duration::from_secs(2));
+    view.update(|_| ());
+    assert_eq!(
+        *view.captured_output().lock().unwrap(),
+        "working\nhello\nworking\n"
+    );
+    view.abandon();
+}
@@ -0,0 +1,149 @@
+//! Test the model of bytes transferred, and tracking byte streams.
+
+use std::io::{BufRead, BufReader, Read, Write};
+
+use nutmeg::io::{ProgressReader, ProgressWriter};
+use nutmeg::models::BytesModel;
+use nutmeg::{format_bytes, Destination, Model, NullView, Options, View};
+
+#[test]
+fn format_bytes_units() {
+    assert_eq!(format_bytes(0), "0 B");
+    assert_eq!(format_bytes(1000), "1.0 kB");
+    assert_eq!(format_bytes(999_999), "1.0 MB");
+    assert_eq!(format_bytes(2_500_000_000), "2.5 GB");
+    assert_eq!(format_bytes(u64::MAX), "18.4 EB");
+}
+
+#[test]
+fn render_with_and_without_total() {
+    let mut model = BytesModel::new("Downloading");
+    model.increment(1500);
+    let rendered = model.render(80);
+    assert!(
+        rendered.starts_with("Downloading: 1.5 kB in 0:00, "),
+        "{rendered:?}"
+    );
+    assert!(rendered.ends_with("/s"), "{rendered:?}");
+    assert_eq!(model.fraction_done(), None);
+
+    model.set_total(6000);
+    let rendered = model.render(80);
+    assert!(
+        rendered.starts_with("Downloading: 1.5 kB of 6.0 kB, 25.0%, "),
+        "{rendered:?}"
+    );
+    assert_eq!(model.fraction_done(), Some(0.25));
+}
+
+#[test]
+fn compact_form_on_narrow_terminals() {
+    let mut model = BytesModel::new("Downloading");
+    model.set_done(1 << 20);
+    assert!(model.render_compact(20).starts_with("1.0 MB "));
+    model.set_total(1 << 22);
+    assert!(model.render_compact(20).starts_with("25.0% "));
+}
+
+#[test]
+fn track_chunks_counts_bytes_and_passes_errors() {
+    let body: Vec<Result<&[u8], &str>> = vec![Ok(b"hello "), Err("reset"), Ok(b"world")];
+    let view = NullView::new(BytesModel::new("Downloading"));
+    let chunks: Vec<_> = nutmeg::io::track_chunks(body, &view).collect();
+    assert_eq!(
+        chunks,
+        [Ok(&b"hello "[..]), Err("reset"), Ok(&b"world"[..])]
+    );
+    assert_eq!(view.finish().done(), 11);
+}
+
+#[test]
+fn callback_sets_bytes_and_total() {
+    let view = View::new(
+        BytesModel::new("Downloading"),
+        Options::default().destination(Destination::Capture),
+    );
+    nutmeg::callback::from_fn(&view)(300, 1200);
+    let model = view.finish();
+    assert_eq!((model.done(), model.total()), (300, Some(1200)));
+}
+
+#[test]
+fn progress_reader_counts_bytes_read_and_consumed() {
+    let view = NullView::new(BytesModel::new("Reading"));
+    let source: &[u8] = b"first line\nsecond line\n";
+    let mut reader = ProgressReader::new(BufReader::with_capacity(4, source), &view);
+    let mut line = String::new();
+    reader.read_line(&mut line).unwrap();
+    assert_eq!(line, "first line\n");
+    assert_eq!(view.inspect_model(|model| model.done()), 11);
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"second line\n");
+    assert_eq!(view.finish().done(), 23);
+}
+
+#[test]
+fn progress_writer_counts_bytes_accepted() {
+    let view = NullView::new(BytesModel::new("Writing"));
+    let mut writer = ProgressWriter::new(Vec::new(), &view);
+    std::io::copy(&mut &[0u8; 3000][..], &mut writer).unwrap();
+    writer.write_all(b"end").unwrap();
+    writer.flush().unwrap();
+    assert_eq!(writer.into_inner().len(), 3003);
+    assert_eq!(view.finish().done(), 3003);
+}
+
+#[test]
+fn progress_writer_does_not_count_failed_writes() {
+    struct Full;
+
+    impl Write for Full {
+        fn write(&mut self, _buf: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::WriteZero.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    let view = NullView::new(BytesModel::new("Writing"));
+    let mut writer = ProgressWriter::new(Full, &view);
+    assert!(writer.write(b"data").is_err());
+    assert_eq!(view.finish().done(), 0);
+}
+
+#[cfg(feature = "futures")]
+#[test]
+fn track_stream_counts_chunks() {
+    use std::pin::Pin;
+    use std::task::{Context, Poll, Waker};
+
+    use futures_core::Stream;
+
+    /// A stream that's always ready, yielding the items of a vec.
+    struct Ready(std::vec::IntoIter<Result<Vec<u8>, &'static str>>);
+
+    impl Stream for Ready {
+        type Item = Result<Vec<u8>, &'static str>;
+
+        fn poll_next(mut self: Pin<&mut Self>, _cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            Poll::Ready(self.0.next())
+        }
+    }
+
+    let view = View::new(
+        BytesModel::new("Downloading"),
+        Options::default().destination(Destination::Capture),
+    );
+    let chunks = vec![Ok(vec![0; 1000]), Err("reset"), Ok(vec![0; 500])];
+    let mut stream = nutmeg::io::track_stream(Ready(chunks.into_iter()), &view);
+    let mut cx = Context::from_waker(Waker::noop());
+    let mut errors = 0;
+    while let Poll::Ready(Some(chunk)) = Pin::new(&mut stream).poll_next(&mut cx) {
+        errors += chunk.is_err() as usize;
+    }
+    assert_eq!(errors, 1);
+    assert_eq!(view.finish().done(), 1500);
+}
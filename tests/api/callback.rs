@@ -0,0 +1,47 @@
+//! Test adapting progress callbacks to update a model.
+
+use nutmeg::callback::{from_fn, from_fn_with};
+use nutmeg::models::{LinearModel, UnboundedModel};
+use nutmeg::{Destination, NullView, Options, View};
+
+/// Like the callbacks taken by archive extractors and download helpers.
+fn transfer(total: u64, mut progress: impl FnMut(u64, u64)) {
+    for done in (0..=total).step_by(10) {
+        progress(done, total);
+    }
+}
+
+#[test]
+fn callback_updates_linear_model() {
+    let view = View::new(
+        LinearModel::new("Transferring", 0),
+        Options::default().destination(Destination::Capture),
+    );
+    transfer(100, from_fn(&view));
+    let model = view.finish();
+    assert_eq!(model.done(), 100);
+    assert_eq!(model.total(), 100);
+}
+
+#[test]
+fn callback_updates_null_view() {
+    let view = NullView::new(LinearModel::new("Transferring", 0));
+    transfer(50, from_fn(&view));
+    assert_eq!(view.finish().done(), 50);
+}
+
+#[test]
+fn callback_with_custom_update() {
+    let view = View::new(
+        UnboundedModel::new("Transferring"),
+        Options::default().destination(Destination::Capture),
+    );
+    let scale = 2;
+    transfer(
+        30,
+        from_fn_with(&view, |model, done, _total| {
+            model.set_done(done as usize * scale)
+        }),
+    );
+    assert_eq!(view.finish().done(), 60);
+}
@@ -0,0 +1,63 @@
+//! Test requesting cancellation of the work shown by a view.
+
+use nutmeg::models::LinearModel;
+use nutmeg::{Destination, Options, View};
+
+fn view() -> View<LinearModel> {
+    let mut model = LinearModel::new("Files", 10);
+    model.set_final_message("Copied {done:file}");
+    View::new(model, Options::default().destination(Destination::Capture))
+}
+
+#[test]
+fn finish_after_cancellation_prints_cancelled_message() {
+    let view = view();
+    let output = view.captured_output();
+    assert!(!view.cancellation_requested());
+    view.request_cancellation();
+    assert!(view.cancellation_requested());
+    view.finish();
+    let output = output.lock().unwrap();
+    assert!(output.ends_with("cancelled\n"), "{output:?}");
+    assert!(!output.contains("Copied"));
+}
+
+#[test]
+fn drop_after_cancellation_prints_cancelled_message() {
+    let view = view();
+    let output = view.captured_output();
+    view.request_cancellation();
+    drop(view);
+    assert!(output.lock().unwrap().ends_with("cancelled\n"));
+}
+
+#[test]
+fn finish_success_overrides_cancellation() {
+    let view = view();
+    let output = view.captured_output();
+    view.update(|model| model.set_done(10));
+    view.request_cancellation();
+    view.finish_success();
+    assert!(output.lock().unwrap().ends_with("Copied 10 files\n"));
+}
+
+#[test]
+fn cancelled_message_can_be_customized() {
+    struct Copy;
+    impl nutmeg::Model for Copy {
+        fn render(&mut self, _width: usize) -> String {
+            "copying".into()
+        }
+        fn cancelled_message(&mut self) -> String {
+            "Interrupted: partial copy left behind".into()
+        }
+    }
+    let view = View::new(Copy, Options::default().destination(Destination::Capture));
+    let output = view.captured_output();
+    view.request_cancellation();
+    view.finish();
+    assert!(output
+        .lock()
+        .unwrap()
+        .ends_with("Interrupted: partial copy left behind\n"));
+}
@@ -0,0 +1,42 @@
+//! Test capturing only the most recent output with `Destination::capture_bounded`.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn bounded_capture_keeps_the_most_recent_output() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default().destination(Destination::capture_bounded(100)),
+    );
+    let output = view.captured_output();
+    let events = view.captured_events();
+    for i in 0..1000 {
+        view.message(format!("line {i}\n"));
+    }
+    let output = output.lock().unwrap();
+    assert_eq!(output.len(), 100);
+    assert!(output.ends_with("line 998\nline 999\n"), "{output:?}");
+    assert!(!output.contains("line 1\n"));
+    let events = events.lock().unwrap();
+    assert!(!events.is_empty() && events.len() < 10, "{}", events.len());
+    drop(view);
+}
+
+#[test]
+fn bounded_capture_does_not_split_characters() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default().destination(Destination::capture_bounded(4)),
+    );
+    let output = view.captured_output();
+    view.message("αβγ\n");
+    assert_eq!(output.lock().unwrap().as_str(), "γ\n");
+    drop(view);
+}
+
+#[test]
+#[should_panic(expected = "can't be a secondary destination")]
+fn bounded_capture_cannot_be_secondary() {
+    let _ = Options::default().also_emit(Destination::capture_bounded(100));
+}
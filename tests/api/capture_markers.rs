@@ -0,0 +1,58 @@
+//! Test delimiting frames and messages in captured output with `Options::capture_markers`.
+
+use nutmeg::models::LinearModel;
+use nutmeg::test_util::{FRAME_END, FRAME_START, MESSAGE_END, MESSAGE_START};
+use nutmeg::{Destination, Options, View};
+
+fn marked_options() -> Options {
+    Options::default()
+        .destination(Destination::Capture)
+        .update_interval(std::time::Duration::ZERO)
+        .print_holdoff(std::time::Duration::ZERO)
+        .capture_markers(true)
+}
+
+#[test]
+fn frames_and_messages_are_delimited() {
+    let mut model = LinearModel::new("Copying", 10);
+    model.set_final_message("Copied {done}");
+    let view = View::new(model, marked_options());
+    let output = view.captured_output();
+    view.update(|model| model.increment(1));
+    view.message("a.txt\n");
+    view.update(|model| model.increment(1));
+    view.finish();
+    let output = output.lock().unwrap();
+
+    let frames: Vec<&str> = output
+        .split(FRAME_START)
+        .skip(1)
+        .map(|s| s.split(FRAME_END).next().unwrap())
+        .collect();
+    assert_eq!(frames.len(), 2);
+    assert!(
+        frames[0].ends_with("Copying: 1/10, 10.0%, 0 sec remaining"),
+        "{frames:?}"
+    );
+
+    let messages: Vec<&str> = output
+        .split(MESSAGE_START)
+        .skip(1)
+        .map(|s| s.split(MESSAGE_END).next().unwrap())
+        .collect();
+    assert_eq!(messages, ["a.txt\n", "Copied 2\n"]);
+}
+
+#[test]
+fn markers_are_off_by_default() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.update(|model| model.increment(1));
+    view.message("a.txt\n");
+    drop(view);
+    let output = output.lock().unwrap();
+    assert!(!output.contains(FRAME_START) && !output.contains(MESSAGE_START));
+}
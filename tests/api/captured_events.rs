@@ -0,0 +1,42 @@
+//! Test timed events recorded by capture destinations.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{min_frame_interval, CapturedEventKind, Destination, Options, View};
+
+#[test]
+fn frames_are_not_drawn_within_print_holdoff() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .fake_clock(true)
+        .update_interval(Duration::ZERO)
+        .print_holdoff(Duration::from_millis(50));
+    let view = View::new(DisplayModel(0), options);
+    let events = view.captured_events();
+    let start = Instant::now();
+    for i in 0..20 {
+        view.set_fake_clock(start + Duration::from_millis(10 * i));
+        if i == 5 {
+            view.message("hello\n");
+        }
+        view.update(|model| model.0 = i);
+    }
+    drop(view);
+
+    let events = events.lock().unwrap();
+    let message_time = events
+        .iter()
+        .find(|e| matches!(e.kind, CapturedEventKind::Message(_)))
+        .unwrap()
+        .time;
+    assert_eq!(message_time, start + Duration::from_millis(50));
+    let first_frame_after = events
+        .iter()
+        .filter(|e| e.is_frame() && e.time > message_time)
+        .map(|e| e.time)
+        .next()
+        .unwrap();
+    assert_eq!(first_frame_after - message_time, Duration::from_millis(50));
+    assert_eq!(min_frame_interval(&events), Some(Duration::from_millis(10)));
+}
@@ -0,0 +1,171 @@
+//! Test configurable clocks.
+
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+
+use nutmeg::{Clock, CoarseClock, Destination, Options, View};
+
+#[test]
+fn coarse_clock_advances() {
+    let first = CoarseClock.now();
+    assert!(first <= Instant::now());
+    std::thread::sleep(CoarseClock::RESOLUTION * 10);
+    let second = CoarseClock.now();
+    assert!(second > first);
+    assert!(Instant::now().duration_since(second) < Duration::from_secs(1));
+}
+
+#[test]
+fn coarse_clock_is_current_after_idling() {
+    CoarseClock.now();
+    std::thread::sleep(CoarseClock::IDLE_TIMEOUT * 3);
+    let before = Instant::now();
+    // The clock thread has stopped, so this read takes the precise time.
+    assert!(CoarseClock.now() >= before);
+}
+
+#[derive(Debug)]
+struct CountingClock(AtomicUsize);
+
+impl Clock for CountingClock {
+    fn now(&self) -> Instant {
+        self.0.fetch_add(1, Ordering::Relaxed);
+        Instant::now()
+    }
+}
+
+struct Count(usize);
+
+impl nutmeg::Model for Count {
+    fn render(&mut self, _width: usize) -> String {
+        format!("count={}", self.0)
+    }
+}
+
+static COUNTING_CLOCK: CountingClock = CountingClock(AtomicUsize::new(0));
+
+#[test]
+fn view_reads_configured_clock() {
+    let view = View::new(
+        Count(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .clock(&COUNTING_CLOCK),
+    );
+    view.update(|c| c.0 += 1);
+    assert!(COUNTING_CLOCK.0.load(Ordering::Relaxed) > 0);
+}
+
+/// A clock that only moves when the test advances it.
+#[derive(Debug)]
+struct ManualClock {
+    base: OnceLock<Instant>,
+    offset_millis: AtomicU64,
+}
+
+impl ManualClock {
+    fn advance(&self, millis: u64) {
+        self.offset_millis.fetch_add(millis, Ordering::Relaxed);
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        *self.base.get_or_init(Instant::now)
+            + Duration::from_millis(self.offset_millis.load(Ordering::Relaxed))
+    }
+}
+
+static MANUAL_CLOCK: ManualClock = ManualClock {
+    base: OnceLock::new(),
+    offset_millis: AtomicU64::new(0),
+};
+
+#[test]
+fn updates_between_frames_are_applied_and_drawn_when_due() {
+    let view = View::new(
+        Count(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .clock(&MANUAL_CLOCK)
+            .update_interval(Duration::from_secs(1)),
+    );
+    let events = view.captured_events();
+    let frames = || {
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.is_frame())
+            .count()
+    };
+    view.update(|c| c.0 += 1);
+    assert_eq!(frames(), 1);
+    for _ in 0..10 {
+        view.update(|c| c.0 += 1);
+    }
+    assert_eq!(frames(), 1);
+    MANUAL_CLOCK.advance(1500);
+    view.update(|c| c.0 += 1);
+    assert_eq!(frames(), 2);
+    assert_eq!(view.inspect_model(|c| c.0), 12);
+
+    // A shorter interval takes effect on the next update.
+    view.replace_options(
+        Options::default()
+            .destination(Destination::Capture)
+            .clock(&MANUAL_CLOCK)
+            .update_interval(Duration::ZERO),
+    );
+    view.update(|c| c.0 += 1);
+    assert_eq!(frames(), 3);
+    view.abandon();
+}
+
+/// A clock that counts precise reads, and whose coarse reading is always the
+/// time it was first read.
+#[derive(Debug)]
+struct StoppedCoarseClock {
+    base: OnceLock<Instant>,
+    precise_reads: AtomicUsize,
+}
+
+impl Clock for StoppedCoarseClock {
+    fn now(&self) -> Instant {
+        self.precise_reads.fetch_add(1, Ordering::Relaxed);
+        *self.base.get_or_init(Instant::now)
+    }
+
+    fn now_coarse(&self) -> Instant {
+        *self.base.get_or_init(Instant::now)
+    }
+}
+
+static STOPPED_COARSE_CLOCK: StoppedCoarseClock = StoppedCoarseClock {
+    base: OnceLock::new(),
+    precise_reads: AtomicUsize::new(0),
+};
+
+#[test]
+fn updates_between_frames_read_only_the_coarse_clock() {
+    let view = View::new(
+        Count(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .clock(&STOPPED_COARSE_CLOCK)
+            .update_interval(Duration::from_secs(1)),
+    );
+    view.update(|c| c.0 += 1);
+    let reads = STOPPED_COARSE_CLOCK.precise_reads.load(Ordering::Relaxed);
+    assert!(reads > 0);
+    for _ in 0..10 {
+        view.update(|c| c.0 += 1);
+    }
+    assert_eq!(
+        STOPPED_COARSE_CLOCK.precise_reads.load(Ordering::Relaxed),
+        reads
+    );
+    assert_eq!(view.inspect_model(|c| c.0), 11);
+    view.abandon();
+}
@@ -0,0 +1,105 @@
+//! Test counting from many threads with `View::counter`.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::LinearModel;
+use nutmeg::{CounterMode, LocalCounter, Options, View};
+
+use crate::capture_options;
+
+fn options() -> Options {
+    capture_options().fake_clock(true)
+}
+
+#[test]
+fn counts_from_many_threads_are_folded_into_the_model() {
+    for mode in [CounterMode::Single, CounterMode::Sharded] {
+        let view = View::new(LinearModel::new("Hashing", 32_000), options());
+        let counter = view.counter(mode, |model, n| model.increment(n as usize));
+        assert_eq!(counter.mode(), mode);
+        std::thread::scope(|scope| {
+            for _ in 0..32 {
+                scope.spawn(|| {
+                    for _ in 0..1000 {
+                        counter.increment(1);
+                    }
+                });
+            }
+        });
+        assert_eq!(counter.pending(), 32_000);
+        assert_eq!(view.inspect_model(|model| model.done()), 32_000);
+        assert_eq!(counter.pending(), 0);
+        view.abandon();
+    }
+}
+
+#[test]
+fn count_is_painted_with_the_next_frame() {
+    let view = View::new(LinearModel::new("Hashing", 100), options());
+    let counter = view.counter(CounterMode::Sharded, |model, n| model.increment(n as usize));
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    counter.increment(40);
+    view.set_fake_clock(start + Duration::from_secs(1));
+    view.update(|_| ());
+    let output = view.captured_output();
+    assert!(
+        output.lock().unwrap().contains("Hashing: 40/100"),
+        "{output:?}"
+    );
+    view.abandon();
+}
+
+#[test]
+fn count_from_dropped_counter_is_not_lost() {
+    let view = View::new(LinearModel::new("Hashing", 100), options());
+    let counter = view.counter(CounterMode::Single, |model, n| model.increment(n as usize));
+    counter.clone().increment(3);
+    counter.increment(4);
+    drop(counter);
+    assert_eq!(view.finish().done(), 7);
+}
+
+#[test]
+fn local_counter_flushes_in_batches_and_when_dropped() {
+    let view = View::new(LinearModel::new("Hashing", 1000), options());
+    let counter = view.counter(CounterMode::Single, |model, n| model.increment(n as usize));
+    let mut local = counter.local();
+    for _ in 0..LocalCounter::FLUSH_INTERVAL - 1 {
+        local.increment(2);
+    }
+    assert_eq!(counter.pending(), 0);
+    local.increment(2);
+    assert_eq!(counter.pending(), 2 * LocalCounter::FLUSH_INTERVAL as u64);
+    local.increment(5);
+    local.flush();
+    assert_eq!(
+        counter.pending(),
+        2 * LocalCounter::FLUSH_INTERVAL as u64 + 5
+    );
+    local.increment(7);
+    drop(local);
+    assert_eq!(
+        view.inspect_model(|model| model.done()),
+        2 * LocalCounter::FLUSH_INTERVAL as usize + 12
+    );
+    view.abandon();
+}
+
+#[test]
+fn count_from_handle_dropped_while_counts_are_taken_is_not_lost() {
+    let view = View::new(LinearModel::new("Hashing", 0), options());
+    let mut late = Some(view.counter(CounterMode::Single, |model, n| model.increment(n as usize)));
+    let trigger = view.counter(CounterMode::Single, move |model, n| {
+        model.increment(n as usize);
+        // Count on the other counter, and drop its last handle, after the
+        // view has taken its count.
+        if let Some(late) = late.take() {
+            late.increment(10);
+        }
+    });
+    trigger.increment(1);
+    assert_eq!(view.inspect_model(|model| model.done()), 1);
+    assert_eq!(view.finish().done(), 11);
+}
@@ -0,0 +1,57 @@
+//! Test where the cursor is left between frames.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{CursorPark, Options, View};
+
+use crate::capture_options;
+
+fn options(park: CursorPark) -> Options {
+    capture_options().cursor_park(park)
+}
+
+#[test]
+fn start_of_bar_redraws_from_the_top() {
+    let view = View::new(DisplayModel("one\ntwo"), options(CursorPark::StartOfBar));
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.update(|model| model.0 = "one\nthree");
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[?7l\x1b[0Jone\ntwo\x1b[1F\
+        \x1b[1G\x1b[?7l\x1b[0Jone\nthree\x1b[1F"
+    );
+    output.lock().unwrap().clear();
+    view.message("hello\n");
+    view.update(|_| ());
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[1G\x1b[0J\x1b[?7hhello\n\x1b[?7l\x1b[0Jone\nthree\x1b[1F"
+    );
+    view.abandon();
+}
+
+#[test]
+fn bottom_left_redraws_only_changed_lines() {
+    let view = View::new(DisplayModel("one\ntwo"), options(CursorPark::BottomLeft));
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.update(|model| model.0 = "one\nthree");
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[?7l\x1b[0Jone\ntwo\x1b[1G\
+        \x1b[1G\x1b[?7l\x1b[0Jthree\x1b[1G"
+    );
+    output.lock().unwrap().clear();
+    view.clear();
+    assert_eq!(*output.lock().unwrap(), "\x1b[1F\x1b[0J\x1b[?7h");
+}
+
+#[test]
+fn abandon_leaves_the_cursor_below_the_bar() {
+    let view = View::new(DisplayModel("a\nb\nc"), options(CursorPark::StartOfBar));
+    let output = view.captured_output();
+    view.update(|_| ());
+    output.lock().unwrap().clear();
+    view.abandon();
+    assert_eq!(*output.lock().unwrap(), "\x1b[2B\n");
+}
@@ -0,0 +1,119 @@
+//! Test application-defined destinations.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use nutmeg::{Destination, DestinationTrait, Options, View};
+
+#[derive(Debug)]
+struct Sink {
+    is_terminal: bool,
+    output: Mutex<String>,
+}
+
+impl DestinationTrait for Sink {
+    fn init(&self) -> bool {
+        self.is_terminal
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(12)
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.output.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+struct Width;
+
+impl nutmeg::Model for Width {
+    fn render(&mut self, width: usize) -> String {
+        format!("width={width}")
+    }
+}
+
+static TERMINAL_SINK: Sink = Sink {
+    is_terminal: true,
+    output: Mutex::new(String::new()),
+};
+
+static FILE_SINK: Sink = Sink {
+    is_terminal: false,
+    output: Mutex::new(String::new()),
+};
+
+#[test]
+fn progress_drawn_to_custom_destination() {
+    let view = View::new(
+        Width,
+        Options::default().destination(Destination::Custom(&TERMINAL_SINK)),
+    );
+    view.update(|_| ());
+    drop(view);
+    assert_eq!(
+        TERMINAL_SINK.output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jwidth=12\x1b[1G\x1b[0J\x1b[?7h"
+    );
+}
+
+#[test]
+fn only_messages_written_when_init_returns_false() {
+    let view = View::new(
+        Width,
+        Options::default().destination(Destination::Custom(&FILE_SINK)),
+    );
+    view.update(|_| ());
+    view.message("hello\n");
+    drop(view);
+    assert_eq!(FILE_SINK.output.lock().unwrap().as_str(), "hello\n");
+}
+
+/// A destination owned by the view, rather than a static.
+#[derive(Debug, Default)]
+struct OwnedSink(Arc<Mutex<String>>);
+
+impl DestinationTrait for OwnedSink {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(12)
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn view_generic_over_destination() {
+    let sink = OwnedSink::default();
+    let output = Arc::clone(&sink.0);
+    let view: View<Width, OwnedSink> = View::with_destination(Width, Options::default(), sink);
+    view.update(|_| ());
+    view.message("hello\n");
+    drop(view);
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jwidth=12\x1b[1G\x1b[0J\x1b[?7hhello\n"
+    );
+}
+
+#[test]
+fn with_destination_accepts_capture() {
+    let view = View::with_destination(Width, Options::default(), Destination::Capture);
+    view.message("hello\n");
+    assert_eq!(view.captured_output().lock().unwrap().as_str(), "hello\n");
+}
@@ -0,0 +1,40 @@
+//! Test `View::emergency_restore`.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+/// Emergency restore doesn't take the lock, so it can be called while it's held,
+/// and does nothing to captured output.
+#[test]
+fn emergency_restore_does_not_lock() {
+    let view = View::new(
+        DisplayModel("hello"),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.update(|_model| view.emergency_restore());
+    view.emergency_restore();
+    // The view knows the bar was erased, so abandoning it doesn't move below
+    // the bar.
+    view.abandon();
+    assert_eq!(output.lock().unwrap().as_str(), "\x1b[?7l\x1b[0Jhello");
+}
+
+/// After an emergency restore, perhaps when the process was stopped and then
+/// continued, the bar is drawn again from scratch.
+#[test]
+fn bar_is_redrawn_after_emergency_restore() {
+    let view = View::new(
+        DisplayModel("hello"),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.emergency_restore();
+    view.update(|_| ());
+    view.abandon();
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jhello\x1b[?7l\x1b[0Jhello\n"
+    );
+}
@@ -0,0 +1,59 @@
+//! Test estimating the time of day when work will finish.
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use nutmeg::eta_clock_time;
+use nutmeg::models::LinearModel;
+use nutmeg::Model;
+
+/// Check that `s` is a time of day like `~14:35`.
+fn assert_time_of_day(s: &str) {
+    let b = s.as_bytes();
+    assert!(
+        b.len() >= 6
+            && b[0] == b'~'
+            && b[3] == b':'
+            && [1, 2, 4, 5].iter().all(|&i| b[i].is_ascii_digit()),
+        "not a time of day: {s:?}"
+    );
+}
+
+#[test]
+fn eta_is_a_time_of_day() {
+    let start = Instant::now() - Duration::from_secs(60);
+    assert_time_of_day(&eta_clock_time(&start, 1, 2));
+}
+
+#[test]
+fn eta_days_ahead_are_counted() {
+    let start = Instant::now() - Duration::from_secs(3600);
+    // About 99 hours to go, so four or five days ahead depending on the time now.
+    let eta = eta_clock_time(&start, 1, 100);
+    assert_time_of_day(&eta);
+    assert!(eta.ends_with(" +4d") || eta.ends_with(" +5d"), "{eta:?}");
+}
+
+#[test]
+fn eta_unknown() {
+    let start = Instant::now();
+    assert_eq!(eta_clock_time(&start, 0, 10), "??");
+    assert_eq!(eta_clock_time(&start, 11, 10), "??");
+}
+
+#[test]
+fn linear_model_shows_time_of_day_when_enough_remains() {
+    let mut model = LinearModel::new("Rendering", 2);
+    model.set_done(1);
+    sleep(Duration::from_millis(10));
+    assert!(model.render(80).ends_with(" remaining"));
+
+    model.set_clock_time_after(Duration::from_secs(3600));
+    assert!(model.render(80).ends_with(" remaining"));
+
+    model.set_clock_time_after(Duration::ZERO);
+    let rendered = model.render(80);
+    let (prefix, eta) = rendered.split_once("done ").unwrap();
+    assert_eq!(prefix, "Rendering: 1/2, 50.0%, ");
+    assert_time_of_day(eta);
+}
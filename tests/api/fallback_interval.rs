@@ -0,0 +1,92 @@
+//! Test printing plain progress lines when the destination isn't a terminal.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use nutmeg::models::{DisplayModel, LinearModel};
+use nutmeg::{DestinationTrait, FallbackInterval, Options, View};
+
+/// A destination like a file or pipe, which declines to draw progress bars.
+#[derive(Debug, Clone, Default)]
+struct Pipe(Arc<Mutex<String>>);
+
+impl DestinationTrait for Pipe {
+    fn init(&self) -> bool {
+        false
+    }
+
+    fn width(&self) -> Option<usize> {
+        None
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn plain_lines_are_printed_every_interval() {
+    let pipe = Pipe::default();
+    let view = View::with_destination(
+        DisplayModel("\x1b[1mworking\x1b[0m 1"),
+        Options::default()
+            .fake_clock(true)
+            .fallback_interval(FallbackInterval::Time(Duration::from_secs(10))),
+        pipe.clone(),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    view.set_fake_clock(start + Duration::from_secs(5));
+    view.update(|model| model.0 = "\x1b[1mworking\x1b[0m 2");
+    assert_eq!(*pipe.0.lock().unwrap(), "");
+
+    view.set_fake_clock(start + Duration::from_secs(11));
+    view.update(|model| model.0 = "\x1b[1mworking\x1b[0m 3");
+    assert_eq!(*pipe.0.lock().unwrap(), "working 3\n");
+
+    view.set_fake_clock(start + Duration::from_secs(15));
+    view.update(|model| model.0 = "\x1b[1mworking\x1b[0m 4");
+    assert_eq!(*pipe.0.lock().unwrap(), "working 3\n");
+    view.abandon();
+}
+
+#[test]
+fn plain_lines_are_printed_every_few_percent() {
+    let pipe = Pipe::default();
+    let view = View::with_destination(
+        LinearModel::new("Copying", 100),
+        Options::default().fallback_interval(FallbackInterval::Percent(25)),
+        pipe.clone(),
+    );
+    for _ in 0..60 {
+        view.update(|model| model.increment(1));
+    }
+    let output = pipe.0.lock().unwrap().clone();
+    let lines: Vec<&str> = output.lines().collect();
+    assert_eq!(lines.len(), 2, "{output:?}");
+    assert!(lines[0].starts_with("Copying: 25/100"), "{output:?}");
+    assert!(lines[1].starts_with("Copying: 50/100"), "{output:?}");
+    view.abandon();
+}
+
+#[test]
+fn nothing_is_printed_by_default() {
+    let pipe = Pipe::default();
+    let view = View::with_destination(
+        LinearModel::new("Copying", 100),
+        Options::default(),
+        pipe.clone(),
+    );
+    for _ in 0..100 {
+        view.update(|model| model.increment(1));
+    }
+    assert_eq!(*pipe.0.lock().unwrap(), "");
+    view.abandon();
+}
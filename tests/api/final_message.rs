@@ -0,0 +1,78 @@
+//! Test layout of the final message.
+
+use nutmeg::{Destination, Options, View};
+
+struct Summary;
+
+impl nutmeg::Model for Summary {
+    fn render(&mut self, _width: usize) -> String {
+        String::new()
+    }
+
+    fn final_message(&mut self) -> String {
+        format!("\x1b[1msummary\x1b[0m: {}\nok", "x".repeat(100))
+    }
+}
+
+#[test]
+fn long_final_message_is_wrapped_to_width() {
+    let view = View::new(
+        Summary,
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.finish();
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        format!(
+            "\x1b[1msummary\x1b[0m: {}\n{}\nok\n",
+            "x".repeat(71),
+            "x".repeat(29)
+        )
+    );
+}
+
+struct Job;
+
+impl nutmeg::Model for Job {
+    fn render(&mut self, _width: usize) -> String {
+        "working".to_owned()
+    }
+
+    fn final_message(&mut self) -> String {
+        "all done".to_owned()
+    }
+}
+
+#[test]
+fn finish_failure_prints_failure_message() {
+    let view = View::new(Job, Options::default().destination(Destination::Capture));
+    let output = view.captured_output();
+    view.finish_failure("disk full");
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1;31mfailed: disk full\x1b[0m\n"
+    );
+}
+
+#[test]
+fn finish_success_rings_bell_if_enabled() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .finish_bell(true);
+    let view = View::new(Job, options);
+    let output = view.captured_output();
+    view.finish_success();
+    assert_eq!(output.lock().unwrap().as_str(), "all done\n\x07");
+}
+
+#[test]
+fn plain_finish_does_not_ring_bell() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .finish_bell(true);
+    let view = View::new(Job, options);
+    let output = view.captured_output();
+    view.finish();
+    assert_eq!(output.lock().unwrap().as_str(), "all done\n");
+}
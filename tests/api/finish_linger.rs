@@ -0,0 +1,91 @@
+//! Test leaving the final state on the screen with `Options::finish_linger`.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{CapturedEventKind, Options, View};
+
+use crate::capture_options;
+
+fn options() -> Options {
+    capture_options().update_interval(Duration::from_secs(3600))
+}
+
+#[test]
+fn final_state_is_drawn_before_erasing() {
+    let view = View::new(
+        DisplayModel(1),
+        options()
+            .fake_clock(true)
+            .finish_linger(Duration::from_secs(1)),
+    );
+    let events = view.captured_events();
+    view.update(|model| model.0 = 2);
+    // Within the update interval, so not drawn.
+    view.update(|model| model.0 = 3);
+    view.finish();
+    let kinds: Vec<CapturedEventKind> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|e| e.kind.clone())
+        .collect();
+    assert_eq!(
+        kinds,
+        [
+            CapturedEventKind::Frame("2".to_owned()),
+            CapturedEventKind::Frame("3".to_owned()),
+            CapturedEventKind::Erase,
+        ]
+    );
+}
+
+#[test]
+fn linger_waits_before_erasing() {
+    let view = View::new(
+        DisplayModel(1),
+        options().finish_linger(Duration::from_millis(100)),
+    );
+    view.update(|model| model.0 = 2);
+    let start = Instant::now();
+    view.finish();
+    assert!(start.elapsed() >= Duration::from_millis(100));
+}
+
+#[test]
+fn no_linger_if_the_bar_is_not_drawn() {
+    let view = View::new(
+        DisplayModel(1),
+        options().finish_linger(Duration::from_secs(3600)),
+    );
+    view.finish();
+}
+
+#[test]
+fn no_linger_after_cancellation() {
+    let view = View::new(
+        DisplayModel(1),
+        options().finish_linger(Duration::from_secs(3600)),
+    );
+    view.update(|model| model.0 = 2);
+    view.request_cancellation();
+    view.finish();
+}
+
+#[test]
+fn no_extra_frame_by_default() {
+    let view = View::new(DisplayModel(1), options());
+    let events = view.captured_events();
+    view.update(|model| model.0 = 2);
+    view.update(|model| model.0 = 3);
+    view.finish();
+    assert_eq!(
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.is_frame())
+            .count(),
+        1
+    );
+}
@@ -0,0 +1,116 @@
+//! Test localizing numbers and durations with a formatter.
+
+use std::time::Duration;
+
+use nutmeg::models::{BytesModel, LinearModel};
+use nutmeg::{
+    current_formatting, estimate_remaining, format_bytes, percent_done, Destination, Formatting,
+    Model, Options, View,
+};
+
+/// Decimal commas, dots between thousands, and German durations.
+#[derive(Debug)]
+struct German;
+
+impl Formatting for German {
+    fn number(&self, n: u64) -> String {
+        let digits = n.to_string();
+        let mut out = String::new();
+        for (i, c) in digits.chars().enumerate() {
+            if i > 0 && (digits.len() - i).is_multiple_of(3) {
+                out.push('.');
+            }
+            out.push(c);
+        }
+        out
+    }
+
+    fn decimal(&self, value: f64, precision: usize) -> String {
+        format!("{value:.precision$}").replace('.', ",")
+    }
+
+    fn duration(&self, duration: Duration) -> String {
+        format!("{} Sek.", duration.as_secs())
+    }
+}
+
+struct Report;
+
+impl Model for Report {
+    fn render(&mut self, _width: usize) -> String {
+        format!("{} {}", percent_done(1, 8), format_bytes(2_500))
+    }
+
+    fn final_message(&mut self) -> String {
+        current_formatting().number(1234567)
+    }
+}
+
+#[test]
+fn helpers_use_default_formatting_outside_views() {
+    assert_eq!(percent_done(1, 8), "12.5%");
+    assert_eq!(format_bytes(2_500), "2.5 kB");
+    assert_eq!(current_formatting().number(1234567), "1234567");
+}
+
+#[test]
+fn formatter_applies_to_render_and_final_message() {
+    let view = View::new(
+        Report,
+        Options::default()
+            .destination(Destination::Capture)
+            .formatter(&German),
+    );
+    view.update(|_| ());
+    let output = view.captured_output();
+    view.finish();
+    let output = output.lock().unwrap();
+    assert!(output.contains("12,5% 2,5 kB"), "{output:?}");
+    assert!(output.ends_with("1.234.567\n"), "{output:?}");
+    // The formatter is only current while the view calls the model.
+    assert_eq!(percent_done(1, 8), "12.5%");
+}
+
+#[test]
+fn built_in_models_use_formatter() {
+    let view = View::new(
+        LinearModel::new("Zählen", 20000),
+        Options::default()
+            .destination(Destination::Capture)
+            .formatter(&German),
+    );
+    view.update(|model| model.set_done(5000));
+    // The formatter is also current inside other calls from the view.
+    let rendered = view.inspect_model(|model| model.render(80));
+    assert!(rendered.starts_with("Zählen: 5.000/20.000, 25,0%, "));
+    let output = view.captured_output();
+    assert!(
+        output
+            .lock()
+            .unwrap()
+            .contains("Zählen: 5.000/20.000, 25,0%, "),
+        "{:?}",
+        output.lock().unwrap()
+    );
+    view.abandon();
+
+    let view = View::new(
+        BytesModel::new("Laden"),
+        Options::default()
+            .destination(Destination::Capture)
+            .formatter(&German),
+    );
+    view.update(|model| model.increment(1_234_567));
+    assert!(view
+        .captured_output()
+        .lock()
+        .unwrap()
+        .contains("Laden: 1,2 MB in 0:00, "));
+    view.abandon();
+}
+
+#[test]
+fn estimate_uses_formatter_duration() {
+    let start = std::time::Instant::now() - Duration::from_secs(10);
+    assert_eq!(estimate_remaining(&start, 1, 2), "10 sec");
+}
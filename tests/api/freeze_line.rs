@@ -0,0 +1,53 @@
+//! Test freezing lines above the progress bar with `View::freeze_line`.
+
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn frozen_line_is_printed_and_bar_redrawn_immediately() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .print_holdoff(Duration::from_secs(3600)),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    output.lock().unwrap().clear();
+
+    view.freeze_line("item 1 done");
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1G\x1b[0J\x1b[?7hitem 1 done\n\x1b[?7l\x1b[0Jworking"
+    );
+    output.lock().unwrap().clear();
+
+    // A trailing newline isn't doubled.
+    view.freeze_line("item 2 done\n");
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1G\x1b[0J\x1b[?7hitem 2 done\n\x1b[?7l\x1b[0Jworking"
+    );
+
+    // Frozen lines stay on the screen after the bar is erased.
+    view.finish();
+    assert!(output
+        .lock()
+        .unwrap()
+        .ends_with("item 2 done\n\x1b[?7l\x1b[0Jworking\x1b[1G\x1b[0J\x1b[?7h"));
+}
+
+#[test]
+fn frozen_line_inside_update_is_deferred() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.update(|_| view.freeze_line("from inside"));
+    view.abandon();
+    assert!(output.lock().unwrap().contains("from inside\n"));
+}
@@ -0,0 +1,41 @@
+//! Test the gallery of built-in models.
+
+use nutmeg::models::{gallery, GALLERY_WIDTHS};
+
+#[test]
+fn gallery_covers_all_models_at_all_widths() {
+    let frames = gallery();
+    for model in [
+        "StringPair",
+        "LinearModel",
+        "UnboundedModel",
+        "ScanThenWork",
+        "BytesModel",
+        "ItemsAndBytesModel",
+        "Group",
+        "SpinnerModel",
+        "ThroughputProbe",
+        "BasicModel",
+        "DisplayModel",
+    ] {
+        for width in GALLERY_WIDTHS {
+            assert!(
+                frames.iter().any(|f| f.model == model && f.width == width),
+                "no frame for {model} at width {width}"
+            );
+        }
+    }
+    assert!(frames.iter().all(|f| !f.rendered.is_empty()));
+    let half = frames
+        .iter()
+        .find(|f| f.model == "LinearModel" && f.state == "50/100" && f.width == 80)
+        .unwrap();
+    assert!(half
+        .rendered
+        .starts_with("Counting raindrops: 50/100, 50.0%, "));
+    let compact = frames
+        .iter()
+        .find(|f| f.model == "LinearModel" && f.state == "50/100" && f.width == 20)
+        .unwrap();
+    assert!(compact.rendered.starts_with("50.0% "));
+}
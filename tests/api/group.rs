@@ -0,0 +1,116 @@
+//! Test groups of models that expand or collapse to fit the terminal.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nutmeg::models::{DisplayModel, Expansion, Group, LinearModel};
+use nutmeg::{current_height, Destination, DestinationTrait, Model, Options, View};
+
+fn group_of(n: usize) -> Group<LinearModel> {
+    let mut group = Group::new("Jobs");
+    for i in 0..n {
+        let mut member = LinearModel::new(format!("job {i}"), 10);
+        member.set_done(if i == 0 { 10 } else { 5 });
+        group.push(member);
+    }
+    group
+}
+
+#[test]
+fn expanded_group_shows_indented_members() {
+    let mut group = group_of(2);
+    group.set_expansion(Expansion::Expanded);
+    let rendered = group.render(80);
+    let lines: Vec<&str> = rendered.lines().collect();
+    assert_eq!(lines.len(), 3);
+    assert_eq!(lines[0], "Jobs: 1/2 complete, 75.0%");
+    assert!(lines[1].starts_with("  job 0: 10/10, 100.0%"), "{lines:?}");
+    assert!(lines[2].starts_with("  job 1: 5/10, 50.0%"), "{lines:?}");
+    assert_eq!(group.summary_counts(), [("complete", 1), ("members", 2)]);
+}
+
+#[test]
+fn group_collapses_when_members_do_not_fit() {
+    let mut group = group_of(5);
+    group.set_expansion(Expansion::UpTo(6));
+    assert_eq!(group.render(80).lines().count(), 6);
+    group.set_expansion(Expansion::UpTo(5));
+    assert_eq!(group.render(80), "Jobs: 1/5 complete, 60.0%");
+}
+
+#[test]
+fn group_without_fractions_has_no_percentage() {
+    let mut group: Group<DisplayModel<&str>> = Group::new("Nothing");
+    group.push(DisplayModel("no fraction"));
+    assert_eq!(group.fraction_done(), None);
+    assert_eq!(group.render(80), "Nothing: 0/1 complete\n  no fraction");
+}
+
+#[derive(Debug, Default)]
+struct ShortTerminal(Arc<Mutex<String>>);
+
+impl DestinationTrait for ShortTerminal {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(80)
+    }
+
+    fn height(&self) -> Option<usize> {
+        Some(8)
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn auto_expansion_uses_half_the_terminal_height() {
+    let terminal = ShortTerminal::default();
+    let output = Arc::clone(&terminal.0);
+    let view = View::with_destination(
+        group_of(3),
+        Options::default().update_interval(Duration::ZERO),
+        terminal,
+    );
+    // Four lines fit in half of eight.
+    view.update(|_| ());
+    assert_eq!(output.lock().unwrap().lines().count(), 4);
+    output.lock().unwrap().clear();
+    view.update(|group| group.push(LinearModel::new("job 3", 10)));
+    assert!(output
+        .lock()
+        .unwrap()
+        .ends_with("Jobs: 1/4 complete, 50.0%"));
+    // The height is only known while the view is rendering.
+    assert_eq!(current_height(), None);
+}
+
+#[test]
+fn auto_expansion_in_a_captured_view() {
+    // Capture destinations are 24 lines high, so up to 12 lines are shown.
+    let view = View::new(
+        group_of(11),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    assert_eq!(output.lock().unwrap().lines().count(), 12);
+    output.lock().unwrap().clear();
+    view.update(|group| group.push(LinearModel::new("job 11", 10)));
+    assert!(output
+        .lock()
+        .unwrap()
+        .ends_with("Jobs: 1/12 complete, 50.0%"));
+}
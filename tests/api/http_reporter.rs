@@ -0,0 +1,101 @@
+//! Test serving progress over HTTP.
+
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpStream;
+use std::time::Duration;
+
+use nutmeg::http::HttpReporter;
+use nutmeg::models::LinearModel;
+use nutmeg::{Destination, Options, View};
+
+fn get(reporter: &HttpReporter, path: &str) -> String {
+    let mut stream = TcpStream::connect(reporter.local_addr()).unwrap();
+    write!(stream, "GET {path} HTTP/1.1\r\nHost: localhost\r\n\r\n").unwrap();
+    let mut response = String::new();
+    stream.read_to_string(&mut response).unwrap();
+    response
+}
+
+#[test]
+fn json_snapshot_served() {
+    let reporter = HttpReporter::bind("127.0.0.1:0").unwrap();
+    let view = View::new(
+        LinearModel::new("Counting", 4),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .http_reporter(reporter),
+    );
+    view.update(|model| model.increment(2));
+    view.message("hello\n");
+    let response = get(reporter, "/");
+    assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+    let body = response.split("\r\n\r\n").nth(1).unwrap();
+    assert!(body.starts_with("{\"frame\":\"Counting: 2/4, 50.0%, "));
+    assert!(body.ends_with("\"fraction\":0.5,\"frames\":1,\"messages\":1,\"finished\":false}"));
+    view.finish();
+    assert!(get(reporter, "/").ends_with("\"finished\":true}"));
+    assert!(get(reporter, "/nothing").starts_with("HTTP/1.1 404"));
+}
+
+#[test]
+fn events_streamed() {
+    let reporter = HttpReporter::bind("127.0.0.1:0").unwrap();
+    let view = View::new(
+        LinearModel::new("Counting", 4),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .http_reporter(reporter),
+    );
+    let mut stream = TcpStream::connect(reporter.local_addr()).unwrap();
+    write!(stream, "GET /events HTTP/1.1\r\n\r\n").unwrap();
+    let mut reader = BufReader::new(stream);
+    let mut next_event = || loop {
+        let mut line = String::new();
+        reader.read_line(&mut line).unwrap();
+        if let Some(data) = line.strip_prefix("data: ") {
+            return data.trim_end().to_owned();
+        }
+    };
+    assert!(next_event().starts_with("{\"frame\":\"\""));
+    view.update(|model| model.increment(1));
+    assert!(next_event().contains("\"fraction\":0.25"));
+    view.update(|model| model.increment(1));
+    assert!(next_event().contains("\"fraction\":0.5"));
+}
+
+#[test]
+fn model_rendered_once_per_frame() {
+    struct Renders(usize);
+
+    impl nutmeg::Model for Renders {
+        fn render(&mut self, _width: usize) -> String {
+            self.0 += 1;
+            format!("render {}", self.0)
+        }
+    }
+
+    let reporter = HttpReporter::bind("127.0.0.1:0").unwrap();
+    let view = View::new(
+        Renders(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .http_reporter(reporter),
+    );
+    for _ in 0..3 {
+        view.update(|_| ());
+    }
+    assert_eq!(view.inspect_model(|model| model.0), 3);
+    let response = get(reporter, "/");
+    assert!(response.contains("\"frame\":\"render 3\""), "{response}");
+}
+
+#[test]
+fn idle_client_does_not_block_others() {
+    let reporter = HttpReporter::bind("127.0.0.1:0").unwrap();
+    // Connect without sending a request.
+    let _idle = TcpStream::connect(reporter.local_addr()).unwrap();
+    assert!(get(reporter, "/").starts_with("HTTP/1.1 200 OK\r\n"));
+}
@@ -0,0 +1,56 @@
+//! Test redrawing the whole bar after `View::invalidate`.
+
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Options, View};
+
+use crate::capture_options;
+
+fn options() -> Options {
+    capture_options().update_interval(Duration::from_secs(3600))
+}
+
+#[test]
+fn invalidated_bar_is_fully_redrawn() {
+    let view = View::new(DisplayModel("one\ntwo"), options());
+    let output = view.captured_output();
+    view.update(|_| ());
+    output.lock().unwrap().clear();
+    view.invalidate();
+    view.update(|_| ());
+    // Back to the top of the bar, erase everything below, and draw both lines.
+    assert_eq!(*output.lock().unwrap(), "\x1b[1F\x1b[?7l\x1b[0Jone\ntwo");
+    view.abandon();
+}
+
+#[test]
+fn invalidate_only_affects_the_next_frame() {
+    let view = View::new(DisplayModel("one"), options());
+    let events = view.captured_events();
+    view.update(|_| ());
+    view.invalidate();
+    view.update(|_| ());
+    view.update(|_| ());
+    assert_eq!(
+        events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.is_frame())
+            .count(),
+        2
+    );
+    view.abandon();
+}
+
+#[test]
+fn invalidate_without_a_bar_does_nothing() {
+    let view = View::new(DisplayModel("one"), options());
+    let output = view.captured_output();
+    view.invalidate();
+    assert_eq!(*output.lock().unwrap(), "");
+    view.update(|_| ());
+    assert_eq!(*output.lock().unwrap(), "\x1b[?7l\x1b[0Jone");
+    view.abandon();
+}
@@ -0,0 +1,58 @@
+//! Test `ItemsAndBytesModel`, which counts items and bytes together.
+
+use nutmeg::models::ItemsAndBytesModel;
+use nutmeg::Model;
+
+#[test]
+fn estimate_comes_from_bytes_when_both_totals_are_known() {
+    let mut model = ItemsAndBytesModel::new("Copying");
+    model.set_total_items(10);
+    model.set_total_bytes(1000);
+    // One big item is most of the bytes.
+    model.increment(1, 900);
+    assert_eq!(model.fraction_done(), Some(0.9));
+    assert!(model
+        .render(100)
+        .starts_with("Copying: 1/10, 900 B of 1.0 kB, 90.0%, "));
+    assert!(model.render_compact(30).starts_with("90.0% "));
+}
+
+#[test]
+fn estimate_comes_from_items_without_total_bytes() {
+    let mut model = ItemsAndBytesModel::new("Copying");
+    model.set_total_items(4);
+    model.increment(1, 5000);
+    model.increment_bytes(5000);
+    assert_eq!(model.fraction_done(), Some(0.25));
+    assert!(model
+        .render(100)
+        .starts_with("Copying: 1/4, 10.0 kB, 25.0%, "));
+}
+
+#[test]
+fn counts_are_shown_without_totals() {
+    let mut model = ItemsAndBytesModel::new("Copying");
+    model.set_done(3, 2_500_000);
+    assert_eq!(model.fraction_done(), None);
+    assert!(model.render(100).starts_with("Copying: 3, 2.5 MB in "));
+    assert_eq!(model.render_compact(30), "3 2.5 MB");
+}
+
+#[test]
+fn final_message_has_items_and_bytes() {
+    let mut model = ItemsAndBytesModel::new("Copying");
+    model.set_total_items(2);
+    model.set_total_bytes(2000);
+    model.set_done(2, 2000);
+    model.set_final_message("Copied {done:file}, {bytes} bytes");
+    assert_eq!(
+        model.summary_counts(),
+        [
+            ("done", 2),
+            ("total", 2),
+            ("bytes", 2000),
+            ("total_bytes", 2000)
+        ]
+    );
+    assert!(model.final_message().contains("Copied 2 files, 2000 bytes"));
+}
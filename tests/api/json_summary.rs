@@ -0,0 +1,118 @@
+//! Test the JSON summary written when a view finishes.
+
+use nutmeg::models::{LinearModel, UnboundedModel};
+use nutmeg::{Destination, Model, Options, View};
+
+struct Copied(usize);
+
+impl Model for Copied {
+    fn render(&mut self, _width: usize) -> String {
+        format!("copying {}", self.0)
+    }
+
+    fn final_message(&mut self) -> String {
+        format!("\x1b[1mCopied\x1b[0m {} \"files\"", self.0)
+    }
+}
+
+#[test]
+fn summary_of_successful_linear_model() {
+    let view = View::new(
+        LinearModel::new("Copying", 4),
+        Options::default()
+            .destination(Destination::Capture)
+            .json_summary(Destination::capture_named("json_summary/success")),
+    );
+    view.update(|model| model.increment(4));
+    view.finish_success();
+    assert_eq!(
+        *Destination::captured_output_named("json_summary/success")
+            .lock()
+            .unwrap(),
+        "{\"outcome\":\"success\",\"message\":\"\",\"fraction\":1,\"done\":4,\"total\":4}\n"
+    );
+}
+
+#[test]
+fn summary_message_is_plain_and_escaped() {
+    let view = View::new(
+        Copied(3),
+        Options::default()
+            .destination(Destination::Capture)
+            .json_summary(Destination::capture_named("json_summary/message")),
+    );
+    view.finish();
+    assert_eq!(
+        *Destination::captured_output_named("json_summary/message")
+            .lock()
+            .unwrap(),
+        "{\"outcome\":\"finished\",\"message\":\"Copied 3 \\\"files\\\"\",\"fraction\":null}\n"
+    );
+}
+
+#[test]
+fn summary_of_failure() {
+    let view = View::new(
+        UnboundedModel::new("Reading"),
+        Options::default()
+            .destination(Destination::Capture)
+            .json_summary(Destination::capture_named("json_summary/failure")),
+    );
+    view.update(|model| model.increment(2));
+    view.finish_failure("disk on fire");
+    let summary = Destination::captured_output_named("json_summary/failure");
+    let summary = summary.lock().unwrap();
+    assert!(
+        summary.starts_with("{\"outcome\":\"failure\",\"message\":\"failed: disk on fire\","),
+        "{summary}"
+    );
+    assert!(summary.ends_with(",\"done\":2}\n"), "{summary}");
+}
+
+#[test]
+fn no_summary_when_abandoned() {
+    let view = View::new(
+        Copied(1),
+        Options::default()
+            .destination(Destination::Capture)
+            .json_summary(Destination::capture_named("json_summary/abandoned")),
+    );
+    view.abandon();
+    assert_eq!(
+        *Destination::captured_output_named("json_summary/abandoned")
+            .lock()
+            .unwrap(),
+        ""
+    );
+}
+
+#[test]
+fn summary_of_cancelled_view() {
+    let view = View::new(
+        LinearModel::new("Copying", 4),
+        Options::default()
+            .destination(Destination::Capture)
+            .json_summary(Destination::capture_named("json_summary/cancelled")),
+    );
+    view.update(|model| model.increment(1));
+    view.request_cancellation();
+    view.finish();
+    assert_eq!(
+        *Destination::captured_output_named("json_summary/cancelled")
+            .lock()
+            .unwrap(),
+        "{\"outcome\":\"cancelled\",\"message\":\"cancelled\",\"fraction\":0.25,\"done\":1,\"total\":4}\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "can't receive a JSON summary")]
+fn capture_can_not_receive_the_summary() {
+    let _ = Options::default().json_summary(Destination::Capture);
+}
+
+#[test]
+#[should_panic(expected = "can't receive a JSON summary")]
+fn bounded_capture_can_not_receive_the_summary() {
+    let _ = Options::default().json_summary(Destination::capture_bounded(100));
+}
@@ -0,0 +1,115 @@
+//! Test frames whose number of lines changes, and reserving space for them.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Model, View};
+
+use crate::capture_options;
+
+#[test]
+fn growing_and_shrinking_frames_clear_orphaned_lines() {
+    let view = View::new(DisplayModel("one".to_owned()), capture_options());
+    let output = view.captured_output();
+    let events = view.captured_events();
+    view.update(|_| ());
+    view.update(|model| model.0 = "one\ntwo\nthree".to_owned());
+    output.lock().unwrap().clear();
+
+    view.update(|model| model.0 = "one".to_owned());
+    // The cursor moves up from the bottom of the three-line frame, and
+    // everything below it is cleared.
+    assert_eq!(output.lock().unwrap().as_str(), "\x1b[2F\x1b[?7l\x1b[0Jone");
+    output.lock().unwrap().clear();
+
+    view.finish();
+    assert_eq!(output.lock().unwrap().as_str(), "\x1b[1G\x1b[0J\x1b[?7h");
+    let line_counts: Vec<usize> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| event.frame_line_count())
+        .collect();
+    assert_eq!(line_counts, [1, 3, 1]);
+}
+
+#[test]
+fn frames_are_cut_to_the_terminal_height() {
+    let tall = (0..100)
+        .map(|i| i.to_string())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let view = View::new(DisplayModel(tall), capture_options());
+    let events = view.captured_events();
+    view.update(|_| ());
+    let events = events.lock().unwrap();
+    // Capture destinations are 24 lines high.
+    assert_eq!(events[0].frame_line_count(), Some(24));
+    assert!(matches!(&events[0].kind, nutmeg::CapturedEventKind::Frame(f) if f.ends_with("\n23")));
+}
+
+struct Growing {
+    lines: usize,
+}
+
+impl Model for Growing {
+    fn render(&mut self, _width: usize) -> String {
+        vec!["line"; self.lines].join("\n")
+    }
+
+    fn max_height(&self) -> Option<usize> {
+        Some(5)
+    }
+}
+
+#[test]
+fn max_height_reserves_space_before_drawing() {
+    let view = View::new(Growing { lines: 1 }, capture_options());
+    let output = view.captured_output();
+    view.update(|_| ());
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\n\n\n\n\x1b[4F\x1b[?7l\x1b[0Jline"
+    );
+    output.lock().unwrap().clear();
+
+    // Redrawing over the bar doesn't reserve space again.
+    view.update(|model| model.lines = 2);
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1G\x1b[?7l\x1b[0Jline\nline"
+    );
+    output.lock().unwrap().clear();
+
+    // After a message, the space is reserved again below it.
+    view.message("hello\n");
+    view.update(|_| ());
+    assert!(output
+        .lock()
+        .unwrap()
+        .ends_with("hello\n\n\n\n\n\x1b[4F\x1b[?7l\x1b[0Jline\nline"));
+}
+
+#[test]
+fn reserve_lines_option_makes_room_before_first_frame() {
+    let view = View::new(DisplayModel("first"), capture_options().reserve_lines(3));
+    let output = view.captured_output();
+    view.update(|_| ());
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\n\n\x1b[2F\x1b[?7l\x1b[0Jfirst"
+    );
+}
+
+#[test]
+fn larger_of_reserve_lines_and_max_height_is_used() {
+    let view = View::new(Growing { lines: 1 }, capture_options().reserve_lines(3));
+    let output = view.captured_output();
+    view.update(|_| ());
+    assert!(output.lock().unwrap().starts_with("\n\n\n\n\x1b[4F"));
+
+    // Reserved space is limited to the height of the terminal.
+    let view = View::new(DisplayModel("tall"), capture_options().reserve_lines(1000));
+    let output = view.captured_output();
+    view.update(|_| ());
+    let expected = format!("{}\x1b[23F", "\n".repeat(23));
+    assert!(output.lock().unwrap().starts_with(&expected));
+}
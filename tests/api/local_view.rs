@@ -0,0 +1,58 @@
+//! Test `LocalView`, for single-threaded programs.
+
+use std::cell::Cell;
+use std::rc::Rc;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{LocalView, Model, ProgressSink};
+
+use crate::capture_options;
+
+/// A model that can't be sent between threads.
+struct Shared(Rc<Cell<usize>>);
+
+impl Model for Shared {
+    fn render(&mut self, _width: usize) -> String {
+        format!("count {}", self.0.get())
+    }
+
+    fn final_message(&mut self) -> String {
+        format!("counted {}", self.0.get())
+    }
+}
+
+#[test]
+fn model_need_not_be_send() {
+    let count = Rc::new(Cell::new(0));
+    let view = LocalView::new(Shared(Rc::clone(&count)), capture_options());
+    let output = view.captured_output();
+    count.set(3);
+    view.update(|_| ());
+    assert!(output.lock().unwrap().ends_with("count 3"), "{output:?}");
+    view.message("hello\n");
+    view.finish();
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[?7l\x1b[0Jcount 3\x1b[1G\x1b[0J\x1b[?7hhello\ncounted 3\n"
+    );
+}
+
+#[test]
+fn works_as_a_progress_sink() {
+    fn count(progress: &impl ProgressSink<DisplayModel<usize>>) {
+        for _ in 0..5 {
+            progress.update(|model| model.0 += 1);
+        }
+    }
+    let view = LocalView::new(DisplayModel(0), capture_options());
+    count(&view);
+    assert_eq!(view.inspect_model(|model| model.0), 5);
+    view.abandon();
+}
+
+#[test]
+#[should_panic(expected = "nutmeg::LocalView::update called reentrantly")]
+fn reentrant_update_panics() {
+    let view = LocalView::new(DisplayModel(0), capture_options());
+    view.update(|_| view.update(|model| model.0 += 1));
+}
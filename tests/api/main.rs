@@ -6,9 +6,98 @@ use std::io::Write;
 use std::thread::sleep;
 use std::time::{Duration, Instant};
 
-use nutmeg::{Destination, Options, View};
+use nutmeg::{ControlDialect, Destination, DropPolicy, Options, View};
 
+mod abbreviate_path;
+mod adapters;
+mod adjust_total;
+mod also_emit;
+mod announce;
+mod bytes_model;
+mod callback;
+mod cancellation;
+mod capture_bounded;
+mod capture_markers;
+mod captured_events;
+mod clock;
+mod counter;
+mod cursor_park;
+mod custom_destination;
+mod emergency_restore;
+mod eta_clock_time;
+mod fallback_interval;
+mod final_message;
+mod finish_linger;
+mod formatter;
+mod freeze_line;
+mod gallery;
+mod group;
+#[cfg(feature = "http")]
+mod http_reporter;
 mod identical_output_suppressed;
+mod invalidate;
+mod items_and_bytes;
+mod json_summary;
+mod line_count;
+mod local_view;
+mod marquee;
+mod message_filter;
+mod micro_bar;
+mod min_width;
+mod model_contract;
+mod model_getters;
+mod model_guard;
+mod monotonic_percent;
+mod multi_view;
+mod named_capture;
+mod new_lazy;
+mod null_view;
+mod options_warnings;
+mod pace;
+mod paint_on_create;
+mod paint_raw;
+mod partial_redraw;
+mod pause_eta;
+mod plural;
+mod preset;
+mod reentrant_message;
+mod render_budget;
+mod replace_options;
+mod resize;
+mod scan_then_work;
+mod signal_cleanup;
+mod skipped_frames;
+mod snapshot_model;
+mod spinner;
+mod stress_widths;
+mod strip_escapes;
+mod subscribe;
+mod sync_view;
+mod synchronized_output;
+mod tasks;
+mod terminal_context;
+mod terminal_guard;
+mod theme;
+mod throughput_probe;
+mod ticker;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
+mod truncate_middle;
+mod try_update;
+mod unicode_truncation;
+mod update_keyed;
+mod validate_render;
+mod write_latency;
+mod writer_destination;
+
+/// Options for a view that captures its output, and paints on every update
+/// without holding off after messages, so that tests see every frame.
+fn capture_options() -> Options {
+    Options::default()
+        .destination(Destination::Capture)
+        .update_interval(Duration::ZERO)
+        .print_holdoff(Duration::ZERO)
+}
 
 struct MultiLineModel {
     i: usize,
@@ -20,7 +109,8 @@ static _SOME_OPTIONS: Options = Options::new()
     .print_holdoff(Duration::from_millis(20))
     .destination(Destination::Stderr)
     .fake_clock(false)
-    .progress_enabled(true);
+    .progress_enabled(true)
+    .toggle_line_wrap(true);
 
 // Just the default options are also OK.
 static _DEFAULT_OPTIONS: Options = Options::new();
@@ -221,3 +311,130 @@ fn rate_limiting_with_fake_clock() {
         \x1b[1G\x1b[0J\x1b[?7h"
     );
 }
+
+#[test]
+fn truncation_does_not_split_escape_sequences() {
+    struct Model;
+    impl nutmeg::Model for Model {
+        fn render(&mut self, _width: usize) -> String {
+            format!("{}\x1b[31mred\x1b[0m", "x".repeat(78))
+        }
+    }
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .toggle_line_wrap(false);
+    let view = View::new(Model, options);
+    let output = view.captured_output();
+
+    view.update(|_model| ());
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        format!("\x1b[0J{}\x1b[31mre\x1b[0m\x1b[1G\x1b[0J", "x".repeat(78))
+    );
+}
+
+#[test]
+fn without_line_wrap_toggle_lines_are_truncated() {
+    struct Model;
+    impl nutmeg::Model for Model {
+        fn render(&mut self, _width: usize) -> String {
+            format!("{}\nshort", "x".repeat(100))
+        }
+    }
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .toggle_line_wrap(false);
+    let view = View::new(Model, options);
+    let output = view.captured_output();
+
+    view.update(|_model| ());
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        format!("\x1b[0J{}\nshort\x1b[1F\x1b[0J", "x".repeat(80))
+    );
+}
+
+#[test]
+fn vt100_dialect() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .update_interval(Duration::ZERO)
+        .control_dialect(ControlDialect::VT100);
+    let view = View::new(MultiLineModel { i: 0 }, options);
+    let output = view.captured_output();
+
+    view.update(|model| model.i = 1);
+    view.update(|model| model.i = 2);
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0J  count: 1\n    bar: *\
+        \x1b[1A\r\x1b[?7l\x1b[0J  count: 2\n    bar: **\
+        \x1b[1A\r\x1b[0J\x1b[?7h"
+    );
+}
+
+#[test]
+fn windows_dialect_does_not_toggle_line_wrap() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .control_dialect(ControlDialect::WINDOWS);
+    let view = View::new(MultiLineModel { i: 0 }, options);
+    let output = view.captured_output();
+
+    view.update(|model| model.i = 1);
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[0J  count: 1\n    bar: *\x1b[1A\r\x1b[0J"
+    );
+}
+
+#[test]
+fn drop_policy_abandon_leaves_bar() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .on_drop(DropPolicy::Abandon);
+    let view = View::new(MultiLineModel { i: 0 }, options);
+    let output = view.captured_output();
+
+    view.update(|model| model.i = 1);
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0J  count: 1\n    bar: *\n"
+    );
+}
+
+#[test]
+fn drop_policy_erase_omits_final_message() {
+    struct Model;
+    impl nutmeg::Model for Model {
+        fn render(&mut self, _width: usize) -> String {
+            "working".to_owned()
+        }
+        fn final_message(&mut self) -> String {
+            "done".to_owned()
+        }
+    }
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .on_drop(DropPolicy::Erase);
+    let view = View::new(Model, options);
+    let output = view.captured_output();
+
+    view.update(|_| ());
+    drop(view);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jworking\x1b[1G\x1b[0J\x1b[?7h"
+    );
+}
@@ -0,0 +1,53 @@
+//! Test scrolling text.
+
+use nutmeg::Marquee;
+
+#[test]
+fn short_text_is_unchanged() {
+    let mut marquee = Marquee::new("short");
+    for _ in 0..100 {
+        assert_eq!(marquee.render(5), "short");
+    }
+}
+
+#[test]
+fn long_text_scrolls_and_restarts() {
+    let mut marquee = Marquee::new("abcdefgh");
+    let frames: Vec<String> = (0..(2 * Marquee::PAUSE_FRAMES + 4) * 2)
+        .map(|_| marquee.render(4))
+        .collect();
+    let period = 4 + 2 * Marquee::PAUSE_FRAMES;
+    assert!(frames[..Marquee::PAUSE_FRAMES].iter().all(|f| f == "abcd"));
+    assert_eq!(frames[Marquee::PAUSE_FRAMES + 1], "bcde");
+    assert_eq!(frames[Marquee::PAUSE_FRAMES + 4], "efgh");
+    assert_eq!(frames[period - 1], "efgh");
+    assert_eq!(frames[period], "abcd");
+    assert!(frames.iter().all(|f| f.chars().count() == 4));
+}
+
+#[test]
+fn set_text_restarts_only_when_changed() {
+    let mut marquee = Marquee::new("abcdefgh");
+    marquee.advance(Marquee::PAUSE_FRAMES + 2);
+    marquee.set_text("abcdefgh");
+    assert_eq!(marquee.render(4), "cdef");
+    marquee.set_text("12345678");
+    assert_eq!(marquee.render(4), "1234");
+    assert_eq!(marquee.text(), "12345678");
+}
+
+#[test]
+fn wide_characters_take_two_columns() {
+    let mut marquee = Marquee::new("日本語のテキスト");
+    assert_eq!(marquee.render(16), "日本語のテキスト");
+    assert_eq!(marquee.render(7), "日本語");
+    marquee.advance(Marquee::PAUSE_FRAMES);
+    assert_eq!(marquee.render(7), "本語の");
+    // The end is visible after scrolling past five characters, leaving one
+    // column unused since the next character is two wide.
+    marquee.advance(3);
+    assert_eq!(marquee.render(7), "キスト");
+    marquee.advance(Marquee::PAUSE_FRAMES - 2);
+    assert_eq!(marquee.render(7), "キスト");
+    assert_eq!(marquee.render(7), "日本語");
+}
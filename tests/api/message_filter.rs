@@ -0,0 +1,105 @@
+//! Test transforming and dropping messages with `Options::message_filters`.
+
+use std::io::Write;
+
+use nutmeg::models::{DisplayModel, LinearModel};
+use nutmeg::{
+    CapturedEventKind, Coalesce, MessageContext, MessageFilter, MessageLevel, MinLevel, Prefix,
+    View,
+};
+
+use crate::capture_options;
+
+#[test]
+fn prefix_is_added_to_each_line_of_partial_writes() {
+    let view = View::new(
+        DisplayModel(""),
+        capture_options().message_filters(&[&Prefix("> ")]),
+    );
+    let output = view.captured_output();
+    let mut writer = &view;
+    write!(writer, "one").unwrap();
+    write!(writer, " more\ntwo\nthr").unwrap();
+    writeln!(writer, "ee").unwrap();
+    view.abandon();
+    assert_eq!(*output.lock().unwrap(), "> one more\n> two\n> three\n");
+}
+
+#[test]
+fn final_message_is_filtered() {
+    let mut model = LinearModel::new("Copying", 1);
+    model.set_final_message("Copied {done:file}");
+    let view = View::new(
+        model,
+        capture_options().message_filters(&[&Prefix("[app] ")]),
+    );
+    let output = view.captured_output();
+    view.update(|model| model.increment(1));
+    view.finish();
+    let output = output.lock().unwrap();
+    assert!(output.ends_with("[app] Copied 1 file\n"), "{output:?}");
+}
+
+#[test]
+fn filters_run_in_order_and_stop_when_one_drops_the_message() {
+    static COALESCE: Coalesce = Coalesce::new();
+    static FILTERS: [&dyn MessageFilter; 3] =
+        [&MinLevel(MessageLevel::Info), &COALESCE, &Prefix("* ")];
+    let view = View::new(
+        DisplayModel(""),
+        capture_options().message_filters(&FILTERS),
+    );
+    let output = view.captured_output();
+    view.message_at(MessageLevel::Info, "waiting\n");
+    // Dropped by the level filter, so the coalescing filter doesn't see it.
+    view.message_at(MessageLevel::Debug, "polling\n");
+    view.message_at(MessageLevel::Info, "waiting\n");
+    view.message("ready\n");
+    view.abandon();
+    assert_eq!(*output.lock().unwrap(), "* waiting\n* ready\n");
+}
+
+#[test]
+fn dropped_message_leaves_the_bar_drawn() {
+    let view = View::new(
+        DisplayModel("working"),
+        capture_options().message_filters(&[&MinLevel(MessageLevel::Warn)]),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    output.lock().unwrap().clear();
+    view.message_at(MessageLevel::Info, "noise\n");
+    assert_eq!(*output.lock().unwrap(), "");
+    view.abandon();
+}
+
+#[test]
+fn events_record_the_filtered_message() {
+    /// Replace secrets before they're printed.
+    #[derive(Debug)]
+    struct Redact;
+
+    impl MessageFilter for Redact {
+        fn filter(&self, message: String, _context: &MessageContext) -> Option<String> {
+            Some(message.replace("hunter2", "*******"))
+        }
+    }
+
+    let view = View::new(
+        DisplayModel(""),
+        capture_options().message_filters(&[&Redact]),
+    );
+    let events = view.captured_events();
+    view.message("password hunter2\n");
+    view.abandon();
+    let messages: Vec<_> = events
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match &event.kind {
+            CapturedEventKind::Message(message) => Some(message.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(messages, ["password *******\n"]);
+}
@@ -0,0 +1,29 @@
+//! Test small fixed-width bars drawn by `micro_bar`.
+
+use nutmeg::micro_bar;
+use unicode_width::UnicodeWidthStr;
+
+#[test]
+fn bar_is_always_the_requested_width() {
+    for width in 0..12 {
+        for i in 0..=100 {
+            let bar = micro_bar(i as f64 / 100.0, width);
+            assert_eq!(bar.width(), width, "{bar:?}");
+            assert_eq!(bar.chars().count(), width, "{bar:?}");
+        }
+    }
+}
+
+#[test]
+fn partial_cells_use_eighth_blocks() {
+    assert_eq!(micro_bar(0.25, 2), "▌ ");
+    assert_eq!(micro_bar(1.0 / 80.0, 10), "▏         ");
+    assert_eq!(micro_bar(7.0 / 8.0, 1), "▉");
+}
+
+#[test]
+fn out_of_range_fractions_are_clamped() {
+    assert_eq!(micro_bar(-1.0, 3), "   ");
+    assert_eq!(micro_bar(2.0, 3), "███");
+    assert_eq!(micro_bar(f64::NAN, 3), "   ");
+}
@@ -0,0 +1,78 @@
+//! Test models that degrade on narrow terminals.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use nutmeg::{DestinationTrait, Options, View};
+
+struct Copy {
+    percent: usize,
+}
+
+impl nutmeg::Model for Copy {
+    fn render(&mut self, _width: usize) -> String {
+        format!("Copying file: {}%", self.percent)
+    }
+
+    fn min_width(&self) -> usize {
+        20
+    }
+
+    fn render_degraded(&mut self, _width: usize) -> String {
+        format!("{}%", self.percent)
+    }
+}
+
+#[derive(Debug)]
+struct Sink {
+    width: usize,
+    output: Arc<Mutex<String>>,
+}
+
+impl DestinationTrait for Sink {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(self.width)
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.output.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn draw_at_width(width: usize) -> String {
+    let output = Arc::new(Mutex::new(String::new()));
+    let sink = Sink {
+        width,
+        output: Arc::clone(&output),
+    };
+    let view = View::with_destination(Copy { percent: 42 }, Options::default(), sink);
+    view.update(|_| ());
+    view.abandon();
+    let output = output.lock().unwrap().clone();
+    output
+}
+
+#[test]
+fn degraded_form_used_below_min_width() {
+    assert!(draw_at_width(10).ends_with("\x1b[0J42%\n"));
+    assert!(draw_at_width(19).ends_with("\x1b[0J42%\n"));
+}
+
+#[test]
+fn full_form_used_at_min_width() {
+    assert!(draw_at_width(20).ends_with("\x1b[0JCopying file: 42%\n"));
+}
+
+#[test]
+fn check_widths_uses_degraded_form() {
+    nutmeg::test_util::check_widths(&mut Copy { percent: 42 }, 3..=200);
+}
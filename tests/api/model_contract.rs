@@ -0,0 +1,53 @@
+//! Test the model contract checker.
+
+use nutmeg::test_util::{check_model_contract, model_contract_violations, ContractViolation};
+
+struct Good;
+
+impl nutmeg::Model for Good {
+    fn render(&mut self, _width: usize) -> String {
+        "ok\n".to_owned()
+    }
+}
+
+#[test]
+fn good_model_passes() {
+    check_model_contract(&mut Good);
+}
+
+struct Bad {
+    renders: usize,
+}
+
+impl nutmeg::Model for Bad {
+    fn render(&mut self, _width: usize) -> String {
+        self.renders += 1;
+        format!("{}\x1b[1A\n\n", self.renders)
+    }
+}
+
+#[test]
+fn bad_model_violations_are_listed() {
+    let violations = model_contract_violations(&mut Bad { renders: 0 });
+    assert_eq!(
+        violations[..3],
+        [
+            ContractViolation::Unstable {
+                width: 10,
+                first: "1\x1b[1A\n\n".to_owned(),
+                second: "2\x1b[1A\n\n".to_owned(),
+            },
+            ContractViolation::TrailingBlankLine { width: 10 },
+            ContractViolation::ForbiddenSequence {
+                width: 10,
+                sequence: "\x1b[1A".to_owned()
+            },
+        ]
+    );
+}
+
+#[test]
+#[should_panic(expected = "Bad breaks the Model contract:\n  at width 10: rendering twice")]
+fn check_panics_on_violation() {
+    check_model_contract(&mut Bad { renders: 0 });
+}
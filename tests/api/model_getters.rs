@@ -0,0 +1,69 @@
+//! Test inspecting the state of built-in models.
+
+use std::time::Instant;
+
+use nutmeg::models::{LinearModel, StringPair, UnboundedModel};
+use nutmeg::{Destination, Options, View};
+
+use crate::capture_options;
+
+#[test]
+fn string_pair_getters() {
+    let view = View::new(StringPair::new("Copying: ", ""), capture_options());
+    view.update(|model| model.set_suffix("a.txt"));
+    view.inspect_model(|model| {
+        assert_eq!(model.prefix(), "Copying: ");
+        assert_eq!(model.suffix(), "a.txt");
+    });
+}
+
+#[test]
+fn linear_model_getters() {
+    let before = Instant::now();
+    let view = View::new(LinearModel::new("Counting", 10), capture_options());
+    view.update(|model| model.increment(3));
+    view.inspect_model(|model| {
+        assert_eq!(model.message(), "Counting");
+        assert_eq!(model.done(), 3);
+        assert_eq!(model.total(), 10);
+        assert!(model.start_time().unwrap() >= before);
+    });
+}
+
+#[test]
+fn unbounded_model_getters() {
+    let before = Instant::now();
+    let view = View::new(UnboundedModel::new("Counting"), capture_options());
+    view.update(|model| model.increment(7));
+    view.inspect_model(|model| {
+        assert_eq!(model.message(), "Counting");
+        assert_eq!(model.done(), 7);
+        assert!(model.start_time().unwrap() >= before);
+    });
+}
+
+#[test]
+fn const_models_start_on_first_render() {
+    static VIEW: View<LinearModel> = View::new(
+        LinearModel::new_const("Counting", 10),
+        Options::new().destination(Destination::Capture),
+    );
+    assert_eq!(VIEW.inspect_model(|model| model.start_time()), None);
+    let before = Instant::now();
+    VIEW.update(|model| model.increment(1));
+    let start = VIEW.inspect_model(|model| model.start_time()).unwrap();
+    assert!(start >= before);
+    assert!(VIEW
+        .captured_output()
+        .lock()
+        .unwrap()
+        .contains("Counting: 1/10"));
+
+    let mut unbounded = UnboundedModel::new_const("Counting");
+    assert_eq!(unbounded.start_time(), None);
+    nutmeg::Model::render(&mut unbounded, 80);
+    assert!(unbounded.start_time().is_some());
+
+    const PAIR: StringPair = StringPair::new_const("Copying: ", "a.txt");
+    assert_eq!(PAIR.suffix(), "a.txt");
+}
@@ -0,0 +1,44 @@
+//! Test updating the model through a guard from `View::model_mut`.
+
+use nutmeg::models::LinearModel;
+use nutmeg::View;
+
+use crate::capture_options;
+
+#[test]
+fn dropping_the_guard_repaints() {
+    let view = View::new(LinearModel::new("Copying", 10), capture_options());
+    let output = view.captured_output();
+    {
+        let mut model = view.model_mut();
+        model.increment(3);
+        assert_eq!(model.done(), 3);
+        assert_eq!(*output.lock().unwrap(), "");
+    }
+    assert!(output.lock().unwrap().contains("3/10"), "{output:?}");
+    view.abandon();
+}
+
+#[test]
+fn messages_while_guard_is_held_are_printed_after_it_drops() {
+    let view = View::new(LinearModel::new("Copying", 10), capture_options());
+    let output = view.captured_output();
+    let mut model = view.model_mut();
+    model.increment(1);
+    view.message("copied a.txt\n");
+    assert_eq!(*output.lock().unwrap(), "");
+    drop(model);
+    assert!(
+        output.lock().unwrap().contains("copied a.txt\n"),
+        "{output:?}"
+    );
+    view.abandon();
+}
+
+#[test]
+#[should_panic(expected = "nutmeg::View::update called reentrantly")]
+fn update_while_guard_is_held_panics() {
+    let view = View::new(LinearModel::new("Copying", 10), capture_options());
+    let _model = view.model_mut();
+    view.update(|model| model.increment(1));
+}
@@ -0,0 +1,82 @@
+//! Test that models can be set to never show a lower percentage than before.
+
+use nutmeg::models::{BytesModel, LinearModel, ScanThenWork};
+use nutmeg::{Model, MonotonicPercent};
+
+#[test]
+fn percentage_is_held_and_then_absorbs_increased_total() {
+    let mut model = LinearModel::new("Files", 100);
+    model.set_monotonic_percent(true);
+    let mut shown = Vec::new();
+    for done in (0..=100).step_by(10) {
+        model.set_done(done);
+        if done == 40 {
+            model.adjust_total(100);
+        }
+        shown.push(model.fraction_done().unwrap());
+        model.render(80);
+    }
+    // After the total grows to 200 at 40 done, the fraction shown stays at
+    // 40% and then moves forward.
+    assert!(shown.windows(2).all(|w| w[0] <= w[1]), "{shown:?}");
+    model.set_done(200);
+    assert!(model.render(80).starts_with("Files: 200/200, 100.0%"));
+}
+
+#[test]
+fn percentage_goes_backwards_by_default() {
+    let mut model = LinearModel::new("Files", 100);
+    model.set_done(50);
+    assert!(model.render(80).starts_with("Files: 50/100, 50.0%"));
+    model.adjust_total(100);
+    assert!(model.render(80).starts_with("Files: 50/200, 25.0%"));
+}
+
+#[test]
+fn decreased_total_moves_forward_immediately() {
+    let mut percent = MonotonicPercent::new();
+    assert_eq!(percent.percent(50, 200), "25.0%");
+    assert_eq!(percent.percent(50, 100), "50.0%");
+    assert_eq!(percent.percent(60, 100), "60.0%");
+}
+
+#[test]
+fn repeated_increases_are_absorbed() {
+    let mut percent = MonotonicPercent::new();
+    assert_eq!(percent.percent(50, 100), "50.0%");
+    assert_eq!(percent.percent(60, 200), "50.0%");
+    assert_eq!(percent.percent(60, 400), "50.0%");
+    // Half the work remaining after the last increase is done.
+    assert_eq!(percent.percent(230, 400), "75.0%");
+    assert_eq!(percent.percent(400, 400), "100.0%");
+}
+
+#[test]
+fn peek_does_not_remember() {
+    let mut percent = MonotonicPercent::new();
+    assert_eq!(percent.peek_fraction(50, 100), Some(0.5));
+    assert_eq!(percent.peek_fraction(50, 200), Some(0.25));
+    assert_eq!(percent.fraction(50, 100), Some(0.5));
+    assert_eq!(percent.peek_fraction(50, 200), Some(0.5));
+    assert_eq!(percent.peek_fraction(0, 0), None);
+}
+
+#[test]
+fn other_percentage_models_are_monotonic() {
+    let mut bytes = BytesModel::new("Downloading");
+    bytes.set_monotonic_percent(true);
+    bytes.set_total(1000);
+    bytes.set_done(500);
+    assert!(bytes.render(80).contains(", 50.0%, "));
+    bytes.set_total(2000);
+    assert!(bytes.render(80).contains(", 50.0%, "));
+
+    let mut scan = ScanThenWork::new("Scanning", "Copying");
+    scan.set_monotonic_percent(true);
+    scan.increment_found(10);
+    scan.start_work();
+    scan.set_done(5);
+    assert!(scan.render(80).starts_with("Copying: 5/10, 50.0%"));
+    scan.increment_found(10);
+    assert!(scan.render(80).starts_with("Copying: 5/20, 50.0%"));
+}
@@ -0,0 +1,101 @@
+//! Test several regions stacked in one `MultiView`.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Model, MultiView};
+
+use crate::capture_options;
+
+/// A model that prints a final message naming itself.
+struct Job(&'static str);
+
+impl Model for Job {
+    fn render(&mut self, _width: usize) -> String {
+        format!("{} running", self.0)
+    }
+
+    fn final_message(&mut self) -> String {
+        format!("{} done", self.0)
+    }
+}
+
+#[test]
+fn regions_are_stacked_in_order_added() {
+    let multi = MultiView::new(capture_options());
+    let output = multi.captured_output();
+    let first = multi.add(DisplayModel("first"));
+    let _second = multi.add(DisplayModel("second"));
+    assert_eq!(multi.region_count(), 2);
+    output.lock().unwrap().clear();
+
+    first.update(|model| model.0 = "first again");
+    let output = output.lock().unwrap().clone();
+    assert!(output.ends_with("first again\nsecond"), "{output:?}");
+}
+
+#[test]
+fn finished_region_prints_final_message_above_others() {
+    let multi = MultiView::new(capture_options());
+    let output = multi.captured_output();
+    let build = multi.add(Job("build"));
+    let _test = multi.add(Job("test"));
+    output.lock().unwrap().clear();
+
+    let job = build.finish();
+    assert_eq!(job.0, "build");
+    assert_eq!(multi.region_count(), 1);
+    let output = output.lock().unwrap().clone();
+    assert!(output.contains("build done\n"), "{output:?}");
+    assert!(output.ends_with("test running"), "{output:?}");
+}
+
+#[test]
+fn dropped_region_is_removed() {
+    let multi = MultiView::new(capture_options());
+    let output = multi.captured_output();
+    {
+        let _region = multi.add(Job("fetch"));
+        assert_eq!(multi.region_count(), 1);
+    }
+    assert_eq!(multi.region_count(), 0);
+    assert!(output.lock().unwrap().contains("fetch done\n"));
+}
+
+#[test]
+fn finish_prints_remaining_final_messages() {
+    let multi = MultiView::new(capture_options());
+    let output = multi.captured_output();
+    let a = multi.add(Job("a"));
+    let b = multi.add(Job("b"));
+    std::mem::forget((a, b));
+    multi.finish();
+    let output = output.lock().unwrap().clone();
+    assert!(output.ends_with("a done\nb done\n"), "{output:?}");
+}
+
+#[test]
+fn regions_update_from_scoped_threads() {
+    let multi = MultiView::new(capture_options());
+    std::thread::scope(|scope| {
+        for i in 0..4 {
+            let multi = &multi;
+            scope.spawn(move || {
+                let region = multi.add(DisplayModel(0));
+                for j in 0..100 {
+                    region.update(|model| model.0 = i * 100 + j);
+                }
+            });
+        }
+    });
+    assert_eq!(multi.region_count(), 0);
+}
+
+#[test]
+fn region_dropped_while_unwinding_is_left_alone() {
+    let multi = MultiView::new(capture_options());
+    let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        let region = multi.add(Job("crash"));
+        // This poisons the view, so removing the region would panic again.
+        region.update(|_| panic!("update failed"));
+    }));
+    assert!(result.is_err());
+}
@@ -0,0 +1,41 @@
+//! Test capturing output from several views by name.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn views_capture_into_separate_named_buffers() {
+    for (name, message) in [
+        ("named_capture/worker-1", "one\n"),
+        ("named_capture/worker-2", "two\n"),
+    ] {
+        let view = View::new(
+            DisplayModel("working"),
+            Options::default().destination(Destination::capture_named(name)),
+        );
+        view.message(message);
+    }
+    // Another view with the same name appends to the same buffer.
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default().destination(Destination::capture_named("named_capture/worker-1")),
+    );
+    view.message("again\n");
+    assert_eq!(
+        view.captured_output().lock().unwrap().as_str(),
+        "one\nagain\n"
+    );
+    drop(view);
+
+    assert_eq!(
+        Destination::capture_names("named_capture/"),
+        ["named_capture/worker-1", "named_capture/worker-2"]
+    );
+    assert_eq!(
+        Destination::captured_output_named("named_capture/worker-2")
+            .lock()
+            .unwrap()
+            .as_str(),
+        "two\n"
+    );
+}
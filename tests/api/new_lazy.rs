@@ -0,0 +1,47 @@
+//! Test views whose model is constructed on first use.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use nutmeg::models::LinearModel;
+use nutmeg::{Destination, Options, View};
+
+static CONSTRUCTED: AtomicUsize = AtomicUsize::new(0);
+
+fn make_model() -> LinearModel {
+    CONSTRUCTED.fetch_add(1, Ordering::SeqCst);
+    LinearModel::new(format!("Counting {}", "sheep"), 10)
+}
+
+static VIEW: View<LinearModel> =
+    View::new_lazy(make_model, Options::new().destination(Destination::Capture));
+
+#[test]
+fn static_lazy_view_constructs_model_once_on_first_use() {
+    assert_eq!(CONSTRUCTED.load(Ordering::SeqCst), 0);
+    VIEW.update(|model| model.increment(3));
+    VIEW.update(|model| model.increment(2));
+    assert_eq!(CONSTRUCTED.load(Ordering::SeqCst), 1);
+    assert_eq!(VIEW.inspect_model(|model| model.done()), 5);
+    assert_eq!(
+        VIEW.inspect_model(|model| model.message().to_owned()),
+        "Counting sheep"
+    );
+}
+
+#[test]
+fn unused_lazy_view_can_be_dropped() {
+    let view: View<LinearModel> = View::new_lazy(
+        || panic!("model should not be constructed"),
+        Options::new().destination(Destination::Capture),
+    );
+    drop(view);
+}
+
+#[test]
+fn lazy_view_can_be_finished_before_use() {
+    let view = View::new_lazy(
+        || LinearModel::new("Counting", 10),
+        Options::new().destination(Destination::Capture),
+    );
+    assert_eq!(view.abandon().done(), 0);
+}
@@ -0,0 +1,28 @@
+//! Test the view that never draws.
+
+use nutmeg::models::LinearModel;
+use nutmeg::{Destination, NullView, Options, ProgressSink, View};
+
+fn count_to_ten(progress: &impl ProgressSink<LinearModel>) -> usize {
+    for _ in 0..10 {
+        progress.update(|model| model.increment(1));
+    }
+    progress.inspect_model(|model| model.done())
+}
+
+#[test]
+fn null_view_updates_model() {
+    let view = NullView::new(LinearModel::new("Counting", 10));
+    assert_eq!(count_to_ten(&view), 10);
+    assert_eq!(view.finish().done(), 10);
+}
+
+#[test]
+fn view_and_null_view_are_interchangeable() {
+    let view = View::new(
+        LinearModel::new("Counting", 10),
+        Options::default().destination(Destination::Capture),
+    );
+    assert_eq!(count_to_ten(&view), 10);
+    assert_eq!(view.abandon().done(), 10);
+}
@@ -0,0 +1,162 @@
+//! Test warnings about combinations of options, and strict mode.
+
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{
+    CapturedEventKind, Destination, Error, FallbackInterval, Options, OptionsWarning, Pace, View,
+};
+
+fn warnings(view: &View<DisplayModel<&'static str>>) -> Vec<OptionsWarning> {
+    view.captured_events()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match event.kind {
+            CapturedEventKind::OptionsWarning(warning) => Some(warning),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn default_options_have_no_warnings() {
+    assert_eq!(Options::default().warnings(), []);
+    assert!(Options::default().strict(true).validate().is_ok());
+}
+
+#[test]
+fn conflicting_options_are_listed() {
+    let options = Options::default()
+        .progress_enabled(false)
+        .paint_on_create(true)
+        .fallback_interval(FallbackInterval::Percent(0))
+        .capture_markers(true);
+    assert_eq!(
+        options.warnings(),
+        [
+            OptionsWarning::PaintOnCreateWithoutProgress,
+            OptionsWarning::CaptureMarkersWithoutCapture,
+            OptionsWarning::FallbackPercentZero,
+        ]
+    );
+    // Not strict, so these are only warnings.
+    assert!(options.validate().is_ok());
+}
+
+#[test]
+fn warnings_are_sent_as_events_when_the_view_is_first_used() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .progress_enabled(false)
+        .paint_on_create(true);
+    let view = View::new(DisplayModel("working"), options);
+    view.update(|_| ());
+    view.update(|_| ());
+    assert_eq!(
+        warnings(&view),
+        [OptionsWarning::PaintOnCreateWithoutProgress]
+    );
+}
+
+#[test]
+fn ticker_with_fake_clock_is_warned() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .fake_clock(true);
+    let view = View::new(DisplayModel("working"), options);
+    view.with_ticker(|| ());
+    assert_eq!(warnings(&view), [OptionsWarning::TickerWithFakeClock]);
+}
+
+#[test]
+fn tick_interval_without_ticker_is_warned_when_finished() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .tick_interval(Duration::from_millis(100));
+    let view = View::new(DisplayModel("working"), options);
+    let events = view.captured_events();
+    view.update(|_| ());
+    assert_eq!(warnings(&view), []);
+    view.finish();
+    assert!(events.lock().unwrap().iter().any(|event| event.kind
+        == CapturedEventKind::OptionsWarning(OptionsWarning::TickIntervalWithoutTicker)));
+}
+
+#[test]
+fn tick_interval_with_ticker_is_not_warned() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .tick_interval(Duration::from_millis(10));
+    let view = View::new(DisplayModel("working"), options);
+    let events = view.captured_events();
+    view.with_ticker(|| view.update(|_| ()));
+    view.abandon();
+    assert!(!events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|event| matches!(event.kind, CapturedEventKind::OptionsWarning(_))));
+}
+
+#[test]
+fn tick_interval_from_pace_is_not_warned() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .pace(Pace::Bulk);
+    let view = View::new(DisplayModel("working"), options);
+    let events = view.captured_events();
+    view.update(|_| ());
+    view.finish();
+    assert!(!events
+        .lock()
+        .unwrap()
+        .iter()
+        .any(|event| matches!(event.kind, CapturedEventKind::OptionsWarning(_))));
+}
+
+#[test]
+fn strict_view_returns_errors_from_try_methods() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .progress_enabled(false)
+        .paint_on_create(true)
+        .strict(true);
+    let view = View::new(DisplayModel("working"), options);
+    let err = view.try_update(|_| ()).unwrap_err();
+    assert!(
+        matches!(&err, Error::InvalidOptions(w) if w == &[OptionsWarning::PaintOnCreateWithoutProgress])
+    );
+    assert_eq!(
+        err.to_string(),
+        "invalid progress options: paint_on_create has no effect when progress is disabled"
+    );
+    assert!(view.try_message("hello\n").is_err());
+}
+
+#[test]
+#[should_panic(expected = "invalid progress options")]
+fn strict_view_panics_from_other_methods() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .progress_enabled(false)
+        .paint_on_create(true)
+        .strict(true);
+    let view = View::new(DisplayModel("working"), options);
+    view.update(|_| ());
+}
+
+#[test]
+#[should_panic(expected = "paint_on_create has no effect")]
+fn strict_replacement_options_are_checked() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default().destination(Destination::Capture),
+    );
+    view.replace_options(
+        Options::default()
+            .progress_enabled(false)
+            .paint_on_create(true)
+            .strict(true),
+    );
+}
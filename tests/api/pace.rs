@@ -0,0 +1,50 @@
+//! Test setting the repaint intervals from a pace preset.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, Pace, View};
+
+#[test]
+fn pace_sets_intervals() {
+    let view = View::new(
+        DisplayModel("working"),
+        Options::default()
+            .destination(Destination::Capture)
+            .pace(Pace::Bulk),
+    );
+    let stats = view.stats();
+    assert_eq!(stats.effective_update_interval, Duration::from_millis(250));
+    assert_eq!(stats.effective_print_holdoff, Duration::from_millis(200));
+
+    view.set_pace(Pace::Background);
+    let stats = view.stats();
+    assert_eq!(stats.effective_update_interval, Duration::from_secs(1));
+    assert_eq!(stats.effective_print_holdoff, Duration::from_millis(500));
+    view.abandon();
+}
+
+#[test]
+fn faster_pace_repaints_sooner() {
+    let view = View::new(
+        DisplayModel("first"),
+        Options::default()
+            .destination(Destination::Capture)
+            .fake_clock(true)
+            .pace(Pace::Background),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    let output = view.captured_output();
+    output.lock().unwrap().clear();
+
+    view.set_fake_clock(start + Duration::from_millis(100));
+    view.update(|model| model.0 = "second");
+    assert_eq!(*output.lock().unwrap(), "");
+
+    view.set_pace(Pace::Interactive);
+    view.update(|model| model.0 = "third");
+    assert!(output.lock().unwrap().ends_with("third"), "{output:?}");
+    view.abandon();
+}
@@ -0,0 +1,42 @@
+//! Test drawing the initial model on construction with `Options::paint_on_create`.
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+use crate::capture_options;
+
+fn options() -> Options {
+    capture_options().paint_on_create(true)
+}
+
+#[test]
+fn start_paints_the_initial_model() {
+    let view = View::start(DisplayModel("Resolving"), options());
+    let output = view.captured_output();
+    assert_eq!(*output.lock().unwrap(), "\x1b[?7l\x1b[0JResolving");
+    view.abandon();
+}
+
+#[test]
+fn start_without_the_option_paints_nothing() {
+    let view = View::start(
+        DisplayModel("Resolving"),
+        Options::default().destination(Destination::Capture),
+    );
+    assert_eq!(*view.captured_output().lock().unwrap(), "");
+    view.abandon();
+}
+
+#[test]
+fn nothing_is_painted_when_progress_is_disabled() {
+    let view = View::start(DisplayModel("Resolving"), options().progress_enabled(false));
+    assert_eq!(*view.captured_output().lock().unwrap(), "");
+    view.abandon();
+}
+
+#[test]
+fn new_ignores_paint_on_create() {
+    let view = View::new(DisplayModel("Resolving"), options());
+    assert_eq!(*view.captured_output().lock().unwrap(), "");
+    view.abandon();
+}
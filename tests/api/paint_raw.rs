@@ -0,0 +1,65 @@
+//! Test drawing frames supplied by the application with `View::paint_raw`.
+
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+fn view() -> View<DisplayModel<&'static str>> {
+    View::new(
+        DisplayModel("from the model"),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .print_holdoff(Duration::ZERO),
+    )
+}
+
+#[test]
+fn raw_frame_is_drawn_and_erased_around_messages() {
+    let view = view();
+    let output = view.captured_output();
+    view.paint_raw("raw 1\nraw 2\n");
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jraw 1\nraw 2"
+    );
+    output.lock().unwrap().clear();
+
+    view.message("hello\n");
+    view.paint_raw("raw 3");
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1F\x1b[0J\x1b[?7hhello\n\x1b[?7l\x1b[0Jraw 3"
+    );
+    output.lock().unwrap().clear();
+
+    // Updating the model replaces the raw frame.
+    view.update(|_| ());
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[1G\x1b[?7l\x1b[0Jfrom the model"
+    );
+}
+
+#[test]
+fn raw_frame_is_not_drawn_while_suspended() {
+    let view = view();
+    let output = view.captured_output();
+    view.suspend();
+    view.paint_raw("hidden");
+    view.abandon();
+    assert_eq!(output.lock().unwrap().as_str(), "");
+}
+
+#[test]
+#[should_panic(expected = "View::paint_raw returned the control sequence")]
+fn raw_frames_are_validated() {
+    let view = View::new(
+        DisplayModel("unused"),
+        Options::default()
+            .destination(Destination::Capture)
+            .validate_render(true),
+    );
+    view.paint_raw("up\x1b[2A");
+}
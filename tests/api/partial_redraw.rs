@@ -0,0 +1,59 @@
+//! Test that only the changed lines of multi-line frames are redrawn.
+
+use std::time::Duration;
+
+use nutmeg::{Destination, Options, View};
+
+struct Tasks(Vec<String>);
+
+impl nutmeg::Model for Tasks {
+    fn render(&mut self, _width: usize) -> String {
+        self.0.join("\n")
+    }
+}
+
+fn view() -> View<Tasks> {
+    View::new(
+        Tasks(vec!["a: 0".into(), "b: 0".into(), "c: 0".into()]),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO),
+    )
+}
+
+fn output_of_update(view: &View<Tasks>, f: impl FnOnce(&mut Tasks)) -> String {
+    let output = view.captured_output();
+    let before = output.lock().unwrap().len();
+    view.update(f);
+    let after = output.lock().unwrap()[before..].to_owned();
+    after
+}
+
+#[test]
+fn only_changed_lines_redrawn() {
+    let view = view();
+    assert_eq!(
+        output_of_update(&view, |_| ()),
+        "\x1b[?7l\x1b[0Ja: 0\nb: 0\nc: 0"
+    );
+    // Only the last line changed, so the cursor stays on it.
+    assert_eq!(
+        output_of_update(&view, |t| t.0[2] = "c: 1".into()),
+        "\x1b[1G\x1b[?7l\x1b[0Jc: 1"
+    );
+    // From the second line down.
+    assert_eq!(
+        output_of_update(&view, |t| t.0[1] = "b: 1".into()),
+        "\x1b[1F\x1b[?7l\x1b[0Jb: 1\nc: 1"
+    );
+    // Shorter frames clear the lines below.
+    assert_eq!(
+        output_of_update(&view, |t| t.0.truncate(2)),
+        "\x1b[1F\x1b[?7l\x1b[0Jb: 1"
+    );
+    // Longer frames are redrawn from the last line that was previously drawn.
+    assert_eq!(
+        output_of_update(&view, |t| t.0.push("d: 0".into())),
+        "\x1b[1G\x1b[?7l\x1b[0Jb: 1\nd: 0"
+    );
+}
@@ -0,0 +1,55 @@
+//! Test that time when the view was paused is left out of time estimates.
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use nutmeg::{paused_since, View};
+
+use crate::capture_options;
+
+/// Renders how long the view has been paused since it was constructed.
+struct Paused(Instant);
+
+impl nutmeg::Model for Paused {
+    fn render(&mut self, _width: usize) -> String {
+        format!("paused {}", paused_since(self.0).as_millis())
+    }
+}
+
+fn last_paused_ms(view: &View<Paused>) -> u128 {
+    let output = view.captured_output();
+    let output = output.lock().unwrap();
+    let (_, ms) = output.rsplit_once("paused ").unwrap();
+    ms.parse().unwrap()
+}
+
+#[test]
+fn suspension_is_excluded() {
+    let view = View::new(Paused(Instant::now()), capture_options());
+    view.update(|_| ());
+    assert_eq!(last_paused_ms(&view), 0);
+    view.suspend();
+    sleep(Duration::from_millis(100));
+    view.resume();
+    assert!(last_paused_ms(&view) >= 100);
+    // Outside rendering, there's no current view.
+    assert_eq!(paused_since(view.inspect_model(|m| m.0)), Duration::ZERO);
+}
+
+#[test]
+fn stalls_are_excluded_only_with_a_threshold() {
+    let view = View::new(
+        Paused(Instant::now()),
+        capture_options().stall_threshold(Duration::from_millis(50)),
+    );
+    view.update(|_| ());
+    sleep(Duration::from_millis(100));
+    view.update(|_| ());
+    assert!(last_paused_ms(&view) >= 100);
+
+    let view = View::new(Paused(Instant::now()), capture_options());
+    view.update(|_| ());
+    sleep(Duration::from_millis(100));
+    view.update(|_| ());
+    assert_eq!(last_paused_ms(&view), 0);
+}
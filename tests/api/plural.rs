@@ -0,0 +1,69 @@
+//! Test pluralization and templates for messages.
+
+use nutmeg::models::{LinearModel, UnboundedModel};
+use nutmeg::{fill_template, plural, Destination, Options, View};
+
+#[test]
+fn plural_nouns() {
+    assert_eq!(plural(1, "file"), "1 file");
+    assert_eq!(plural(0, "file"), "0 files");
+    assert_eq!(plural(2, "match"), "2 matches");
+    assert_eq!(plural(2, "box"), "2 boxes");
+    assert_eq!(plural(2, "directory"), "2 directories");
+    assert_eq!(plural(2, "key"), "2 keys");
+}
+
+#[test]
+fn template_placeholders() {
+    let counts = [("done", 2), ("total", 1)];
+    assert_eq!(fill_template("", &counts), "");
+    assert_eq!(fill_template("no placeholders", &counts), "no placeholders");
+    assert_eq!(
+        fill_template("{done:file} of {total}", &counts),
+        "2 files of 1"
+    );
+    assert_eq!(fill_template("{total:file}!", &counts), "1 file!");
+    assert_eq!(fill_template("{other} {done}", &counts), "{other} 2");
+    assert_eq!(fill_template("open {done", &counts), "open {done");
+}
+
+#[test]
+fn built_in_models_have_no_final_message_by_default() {
+    let view = View::new(
+        LinearModel::new("Copying", 1),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.finish();
+    assert_eq!(*output.lock().unwrap(), "");
+}
+
+#[test]
+fn linear_model_final_message_template() {
+    let view = View::new(
+        LinearModel::new("Copying", 3),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update(|model| {
+        model.set_final_message("Copied {done:file} of {total}");
+        model.increment(1);
+    });
+    let output = view.captured_output();
+    view.finish();
+    assert!(output.lock().unwrap().ends_with("Copied 1 file of 3\n"));
+}
+
+#[test]
+fn unbounded_model_final_message_template() {
+    let view = View::new(
+        UnboundedModel::new("Searching"),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update(|model| {
+        model.set_final_message("Found {done:match}");
+        model.increment(5);
+    });
+    let output = view.captured_output();
+    view.finish();
+    assert!(output.lock().unwrap().ends_with("Found 5 matches\n"));
+}
@@ -0,0 +1,72 @@
+//! Test option presets.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, Preset, View};
+
+#[test]
+fn test_harness_paints_every_update() {
+    let view = View::new(
+        DisplayModel("one"),
+        Options::default().preset(Preset::TestHarness),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.update(|model| model.0 = "two");
+    view.message("hello\n");
+    view.update(|_| ());
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[?7l\x1b[0Jone\x1b[1G\x1b[?7l\x1b[0Jtwo\
+        \x1b[1G\x1b[0J\x1b[?7hhello\n\x1b[?7l\x1b[0Jtwo"
+    );
+    drop(view);
+    // The view is abandoned when dropped, leaving the bar in place.
+    assert!(output.lock().unwrap().ends_with("two\n"));
+}
+
+#[test]
+fn daemon_never_draws_a_bar() {
+    let options = Options::default()
+        .preset(Preset::Daemon)
+        .destination(Destination::Capture);
+    let view = View::new(DisplayModel("working"), options);
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.message("started\n");
+    drop(view);
+    assert_eq!(*output.lock().unwrap(), "started\n");
+}
+
+#[test]
+fn daemon_prints_a_progress_line_every_minute_on_a_terminal() {
+    let options = Options::default()
+        .preset(Preset::Daemon)
+        .destination(Destination::Capture)
+        .fake_clock(true);
+    let view = View::new(DisplayModel("working"), options);
+    let output = view.captured_output();
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    view.set_fake_clock(start + Duration::from_secs(30));
+    view.update(|_| ());
+    assert_eq!(*output.lock().unwrap(), "");
+    view.set_fake_clock(start + Duration::from_secs(61));
+    view.update(|_| ());
+    assert_eq!(*output.lock().unwrap(), "working\n");
+    drop(view);
+    assert_eq!(*output.lock().unwrap(), "working\n");
+}
+
+#[test]
+fn later_options_override_the_preset() {
+    let options = Options::default()
+        .preset(Preset::TestHarness)
+        .progress_enabled(false);
+    let view = View::new(DisplayModel("working"), options);
+    let output = view.captured_output();
+    view.update(|_| ());
+    assert_eq!(*output.lock().unwrap(), "");
+}
@@ -0,0 +1,88 @@
+//! Test calls into a View from inside its own update or render.
+
+use std::time::Duration;
+
+use nutmeg::{Destination, Options, View};
+
+struct Count(usize);
+
+impl nutmeg::Model for Count {
+    fn render(&mut self, _width: usize) -> String {
+        format!("count={}", self.0)
+    }
+}
+
+#[test]
+fn message_from_update_is_deferred() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .update_interval(Duration::ZERO)
+        .print_holdoff(Duration::ZERO);
+    let view = View::new(Count(0), options);
+    let output = view.captured_output();
+
+    view.update(|model| {
+        model.0 = 1;
+        view.message("inside update\n");
+    });
+    view.abandon();
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jcount=1\x1b[1G\x1b[0J\x1b[?7hinside update\n"
+    );
+}
+
+static WARNING_VIEW: View<Warner> = View::new(
+    Warner(0),
+    Options::new()
+        .destination(Destination::Capture)
+        .update_interval(Duration::ZERO)
+        .print_holdoff(Duration::ZERO),
+);
+
+struct Warner(usize);
+
+impl nutmeg::Model for Warner {
+    fn render(&mut self, _width: usize) -> String {
+        WARNING_VIEW.message(format!("warning from render {}\n", self.0));
+        format!("count={}", self.0)
+    }
+}
+
+#[test]
+fn message_from_render_is_deferred() {
+    let output = WARNING_VIEW.captured_output();
+    WARNING_VIEW.update(|model| model.0 = 1);
+
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jcount=1\x1b[1G\x1b[0J\x1b[?7hwarning from render 1\n"
+    );
+}
+
+#[test]
+#[should_panic(expected = "nutmeg::View::update called reentrantly")]
+fn reentrant_update_panics() {
+    let view = View::new(
+        Count(0),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update(|_model| view.update(|model| model.0 += 1));
+}
+
+#[test]
+fn try_update_nonblocking_from_update_would_block() {
+    let view = View::new(
+        Count(0),
+        Options::default().destination(Destination::Capture),
+    );
+    let err = view
+        .update(|_model| view.try_update_nonblocking(|model| model.0 += 1))
+        .unwrap_err();
+    assert_eq!(err.kind(), std::io::ErrorKind::WouldBlock);
+    assert_eq!(view.inspect_model(|m| m.0), 0);
+
+    view.try_update_nonblocking(|model| model.0 += 1).unwrap();
+    assert_eq!(view.inspect_model(|m| m.0), 1);
+}
@@ -0,0 +1,85 @@
+//! Test limiting and reporting the time spent rendering.
+
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use nutmeg::{CapturedEventKind, Options, View};
+
+use crate::capture_options;
+
+struct Slow(usize);
+
+impl nutmeg::Model for Slow {
+    fn render(&mut self, _width: usize) -> String {
+        sleep(Duration::from_millis(5));
+        format!("count={}", self.0)
+    }
+}
+
+fn options() -> Options {
+    capture_options()
+        .fake_clock(true)
+        .update_interval(Duration::from_millis(10))
+}
+
+#[test]
+fn slow_render_lengthens_interval() {
+    let view = View::new(Slow(0), options().max_render_time_share(0.05));
+    let start = Instant::now();
+    for i in 0..10 {
+        view.set_fake_clock(start + Duration::from_millis(20 * i));
+        view.update(|model| model.0 += 1);
+    }
+    let stats = view.stats();
+    // Each frame takes at least 5ms, so they're drawn at most every 100ms.
+    assert!(stats.effective_update_interval >= Duration::from_millis(100));
+    assert!(stats.mean_render_time().unwrap() >= Duration::from_millis(5));
+    assert!(stats.frames <= 2, "{stats:?}");
+}
+
+#[test]
+fn interval_unchanged_without_budget() {
+    let view = View::new(Slow(0), options());
+    let start = Instant::now();
+    for i in 0..5 {
+        view.set_fake_clock(start + Duration::from_millis(20 * i));
+        view.update(|model| model.0 += 1);
+    }
+    let stats = view.stats();
+    assert_eq!(stats.effective_update_interval, Duration::from_millis(10));
+    assert_eq!(stats.frames, 5);
+    assert_eq!(stats.renders, 5);
+}
+
+#[test]
+fn slow_renders_reported() {
+    let view = View::new(
+        Slow(0),
+        options().slow_render_threshold(Duration::from_millis(1)),
+    );
+    let events = view.subscribe();
+    view.update(|model| model.0 += 1);
+    let stats = view.stats();
+    assert_eq!(stats.slow_renders, 1);
+    assert!(stats.slowest_render >= Duration::from_millis(5));
+    let slow = events
+        .try_iter()
+        .find_map(|e| match e.kind {
+            CapturedEventKind::SlowRender(d) => Some(d),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(slow, stats.slowest_render);
+}
+
+#[test]
+fn fast_renders_not_reported() {
+    let view = View::new(
+        Slow(0),
+        options().slow_render_threshold(Duration::from_secs(10)),
+    );
+    view.update(|model| model.0 += 1);
+    let stats = view.stats();
+    assert_eq!(stats.slow_renders, 0);
+    assert!(stats.slowest_render > Duration::ZERO);
+}
@@ -0,0 +1,65 @@
+//! Test replacing a view's options while it's running.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+use crate::capture_options;
+
+fn options() -> Options {
+    capture_options()
+        .fake_clock(true)
+        .update_interval(Duration::from_secs(1))
+}
+
+#[test]
+fn new_update_interval_takes_effect() {
+    let view = View::new(DisplayModel("first"), options());
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    let output = view.captured_output();
+    output.lock().unwrap().clear();
+
+    view.set_fake_clock(start + Duration::from_millis(100));
+    view.update(|model| model.0 = "second");
+    assert_eq!(*output.lock().unwrap(), "");
+
+    view.replace_options(options().update_interval(Duration::from_millis(50)));
+    assert_eq!(
+        view.stats().effective_update_interval,
+        Duration::from_millis(50)
+    );
+    view.set_fake_clock(start + Duration::from_millis(200));
+    view.update(|model| model.0 = "third");
+    assert!(output.lock().unwrap().ends_with("third"), "{output:?}");
+    view.abandon();
+}
+
+#[test]
+fn destination_is_kept() {
+    let view = View::new(DisplayModel("working"), options());
+    view.set_fake_clock(Instant::now());
+    view.update(|_| ());
+    let output = view.captured_output();
+    view.replace_options(options().destination(Destination::Stderr));
+    view.message("hello\n");
+    assert!(output.lock().unwrap().contains("hello\n"));
+    view.abandon();
+}
+
+#[test]
+fn disabling_progress_erases_the_bar() {
+    let view = View::new(DisplayModel("working"), options());
+    view.set_fake_clock(Instant::now());
+    view.update(|_| ());
+    let output = view.captured_output();
+    output.lock().unwrap().clear();
+    view.replace_options(options().progress_enabled(false));
+    assert_eq!(*output.lock().unwrap(), "\x1b[1G\x1b[0J\x1b[?7h");
+    output.lock().unwrap().clear();
+    view.update(|model| model.0 = "hidden");
+    assert_eq!(*output.lock().unwrap(), "");
+    view.abandon();
+}
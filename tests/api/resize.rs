@@ -0,0 +1,88 @@
+//! Test redrawing the progress bar after the terminal is resized.
+
+use std::io;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{DestinationTrait, Options, View};
+
+/// A terminal whose width can be changed by the test.
+#[derive(Debug, Clone, Default)]
+struct Resizable {
+    width: Arc<AtomicUsize>,
+    output: Arc<Mutex<String>>,
+}
+
+impl DestinationTrait for Resizable {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(self.width.load(Ordering::SeqCst))
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.output.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn view_of(frame: String, width: usize) -> (View<DisplayModel<String>, Resizable>, Resizable) {
+    let terminal = Resizable::default();
+    terminal.width.store(width, Ordering::SeqCst);
+    let view = View::with_destination(
+        DisplayModel(frame),
+        Options::default()
+            .update_interval(Duration::ZERO)
+            .print_holdoff(Duration::ZERO),
+        terminal.clone(),
+    );
+    view.update(|_| ());
+    terminal.output.lock().unwrap().clear();
+    (view, terminal)
+}
+
+#[test]
+fn narrower_terminal_erases_wrapped_lines_and_redraws() {
+    let (view, terminal) = view_of(format!("{}\nshort", "x".repeat(30)), 40);
+    terminal.width.store(10, Ordering::SeqCst);
+    // The frame is redrawn from the top of the first line, which the
+    // terminal may have wrapped onto three rows, and cut to the new width.
+    view.update(|_| ());
+    assert_eq!(
+        terminal.output.lock().unwrap().as_str(),
+        format!("\x1b[3F\x1b[?7l\x1b[0J{}\nshort", "x".repeat(10))
+    );
+}
+
+#[test]
+fn erase_after_narrowing_clears_wrapped_lines() {
+    let (view, terminal) = view_of(format!("{}\nshort", "x".repeat(30)), 40);
+    terminal.width.store(15, Ordering::SeqCst);
+    view.message("hello\n");
+    assert!(terminal
+        .output
+        .lock()
+        .unwrap()
+        .starts_with("\x1b[2F\x1b[0J"));
+}
+
+#[test]
+fn wider_terminal_redraws_only_changed_lines() {
+    let (view, terminal) = view_of("one\ntwo".to_owned(), 40);
+    terminal.width.store(80, Ordering::SeqCst);
+    view.update(|_| ());
+    assert_eq!(terminal.output.lock().unwrap().as_str(), "");
+    view.update(|model| model.0 = "one\nthree".to_owned());
+    assert_eq!(
+        terminal.output.lock().unwrap().as_str(),
+        "\x1b[1G\x1b[?7l\x1b[0Jthree"
+    );
+}
@@ -0,0 +1,38 @@
+//! Test the two-phase scan and work model.
+
+use nutmeg::models::ScanThenWork;
+use nutmeg::{Model, NullView};
+
+#[test]
+fn scanning_counts_found_items() {
+    let mut model = ScanThenWork::new("Scanning", "Copying");
+    model.increment_found(3);
+    model.increment_found(4);
+    assert!(model.is_scanning());
+    assert!(model.render(80).starts_with("Scanning: 7 found in 0:00"));
+    assert_eq!(model.fraction_done(), None);
+}
+
+#[test]
+fn work_phase_uses_found_count_as_total() {
+    let mut model = ScanThenWork::new("Scanning", "Copying");
+    model.increment_found(8);
+    model.start_work();
+    model.increment(2);
+    assert!(!model.is_scanning());
+    assert!(model.render(80).starts_with("Copying: 2/8, 25.0%, "));
+    assert!(model.render_compact(20).starts_with("25.0% "));
+    assert_eq!(model.fraction_done(), Some(0.25));
+}
+
+#[test]
+fn run_scans_everything_before_working() {
+    let view = NullView::new(ScanThenWork::new("Scanning", "Copying"));
+    let mut totals_seen = Vec::new();
+    ScanThenWork::run(&view, 0..5, |_| {
+        totals_seen.push(view.inspect_model(|m| (m.is_scanning(), m.total())))
+    });
+    assert_eq!(totals_seen, [(false, 5); 5]);
+    let model = view.finish();
+    assert_eq!((model.done(), model.total()), (5, 5));
+}
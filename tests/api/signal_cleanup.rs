@@ -0,0 +1,73 @@
+//! Test registering views for cleanup on signals with `View::install_signal_cleanup`.
+
+use std::sync::Mutex;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, View};
+
+/// Tests that count registrations hold this, since the slots are shared by
+/// the whole process.
+static SLOTS: Mutex<()> = Mutex::new(());
+
+fn captured_view(i: usize) -> View<DisplayModel<usize>> {
+    View::new(
+        DisplayModel(i),
+        Options::default().destination(Destination::Capture),
+    )
+}
+
+#[test]
+fn registrations_are_released_when_dropped() {
+    let _slots = SLOTS.lock().unwrap();
+    let views: Vec<_> = (0..20).map(captured_view).collect();
+    for _ in 0..3 {
+        let cleanups: Vec<_> = views
+            .iter()
+            .map(|view| view.install_signal_cleanup())
+            .collect();
+        let registered = cleanups.iter().filter(|c| c.is_registered()).count();
+        if cfg!(unix) {
+            assert_eq!(registered, 8);
+        } else {
+            assert_eq!(registered, 0);
+        }
+    }
+}
+
+/// A forgotten registration is released when the view is dropped, even if the
+/// view was moved in the meantime.
+#[test]
+fn forgotten_registration_is_released_when_view_is_dropped() {
+    let _slots = SLOTS.lock().unwrap();
+    for i in 0..20 {
+        let view = captured_view(i);
+        std::mem::forget(view.install_signal_cleanup());
+        let view = Box::new(view);
+        view.update(|model| model.0 += 1);
+        drop(view);
+    }
+    let view = captured_view(0);
+    assert_eq!(view.install_signal_cleanup().is_registered(), cfg!(unix));
+}
+
+/// The view keeps track of what's drawn while it's registered, and after it's
+/// unregistered.
+#[test]
+fn registered_view_is_drawn_as_usual() {
+    let _slots = SLOTS.lock().unwrap();
+    let view = View::new(
+        DisplayModel("hello"),
+        Options::default().destination(Destination::Capture),
+    );
+    let output = view.captured_output();
+    view.update(|_| ());
+    let cleanup = view.install_signal_cleanup();
+    view.emergency_restore();
+    view.update(|_| ());
+    drop(cleanup);
+    view.abandon();
+    assert_eq!(
+        output.lock().unwrap().as_str(),
+        "\x1b[?7l\x1b[0Jhello\x1b[?7l\x1b[0Jhello\n"
+    );
+}
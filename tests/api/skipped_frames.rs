@@ -0,0 +1,44 @@
+//! Test that models are told about frames skipped during pauses.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::{Destination, Options, View};
+
+#[derive(Default)]
+struct Animation {
+    frame: usize,
+    skipped: Vec<usize>,
+}
+
+impl nutmeg::Model for Animation {
+    fn render(&mut self, _width: usize) -> String {
+        self.frame += 1;
+        format!("frame {}", self.frame)
+    }
+
+    fn on_skipped_frames(&mut self, skipped: usize) {
+        self.skipped.push(skipped);
+        self.frame += skipped;
+    }
+}
+
+#[test]
+fn skipped_frames_reported_after_pause() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .fake_clock(true)
+        .update_interval(Duration::from_millis(100));
+    let view = View::new(Animation::default(), options);
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    // A redraw on time doesn't skip anything.
+    view.set_fake_clock(start + Duration::from_millis(100));
+    view.update(|_| ());
+    // After a pause of three and a half intervals, two frames were skipped.
+    view.set_fake_clock(start + Duration::from_millis(450));
+    view.update(|_| ());
+    let model = view.abandon();
+    assert_eq!(model.skipped, [2]);
+    assert_eq!(model.frame, 5);
+}
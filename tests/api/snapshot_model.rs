@@ -0,0 +1,29 @@
+//! Test taking a copy of the model with `View::snapshot_model`.
+
+use nutmeg::models::LinearModel;
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn snapshot_is_independent_of_later_updates() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update(|model| model.increment(4));
+    let snapshot = view.snapshot_model();
+    view.update(|model| model.increment(2));
+    assert_eq!(snapshot.done(), 4);
+    assert_eq!(view.snapshot_model().done(), 6);
+    view.abandon();
+}
+
+#[test]
+fn snapshot_includes_pending_keyed_updates() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update_keyed("copy", |model| model.set_done(7));
+    assert_eq!(view.snapshot_model().done(), 7);
+    view.abandon();
+}
@@ -0,0 +1,66 @@
+//! Test the spinner, which turns with the view's clock rather than with updates.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::SpinnerModel;
+use nutmeg::{Destination, Options, View};
+
+fn last_line(view: &View<SpinnerModel>) -> String {
+    let output = view.captured_output();
+    let output = output.lock().unwrap();
+    output.rsplit("\x1b[0J").next().unwrap().to_owned()
+}
+
+#[test]
+fn spinner_follows_the_clock() {
+    let view = View::new(
+        SpinnerModel::new("Waiting"),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .fake_clock(true),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|_| ());
+    assert_eq!(last_line(&view), "⠋ Waiting");
+
+    // Many updates in the same instant don't move the spinner.
+    for _ in 0..5 {
+        view.update(|_| ());
+    }
+    assert_eq!(last_line(&view), "⠋ Waiting");
+
+    view.set_fake_clock(start + Duration::from_millis(250));
+    view.update(|_| ());
+    assert_eq!(last_line(&view), "⠹ Waiting");
+
+    // It wraps around after the last frame.
+    view.set_fake_clock(start + Duration::from_millis(1050));
+    view.update(|model| model.set_message("Still waiting"));
+    assert_eq!(last_line(&view), "⠋ Still waiting");
+}
+
+#[test]
+fn custom_frames() {
+    let view = View::new(
+        SpinnerModel::new("Thinking"),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .fake_clock(true),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|model| model.set_frames(&["-", "|"], Duration::from_secs(1)));
+    assert_eq!(last_line(&view), "- Thinking");
+    view.set_fake_clock(start + Duration::from_millis(1500));
+    view.update(|_| ());
+    assert_eq!(last_line(&view), "| Thinking");
+}
+
+#[test]
+#[should_panic(expected = "spinner frames are empty")]
+fn empty_frames_panic() {
+    SpinnerModel::new("Nothing").set_frames(&[], Duration::from_secs(1));
+}
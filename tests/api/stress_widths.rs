@@ -0,0 +1,40 @@
+//! Test the width stress-testing helpers.
+
+use nutmeg::models::LinearModel;
+use nutmeg::test_util::{check_widths, stress_widths};
+
+struct Bar;
+
+impl nutmeg::Model for Bar {
+    fn render(&mut self, width: usize) -> String {
+        // Wide characters and styling don't count against the width incorrectly.
+        let fill = width / 2;
+        format!("\x1b[32m{}\x1b[0m", "盛".repeat(fill))
+    }
+}
+
+#[test]
+fn fitting_model_passes() {
+    stress_widths(&mut Bar);
+}
+
+struct Overflow;
+
+impl nutmeg::Model for Overflow {
+    fn render(&mut self, width: usize) -> String {
+        let label = "label: ";
+        format!("{label}{}", "#".repeat(width - label.len()))
+    }
+}
+
+#[test]
+#[should_panic(expected = "Overflow::render panicked at width 1")]
+fn underflow_is_reported() {
+    stress_widths(&mut Overflow);
+}
+
+#[test]
+#[should_panic(expected = "produced a line")]
+fn over_width_lines_are_reported() {
+    check_widths(&mut LinearModel::new("Counting", 100), 3..=20);
+}
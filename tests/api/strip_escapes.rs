@@ -0,0 +1,64 @@
+//! Test removing escape sequences from messages written to a destination
+//! that isn't a terminal.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use nutmeg::models::LinearModel;
+use nutmeg::{DestinationTrait, Options, View};
+
+/// A destination like a file or pipe, which declines to draw progress bars.
+#[derive(Debug, Clone, Default)]
+struct Pipe(Arc<Mutex<String>>);
+
+impl DestinationTrait for Pipe {
+    fn init(&self) -> bool {
+        false
+    }
+
+    fn width(&self) -> Option<usize> {
+        None
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+fn copying() -> LinearModel {
+    let mut model = LinearModel::new("Copying", 2);
+    model.set_final_message("Copied {done:file}");
+    model
+}
+
+#[test]
+fn messages_and_final_message_are_plain_by_default() {
+    let pipe = Pipe::default();
+    let view = View::with_destination(copying(), Options::default(), pipe.clone());
+    view.update(|model| model.increment(2));
+    view.message("\x1b[1mcopied\x1b[0m a.txt\n");
+    view.finish();
+    assert_eq!(*pipe.0.lock().unwrap(), "copied a.txt\nCopied 2 files\n");
+}
+
+#[test]
+fn escapes_are_kept_if_stripping_is_off() {
+    let pipe = Pipe::default();
+    let view = View::with_destination(
+        copying(),
+        Options::default().strip_escapes(false),
+        pipe.clone(),
+    );
+    view.update(|model| model.increment(2));
+    view.message("\x1b[1mcopied\x1b[0m a.txt\n");
+    view.finish();
+    assert_eq!(
+        *pipe.0.lock().unwrap(),
+        "\x1b[1mcopied\x1b[0m a.txt\nCopied 2 files\n"
+    );
+}
@@ -0,0 +1,44 @@
+//! Test subscribing to events from a view.
+
+use std::thread;
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{CapturedEventKind, Destination, Options, View};
+
+#[test]
+fn all_subscribers_receive_events() {
+    let view = View::new(
+        DisplayModel(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO),
+    );
+    let first = view.subscribe();
+    let second = view.subscribe();
+    let logger = thread::spawn(move || second.iter().map(|e| e.kind).collect::<Vec<_>>());
+    view.update(|model| model.0 = 1);
+    view.message("hello\n");
+    view.finish();
+    let kinds: Vec<_> = first.iter().map(|e| e.kind).collect();
+    assert_eq!(
+        kinds,
+        [
+            CapturedEventKind::Frame("1".to_owned()),
+            CapturedEventKind::Erase,
+            CapturedEventKind::Message("hello\n".to_owned()),
+        ]
+    );
+    assert_eq!(logger.join().unwrap(), kinds);
+}
+
+#[test]
+fn dropped_subscriber_is_forgotten() {
+    let view = View::new(
+        DisplayModel(0),
+        Options::default().destination(Destination::Capture),
+    );
+    drop(view.subscribe());
+    view.message("hello\n");
+    assert_eq!(view.captured_events().lock().unwrap().len(), 1);
+}
@@ -0,0 +1,57 @@
+//! Test `SyncView`, whose model is updated through a shared reference.
+
+use std::sync::atomic::AtomicUsize;
+use std::sync::atomic::Ordering::Relaxed;
+use std::time::Duration;
+
+use nutmeg::{Destination, Options, SyncView};
+
+#[derive(Default)]
+struct Model {
+    i: AtomicUsize,
+}
+
+impl nutmeg::Model for Model {
+    fn render(&mut self, _width: usize) -> String {
+        format!("i={}", self.i.load(Relaxed))
+    }
+
+    fn final_message(&mut self) -> String {
+        format!("done at {}", self.i.load(Relaxed))
+    }
+}
+
+#[test]
+fn update_draws_the_model() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .update_interval(Duration::ZERO);
+    let view = SyncView::new(Model::default(), options);
+    let output = view.captured_output();
+    assert_eq!(view.update(|model| model.i.fetch_add(1, Relaxed)), 0);
+    assert_eq!(*output.lock().unwrap(), "\x1b[?7l\x1b[0Ji=1");
+    view.message("hello\n");
+    assert_eq!(
+        *output.lock().unwrap(),
+        "\x1b[?7l\x1b[0Ji=1\x1b[1G\x1b[0J\x1b[?7hhello\n"
+    );
+    let model = view.finish();
+    assert_eq!(model.i.load(Relaxed), 1);
+    assert!(output.lock().unwrap().ends_with("done at 1\n"));
+}
+
+#[test]
+fn concurrent_updates_are_all_applied() {
+    let options = Options::default().destination(Destination::Capture);
+    let view = SyncView::new(Model::default(), options);
+    std::thread::scope(|scope| {
+        for _ in 0..8 {
+            scope.spawn(|| {
+                for _ in 0..1000 {
+                    view.update(|model| model.i.fetch_add(1, Relaxed));
+                }
+            });
+        }
+    });
+    assert_eq!(view.abandon().i.into_inner(), 8000);
+}
@@ -0,0 +1,83 @@
+//! Test drawing frames as synchronized updates (mode 2026).
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use nutmeg::ansi::{BEGIN_SYNCHRONIZED_UPDATE, END_SYNCHRONIZED_UPDATE};
+use nutmeg::models::DisplayModel;
+use nutmeg::{DestinationTrait, View};
+
+use crate::capture_options;
+
+#[test]
+fn frames_are_wrapped_when_enabled() {
+    let view = View::new(
+        DisplayModel("one\ntwo"),
+        capture_options().synchronized_output(true),
+    );
+    view.update(|_| ());
+    let output = view.captured_output();
+    assert_eq!(
+        *output.lock().unwrap(),
+        format!("{BEGIN_SYNCHRONIZED_UPDATE}\x1b[?7l\x1b[0Jone\ntwo{END_SYNCHRONIZED_UPDATE}")
+    );
+    view.abandon();
+}
+
+#[test]
+fn capture_is_not_synchronized_by_default() {
+    let view = View::new(DisplayModel("one"), capture_options());
+    view.update(|_| ());
+    assert!(!view.captured_output().lock().unwrap().contains("2026"));
+    view.abandon();
+}
+
+/// A terminal that reports support for synchronized updates.
+#[derive(Debug, Clone, Default)]
+struct SyncTerminal(Arc<Mutex<String>>);
+
+impl DestinationTrait for SyncTerminal {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(80)
+    }
+
+    fn synchronized_output(&self) -> bool {
+        true
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        self.0.lock().unwrap().push_str(buf);
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn destination_support_is_used_unless_turned_off() {
+    let terminal = SyncTerminal::default();
+    let view = View::with_destination(DisplayModel("one"), capture_options(), terminal.clone());
+    view.update(|_| ());
+    assert!(terminal
+        .0
+        .lock()
+        .unwrap()
+        .starts_with(BEGIN_SYNCHRONIZED_UPDATE));
+    view.abandon();
+
+    let terminal = SyncTerminal::default();
+    let view = View::with_destination(
+        DisplayModel("one"),
+        capture_options().synchronized_output(false),
+        terminal.clone(),
+    );
+    view.update(|_| ());
+    assert!(!terminal.0.lock().unwrap().contains("2026"));
+    view.abandon();
+}
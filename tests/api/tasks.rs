@@ -0,0 +1,174 @@
+//! Test tasks added by `View::add_task` and drawn below the model.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{CapturedEventKind, Expansion, TaskFinishPolicy, View};
+
+use crate::capture_options;
+
+/// The text of the last frame drawn by the view.
+fn last_frame<M: nutmeg::Model>(view: &View<M>) -> String {
+    view.captured_events()
+        .lock()
+        .unwrap()
+        .iter()
+        .rev()
+        .find_map(|event| match &event.kind {
+            CapturedEventKind::Frame(frame) => Some(frame.clone()),
+            _ => None,
+        })
+        .unwrap()
+}
+
+/// The messages printed by the view.
+fn messages<M: nutmeg::Model>(view: &View<M>) -> Vec<String> {
+    view.captured_events()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|event| match &event.kind {
+            CapturedEventKind::Message(message) => Some(message.clone()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn active_tasks_are_drawn_below_the_model() {
+    let view = View::new(DisplayModel("Fetching"), capture_options());
+    let alpha = view.add_task("alpha");
+    let beta = view.add_task(String::from("beta"));
+    alpha.increment(3);
+    beta.set_message("resolving deltas");
+    beta.increment(12);
+    assert_eq!(
+        last_frame(&view),
+        "Fetching\nalpha: 3\nbeta: 12 resolving deltas"
+    );
+    assert_eq!(view.task_count(), 2);
+    assert_eq!(alpha.finish(), 3);
+    drop(beta);
+    assert_eq!(view.task_count(), 0);
+    assert_eq!(last_frame(&view), "Fetching");
+    view.abandon();
+}
+
+#[test]
+fn tasks_are_drawn_without_a_model_line() {
+    let view = View::new(DisplayModel(""), capture_options());
+    let task = view.add_task("build");
+    task.increment(1);
+    assert_eq!(last_frame(&view), "build: 1");
+    assert_eq!(task.done(), 1);
+    drop(task);
+    view.abandon();
+}
+
+#[test]
+fn tasks_can_be_updated_from_several_threads() {
+    let view = View::new(DisplayModel("Working"), capture_options());
+    let counts: Vec<u64> = std::thread::scope(|scope| {
+        let handles: Vec<_> = (0..4)
+            .map(|i| {
+                let view = &view;
+                scope.spawn(move || {
+                    let task = view.add_task(format!("job {i}"));
+                    for _ in 0..100 {
+                        task.increment(1);
+                    }
+                    task.finish()
+                })
+            })
+            .collect();
+        handles.into_iter().map(|h| h.join().unwrap()).collect()
+    });
+    assert_eq!(counts, [100; 4]);
+    assert_eq!(view.task_count(), 0);
+    view.abandon();
+}
+
+#[test]
+fn finished_tasks_are_summarized_above_the_bar() {
+    let view = View::new(
+        DisplayModel("Building"),
+        capture_options()
+            .fake_clock(true)
+            .on_task_finish(TaskFinishPolicy::Summarize),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    let compile = view.add_task("compile");
+    let link = view.add_task("link");
+    let copy = view.add_task("file.txt");
+    compile.increment(12);
+    copy.set_size("12 MB");
+    view.set_fake_clock(start + Duration::from_millis(1200));
+    assert_eq!(compile.finish(), 12);
+    drop(link);
+    copy.finish();
+    assert_eq!(
+        messages(&view),
+        [
+            "✔ compile 12 in 1.2s\n",
+            "✔ link 0 in 1.2s\n",
+            "✔ file.txt 12 MB in 1.2s\n"
+        ]
+    );
+    assert_eq!(last_frame(&view), "Building");
+    view.abandon();
+}
+
+#[test]
+fn finished_tasks_are_not_summarized_by_default() {
+    let view = View::new(DisplayModel("Building"), capture_options());
+    view.add_task("compile").finish();
+    assert!(messages(&view).is_empty());
+    view.abandon();
+}
+
+#[test]
+fn grouped_tasks_are_drawn_under_a_summary() {
+    let view = View::new(DisplayModel("Testing"), capture_options());
+    let lint = view.add_task("lint");
+    let group = view.add_task_group("tests");
+    let first = group.add_task("test a");
+    let second = group.add_task("test b");
+    first.increment(2);
+    assert_eq!(
+        last_frame(&view),
+        "Testing\nlint: 0\ntests: 0/2 complete\n  test a: 2\n  test b: 0"
+    );
+    first.finish();
+    assert_eq!(
+        last_frame(&view),
+        "Testing\nlint: 0\ntests: 1/2 complete\n  test b: 0"
+    );
+    group.finish();
+    // Members of a finished group are drawn as ungrouped tasks.
+    assert_eq!(last_frame(&view), "Testing\nlint: 0\ntest b: 0");
+    drop((lint, second));
+    view.abandon();
+}
+
+#[test]
+fn task_group_collapses_to_fit_the_terminal() {
+    // Capture destinations are 24 lines high, so a group is expanded only if
+    // it fits in 12 lines.
+    let view = View::new(DisplayModel("Testing"), capture_options());
+    let group = view.add_task_group("tests");
+    let mut members: Vec<_> = (0..11)
+        .map(|i| group.add_task(format!("test {i}")))
+        .collect();
+    members[0].increment(1);
+    assert_eq!(last_frame(&view).lines().count(), 13);
+    members.push(group.add_task("test 11"));
+    assert_eq!(last_frame(&view), "Testing\ntests: 0/12 complete");
+    group.set_expansion(Expansion::Expanded);
+    assert_eq!(last_frame(&view).lines().count(), 14);
+    group.set_expansion(Expansion::Collapsed);
+    assert_eq!(last_frame(&view), "Testing\ntests: 0/12 complete");
+    drop(members);
+    drop(group);
+    view.abandon();
+}
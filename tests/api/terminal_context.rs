@@ -0,0 +1,93 @@
+//! Test choosing the update interval from the terminal context.
+
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, Options, TerminalContext, View};
+
+#[test]
+fn default_intervals_by_context() {
+    assert_eq!(
+        TerminalContext::Local.default_update_interval(),
+        Duration::from_millis(100)
+    );
+    assert!(
+        TerminalContext::Remote.default_update_interval()
+            > TerminalContext::Local.default_update_interval()
+    );
+    assert!(
+        TerminalContext::Mosh.default_update_interval()
+            > TerminalContext::Remote.default_update_interval()
+    );
+    assert!(
+        TerminalContext::Ci.default_update_interval()
+            > TerminalContext::Mosh.default_update_interval()
+    );
+}
+
+#[test]
+fn detection_from_vars() {
+    let detect = |vars: &[(&str, &str)]| TerminalContext::from_vars(vars.iter().copied());
+    assert_eq!(detect(&[]), TerminalContext::Local);
+    assert_eq!(detect(&[("CI", "")]), TerminalContext::Local);
+    assert_eq!(
+        detect(&[("SSH_CONNECTION", "10.0.0.1 22 10.0.0.2 22")]),
+        TerminalContext::Remote
+    );
+    assert_eq!(
+        detect(&[("SSH_CLIENT", "10.0.0.1 22 22"), ("LC_MOSH", "1")]),
+        TerminalContext::Mosh
+    );
+    assert_eq!(
+        detect(&[("MOSH_SERVER_PID", "1234")]),
+        TerminalContext::Mosh
+    );
+    assert_eq!(
+        detect(&[("MOSH_SERVER_PID", "1234"), ("CI", "true")]),
+        TerminalContext::Ci
+    );
+}
+
+#[test]
+fn detection_is_stable() {
+    assert_eq!(TerminalContext::detect(), TerminalContext::detect());
+}
+
+#[test]
+fn capture_keeps_default_interval() {
+    let view = View::new(
+        DisplayModel("hi"),
+        Options::default().destination(Destination::Capture),
+    );
+    view.update(|_| ());
+    assert_eq!(
+        view.stats().effective_update_interval,
+        Duration::from_millis(100)
+    );
+    view.abandon();
+}
+
+#[test]
+fn explicit_interval_is_kept_on_stdout() {
+    let view = View::new(
+        DisplayModel("hi"),
+        Options::default().update_interval(Duration::from_millis(7)),
+    );
+    view.update(|_| ());
+    assert_eq!(
+        view.stats().effective_update_interval,
+        Duration::from_millis(7)
+    );
+    view.abandon();
+}
+
+#[test]
+fn stdout_uses_detected_interval() {
+    let view = View::new(DisplayModel("hi"), Options::default());
+    view.update(|_| ());
+    assert_eq!(
+        view.stats().effective_update_interval,
+        TerminalContext::detect().default_update_interval()
+    );
+    view.abandon();
+}
@@ -0,0 +1,26 @@
+//! Test `nutmeg::terminal::Guard`.
+
+use nutmeg::terminal::{Guard, Mode};
+use nutmeg::Destination;
+
+#[test]
+fn guard_records_and_forgets_modes() {
+    let guard = Guard::new(Destination::Capture);
+    assert!(!guard.is_recorded(Mode::CursorHidden));
+    guard.record(Mode::CursorHidden);
+    guard.record(Mode::LineWrapDisabled);
+    assert!(guard.is_recorded(Mode::CursorHidden));
+    guard.forget(Mode::CursorHidden);
+    assert!(!guard.is_recorded(Mode::CursorHidden));
+    assert!(guard.is_recorded(Mode::LineWrapDisabled));
+    guard.restore();
+    assert!(!guard.is_recorded(Mode::LineWrapDisabled));
+}
+
+#[test]
+fn mode_sequences() {
+    assert_eq!(Mode::LineWrapDisabled.set_sequence(), "\x1b[?7l");
+    assert_eq!(Mode::LineWrapDisabled.restore_sequence(), "\x1b[?7h");
+    assert_eq!(Mode::CursorHidden.restore_sequence(), "\x1b[?25h");
+    assert_eq!(Mode::AlternateScreen.restore_sequence(), "\x1b[?1049l");
+}
@@ -0,0 +1,85 @@
+//! Test themes for success and failure messages.
+
+use nutmeg::models::LinearModel;
+use nutmeg::theme::{current_theme, Theme};
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn default_theme_styles_only_failure() {
+    assert_eq!(current_theme(), Theme::Default);
+    assert_eq!(Theme::Default.success("done"), "done");
+    assert_eq!(Theme::Default.warning("hmm"), "hmm");
+    assert_eq!(Theme::Default.completed("done"), "✔ done");
+    assert!(Theme::Default.failure("oops").contains("oops"));
+}
+
+#[test]
+fn accessible_theme_uses_symbols_and_colors() {
+    let success = Theme::Accessible.success("done");
+    assert!(success.contains("✔ done"));
+    assert!(success.contains("\x1b[38;2;0;114;178m"), "{success:?}");
+    assert_eq!(Theme::Accessible.completed("done"), success);
+    assert!(Theme::Accessible.warning("hmm").contains("! hmm"));
+    let failure = Theme::Accessible.failure("oops");
+    assert!(failure.contains("✖ oops"));
+    assert!(failure.contains("38;2;213;94;0"), "{failure:?}");
+}
+
+#[test]
+fn accessible_failure_message() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default()
+            .destination(Destination::Capture)
+            .theme(Theme::Accessible),
+    );
+    let output = view.captured_output();
+    view.finish_failure("disk full");
+    assert!(output.lock().unwrap().contains("✖ failed: disk full"));
+}
+
+#[test]
+fn accessible_success_final_message() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default()
+            .destination(Destination::Capture)
+            .theme(Theme::Accessible),
+    );
+    view.update(|model| {
+        model.set_final_message("Copied {done:file}");
+        model.increment(10);
+    });
+    let output = view.captured_output();
+    view.finish();
+    assert!(output.lock().unwrap().contains("✔ Copied 10 files"));
+}
+
+#[test]
+fn monochrome_uses_emphasis_instead_of_color() {
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default()
+            .destination(Destination::Capture)
+            .theme(Theme::Accessible)
+            .monochrome(true),
+    );
+    assert!(view.inspect_model(|_| nutmeg::theme::monochrome_advised()));
+    let output = view.captured_output();
+    view.finish_failure("disk full");
+    let output = output.lock().unwrap();
+    assert!(output.contains("✖ failed: disk full"), "{output:?}");
+    assert!(output.contains("\x1b[1;4m"), "{output:?}");
+    assert!(!output.contains("38;2"), "{output:?}");
+}
+
+#[test]
+fn monochrome_is_only_advised_inside_view_calls() {
+    assert!(!nutmeg::theme::monochrome_advised());
+    let view = View::new(
+        LinearModel::new("Copying", 10),
+        Options::default().destination(Destination::Capture),
+    );
+    assert!(!view.inspect_model(|_| nutmeg::theme::monochrome_advised()));
+    view.abandon();
+}
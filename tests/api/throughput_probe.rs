@@ -0,0 +1,56 @@
+//! Test measuring update and paint rates with `models::ThroughputProbe`.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::models::ThroughputProbe;
+use nutmeg::{Destination, Options, View};
+
+#[test]
+fn probe_shows_rates_once_a_window_has_passed() {
+    let view = View::new(
+        ThroughputProbe::new("Hashing"),
+        Options::default()
+            .destination(Destination::Capture)
+            .fake_clock(true),
+    );
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update(|model| model.record_update());
+    assert_eq!(view.inspect_model(|m| (m.updates(), m.paints())), (1, 1));
+    assert_eq!(view.inspect_model(|m| m.update_rate()), None);
+    let output = view.captured_output();
+    assert!(
+        output
+            .lock()
+            .unwrap()
+            .contains("Hashing: 1 updates, 1 paints"),
+        "{output:?}"
+    );
+
+    // Updates at the same instant aren't painted.
+    for _ in 0..199 {
+        view.update(|model| model.record_update());
+    }
+    assert_eq!(view.inspect_model(|m| m.paints()), 1);
+
+    view.set_fake_clock(start + Duration::from_secs(1));
+    view.update(|model| model.record_update());
+    let probe = view.snapshot_model();
+    assert_eq!(probe.paints(), 2);
+    assert_eq!(probe.update_rate(), Some(200.0));
+    assert_eq!(probe.paint_rate(), Some(1.0));
+    assert!(
+        output
+            .lock()
+            .unwrap()
+            .contains("Hashing: 200 updates/s, 1 paints/s, 200 updates per paint"),
+        "{output:?}"
+    );
+    view.abandon();
+}
+
+#[test]
+#[should_panic(expected = "throughput window is zero")]
+fn zero_window_panics() {
+    ThroughputProbe::new("Hashing").set_window(Duration::ZERO);
+}
@@ -0,0 +1,118 @@
+//! Test repainting from a background ticker with `View::with_ticker`.
+
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::thread::sleep;
+use std::time::Duration;
+
+use nutmeg::{Destination, Options, View};
+
+struct Ticks(usize);
+
+impl nutmeg::Model for Ticks {
+    fn render(&mut self, _width: usize) -> String {
+        self.0 += 1;
+        format!("render {}", self.0)
+    }
+}
+
+fn view() -> View<Ticks> {
+    View::new(
+        Ticks(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .tick_interval(Duration::from_millis(10)),
+    )
+}
+
+#[test]
+fn ticker_repaints_without_updates() {
+    let view = view();
+    let output = view.captured_output();
+    let r = view.with_ticker(|| {
+        sleep(Duration::from_millis(200));
+        "done"
+    });
+    assert_eq!(r, "done");
+    assert!(view.stats().frames >= 3, "{:?}", view.stats());
+    assert!(output.lock().unwrap().contains("render 3"));
+
+    // The ticker stops when the closure returns.
+    let frames = view.stats().frames;
+    sleep(Duration::from_millis(50));
+    assert_eq!(view.stats().frames, frames);
+}
+
+#[test]
+fn ticker_stops_when_closure_panics() {
+    let view = view();
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        view.with_ticker(|| panic!("interrupted"));
+    }));
+    assert!(result.is_err());
+    let frames = view.stats().frames;
+    sleep(Duration::from_millis(50));
+    assert_eq!(view.stats().frames, frames);
+}
+
+#[derive(Debug)]
+struct Broken;
+
+impl nutmeg::DestinationTrait for Broken {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(80)
+    }
+
+    fn write(&self, _buf: &str) -> std::io::Result<()> {
+        Err(std::io::ErrorKind::BrokenPipe.into())
+    }
+
+    fn flush(&self) -> std::io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn ticker_ignores_write_errors() {
+    static BROKEN: Broken = Broken;
+    let view = View::new(
+        Ticks(0),
+        Options::default()
+            .destination(Destination::Custom(&BROKEN))
+            .update_interval(Duration::ZERO)
+            .tick_interval(Duration::from_millis(10))
+            .on_drop(nutmeg::DropPolicy::Abandon),
+    );
+    let r = view.with_ticker(|| {
+        sleep(Duration::from_millis(100));
+        "done"
+    });
+    assert_eq!(r, "done");
+    assert!(view.inspect_model(|model| model.0) >= 2);
+}
+
+/// After the bar is erased by an emergency restore, for example when the
+/// process is stopped by SIGTSTP, the ticker draws it again without waiting
+/// for an update or the update interval.
+#[test]
+fn ticker_redraws_after_emergency_restore() {
+    let view = View::new(
+        Ticks(0),
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::from_secs(3600))
+            .tick_interval(Duration::from_millis(10)),
+    );
+    view.with_ticker(|| {
+        view.update(|_| ());
+        sleep(Duration::from_millis(50));
+        assert_eq!(view.stats().frames, 1);
+        view.emergency_restore();
+        sleep(Duration::from_millis(100));
+    });
+    assert_eq!(view.stats().frames, 2);
+}
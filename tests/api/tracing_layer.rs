@@ -0,0 +1,94 @@
+//! Test showing tracing spans as progress with `nutmeg::tracing::ProgressLayer`.
+
+use std::time::Duration;
+
+use nutmeg::tracing::ProgressLayer;
+use nutmeg::{Destination, MessageLevel, MinLevel, Options};
+use tracing_subscriber::prelude::*;
+
+fn capture_layer() -> ProgressLayer {
+    ProgressLayer::new(
+        Options::default()
+            .destination(Destination::Capture)
+            .update_interval(Duration::ZERO)
+            .print_holdoff(Duration::ZERO),
+    )
+}
+
+#[test]
+fn entered_spans_are_drawn_with_fields_and_nesting() {
+    let layer = capture_layer();
+    let output = layer.captured_output();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let _outer = tracing::info_span!("backup", dest = "/mnt").entered();
+        let inner = tracing::info_span!("copy", file = "a.txt", size = 3);
+        let inner = inner.entered();
+        let drawn = output.lock().unwrap().clone();
+        assert!(
+            drawn.ends_with("backup: dest=/mnt\n  copy: file=a.txt size=3"),
+            "{drawn:?}"
+        );
+        drop(inner);
+        let drawn = output.lock().unwrap().clone();
+        assert!(drawn.ends_with("backup: dest=/mnt"), "{drawn:?}");
+    });
+}
+
+#[test]
+fn spans_not_entered_are_not_drawn() {
+    let layer = capture_layer();
+    let output = layer.captured_output();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("idle");
+        let _active = tracing::info_span!("active").entered();
+    });
+    let drawn = output.lock().unwrap().clone();
+    assert!(drawn.contains("active"), "{drawn:?}");
+    assert!(!drawn.contains("idle"), "{drawn:?}");
+}
+
+#[test]
+fn recorded_fields_are_added() {
+    let layer = capture_layer();
+    let output = layer.captured_output();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let span = tracing::info_span!("scan", files = tracing::field::Empty).entered();
+        span.record("files", 42);
+        let drawn = output.lock().unwrap().clone();
+        assert!(drawn.ends_with("scan: files=42"), "{drawn:?}");
+    });
+}
+
+#[test]
+fn events_are_printed_as_messages() {
+    let layer = capture_layer();
+    let output = layer.captured_output();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        let _span = tracing::info_span!("work").entered();
+        tracing::warn!(path = "b.txt", "skipped");
+        let drawn = output.lock().unwrap().clone();
+        assert!(drawn.ends_with(" WARN skipped path=b.txt\n"), "{drawn:?}");
+    });
+}
+
+#[test]
+fn events_are_filtered_by_level() {
+    let layer = ProgressLayer::new(
+        Options::default()
+            .destination(Destination::Capture)
+            .message_filters(&[&MinLevel(MessageLevel::Warn)]),
+    );
+    let output = layer.captured_output();
+    let subscriber = tracing_subscriber::registry().with(layer);
+    tracing::subscriber::with_default(subscriber, || {
+        tracing::info!("copied");
+        tracing::error!("failed");
+    });
+    let drawn = output.lock().unwrap().clone();
+    assert!(!drawn.contains("copied"), "{drawn:?}");
+    assert!(drawn.contains("ERROR failed\n"), "{drawn:?}");
+}
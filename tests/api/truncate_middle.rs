@@ -0,0 +1,29 @@
+//! Test truncating text in the middle.
+
+use nutmeg::truncate_middle;
+
+#[test]
+fn result_fits_width() {
+    let text = "https://example.com/a/very/long/url/with/many/parts";
+    for width in 0..60 {
+        let short = truncate_middle(text, width);
+        assert!(short.chars().count() <= width, "{short:?} too wide");
+        if width >= 3 && width < text.len() {
+            assert!(short.starts_with('h'));
+            assert!(short.ends_with('s'));
+            assert_eq!(short.matches('…').count(), 1);
+        }
+    }
+}
+
+#[test]
+fn wide_characters_count_double() {
+    assert_eq!(truncate_middle("写真旅行東京画像", 9), "写真…画像");
+    assert_eq!(truncate_middle("写真旅行東京画像", 8), "写真…像");
+    assert_eq!(truncate_middle("写真旅行東京画像", 7), "写…画像");
+}
+
+#[test]
+fn zero_width_keeps_only_escapes() {
+    assert_eq!(truncate_middle("\x1b[31mred\x1b[0m", 0), "\x1b[31m\x1b[0m");
+}
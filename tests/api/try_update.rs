@@ -0,0 +1,96 @@
+//! Test returning errors from `View::try_update` and `View::try_message`.
+
+use std::io;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Destination, DestinationTrait, Error, Options, View};
+
+#[derive(Debug)]
+struct Broken;
+
+impl DestinationTrait for Broken {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(80)
+    }
+
+    fn write(&self, _buf: &str) -> io::Result<()> {
+        Err(io::Error::new(
+            io::ErrorKind::BrokenPipe,
+            "terminal went away",
+        ))
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+static BROKEN: Broken = Broken;
+
+fn broken_view() -> View<DisplayModel<usize>> {
+    View::new(
+        DisplayModel(0),
+        Options::default()
+            .destination(Destination::Custom(&BROKEN))
+            .on_drop(nutmeg::DropPolicy::Abandon),
+    )
+}
+
+#[test]
+fn write_errors_are_returned() {
+    let view = broken_view();
+    let err = view.try_update(|model| model.0 = 1).unwrap_err();
+    let Error::Io(io_err) = &err else {
+        panic!("unexpected error {err:?}");
+    };
+    assert_eq!(io_err.kind(), io::ErrorKind::BrokenPipe);
+    assert_eq!(
+        err.to_string(),
+        "failed to write progress: terminal went away"
+    );
+    // The update was still applied.
+    assert_eq!(view.inspect_model(|model| model.0), 1);
+
+    let err = view.try_message("hello\n").unwrap_err();
+    assert!(matches!(err, Error::Io(_)), "{err:?}");
+}
+
+#[test]
+fn poisoned_view_returns_an_error() {
+    let view = View::new(
+        DisplayModel(0),
+        Options::default().destination(Destination::Capture),
+    );
+    let result = catch_unwind(AssertUnwindSafe(|| {
+        view.update(|_| panic!("failed in update"));
+    }));
+    assert!(result.is_err());
+    assert!(matches!(
+        view.try_update(|model| model.0 = 1),
+        Err(Error::Poisoned)
+    ));
+    assert!(matches!(view.try_message("hello\n"), Err(Error::Poisoned)));
+}
+
+#[test]
+fn successful_calls_return_ok() {
+    let view = View::new(
+        DisplayModel(0),
+        Options::default().destination(Destination::Capture),
+    );
+    assert_eq!(
+        view.try_update(|model| {
+            model.0 = 2;
+            model.0 * 10
+        })
+        .unwrap(),
+        20
+    );
+    view.try_message("hello\n").unwrap();
+    view.abandon();
+}
@@ -0,0 +1,99 @@
+//! Test that over-wide lines of the progress bar are cut by terminal columns.
+
+use std::io;
+use std::sync::{Arc, Mutex};
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{Options, View};
+use unicode_width::UnicodeWidthStr;
+
+/// Draw `frame` at `width` columns and return what was written for it.
+fn draw(frame: &str, width: usize) -> String {
+    #[derive(Clone, Default)]
+    struct Buf(Arc<Mutex<Vec<u8>>>);
+    impl io::Write for Buf {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+    let buf = Buf::default();
+    let view = View::write_to(
+        DisplayModel(frame.to_owned()),
+        Options::default().toggle_line_wrap(false),
+        buf.clone(),
+        width,
+    );
+    view.update(|_| ());
+    view.abandon();
+    let out = String::from_utf8(buf.0.lock().unwrap().clone()).unwrap();
+    out.strip_prefix("\x1b[0J")
+        .and_then(|s| s.strip_suffix('\n'))
+        .unwrap_or_else(|| panic!("unexpected framing {out:?}"))
+        .to_owned()
+}
+
+#[test]
+fn wide_characters_count_two_columns() {
+    assert_eq!(draw("盛盛盛盛盛盛", 8), "盛盛盛盛");
+    // A wide character that would cross the edge is left out.
+    assert_eq!(draw("a盛盛盛盛盛", 8), "a盛盛盛");
+    assert_eq!(draw("🦀🦀🦀 done", 5), "🦀🦀");
+}
+
+#[test]
+fn combining_marks_stay_with_their_character() {
+    assert_eq!(draw("cafe\u{301}s", 4), "cafe\u{301}");
+}
+
+#[test]
+fn escape_sequences_take_no_space_and_are_not_split() {
+    assert_eq!(
+        draw("\x1b[32mgreen\x1b[0m and plain", 7),
+        "\x1b[32mgreen\x1b[0m a"
+    );
+    // Escapes after the cut are kept, so the style is still reset.
+    assert_eq!(draw("\x1b[1mbold text\x1b[0m", 4), "\x1b[1mbold\x1b[0m");
+}
+
+#[test]
+fn cut_styled_lines_end_by_resetting_the_style() {
+    // The model never resets the color, so without a reset it would bleed
+    // into whatever's printed next.
+    assert_eq!(
+        draw("\x1b[31mred forever and ever\nnext", 8),
+        "\x1b[31mred fore\x1b[0m\nnext"
+    );
+    // A line that isn't cut is left alone.
+    assert_eq!(draw("\x1b[31mred", 8), "\x1b[31mred");
+    // Plain lines don't need a reset.
+    assert_eq!(draw("plain and long", 5), "plain");
+}
+
+#[test]
+fn every_line_fits() {
+    let frame = "第一行很长很长很长\n\x1b[33m⚠ warning: something long\x1b[0m\nshort";
+    for width in 1..30 {
+        for line in draw(frame, width).split('\n') {
+            let plain = strip_sgr(line);
+            assert!(plain.width() <= width, "{line:?} at {width}");
+        }
+    }
+}
+
+/// Remove `ESC [ ... m` styling sequences.
+fn strip_sgr(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(ch) = chars.next() {
+        if ch == '\x1b' {
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            out.push(ch);
+        }
+    }
+    out
+}
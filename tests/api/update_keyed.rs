@@ -0,0 +1,94 @@
+//! Test coalescing updates by key.
+
+use std::time::{Duration, Instant};
+
+use nutmeg::{CapturedEventKind, Options, View};
+
+use crate::capture_options;
+
+#[derive(Default)]
+struct Workers {
+    files: [String; 2],
+    applied: usize,
+}
+
+impl nutmeg::Model for Workers {
+    fn render(&mut self, _width: usize) -> String {
+        self.files.join(" ")
+    }
+}
+
+fn options() -> Options {
+    capture_options()
+        .fake_clock(true)
+        .update_interval(Duration::from_millis(100))
+}
+
+fn set_file(worker: usize, file: &'static str) -> impl FnOnce(&mut Workers) + Send {
+    move |model: &mut Workers| {
+        model.files[worker] = file.to_owned();
+        model.applied += 1;
+    }
+}
+
+#[test]
+fn updates_between_frames_are_coalesced_per_key() {
+    let view = View::new(Workers::default(), options());
+    let start = Instant::now();
+    view.set_fake_clock(start);
+    view.update_keyed("0", set_file(0, "a"));
+    for file in ["b", "c", "d"] {
+        view.update_keyed("0", set_file(0, file));
+        view.update_keyed("1", set_file(1, file));
+    }
+    view.set_fake_clock(start + Duration::from_millis(100));
+    view.update_keyed("1", set_file(1, "e"));
+    let frames: Vec<String> = view
+        .captured_events()
+        .lock()
+        .unwrap()
+        .iter()
+        .filter_map(|e| match &e.kind {
+            CapturedEventKind::Frame(f) => Some(f.clone()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(frames, ["a ", "d e"]);
+    // The first update drew a frame, and then only the last for each key ran.
+    assert_eq!(view.inspect_model(|m| m.applied), 3);
+}
+
+#[test]
+fn pending_updates_applied_before_other_access() {
+    let view = View::new(Workers::default(), options());
+    view.update_keyed("0", set_file(0, "a"));
+    view.update_keyed("1", set_file(1, "b"));
+    view.update(|model| assert_eq!(model.files, ["a", "b"]));
+    view.update_keyed("0", set_file(0, "c"));
+    let model = view.abandon();
+    assert_eq!(model.files, ["c", "b"]);
+}
+
+#[test]
+fn pending_updates_wait_while_progress_is_disabled() {
+    let view = View::new(Workers::default(), options().progress_enabled(false));
+    view.update_keyed("0", set_file(0, "a"));
+    view.update_keyed("0", set_file(0, "b"));
+    assert_eq!(view.inspect_model(|m| m.applied), 1);
+    view.update_keyed("1", set_file(1, "c"));
+    let model = view.finish();
+    assert_eq!(model.files, ["b", "c"]);
+    assert_eq!(model.applied, 2);
+}
+
+#[test]
+fn pending_updates_wait_while_suspended() {
+    let view = View::new(Workers::default(), options());
+    view.suspend();
+    view.update_keyed("0", set_file(0, "a"));
+    view.update_keyed("0", set_file(0, "b"));
+    assert_eq!(view.inspect_model(|m| m.files.clone()), ["b", ""]);
+    view.update_keyed("1", set_file(1, "c"));
+    view.resume();
+    assert_eq!(view.abandon().files, ["b", "c"]);
+}
@@ -0,0 +1,48 @@
+//! Test that rendered output is checked for sequences that would corrupt the display.
+
+use nutmeg::{Destination, Options, View};
+
+struct CursorMover;
+
+impl nutmeg::Model for CursorMover {
+    fn render(&mut self, _width: usize) -> String {
+        "\x1b[31mred\x1b[0m and up\x1b[2A".to_owned()
+    }
+}
+
+#[test]
+#[should_panic(expected = "CursorMover::render returned the control sequence \"\\u{1b}[2A\"")]
+fn cursor_movement_is_rejected() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .validate_render(true);
+    let view = View::new(CursorMover, options);
+    view.update(|_| ());
+}
+
+#[test]
+fn validation_can_be_turned_off() {
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .validate_render(false);
+    let view = View::new(CursorMover, options);
+    let output = view.captured_output();
+    view.update(|_| ());
+    view.abandon();
+    assert!(output.lock().unwrap().contains("and up\x1b[2A"));
+}
+
+#[test]
+fn styling_is_allowed() {
+    struct Styled;
+    impl nutmeg::Model for Styled {
+        fn render(&mut self, _width: usize) -> String {
+            "\x1b[1;32mgreen\x1b[0m\x1b[0K".to_owned()
+        }
+    }
+    let options = Options::default()
+        .destination(Destination::Capture)
+        .validate_render(true);
+    let view = View::new(Styled, options);
+    view.update(|_| ());
+}
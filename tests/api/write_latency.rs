@@ -0,0 +1,81 @@
+//! Test measuring write latency and adapting to slow terminals.
+
+use std::io;
+use std::sync::Mutex;
+use std::thread::sleep;
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{DestinationTrait, Options, View};
+
+#[derive(Debug, Default)]
+struct Terminal {
+    delay: Duration,
+    writes: Mutex<Vec<String>>,
+}
+
+impl DestinationTrait for Terminal {
+    fn init(&self) -> bool {
+        true
+    }
+
+    fn width(&self) -> Option<usize> {
+        Some(80)
+    }
+
+    fn write(&self, buf: &str) -> io::Result<()> {
+        sleep(self.delay);
+        self.writes.lock().unwrap().push(buf.to_owned());
+        Ok(())
+    }
+
+    fn flush(&self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[test]
+fn slow_writes_stretch_intervals() {
+    let view = View::with_destination(
+        DisplayModel("copying"),
+        Options::new()
+            .update_interval(Duration::from_millis(10))
+            .print_holdoff(Duration::from_millis(10)),
+        Terminal {
+            delay: Duration::from_millis(20),
+            ..Terminal::default()
+        },
+    );
+    view.update(|_| ());
+    let stats = view.stats();
+    assert_eq!(stats.writes, 1);
+    let latency = stats.mean_write_latency().unwrap();
+    assert!(latency >= Duration::from_millis(20), "{latency:?}");
+    assert!(stats.effective_update_interval >= Duration::from_millis(200));
+    assert!(stats.effective_print_holdoff >= Duration::from_millis(200));
+    view.abandon();
+}
+
+#[test]
+fn fast_writes_keep_intervals() {
+    let view = View::with_destination(
+        DisplayModel("copying"),
+        Options::new().update_interval(Duration::from_millis(100)),
+        Terminal::default(),
+    );
+    view.update(|_| ());
+    let stats = view.stats();
+    assert_eq!(stats.writes, 1);
+    assert_eq!(stats.effective_update_interval, Duration::from_millis(100));
+    assert_eq!(stats.effective_print_holdoff, Duration::from_millis(100));
+    view.abandon();
+}
+
+#[test]
+fn message_erases_bar_in_the_same_write() {
+    let view = View::with_destination(DisplayModel("copying"), Options::new(), Terminal::default());
+    view.update(|_| ());
+    view.message("hello\n");
+    assert_eq!(view.stats().writes, 2);
+    view.abandon();
+}
@@ -0,0 +1,66 @@
+//! Test drawing to any `Write` implementation through `WriterDestination`.
+
+use std::io::{self, Write};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use nutmeg::models::DisplayModel;
+use nutmeg::{DestinationTrait, Options, View, WriterDestination};
+
+/// A writer whose output can be read while the view owns it.
+#[derive(Clone, Default)]
+struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+impl Write for SharedBuffer {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.lock().unwrap().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl SharedBuffer {
+    fn contents(&self) -> String {
+        String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+    }
+}
+
+#[test]
+fn view_draws_to_writer() {
+    let buffer = SharedBuffer::default();
+    let view = View::write_to(
+        DisplayModel("a long progress line"),
+        Options::default()
+            .update_interval(Duration::ZERO)
+            .toggle_line_wrap(false),
+        buffer.clone(),
+        10,
+    );
+    view.update(|_| ());
+    view.message("hello\n");
+    view.finish();
+    assert_eq!(buffer.contents(), "\x1b[0Ja long pro\x1b[1G\x1b[0Jhello\n");
+}
+
+#[test]
+fn boxed_writer() {
+    let buffer = SharedBuffer::default();
+    let writer: Box<dyn Write + Send> = Box::new(buffer.clone());
+    let view = View::write_to(DisplayModel("boxed"), Options::default(), writer, 80);
+    view.update(|_| ());
+    drop(view);
+    assert!(buffer.contents().contains("boxed"));
+}
+
+#[test]
+fn destination_reports_size_and_returns_writer() {
+    let destination = WriterDestination::new(Vec::new(), 40).with_height(12);
+    assert!(destination.init());
+    assert_eq!(destination.width(), Some(40));
+    assert_eq!(destination.height(), Some(12));
+    destination.write("text").unwrap();
+    assert_eq!(destination.into_inner(), b"text");
+}
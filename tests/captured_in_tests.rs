@@ -15,7 +15,10 @@ use nutmeg::models::DisplayModel;
 
 #[test]
 fn view_stdout_captured() {
-    let mut view = nutmeg::View::new(DisplayModel("hello"), nutmeg::Options::default());
+    let mut view = nutmeg::View::new(
+        DisplayModel("hello"),
+        nutmeg::Options::default().in_captured_test(true),
+    );
     view.update(|DisplayModel(message)| *message = "stdout progress should be captured");
     writeln!(view, "stdout message should be captured").unwrap();
 }
@@ -29,3 +32,17 @@ fn view_stderr_captured() {
     view.update(|model| model.0 = "stderr progress should be captured");
     writeln!(view, "stderr message should be captured").unwrap();
 }
+
+#[test]
+fn write_to_print_captured() {
+    writeln!(
+        nutmeg::to_print::WriteToPrint,
+        "WriteToPrint should be captured"
+    )
+    .unwrap();
+    writeln!(
+        nutmeg::to_print::WriteToStderr,
+        "WriteToStderr should be captured"
+    )
+    .unwrap();
+}